@@ -16,6 +16,7 @@ use tracing::debug;
 use tracing::trace;
 
 use crate::ModelProviderInfo;
+use crate::client_common::LastRequest;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::client_common::ResponseStream;
@@ -34,6 +35,7 @@ pub(crate) async fn stream_chat_completions(
     model_family: &ModelFamily,
     client: &reqwest::Client,
     provider: &ModelProviderInfo,
+    last_request: &LastRequest,
 ) -> Result<ResponseStream> {
     // Build messages array
     let mut messages = Vec::<serde_json::Value>::new();
@@ -116,6 +118,7 @@ pub(crate) async fn stream_chat_completions(
         "stream": true,
         "tools": tools_json,
     });
+    last_request.record(&payload);
 
     debug!(
         "POST to {}: {}",
@@ -154,10 +157,6 @@ pub(crate) async fn stream_chat_completions(
                     return Err(CodexErr::UnexpectedStatus(status, body));
                 }
 
-                if attempt > max_retries {
-                    return Err(CodexErr::RetryLimit(status));
-                }
-
                 let retry_after_secs = res
                     .headers()
                     .get(reqwest::header::RETRY_AFTER)
@@ -167,6 +166,14 @@ pub(crate) async fn stream_chat_completions(
                 let delay = retry_after_secs
                     .map(|s| Duration::from_millis(s * 1_000))
                     .unwrap_or_else(|| backoff(attempt));
+
+                if attempt > max_retries {
+                    if status == StatusCode::TOO_MANY_REQUESTS {
+                        return Err(CodexErr::RateLimited { retry_after: delay });
+                    }
+                    return Err(CodexErr::RetryLimit(status));
+                }
+
                 tokio::time::sleep(delay).await;
             }
             Err(e) => {
@@ -381,9 +388,10 @@ async fn process_chat_sse<S>(
 
                         let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
                     }
-                    "stop" => {
+                    "stop" | "length" => {
                         // Regular turn without tool-call. Emit the final assistant message
                         // as a single OutputItemDone so non-delta consumers see the result.
+                        // A `length` finish is the same, except the text was cut short.
                         if !assistant_text.is_empty() {
                             let item = ResponseItem::Message {
                                 role: "assistant".to_string(),
@@ -410,6 +418,14 @@ async fn process_chat_sse<S>(
                     _ => {}
                 }
 
+                if finish_reason == "length" {
+                    let _ = tx_event
+                        .send(Ok(ResponseEvent::Incomplete {
+                            reason: "max_output_tokens".to_string(),
+                        }))
+                        .await;
+                }
+
                 // Emit Completed regardless of reason so the agent can advance.
                 let _ = tx_event
                     .send(Ok(ResponseEvent::Completed {
@@ -591,6 +607,9 @@ where
                 Poll::Ready(Some(Ok(ResponseEvent::ReasoningSummaryPartAdded))) => {
                     continue;
                 }
+                Poll::Ready(Some(Ok(ev @ ResponseEvent::Incomplete { .. }))) => {
+                    return Poll::Ready(Some(Ok(ev)));
+                }
             }
         }
     }
@@ -21,6 +21,7 @@ use uuid::Uuid;
 
 use crate::chat_completions::AggregateStreamExt;
 use crate::chat_completions::stream_chat_completions;
+use crate::client_common::LastRequest;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::client_common::ResponseStream;
@@ -61,6 +62,7 @@ pub struct ModelClient {
     session_id: Uuid,
     effort: ReasoningEffortConfig,
     summary: ReasoningSummaryConfig,
+    last_request: LastRequest,
 }
 
 impl ModelClient {
@@ -80,9 +82,15 @@ impl ModelClient {
             session_id,
             effort,
             summary,
+            last_request: LastRequest::default(),
         }
     }
 
+    /// Body of the most recent request sent to the model, with auth redacted.
+    pub fn last_request(&self) -> Option<Value> {
+        self.last_request.get()
+    }
+
     /// Dispatches to either the Responses or Chat implementation depending on
     /// the provider config.  Public callers always invoke `stream()` – the
     /// specialised helpers are private to avoid accidental misuse.
@@ -96,6 +104,7 @@ impl ModelClient {
                     &self.config.model_family,
                     &self.client,
                     &self.provider,
+                    &self.last_request,
                 )
                 .await?;
 
@@ -173,6 +182,8 @@ impl ModelClient {
             prompt_cache_key: Some(self.session_id.to_string()),
         };
 
+        self.last_request.record(&serde_json::to_value(&payload)?);
+
         let mut attempt = 0;
         let max_retries = self.provider.request_max_retries();
 
@@ -276,17 +287,21 @@ impl ModelClient {
                         }
                     }
 
+                    let delay = retry_after_secs
+                        .map(|s| Duration::from_millis(s * 1_000))
+                        .unwrap_or_else(|| backoff(attempt));
+
                     if attempt > max_retries {
                         if status == StatusCode::INTERNAL_SERVER_ERROR {
                             return Err(CodexErr::InternalServerError);
                         }
+                        if status == StatusCode::TOO_MANY_REQUESTS {
+                            return Err(CodexErr::RateLimited { retry_after: delay });
+                        }
 
                         return Err(CodexErr::RetryLimit(status));
                     }
 
-                    let delay = retry_after_secs
-                        .map(|s| Duration::from_millis(s * 1_000))
-                        .unwrap_or_else(|| backoff(attempt));
                     tokio::time::sleep(delay).await;
                 }
                 Err(e) => {
@@ -503,6 +518,32 @@ async fn process_sse<S>(
                     };
                 };
             }
+            // The response stopped early (e.g. `max_output_tokens`). Its
+            // output items have already been forwarded; report why and then
+            // finish like a completed response.
+            "response.incomplete" => {
+                if let Some(resp_val) = event.response {
+                    let reason = resp_val
+                        .get("incomplete_details")
+                        .and_then(|v| v.get("reason"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("unknown")
+                        .to_string();
+                    match serde_json::from_value::<ResponseCompleted>(resp_val) {
+                        Ok(r) => {
+                            response_completed = Some(r);
+                        }
+                        Err(e) => {
+                            debug!("failed to parse incomplete response: {e}");
+                            continue;
+                        }
+                    };
+                    let event = ResponseEvent::Incomplete { reason };
+                    if tx_event.send(Ok(event)).await.is_err() {
+                        return;
+                    }
+                }
+            }
             "response.content_part.done"
             | "response.function_call_arguments.delta"
             | "response.in_progress"
@@ -763,6 +804,9 @@ mod tests {
         fn is_output(ev: &ResponseEvent) -> bool {
             matches!(ev, ResponseEvent::OutputItemDone(_))
         }
+        fn is_incomplete(ev: &ResponseEvent) -> bool {
+            matches!(ev, ResponseEvent::Incomplete { reason } if reason == "max_output_tokens")
+        }
         fn is_completed(ev: &ResponseEvent) -> bool {
             matches!(ev, ResponseEvent::Completed { .. })
         }
@@ -804,6 +848,19 @@ mod tests {
                 expect_first: is_output,
                 expected_len: 2,
             },
+            TestCase {
+                name: "incomplete",
+                event: json!({
+                    "type": "response.incomplete",
+                    "response": {
+                        "id": "i",
+                        "usage": null,
+                        "incomplete_details": {"reason": "max_output_tokens"}
+                    }
+                }),
+                expect_first: is_incomplete,
+                expected_len: 2,
+            },
             TestCase {
                 name: "unknown",
                 event: json!({"type": "response.new_tool_event"}),
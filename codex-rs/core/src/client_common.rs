@@ -15,6 +15,8 @@ use std::borrow::Cow;
 use std::fmt::Display;
 use std::path::PathBuf;
 use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
 use std::task::Context;
 use std::task::Poll;
 use tokio::sync::mpsc;
@@ -145,6 +147,11 @@ pub enum ResponseEvent {
     ReasoningSummaryDelta(String),
     ReasoningContentDelta(String),
     ReasoningSummaryPartAdded,
+    /// The response was cut short (e.g. by the output token limit). Sent
+    /// before `Completed`.
+    Incomplete {
+        reason: String,
+    },
 }
 
 #[derive(Debug, Serialize)]
@@ -237,6 +244,53 @@ pub(crate) fn create_reasoning_param_for_request(
     }
 }
 
+/// Object keys whose values are replaced before a request body is retained.
+const REDACTED_KEYS: &[&str] = &[
+    "authorization",
+    "api_key",
+    "apikey",
+    "access_token",
+    "token",
+];
+const REDACTED_VALUE: &str = "[redacted]";
+
+/// Shared slot holding the body of the most recent request sent to the model,
+/// so front-ends can display exactly what was sent via `Op::GetLastRequest`.
+/// Credentials are carried in headers and never stored here, but any
+/// auth-looking fields in the body are redacted defensively.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LastRequest(Arc<Mutex<Option<serde_json::Value>>>);
+
+impl LastRequest {
+    pub(crate) fn record(&self, payload: &serde_json::Value) {
+        let mut payload = payload.clone();
+        redact_auth(&mut payload);
+        if let Ok(mut guard) = self.0.lock() {
+            *guard = Some(payload);
+        }
+    }
+
+    pub(crate) fn get(&self) -> Option<serde_json::Value> {
+        self.0.lock().ok().and_then(|guard| guard.clone())
+    }
+}
+
+fn redact_auth(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                if REDACTED_KEYS.contains(&key.to_ascii_lowercase().as_str()) {
+                    *v = serde_json::Value::String(REDACTED_VALUE.to_string());
+                } else {
+                    redact_auth(v);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_auth),
+        _ => {}
+    }
+}
+
 pub(crate) struct ResponseStream {
     pub(crate) rx_event: mpsc::Receiver<Result<ResponseEvent>>,
 }
@@ -267,4 +321,25 @@ mod tests {
         let full = prompt.get_full_instructions(&model_family);
         assert_eq!(full, expected);
     }
+
+    #[test]
+    fn last_request_redacts_auth_fields() {
+        let last = LastRequest::default();
+        assert_eq!(last.get(), None);
+
+        last.record(&serde_json::json!({
+            "model": "gpt-5",
+            "metadata": { "Authorization": "Bearer sk-secret", "note": "keep" },
+            "input": [{ "api_key": "sk-other" }],
+        }));
+
+        assert_eq!(
+            last.get(),
+            Some(serde_json::json!({
+                "model": "gpt-5",
+                "metadata": { "Authorization": "[redacted]", "note": "keep" },
+                "input": [{ "api_key": "[redacted]" }],
+            }))
+        );
+    }
 }
@@ -79,6 +79,7 @@ use crate::protocol::AgentReasoningSectionBreakEvent;
 use crate::protocol::ApplyPatchApprovalRequestEvent;
 use crate::protocol::AskForApproval;
 use crate::protocol::BackgroundEventEvent;
+use crate::protocol::ContextCompactedEvent;
 use crate::protocol::ErrorEvent;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
@@ -90,6 +91,8 @@ use crate::protocol::InputItem;
 use crate::protocol::Op;
 use crate::protocol::PatchApplyBeginEvent;
 use crate::protocol::PatchApplyEndEvent;
+use crate::protocol::RateLimitedEvent;
+use crate::protocol::ResponseTruncatedEvent;
 use crate::protocol::ReviewDecision;
 use crate::protocol::SandboxPolicy;
 use crate::protocol::SessionConfiguredEvent;
@@ -693,6 +696,7 @@ impl AgentTask {
                 id: self.sub_id,
                 msg: EventMsg::Error(ErrorEvent {
                     message: " Turn interrupted".to_string(),
+                    transient: false,
                 }),
             };
             let tx_event = self.sess.tx_event.clone();
@@ -719,6 +723,7 @@ async fn submission_loop(
             msg: EventMsg::Error(ErrorEvent {
                 message: "No session initialized, expected 'ConfigureSession' as first Op"
                     .to_string(),
+                transient: false,
             }),
         };
         tx_event.send(event).await.ok();
@@ -773,7 +778,10 @@ async fn submission_loop(
                     error!(message);
                     let event = Event {
                         id: sub.id,
-                        msg: EventMsg::Error(ErrorEvent { message }),
+                        msg: EventMsg::Error(ErrorEvent {
+                            message,
+                            transient: false,
+                        }),
                     };
                     if let Err(e) = tx_event.send(event).await {
                         error!("failed to send error message: {e:?}");
@@ -849,7 +857,10 @@ async fn submission_loop(
                             error!("{message}");
                             mcp_connection_errors.push(Event {
                                 id: sub.id.clone(),
-                                msg: EventMsg::Error(ErrorEvent { message }),
+                                msg: EventMsg::Error(ErrorEvent {
+                                    message,
+                                    transient: false,
+                                }),
                             });
                             (McpConnectionManager::default(), Default::default())
                         }
@@ -863,7 +874,10 @@ async fn submission_loop(
                         error!("{message}");
                         mcp_connection_errors.push(Event {
                             id: sub.id.clone(),
-                            msg: EventMsg::Error(ErrorEvent { message }),
+                            msg: EventMsg::Error(ErrorEvent {
+                                message,
+                                transient: false,
+                            }),
                         });
                     }
                 }
@@ -1012,6 +1026,16 @@ async fn submission_loop(
                     }
                 });
             }
+            Op::GetLastRequest => {
+                let request = sess.as_ref().and_then(|sess| sess.client.last_request());
+                let event = Event {
+                    id: sub.id.clone(),
+                    msg: EventMsg::LastRequest(crate::protocol::LastRequestEvent { request }),
+                };
+                if let Err(e) = tx_event.send(event).await {
+                    warn!("failed to send LastRequest event: {e}");
+                }
+            }
             Op::Compact => {
                 let sess = match sess.as_ref() {
                     Some(sess) => sess,
@@ -1051,6 +1075,7 @@ async fn submission_loop(
                                 id: sub.id.clone(),
                                 msg: EventMsg::Error(ErrorEvent {
                                     message: "Failed to shutdown rollout recorder".to_string(),
+                                    transient: false,
                                 }),
                             };
                             if let Err(e) = tx_event.send(event).await {
@@ -1245,12 +1270,19 @@ async fn run_task(sess: Arc<Session>, sub_id: String, input: Vec<InputItem>) {
             }
             Err(e) => {
                 info!("Turn error: {e:#}");
-                let event = Event {
-                    id: sub_id.clone(),
-                    msg: EventMsg::Error(ErrorEvent {
+                let msg = match e {
+                    CodexErr::RateLimited { retry_after } => {
+                        EventMsg::RateLimited(RateLimitedEvent { retry_after })
+                    }
+                    e => EventMsg::Error(ErrorEvent {
                         message: e.to_string(),
+                        transient: e.is_transient(),
                     }),
                 };
+                let event = Event {
+                    id: sub_id.clone(),
+                    msg,
+                };
                 sess.tx_event.send(event).await.ok();
                 // let the user continue the conversation
                 break;
@@ -1303,7 +1335,11 @@ async fn run_turn(
                 let max_retries = sess.client.get_provider().stream_max_retries();
                 if retries < max_retries {
                     retries += 1;
-                    let delay = backoff(retries);
+                    // A rate limit says how long to wait; other errors back off.
+                    let delay = match &e {
+                        CodexErr::RateLimited { retry_after } => *retry_after,
+                        _ => backoff(retries),
+                    };
                     warn!(
                         "stream disconnected - retrying turn ({retries}/{max_retries} in {delay:?})...",
                     );
@@ -1485,6 +1521,13 @@ async fn try_run_turn(
                 };
                 sess.tx_event.send(event).await.ok();
             }
+            ResponseEvent::Incomplete { reason } => {
+                let event = Event {
+                    id: sub_id.to_string(),
+                    msg: EventMsg::ResponseTruncated(ResponseTruncatedEvent { reason }),
+                };
+                sess.tx_event.send(event).await.ok();
+            }
             ResponseEvent::ReasoningContentDelta(delta) => {
                 if sess.show_raw_agent_reasoning {
                     let event = Event {
@@ -1554,6 +1597,7 @@ async fn run_compact_task(
                         id: sub_id.clone(),
                         msg: EventMsg::Error(ErrorEvent {
                             message: e.to_string(),
+                            transient: e.is_transient(),
                         }),
                     };
                     sess.send_event(event).await;
@@ -1564,11 +1608,24 @@ async fn run_compact_task(
     }
 
     sess.remove_task(&sub_id);
+    let summary = {
+        let mut state = sess.state.lock().unwrap();
+        state.history.keep_last_messages(1);
+        let mut summary = String::new();
+        for item in state.history.contents() {
+            if let ResponseItem::Message { content, .. } = item {
+                for content in content {
+                    if let ContentItem::OutputText { text } = content {
+                        summary.push_str(&text);
+                    }
+                }
+            }
+        }
+        summary
+    };
     let event = Event {
         id: sub_id.clone(),
-        msg: EventMsg::AgentMessage(AgentMessageEvent {
-            message: "Compact task completed".to_string(),
-        }),
+        msg: EventMsg::ContextCompacted(ContextCompactedEvent { summary }),
     };
     sess.send_event(event).await;
     let event = Event {
@@ -1578,9 +1635,6 @@ async fn run_compact_task(
         }),
     };
     sess.send_event(event).await;
-
-    let mut state = sess.state.lock().unwrap();
-    state.history.keep_last_messages(1);
 }
 
 async fn handle_response_item(
@@ -7,6 +7,7 @@ use crate::config_types::SandboxMode;
 use crate::config_types::SandboxWorkspaceWrite;
 use crate::config_types::ShellEnvironmentPolicy;
 use crate::config_types::ShellEnvironmentPolicyToml;
+use crate::config_types::TimestampZone;
 use crate::config_types::Tui;
 use crate::config_types::UriBasedFileOpener;
 use crate::model_family::ModelFamily;
@@ -16,6 +17,9 @@ use crate::model_provider_info::built_in_model_providers;
 use crate::openai_model_info::get_model_info;
 use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
+use chrono::DateTime;
+use chrono::Local;
+use chrono::Utc;
 use dirs::home_dir;
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -70,6 +74,9 @@ pub struct Config {
     /// Defaults to `false`.
     pub show_raw_agent_reasoning: bool,
 
+    /// Time zone of displayed timestamps.
+    pub timestamp_zone: TimestampZone,
+
     /// Disable server-side response storage (sends the full conversation
     /// context with every request). Currently necessary for OpenAI customers
     /// who have opted into Zero Data Retention (ZDR).
@@ -194,6 +201,34 @@ impl Config {
         // Step 4: merge with the strongly-typed overrides.
         Self::load_from_base_config_with_overrides(cfg, overrides, codex_home)
     }
+
+    /// This config with `model` as the model, and the model family and token
+    /// limits looked up for it. Limits configured for the previous model are
+    /// dropped since they do not apply to another one.
+    pub fn with_model(&self, model: &str) -> Self {
+        let model_family =
+            model_family_or_default(model, self.model_family.supports_reasoning_summaries);
+        let model_info = get_model_info(&model_family);
+        Self {
+            model: model.to_string(),
+            model_context_window: model_info.as_ref().map(|info| info.context_window),
+            model_max_output_tokens: model_info.as_ref().map(|info| info.max_output_tokens),
+            model_family,
+            ..self.clone()
+        }
+    }
+}
+
+/// The known family of `model`, or a generic one that only claims reasoning
+/// summary support when configured to.
+fn model_family_or_default(model: &str, supports_reasoning_summaries: bool) -> ModelFamily {
+    find_family_for_model(model).unwrap_or_else(|| ModelFamily {
+        slug: model.to_string(),
+        family: model.to_string(),
+        needs_special_apply_patch_instructions: false,
+        supports_reasoning_summaries,
+        uses_local_shell_tool: false,
+    })
 }
 
 pub fn load_config_as_toml_with_cli_overrides(
@@ -385,6 +420,10 @@ pub struct ConfigToml {
     /// Defaults to `false`.
     pub show_raw_agent_reasoning: Option<bool>,
 
+    /// Time zone of displayed timestamps: `local` (the default), `utc`, or
+    /// an IANA name such as `Europe/Berlin`.
+    pub timestamp_zone: Option<TimestampZone>,
+
     pub model_reasoning_effort: Option<ReasoningEffort>,
     pub model_reasoning_summary: Option<ReasoningSummary>,
 
@@ -572,17 +611,10 @@ impl Config {
             .or(config_profile.model)
             .or(cfg.model)
             .unwrap_or_else(default_model);
-        let model_family = find_family_for_model(&model).unwrap_or_else(|| {
-            let supports_reasoning_summaries =
-                cfg.model_supports_reasoning_summaries.unwrap_or(false);
-            ModelFamily {
-                slug: model.clone(),
-                family: model.clone(),
-                needs_special_apply_patch_instructions: false,
-                supports_reasoning_summaries,
-                uses_local_shell_tool: false,
-            }
-        });
+        let model_family = model_family_or_default(
+            &model,
+            cfg.model_supports_reasoning_summaries.unwrap_or(false),
+        );
 
         let openai_model_info = get_model_info(&model_family);
         let model_context_window = cfg
@@ -594,7 +626,25 @@ impl Config {
                 .map(|info| info.max_output_tokens)
         });
 
-        let experimental_resume = cfg.experimental_resume;
+        let experimental_resume = cfg
+            .experimental_resume
+            .map(|p| expand_config_path("experimental_resume", &p))
+            .transpose()?;
+
+        let mut tui = cfg.tui.unwrap_or_default();
+        tui.plain_log_path = tui
+            .plain_log_path
+            .map(|p| expand_config_path("tui.plain_log_path", &p))
+            .transpose()?;
+        tui.reasoning_log_path = tui
+            .reasoning_log_path
+            .map(|p| expand_config_path("tui.reasoning_log_path", &p))
+            .transpose()?;
+        tui.event_tap.path = tui
+            .event_tap
+            .path
+            .map(|p| expand_config_path("tui.event_tap.path", &p))
+            .transpose()?;
 
         // Load base instructions override from a file if specified. If the
         // path is relative, resolve it against the effective cwd so the
@@ -602,9 +652,11 @@ impl Config {
         let experimental_instructions_path = config_profile
             .experimental_instructions_file
             .as_ref()
-            .or(cfg.experimental_instructions_file.as_ref());
+            .or(cfg.experimental_instructions_file.as_ref())
+            .map(|p| expand_config_path("experimental_instructions_file", p))
+            .transpose()?;
         let file_base_instructions =
-            Self::get_base_instructions(experimental_instructions_path, &resolved_cwd)?;
+            Self::get_base_instructions(experimental_instructions_path.as_ref(), &resolved_cwd)?;
         let base_instructions = base_instructions.or(file_base_instructions);
 
         let config = Self {
@@ -635,7 +687,7 @@ impl Config {
             codex_home,
             history,
             file_opener: cfg.file_opener.unwrap_or(UriBasedFileOpener::VsCode),
-            tui: cfg.tui.unwrap_or_default(),
+            tui,
             codex_linux_sandbox_exe,
 
             hide_agent_reasoning: cfg.hide_agent_reasoning.unwrap_or(false),
@@ -643,6 +695,7 @@ impl Config {
                 .show_raw_agent_reasoning
                 .or(show_raw_agent_reasoning)
                 .unwrap_or(false),
+            timestamp_zone: cfg.timestamp_zone.unwrap_or_default(),
             model_reasoning_effort: config_profile
                 .model_reasoning_effort
                 .or(cfg.model_reasoning_effort)
@@ -724,10 +777,100 @@ impl Config {
     }
 }
 
+/// Expand a leading `~` and `$VAR` / `${VAR}` references in a path read from
+/// config. `key` names the setting in the error for an unset variable.
+fn expand_config_path(key: &str, path: &Path) -> std::io::Result<PathBuf> {
+    let Some(raw) = path.to_str() else {
+        return Ok(path.to_path_buf());
+    };
+
+    let mut out = String::new();
+    let mut rest = raw;
+    if rest == "~" || rest.starts_with("~/") {
+        let home = home_dir().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("cannot expand `~` in {key}: home directory not found"),
+            )
+        })?;
+        out.push_str(&home.to_string_lossy());
+        rest = &rest[1..];
+    }
+
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        let (name, tail) = match after.strip_prefix('{') {
+            Some(braced) => {
+                let end = braced.find('}').ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!("unterminated `${{` in {key}: {raw}"),
+                    )
+                })?;
+                (&braced[..end], &braced[end + 1..])
+            }
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], &after[end..])
+            }
+        };
+        if name.is_empty() {
+            // A lone `$` is kept as-is.
+            out.push('$');
+            rest = after;
+            continue;
+        }
+        let value = std::env::var(name).map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("environment variable `{name}` used in {key} is not set: {raw}"),
+            )
+        })?;
+        out.push_str(&value);
+        rest = tail;
+    }
+    out.push_str(rest);
+
+    Ok(PathBuf::from(out))
+}
+
 fn default_model() -> String {
     OPENAI_DEFAULT_MODEL.to_string()
 }
 
+impl TryFrom<String> for TimestampZone {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        match name.to_ascii_lowercase().as_str() {
+            "local" => Ok(Self::Local),
+            "utc" => Ok(Self::Utc),
+            _ => match name.parse::<chrono_tz::Tz>() {
+                Ok(_) => Ok(Self::Named(name)),
+                Err(e) => Err(format!("unknown time zone `{name}`: {e}")),
+            },
+        }
+    }
+}
+
+impl TimestampZone {
+    /// Format `at` in this zone with a `strftime`-style `format`. Named zones
+    /// were checked at load, so a lookup cannot fail; UTC is the fallback.
+    pub fn format(&self, at: DateTime<Utc>, format: &str) -> String {
+        match self {
+            Self::Local => at.with_timezone(&Local).format(format).to_string(),
+            Self::Utc => at.format(format).to_string(),
+            Self::Named(name) => match name.parse::<chrono_tz::Tz>() {
+                Ok(zone) => at.with_timezone(&zone).format(format).to_string(),
+                Err(_) => at.format(format).to_string(),
+            },
+        }
+    }
+}
+
 /// Returns the path to the Codex configuration directory, which can be
 /// specified by the `CODEX_HOME` environment variable. If not set, defaults to
 /// `~/.codex`.
@@ -772,6 +915,29 @@ mod tests {
     use pretty_assertions::assert_eq;
     use tempfile::TempDir;
 
+    #[test]
+    fn timestamp_zone_accepts_known_zones_and_rejects_unknown_ones() {
+        let parse = |value: &str| {
+            toml::from_str::<ConfigToml>(&format!("timestamp_zone = \"{value}\""))
+                .map(|cfg| cfg.timestamp_zone)
+        };
+        assert_eq!(parse("UTC").unwrap(), Some(TimestampZone::Utc));
+        assert_eq!(parse("local").unwrap(), Some(TimestampZone::Local));
+        assert_eq!(
+            parse("Europe/Berlin").unwrap(),
+            Some(TimestampZone::Named("Europe/Berlin".to_string()))
+        );
+        let err = parse("Mars/Olympus_Mons").unwrap_err().to_string();
+        assert!(
+            err.contains("unknown time zone `Mars/Olympus_Mons`"),
+            "{err}"
+        );
+        assert_eq!(
+            toml::from_str::<ConfigToml>("").unwrap().timestamp_zone,
+            None
+        );
+    }
+
     #[test]
     fn test_toml_parsing() {
         let history_with_persistence = r#"
@@ -1017,6 +1183,7 @@ disable_response_storage = true
                 codex_linux_sandbox_exe: None,
                 hide_agent_reasoning: false,
                 show_raw_agent_reasoning: false,
+                timestamp_zone: TimestampZone::Local,
                 model_reasoning_effort: ReasoningEffort::High,
                 model_reasoning_summary: ReasoningSummary::Detailed,
                 chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
@@ -1068,6 +1235,7 @@ disable_response_storage = true
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
             show_raw_agent_reasoning: false,
+            timestamp_zone: TimestampZone::Local,
             model_reasoning_effort: ReasoningEffort::default(),
             model_reasoning_summary: ReasoningSummary::default(),
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
@@ -1134,6 +1302,7 @@ disable_response_storage = true
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
             show_raw_agent_reasoning: false,
+            timestamp_zone: TimestampZone::Local,
             model_reasoning_effort: ReasoningEffort::default(),
             model_reasoning_summary: ReasoningSummary::default(),
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
@@ -1147,4 +1316,90 @@ disable_response_storage = true
 
         Ok(())
     }
+
+    #[test]
+    fn config_paths_expand_tilde() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg =
+            toml::from_str::<ConfigToml>(r#"experimental_resume = "~/sessions/rollout.jsonl""#)
+                .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        let home = home_dir().expect("home directory");
+        assert_eq!(
+            Some(PathBuf::from(format!(
+                "{}/sessions/rollout.jsonl",
+                home.to_string_lossy()
+            ))),
+            config.experimental_resume
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tui_log_paths_expand_tilde() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[tui]
+plain_log_path = "~/logs/conversation.log"
+reasoning_log_path = "~/logs/reasoning.log"
+
+[tui.event_tap]
+path = "~/events.sock"
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        let home = home_dir().expect("home directory");
+        let home = home.to_string_lossy();
+        assert_eq!(
+            Some(PathBuf::from(format!("{home}/logs/conversation.log"))),
+            config.tui.plain_log_path
+        );
+        assert_eq!(
+            Some(PathBuf::from(format!("{home}/logs/reasoning.log"))),
+            config.tui.reasoning_log_path
+        );
+        assert_eq!(
+            Some(PathBuf::from(format!("{home}/events.sock"))),
+            config.tui.event_tap.path
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn config_paths_with_undefined_variable_fail_to_load() {
+        let codex_home = TempDir::new().unwrap();
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"experimental_instructions_file = "${CODEX_TEST_UNDEFINED_VAR}/instructions.md""#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect_err("undefined variable should be an error");
+
+        assert_eq!(std::io::ErrorKind::NotFound, err.kind());
+        assert!(
+            err.to_string().contains(
+                "environment variable `CODEX_TEST_UNDEFINED_VAR` used in experimental_instructions_file is not set"
+            ),
+            "{err}"
+        );
+    }
 }
@@ -76,7 +76,639 @@ pub enum HistoryPersistence {
 
 /// Collection of settings that are specific to the TUI.
 #[derive(Deserialize, Debug, Clone, PartialEq, Default)]
-pub struct Tui {}
+pub struct Tui {
+    /// Rules used to answer exec approval requests without prompting.
+    #[serde(default)]
+    pub exec_policy: ExecPolicy,
+
+    /// Expand tabs in code blocks to this many columns. 0, the default,
+    /// leaves tabs for the terminal to render.
+    #[serde(default)]
+    pub tab_width: usize,
+
+    /// Render trailing whitespace in code blocks as dim `·` characters.
+    #[serde(default)]
+    pub show_trailing_whitespace: bool,
+
+    /// Shorten runs of blank lines in model output to at most this many
+    /// lines on screen. Code blocks are left alone. Off when unset.
+    #[serde(default)]
+    pub collapse_blank_lines: Option<usize>,
+
+    /// Also apply `collapse_blank_lines` to `/export`, which otherwise keeps
+    /// the original spacing.
+    #[serde(default)]
+    pub collapse_blank_lines_in_export: bool,
+
+    /// Fold a run of more than this many identical lines in model output
+    /// into one line marked `⟲ ×N`. Code blocks and `/export` keep every
+    /// line. Off when unset.
+    #[serde(default)]
+    pub collapse_repeated_lines: Option<usize>,
+
+    /// How the transcript view follows new output while it streams.
+    #[serde(default)]
+    pub scroll_policy: ScrollPolicy,
+
+    /// Show each block's anchor (for `/goto`) dimly next to its header in the
+    /// transcript view.
+    #[serde(default)]
+    pub show_anchors: bool,
+
+    /// Show at most this many lines of each reasoning block; earlier lines
+    /// are replaced by a marker. Unlimited when unset.
+    #[serde(default)]
+    pub max_reasoning_lines: Option<usize>,
+
+    /// Wrap history at most this many columns, even in a wider terminal.
+    /// Full width when unset. Can be changed at runtime with `/wrap`.
+    #[serde(default)]
+    pub max_wrap_columns: Option<u16>,
+
+    /// Alignment of prose paragraphs in model output. `full` needs
+    /// `max_wrap_columns` as its measure and is ragged without it.
+    #[serde(default)]
+    pub justify: Justify,
+
+    /// Show `$…$` and `$$…$$` TeX math in model output as its source in a
+    /// style of its own, instead of as prose.
+    #[serde(default)]
+    pub render_math: bool,
+
+    /// Shade every other body row of markdown tables.
+    #[serde(default)]
+    pub table_stripes: bool,
+
+    /// Background of the shaded table rows (a color name or `#rrggbb`).
+    /// Picked for the theme when unset.
+    #[serde(default)]
+    pub table_stripe_color: Option<String>,
+
+    /// Show `~~old~~ new` in model output as an inline change: the struck-out
+    /// text in red and its replacement in green.
+    #[serde(default)]
+    pub inline_diffs: bool,
+
+    /// How links in model output are shown: `text (url)` inline, or `text[n]`
+    /// with the URLs listed under the message.
+    #[serde(default)]
+    pub link_style: LinkStyle,
+
+    /// Whether backslash escapes in model output, such as `\*`, are honored
+    /// or shown as written.
+    #[serde(default)]
+    pub markdown_escapes: MarkdownEscapes,
+
+    /// Color theme for the TUI. Can be changed at runtime with `/theme`.
+    #[serde(default)]
+    pub theme: Theme,
+
+    /// Background of search matches in the transcript view (a color name
+    /// or `#rrggbb`). Picked for the theme when unset.
+    #[serde(default)]
+    pub search_match_color: Option<String>,
+
+    /// Background of the current search match; must differ from
+    /// `search_match_color` to be told apart. Picked for the theme when unset.
+    #[serde(default)]
+    pub search_current_color: Option<String>,
+
+    /// Text standing in for lines hidden by a collapsed or truncated block,
+    /// with `{n}` for their number and `{lines}` for "line" or "lines".
+    /// Defaults to `… {n} more {lines}`.
+    #[serde(default)]
+    pub truncation_indicator: Option<String>,
+
+    /// Color of the truncation indicator (a color name or `#rrggbb`). Dimmed
+    /// text when unset.
+    #[serde(default)]
+    pub truncation_indicator_color: Option<String>,
+
+    /// Stream answers as they arrive, or show them once the turn completes.
+    #[serde(default)]
+    pub stream_mode: StreamMode,
+
+    /// Key bindings of the composer.
+    #[serde(default)]
+    pub editing_mode: EditingMode,
+
+    /// Copy the agent's answer to the clipboard when each turn completes.
+    #[serde(default)]
+    pub auto_copy_answers: bool,
+
+    /// What to do with bidi overrides, zero-width and control characters in
+    /// model output before it is drawn.
+    #[serde(default)]
+    pub control_chars: ControlChars,
+
+    /// URL opened for `path:line` references in agent output. `{path}` is
+    /// replaced with the absolute path and `{line}` with the line number.
+    /// Defaults to the `file_opener` scheme.
+    #[serde(default)]
+    pub file_reference_url: Option<String>,
+
+    /// Decoration drawn around fenced code blocks.
+    #[serde(default)]
+    pub code_block_style: CodeBlockStyle,
+
+    /// How reasoning is shown while it streams.
+    #[serde(default)]
+    pub reasoning_display: ReasoningDisplay,
+
+    /// How the sections of a reasoning summary are set apart.
+    #[serde(default)]
+    pub reasoning_section_titles: ReasoningSectionTitles,
+
+    /// Show provisional answer text sent while reasoning streams, dimmed,
+    /// until the answer itself starts.
+    #[serde(default)]
+    pub answer_preview: bool,
+
+    /// Append a plain-text copy of each completed turn to this file.
+    #[serde(default)]
+    pub plain_log_path: Option<PathBuf>,
+
+    /// With `reasoning_display = "log-only"`, append reasoning to this file
+    /// instead of the TUI's log.
+    #[serde(default)]
+    pub reasoning_log_path: Option<PathBuf>,
+
+    /// Caret shown at the insertion point while an answer streams.
+    #[serde(default)]
+    pub streaming_caret: StreamingCaret,
+
+    /// How quiet reasoning's summary gives way to the answer.
+    #[serde(default)]
+    pub reasoning_transition: ReasoningTransition,
+
+    /// What is shown when the core compacts the conversation context.
+    #[serde(default)]
+    pub compaction_display: CompactionDisplay,
+
+    /// Resend the last message once a rate limit's backoff has elapsed.
+    #[serde(default)]
+    pub auto_retry_rate_limited: bool,
+
+    /// Send a prompt again when its turn fails with a transient error.
+    #[serde(default)]
+    pub retry: Retry,
+
+    /// Where the view starts when resuming a session (`experimental_resume`).
+    #[serde(default)]
+    pub resume_scroll: ResumeScroll,
+
+    /// What Enter does when the composer is empty or only whitespace.
+    #[serde(default)]
+    pub empty_submit: EmptySubmit,
+
+    /// What happens to a prompt submitted while a turn is running.
+    #[serde(default)]
+    pub concurrent_submit_policy: ConcurrentSubmitPolicy,
+
+    /// What Ctrl+C does: cancel the turn first, or quit at once.
+    #[serde(default)]
+    pub ctrl_c: CtrlCBehavior,
+
+    /// How long after a Ctrl+C a second one quits, in milliseconds.
+    /// Defaults to 2000.
+    #[serde(default)]
+    pub ctrl_c_quit_window_ms: Option<u64>,
+
+    /// Hold back a prompt whose text, with the attached file contents, is
+    /// longer than this many characters until it is submitted a second time.
+    #[serde(default)]
+    pub max_prompt_chars: Option<usize>,
+
+    /// Show a user prompt of more than this many lines as its first lines
+    /// and a count of the rest. The model receives it in full. Off when unset.
+    #[serde(default)]
+    pub collapse_prompt_lines: Option<usize>,
+
+    /// Open the transcript view in focus mode, which dims every turn but the
+    /// one in view. `f` toggles it.
+    #[serde(default)]
+    pub focus_mode: bool,
+
+    /// Show the active model and provider in a line above the composer.
+    #[serde(default)]
+    pub model_header: bool,
+
+    /// Below this many columns, use a compact layout without the model
+    /// header and streaming caret rows. Always the full layout when unset.
+    #[serde(default)]
+    pub full_layout_min_width: Option<u16>,
+
+    /// How the JSON arguments of tool calls are shown. The transcript view
+    /// toggles them with `a`.
+    #[serde(default)]
+    pub tool_arguments: ToolArguments,
+
+    /// Boilerplate closing sentences of answers to dim or strip on screen.
+    #[serde(default)]
+    pub sign_offs: SignOffs,
+
+    /// Which kinds of content the transcript view shows in full; the others
+    /// are folded to their first line.
+    #[serde(default)]
+    pub expand: Expand,
+
+    /// Stream the session event log to a local socket for external monitors.
+    #[serde(default)]
+    pub event_tap: EventTap,
+
+    /// Glyphs drawn before the `user`, `codex` and `thinking` headers.
+    #[serde(default)]
+    pub header_glyphs: HeaderGlyphs,
+
+    /// How prompts and answers are laid out in the history.
+    #[serde(default)]
+    pub message_layout: MessageLayout,
+
+    /// Widest a bubble of the `bubbles` layout gets, in columns. Defaults to
+    /// 72; never wider than the terminal.
+    #[serde(default)]
+    pub bubble_max_width: Option<u16>,
+}
+
+/// Glyphs prepended to history headers, e.g. `codex = "🤖"`. Glyphs are
+/// padded to the widest one, and headers of roles without a glyph are
+/// indented to match, so the labels line up.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct HeaderGlyphs {
+    #[serde(default)]
+    pub user: Option<String>,
+
+    #[serde(default)]
+    pub codex: Option<String>,
+
+    #[serde(default)]
+    pub thinking: Option<String>,
+}
+
+/// What Enter does with an empty composer.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum EmptySubmit {
+    /// Nothing is sent.
+    #[default]
+    Ignore,
+    /// Send the text of the latest prompt again, as a new turn.
+    RerunLast,
+}
+
+/// What a prompt submitted while a turn is running does.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConcurrentSubmitPolicy {
+    /// Send it; the agent takes it up once it can.
+    #[default]
+    Queue,
+    /// Keep it in the composer, with a note, and send nothing.
+    Reject,
+    /// Interrupt the running turn and start a new one with it.
+    AbortAndStart,
+}
+
+/// What Ctrl+C does.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum CtrlCBehavior {
+    /// Cancel the running turn, or close a dialog; a second Ctrl+C soon
+    /// after quits.
+    #[default]
+    CancelThenQuit,
+    /// Quit at once, even during a turn.
+    Quit,
+}
+
+/// Trailing sign-offs such as "Let me know if you need anything else.".
+/// Each pattern is a regular expression matched, ignoring case, against the
+/// whole last sentence of an answer.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct SignOffs {
+    #[serde(default)]
+    pub patterns: Vec<String>,
+
+    #[serde(default)]
+    pub action: SignOffAction,
+}
+
+/// What happens to an answer's last sentence when it matches a sign-off
+/// pattern.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SignOffAction {
+    /// Show it on a line of its own, dimmed.
+    #[default]
+    Dim,
+    /// Leave it out.
+    Strip,
+}
+
+/// Per-kind expand defaults of the transcript view, e.g. `prose = false`.
+/// A folded section shows its first line and the number of lines hidden.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Expand {
+    /// Fenced code blocks of answers.
+    pub code: bool,
+
+    /// Answer text between code blocks.
+    pub prose: bool,
+
+    /// Reasoning blocks, below their header.
+    pub reasoning: bool,
+
+    /// `<details>` sections of answers, below their summary.
+    pub details: bool,
+}
+
+impl Default for Expand {
+    fn default() -> Self {
+        Self {
+            code: true,
+            prose: true,
+            reasoning: true,
+            details: false,
+        }
+    }
+}
+
+/// Automatic retry of turns that fail with a transient error (a dropped
+/// stream, a network or server error), with exponential backoff.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Retry {
+    /// Retries per prompt; `0` turns retrying off.
+    pub max_attempts: u32,
+
+    /// Wait before the first retry, in milliseconds. Each further retry
+    /// waits twice as long as the one before.
+    pub base_delay_ms: u64,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Self {
+            max_attempts: 0,
+            base_delay_ms: 1000,
+        }
+    }
+}
+
+/// Time zone of displayed timestamps: `"local"`, `"utc"`, or an IANA name
+/// such as `"Europe/Berlin"`. Names are checked when the config is loaded.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(try_from = "String")]
+pub enum TimestampZone {
+    /// The system's local time zone.
+    #[default]
+    Local,
+    Utc,
+    /// A zone from the time zone database.
+    Named(String),
+}
+
+/// Handling of characters that can spoof text or corrupt the terminal
+/// layout (bidi overrides, zero-width spaces, C0/C1 controls).
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ControlChars {
+    /// Replace each with a visible `⟨U+XXXX⟩` marker.
+    #[default]
+    Mark,
+    /// Remove them.
+    Strip,
+    /// Draw them unchanged.
+    Keep,
+}
+
+/// Decoration of fenced code blocks in the TUI. Colors follow the theme.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum CodeBlockStyle {
+    #[default]
+    Plain,
+    /// A bar in the left column of every line.
+    Border,
+    /// A subtly different background behind the code.
+    Background,
+}
+
+/// Alignment of prose paragraphs in model output. Code blocks, tables and
+/// box art are never justified.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum Justify {
+    /// Left-aligned with a ragged right edge.
+    #[default]
+    Ragged,
+    /// Spaces between words are widened so every wrapped line but the last
+    /// of a paragraph reaches the wrap column.
+    Full,
+}
+
+/// How markdown links in model output are shown.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum LinkStyle {
+    /// `text (url)`, where the link appears.
+    #[default]
+    Inline,
+    /// `text[n]`, with the URLs numbered per message and listed under it.
+    Reference,
+}
+
+/// What a backslash escape in model output (`\*`, `\_`, `` \` ``) shows.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum MarkdownEscapes {
+    /// The escaped character, as plain text: `\*literal\*` shows `*literal*`.
+    #[default]
+    Honor,
+    /// The backslash and the character, as the model wrote them.
+    Show,
+}
+
+/// How keys edit the composer text.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum EditingMode {
+    /// A plain line editor with emacs-style shortcuts.
+    #[default]
+    Simple,
+    /// Modal editing with vim-like normal and insert modes.
+    Vim,
+}
+
+/// When reasoning and answers are written to the history.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum StreamMode {
+    /// Render output line by line as it streams in.
+    #[default]
+    Stream,
+    /// Hold all output and render it in one go when the turn completes.
+    WaitForComplete,
+}
+
+/// How the TUI shows the model's reasoning.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReasoningDisplay {
+    /// Stream the reasoning text into the history.
+    #[default]
+    Full,
+    /// Show only a "thinking…" indicator while reasoning streams, then a
+    /// one-line summary in the history.
+    Quiet,
+    /// Never show reasoning; write each completed block to
+    /// `reasoning_log_path`, or to the TUI's log when that is unset.
+    LogOnly,
+}
+
+/// How the sections of a streamed reasoning summary are set apart.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReasoningSectionTitles {
+    /// A blank line between sections, which are shown as the model wrote
+    /// them, including any title it gave them.
+    #[default]
+    Separator,
+    /// Each section is headed `Reasoning 1`, `Reasoning 2`, …
+    Numbered,
+    /// A blank line between sections, with the titles the model gave them
+    /// left out.
+    Hidden,
+}
+
+/// Caret drawn below the last streamed line of an answer.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum StreamingCaret {
+    /// A block caret that blinks while lines are being committed.
+    #[default]
+    Blink,
+    /// No caret.
+    Off,
+}
+
+/// How the one-line summary of quiet reasoning appears when the answer starts.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReasoningTransition {
+    /// The summary brightens above the composer for a few frames before it
+    /// moves into the history and the answer streams below it.
+    #[default]
+    Settle,
+    /// The summary is written to the history at once.
+    Off,
+}
+
+/// Initial scroll target after the history of a resumed session is shown.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ResumeScroll {
+    /// The latest output, as in a new session.
+    #[default]
+    Bottom,
+    /// The transcript view, with the last user prompt at the top.
+    LastUser,
+    /// The transcript view, at the start of the conversation.
+    Top,
+}
+
+/// How the arguments of a tool call are shown under its `tool` header.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ToolArguments {
+    /// Pretty-printed, one field per line.
+    #[default]
+    Expanded,
+    /// Compact on one line after the tool name, truncated.
+    Collapsed,
+}
+
+/// What the TUI shows when older context is replaced by a summary.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum CompactionDisplay {
+    /// A "context compacted" marker followed by the summary.
+    #[default]
+    Summary,
+    /// Only the marker.
+    Marker,
+}
+
+/// Local unix socket that streams the TUI's session events as JSON lines
+/// (the same `dir`/`kind`/`payload` records as the debug session log).
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct EventTap {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Socket path. Defaults to `tui-events.sock` in the log directory.
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+}
+
+/// Color theme of the TUI.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme {
+    /// Colors suited to dark terminal backgrounds.
+    #[default]
+    Dark,
+    /// Colors suited to light terminal backgrounds.
+    Light,
+    /// No colors; emphasis only.
+    Mono,
+}
+
+/// Layout of prompts and answers in the history.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum MessageLayout {
+    /// Each message under its `user` or `codex` header.
+    #[default]
+    Headers,
+    /// Each message in a bordered bubble: prompts on the right, answers on
+    /// the left.
+    Bubbles,
+}
+
+/// Auto-scroll behavior of the transcript view. Either way, scrolling up
+/// locks the view in place until you scroll back to the bottom.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScrollPolicy {
+    /// Bring each new reasoning or answer block to the top of the view as it
+    /// starts, then follow its tail once it outgrows the view.
+    #[default]
+    FollowActiveBlock,
+    /// Always keep the last line of output at the bottom of the view.
+    FollowBottom,
+}
+
+/// Command prefixes that are answered automatically when the agent asks for
+/// approval to run a command. Each entry is matched against the leading words
+/// of the command line, so `"git status"` matches `git status --short` but not
+/// `git statusx`. Deny rules take precedence over allow rules; commands that
+/// match neither list fall through to the interactive prompt.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ExecPolicy {
+    #[serde(default)]
+    pub allow: Vec<String>,
+
+    #[serde(default)]
+    pub deny: Vec<String>,
+
+    /// Commands that need an explicit `yes` before they run. Unlike `allow`
+    /// and `deny`, these match anywhere in the command line, so
+    /// `"rm -rf"` also flags `cd /tmp && rm -rf build`.
+    #[serde(default)]
+    pub dangerous: Vec<String>,
+}
 
 #[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default, Serialize)]
 #[serde(rename_all = "kebab-case")]
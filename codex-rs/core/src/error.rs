@@ -1,6 +1,7 @@
 use reqwest::StatusCode;
 use serde_json;
 use std::io;
+use std::time::Duration;
 use thiserror::Error;
 use tokio::task::JoinError;
 
@@ -77,6 +78,12 @@ pub enum CodexErr {
     #[error("exceeded retry limit, last status: {0}")]
     RetryLimit(StatusCode),
 
+    /// The provider kept answering 429 Too Many Requests until the retry
+    /// budget ran out. `retry_after` is its `Retry-After` hint, or our own
+    /// backoff when it sent none.
+    #[error("rate limited; retry in {}s", .retry_after.as_secs())]
+    RateLimited { retry_after: Duration },
+
     /// Agent loop died unexpectedly
     #[error("internal error; agent loop died unexpectedly")]
     InternalAgentDied,
@@ -166,6 +173,19 @@ impl CodexErr {
     pub fn downcast_ref<T: std::any::Any>(&self) -> Option<&T> {
         (self as &dyn std::any::Any).downcast_ref::<T>()
     }
+
+    /// Whether the same request may succeed if sent again later: the stream
+    /// dropped, the network failed, or the server had an error.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            CodexErr::Stream(_)
+            | CodexErr::InternalServerError
+            | CodexErr::RetryLimit(_)
+            | CodexErr::Reqwest(_) => true,
+            CodexErr::UnexpectedStatus(status, _) => status.is_server_error(),
+            _ => false,
+        }
+    }
 }
 
 pub fn get_error_message_ui(e: &CodexErr) -> String {
@@ -123,6 +123,10 @@ pub enum Op {
     /// Request a single history entry identified by `log_id` + `offset`.
     GetHistoryEntryRequest { offset: usize, log_id: u64 },
 
+    /// Request the body of the most recent request sent to the model. The
+    /// reply is a `LastRequest` event.
+    GetLastRequest,
+
     /// Request the agent to summarize the current conversation context.
     /// The agent will use its existing context (either conversation history or previous response id)
     /// to generate a summary which will be returned as an AgentMessage event.
@@ -372,6 +376,10 @@ pub enum EventMsg {
     /// Agent text output delta message
     AgentMessageDelta(AgentMessageDeltaEvent),
 
+    /// Provisional answer text streamed while the agent is still reasoning.
+    /// It is not part of the answer: the first `AgentMessageDelta` replaces it.
+    AgentMessagePreviewDelta(AgentMessagePreviewDeltaEvent),
+
     /// Reasoning event from agent.
     AgentReasoning(AgentReasoningEvent),
 
@@ -386,6 +394,10 @@ pub enum EventMsg {
     /// Signaled when the model begins a new reasoning summary section (e.g., a new titled block).
     AgentReasoningSectionBreak(AgentReasoningSectionBreakEvent),
 
+    /// The model stopped before finishing its response, e.g. because it hit
+    /// the output token limit. Everything streamed so far has been delivered.
+    ResponseTruncated(ResponseTruncatedEvent),
+
     /// Ack the client's configure message.
     SessionConfigured(SessionConfiguredEvent),
 
@@ -419,8 +431,18 @@ pub enum EventMsg {
     /// Response to GetHistoryEntryRequest.
     GetHistoryEntryResponse(GetHistoryEntryResponseEvent),
 
+    /// Response to GetLastRequest.
+    LastRequest(LastRequestEvent),
+
     PlanUpdate(UpdatePlanArgs),
 
+    /// Older context was replaced by a model-written summary (`/compact`).
+    ContextCompacted(ContextCompactedEvent),
+
+    /// The provider is rate limiting requests and the retry budget ran out;
+    /// the turn ended without a response.
+    RateLimited(RateLimitedEvent),
+
     /// Notification that the agent is shutting down.
     ShutdownComplete,
 }
@@ -430,6 +452,10 @@ pub enum EventMsg {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ErrorEvent {
     pub message: String,
+    /// The turn failed for a reason that may go away on its own (a dropped
+    /// stream, a network or server error), so sending it again may succeed.
+    #[serde(default)]
+    pub transient: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -517,6 +543,11 @@ pub struct AgentMessageDeltaEvent {
     pub delta: String,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AgentMessagePreviewDeltaEvent {
+    pub delta: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AgentReasoningEvent {
     pub text: String,
@@ -535,6 +566,13 @@ pub struct AgentReasoningRawContentDeltaEvent {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AgentReasoningSectionBreakEvent {}
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ResponseTruncatedEvent {
+    /// Why the response stopped early, as reported by the provider
+    /// (e.g. `max_output_tokens`).
+    pub reason: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AgentReasoningDeltaEvent {
     pub delta: String,
@@ -686,6 +724,27 @@ pub struct GetHistoryEntryResponseEvent {
     pub entry: Option<HistoryEntry>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ContextCompactedEvent {
+    /// The summary that replaced the conversation history; it is all the
+    /// model retains of the earlier turns.
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RateLimitedEvent {
+    /// How long the provider asked us to wait before trying again.
+    pub retry_after: Duration,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LastRequestEvent {
+    /// JSON body of the most recent model request with auth redacted, or
+    /// `None` if nothing has been sent to the model yet.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request: Option<serde_json::Value>,
+}
+
 #[derive(Debug, Default, Clone, Deserialize, Serialize)]
 pub struct SessionConfiguredEvent {
     /// Unique id for this session.
@@ -0,0 +1,85 @@
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use codex_core::Codex;
+use codex_core::CodexSpawnOk;
+use codex_core::ModelProviderInfo;
+use codex_core::built_in_model_providers;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::LastRequestEvent;
+use codex_core::protocol::Op;
+use codex_login::CodexAuth;
+use core_test_support::load_default_config_for_test;
+use core_test_support::load_sse_fixture_with_id;
+use core_test_support::wait_for_event;
+use tempfile::TempDir;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_last_request_returns_payload_of_previous_turn() {
+    let server = MockServer::start().await;
+    let sse = load_sse_fixture_with_id("tests/fixtures/completed_template.json", "resp");
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_raw(sse, "text/event-stream"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        ..built_in_model_providers()["openai"].clone()
+    };
+    let codex_home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&codex_home);
+    config.model_provider = model_provider;
+    let model = config.model.clone();
+
+    let ctrl_c = std::sync::Arc::new(tokio::sync::Notify::new());
+    let CodexSpawnOk { codex, .. } = Codex::spawn(
+        config,
+        Some(CodexAuth::from_api_key("Test API Key")),
+        ctrl_c.clone(),
+    )
+    .await
+    .unwrap();
+
+    // Before any turn there is nothing to show.
+    codex.submit(Op::GetLastRequest).await.unwrap();
+    let EventMsg::LastRequest(LastRequestEvent { request }) =
+        wait_for_event(&codex, |ev| matches!(ev, EventMsg::LastRequest(_))).await
+    else {
+        unreachable!();
+    };
+    assert_eq!(request, None);
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "show me the payload".into(),
+            }],
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    codex.submit(Op::GetLastRequest).await.unwrap();
+    let EventMsg::LastRequest(LastRequestEvent { request }) =
+        wait_for_event(&codex, |ev| matches!(ev, EventMsg::LastRequest(_))).await
+    else {
+        unreachable!();
+    };
+    let request = request.expect("request after a turn");
+    assert_eq!(request["model"], serde_json::json!(model));
+    let body = request.to_string();
+    assert!(body.contains("show me the payload"), "{body}");
+    assert!(!body.contains("Test API Key"), "{body}");
+}
@@ -88,7 +88,7 @@ async fn live_streaming_and_prev_id_reset() {
         match ev.msg {
             EventMsg::AgentMessage(_) => saw_message_before_complete = true,
             EventMsg::TaskComplete(_) => break,
-            EventMsg::Error(ErrorEvent { message }) => {
+            EventMsg::Error(ErrorEvent { message, .. }) => {
                 panic!("agent reported error in task1: {message}")
             }
             _ => {
@@ -126,7 +126,7 @@ async fn live_streaming_and_prev_id_reset() {
                 got_expected = true;
             }
             EventMsg::TaskComplete(_) => break,
-            EventMsg::Error(ErrorEvent { message }) => {
+            EventMsg::Error(ErrorEvent { message, .. }) => {
                 panic!("agent reported error in task2: {message}")
             }
             _ => {
@@ -192,7 +192,7 @@ async fn live_shell_function_call() {
                 saw_end_with_output = true;
             }
             EventMsg::TaskComplete(_) => break,
-            EventMsg::Error(codex_core::protocol::ErrorEvent { message }) => {
+            EventMsg::Error(codex_core::protocol::ErrorEvent { message, .. }) => {
                 panic!("agent error during shell test: {message}")
             }
             _ => {
@@ -1,6 +1,7 @@
 use codex_common::elapsed::format_duration;
 use codex_common::elapsed::format_elapsed;
 use codex_core::config::Config;
+use codex_core::config_types::TimestampZone;
 use codex_core::plan_tool::UpdatePlanArgs;
 use codex_core::protocol::AgentMessageDeltaEvent;
 use codex_core::protocol::AgentMessageEvent;
@@ -8,6 +9,7 @@ use codex_core::protocol::AgentReasoningDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::ContextCompactedEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
@@ -19,6 +21,8 @@ use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::RateLimitedEvent;
+use codex_core::protocol::ResponseTruncatedEvent;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TurnDiffEvent;
@@ -61,6 +65,8 @@ pub(crate) struct EventProcessorWithHumanOutput {
     reasoning_started: bool,
     raw_reasoning_started: bool,
     last_message_path: Option<PathBuf>,
+    /// Zone of the timestamp printed before each event.
+    timestamp_zone: TimestampZone,
 }
 
 impl EventProcessorWithHumanOutput {
@@ -89,6 +95,7 @@ impl EventProcessorWithHumanOutput {
                 reasoning_started: false,
                 raw_reasoning_started: false,
                 last_message_path,
+                timestamp_zone: config.timestamp_zone.clone(),
             }
         } else {
             Self {
@@ -107,6 +114,7 @@ impl EventProcessorWithHumanOutput {
                 reasoning_started: false,
                 raw_reasoning_started: false,
                 last_message_path,
+                timestamp_zone: config.timestamp_zone.clone(),
             }
         }
     }
@@ -121,17 +129,21 @@ struct PatchApplyBegin {
     auto_approved: bool,
 }
 
-// Timestamped println helper. The timestamp is styled with self.dimmed.
+// Timestamped println helper. The timestamp is in self.timestamp_zone and
+// styled with self.dimmed.
 #[macro_export]
 macro_rules! ts_println {
     ($self:ident, $($arg:tt)*) => {{
-        let now = chrono::Utc::now();
-        let formatted = now.format("[%Y-%m-%dT%H:%M:%S]");
+        let formatted = format_timestamp(chrono::Utc::now(), &$self.timestamp_zone);
         print!("{} ", formatted.style($self.dimmed));
         println!($($arg)*);
     }};
 }
 
+fn format_timestamp(at: chrono::DateTime<chrono::Utc>, zone: &TimestampZone) -> String {
+    zone.format(at, "[%Y-%m-%dT%H:%M:%S]")
+}
+
 impl EventProcessor for EventProcessorWithHumanOutput {
     /// Print a concise summary of the effective configuration that will be used
     /// for the session. This mirrors the information shown in the TUI welcome
@@ -166,13 +178,20 @@ impl EventProcessor for EventProcessorWithHumanOutput {
     fn process_event(&mut self, event: Event) -> CodexStatus {
         let Event { id: _, msg } = event;
         match msg {
-            EventMsg::Error(ErrorEvent { message }) => {
+            EventMsg::Error(ErrorEvent { message, .. }) => {
                 let prefix = "ERROR:".style(self.red);
                 ts_println!(self, "{prefix} {message}");
             }
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
                 ts_println!(self, "{}", message.style(self.dimmed));
             }
+            EventMsg::ResponseTruncated(ResponseTruncatedEvent { reason }) => {
+                ts_println!(
+                    self,
+                    "{}",
+                    format!("(response truncated: {reason})").style(self.dimmed)
+                );
+            }
             EventMsg::TaskStarted => {
                 // Ignore.
             }
@@ -519,7 +538,18 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 ts_println!(self, "explanation: {explanation:?}");
                 ts_println!(self, "plan: {plan:?}");
             }
-            EventMsg::GetHistoryEntryResponse(_) => {
+            EventMsg::ContextCompacted(ContextCompactedEvent { summary }) => {
+                ts_println!(self, "{}", "context compacted".style(self.dimmed));
+                println!("{}", summary.style(self.dimmed));
+            }
+            EventMsg::RateLimited(RateLimitedEvent { retry_after }) => {
+                let prefix = "RATE LIMITED:".style(self.red);
+                ts_println!(self, "{prefix} retry in {}", format_duration(retry_after));
+            }
+            EventMsg::AgentMessagePreviewDelta(_) => {
+                // Printed output cannot be taken back once the answer starts.
+            }
+            EventMsg::GetHistoryEntryResponse(_) | EventMsg::LastRequest(_) => {
                 // Currently ignored in exec output.
             }
             EventMsg::ShutdownComplete => return CodexStatus::Shutdown,
@@ -562,3 +592,26 @@ fn format_mcp_invocation(invocation: &McpInvocation) -> String {
         format!("{fq_tool_name}({args_str})")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn timestamps_use_the_configured_zone() {
+        let at: chrono::DateTime<chrono::Utc> = "2025-08-22T09:12:03Z".parse().unwrap();
+        let zone = |name: &str| TimestampZone::try_from(name.to_string()).unwrap();
+        assert_eq!(format_timestamp(at, &zone("utc")), "[2025-08-22T09:12:03]");
+        assert_eq!(
+            format_timestamp(at, &zone("Asia/Tokyo")),
+            "[2025-08-22T18:12:03]"
+        );
+        assert_eq!(
+            format_timestamp(at, &zone("America/New_York")),
+            "[2025-08-22T05:12:03]"
+        );
+    }
+}
@@ -15,6 +15,7 @@ use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::InputItem;
 use codex_core::protocol::Op;
+use codex_core::protocol::RateLimitedEvent;
 use codex_core::protocol::Submission;
 use codex_core::protocol::TaskCompleteEvent;
 use mcp_types::CallToolResult;
@@ -196,6 +197,16 @@ async fn run_codex_tool_session_inner(
                         outgoing.send_response(request_id.clone(), result).await;
                         break;
                     }
+                    EventMsg::RateLimited(RateLimitedEvent { retry_after }) => {
+                        // The turn ended without an answer, as with an error;
+                        // tell the caller how long the provider asked to wait.
+                        let result = json!({
+                            "error": "rate limited",
+                            "retry_after_secs": retry_after.as_secs_f64(),
+                        });
+                        outgoing.send_response(request_id.clone(), result).await;
+                        break;
+                    }
                     EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
                         call_id,
                         reason,
@@ -258,6 +269,8 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::TokenCount(_)
                     | EventMsg::AgentReasoning(_)
                     | EventMsg::AgentReasoningSectionBreak(_)
+                    | EventMsg::AgentMessagePreviewDelta(_)
+                    | EventMsg::ResponseTruncated(_)
                     | EventMsg::McpToolCallBegin(_)
                     | EventMsg::McpToolCallEnd(_)
                     | EventMsg::ExecCommandBegin(_)
@@ -268,7 +281,9 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::PatchApplyEnd(_)
                     | EventMsg::TurnDiff(_)
                     | EventMsg::GetHistoryEntryResponse(_)
+                    | EventMsg::LastRequest(_)
                     | EventMsg::PlanUpdate(_)
+                    | EventMsg::ContextCompacted(_)
                     | EventMsg::ShutdownComplete => {
                         // For now, we do not do anything extra for these
                         // events. Note that
@@ -96,6 +96,8 @@ pub async fn run_conversation_loop(
                     | EventMsg::TokenCount(_)
                     | EventMsg::AgentReasoning(_)
                     | EventMsg::AgentReasoningSectionBreak(_)
+                    | EventMsg::AgentMessagePreviewDelta(_)
+                    | EventMsg::ResponseTruncated(_)
                     | EventMsg::McpToolCallBegin(_)
                     | EventMsg::McpToolCallEnd(_)
                     | EventMsg::ExecCommandBegin(_)
@@ -106,7 +108,10 @@ pub async fn run_conversation_loop(
                     | EventMsg::PatchApplyBegin(_)
                     | EventMsg::PatchApplyEnd(_)
                     | EventMsg::GetHistoryEntryResponse(_)
+                    | EventMsg::LastRequest(_)
                     | EventMsg::PlanUpdate(_)
+                    | EventMsg::ContextCompacted(_)
+                    | EventMsg::RateLimited(_)
                     | EventMsg::ShutdownComplete => {
                         // For now, we do not do anything extra for these
                         // events. Note that
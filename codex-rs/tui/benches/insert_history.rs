@@ -0,0 +1,56 @@
+//! Throughput of `insert_history_lines_to_writer` on a recorded session, in
+//! lines and in bytes of ANSI output.
+//!
+//! Run with `cargo bench -p codex-tui --bench insert_history`.
+
+#![expect(clippy::expect_used)]
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use codex_tui::custom_terminal::Terminal;
+use codex_tui::insert_history::insert_history_lines_to_writer;
+use criterion::BatchSize;
+use criterion::Criterion;
+use criterion::Throughput;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use ratatui::backend::TestBackend;
+use ratatui::layout::Rect;
+use ratatui::text::Line;
+
+const WIDTH: u16 = 80;
+const HEIGHT: u16 = 24;
+
+fn terminal() -> Terminal<TestBackend> {
+    let mut terminal =
+        Terminal::with_options(TestBackend::new(WIDTH, HEIGHT)).expect("create terminal");
+    terminal.set_viewport_area(Rect::new(0, HEIGHT - 1, WIDTH, 1));
+    terminal
+}
+
+fn insert(lines: Vec<Line<'static>>) -> Vec<u8> {
+    let mut out = Vec::new();
+    insert_history_lines_to_writer(&mut terminal(), &mut out, lines);
+    out
+}
+
+fn insert_history(c: &mut Criterion) {
+    let lines = common::fixture_history_lines("longer-markdown-session.jsonl");
+    let ansi_bytes = insert(lines.clone()).len();
+    eprintln!("{} lines, {ansi_bytes} bytes of ANSI output", lines.len());
+
+    let mut group = c.benchmark_group("insert_history");
+    group.throughput(Throughput::Elements(lines.len() as u64));
+    group.bench_function("lines", |b| {
+        b.iter_batched(|| lines.clone(), insert, BatchSize::SmallInput)
+    });
+    group.throughput(Throughput::Bytes(ansi_bytes as u64));
+    group.bench_function("ansi_bytes", |b| {
+        b.iter_batched(|| lines.clone(), insert, BatchSize::SmallInput)
+    });
+    group.finish();
+}
+
+criterion_group!(benches, insert_history);
+criterion_main!(benches);
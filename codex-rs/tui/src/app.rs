@@ -1,15 +1,21 @@
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
+use crate::attachments::UserMessage;
 use crate::chatwidget::ChatWidget;
+use crate::chatwidget::InitialPrompt;
+use crate::file_reference::FileLinks;
 use crate::file_search::FileSearchManager;
+use crate::follow::LogFollower;
 use crate::get_git_diff::get_git_diff;
 use crate::onboarding::onboarding_screen::KeyboardHandler;
 use crate::onboarding::onboarding_screen::OnboardingScreen;
 use crate::onboarding::onboarding_screen::OnboardingScreenArgs;
+use crate::render::line_utils::strip_colors;
 use crate::should_show_login_screen;
 use crate::slash_command::SlashCommand;
 use crate::tui;
 use codex_core::config::Config;
+use codex_core::config_types::Theme;
 use codex_core::protocol::Event;
 use codex_core::protocol::Op;
 use color_eyre::eyre::Result;
@@ -17,9 +23,14 @@ use crossterm::SynchronizedUpdate;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
+use crossterm::execute;
+use crossterm::terminal::EnterAlternateScreen;
+use crossterm::terminal::LeaveAlternateScreen;
 use crossterm::terminal::supports_keyboard_enhancement;
 use ratatui::layout::Offset;
+use ratatui::layout::Rect;
 use ratatui::prelude::Backend;
+use ratatui::style::Color;
 use ratatui::text::Line;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -66,6 +77,10 @@ pub(crate) struct App<'a> {
 
     /// Controls the animation thread that sends CommitTick events.
     commit_anim_running: Arc<AtomicBool>,
+
+    /// Inline viewport to restore when the full-screen transcript view
+    /// closes; `Some` while the view is open.
+    transcript_view_saved_viewport: Option<Rect>,
 }
 
 /// Aggregate parameters needed to create a `ChatWidget`, as creation may be
@@ -73,16 +88,17 @@ pub(crate) struct App<'a> {
 #[derive(Clone, Debug)]
 pub(crate) struct ChatWidgetArgs {
     pub(crate) config: Config,
-    initial_prompt: Option<String>,
-    initial_images: Vec<PathBuf>,
+    initial_message: Option<UserMessage>,
+    initial_prompt: InitialPrompt,
     enhanced_keys_supported: bool,
 }
 
 impl App<'_> {
     pub(crate) fn new(
         config: Config,
-        initial_prompt: Option<String>,
-        initial_images: Vec<std::path::PathBuf>,
+        initial_message: Option<UserMessage>,
+        initial_prompt: InitialPrompt,
+        follower: Option<LogFollower>,
         show_trust_screen: bool,
     ) -> Self {
         let (app_event_tx, app_event_rx) = channel();
@@ -133,11 +149,21 @@ impl App<'_> {
         }
 
         let show_login_screen = should_show_login_screen(&config);
-        let app_state = if show_login_screen || show_trust_screen {
+        let app_state = if let Some(follower) = follower {
+            // A followed log needs neither a login nor a trusted directory.
+            follower.spawn(app_event_tx.clone());
+            AppState::Chat {
+                widget: Box::new(ChatWidget::new_follower(
+                    config.clone(),
+                    app_event_tx.clone(),
+                    enhanced_keys_supported,
+                )),
+            }
+        } else if show_login_screen || show_trust_screen {
             let chat_widget_args = ChatWidgetArgs {
                 config: config.clone(),
+                initial_message,
                 initial_prompt,
-                initial_images,
                 enhanced_keys_supported,
             };
             AppState::Onboarding {
@@ -154,10 +180,10 @@ impl App<'_> {
             let chat_widget = ChatWidget::new(
                 config.clone(),
                 app_event_tx.clone(),
-                initial_prompt,
-                initial_images,
+                initial_message,
                 enhanced_keys_supported,
-            );
+            )
+            .with_initial_prompt(initial_prompt);
             AppState::Chat {
                 widget: Box::new(chat_widget),
             }
@@ -174,6 +200,7 @@ impl App<'_> {
             pending_redraw,
             enhanced_keys_supported,
             commit_anim_running: Arc::new(AtomicBool::new(false)),
+            transcript_view_saved_viewport: None,
         }
     }
 
@@ -216,6 +243,11 @@ impl App<'_> {
                     self.pending_history_lines.extend(lines);
                     self.app_event_tx.send(AppEvent::RequestRedraw);
                 }
+                AppEvent::CopyToClipboard(text) => {
+                    if let Err(e) = crate::clipboard::copy_to_clipboard(&text) {
+                        tracing::error!("failed to copy to clipboard: {e}");
+                    }
+                }
                 AppEvent::RequestRedraw => {
                     self.schedule_redraw();
                 }
@@ -246,6 +278,19 @@ impl App<'_> {
                         widget.on_commit_tick();
                     }
                 }
+                AppEvent::FirstToken => {
+                    // The chat widget has already updated its status line.
+                }
+                AppEvent::RetryAfterRateLimit { limit } => {
+                    if let AppState::Chat { widget } = &mut self.app_state {
+                        widget.retry_after_rate_limit(limit);
+                    }
+                }
+                AppEvent::RetryAfterTransientError { attempt } => {
+                    if let AppState::Chat { widget } = &mut self.app_state {
+                        widget.retry_after_transient_error(attempt);
+                    }
+                }
                 AppEvent::KeyEvent(key_event) => {
                     match key_event {
                         KeyEvent {
@@ -273,12 +318,13 @@ impl App<'_> {
                             }
                             // No-op on non-Unix platforms.
                         }
+                        // In the transcript view, Ctrl+D scrolls down half a page.
                         KeyEvent {
                             code: KeyCode::Char('d'),
                             modifiers: crossterm::event::KeyModifiers::CONTROL,
                             kind: KeyEventKind::Press,
                             ..
-                        } => {
+                        } if self.transcript_view_saved_viewport.is_none() => {
                             match &mut self.app_state {
                                 AppState::Chat { widget } => {
                                     if widget.composer_is_empty() {
@@ -295,6 +341,28 @@ impl App<'_> {
                                 }
                             }
                         }
+                        KeyEvent {
+                            code: KeyCode::Char('t'),
+                            modifiers: crossterm::event::KeyModifiers::CONTROL,
+                            kind: KeyEventKind::Press,
+                            ..
+                        } => {
+                            if self.transcript_view_saved_viewport.is_some() {
+                                self.close_transcript_view(terminal)?;
+                            } else {
+                                self.open_transcript_view(terminal)?;
+                            }
+                        }
+                        KeyEvent {
+                            kind: KeyEventKind::Press | KeyEventKind::Repeat,
+                            ..
+                        } if self.transcript_view_saved_viewport.is_some() => {
+                            if let AppState::Chat { widget } = &mut self.app_state {
+                                if !widget.handle_transcript_key_event(key_event) {
+                                    self.close_transcript_view(terminal)?;
+                                }
+                            }
+                        }
                         KeyEvent {
                             kind: KeyEventKind::Press | KeyEventKind::Repeat,
                             ..
@@ -313,6 +381,7 @@ impl App<'_> {
                     self.dispatch_codex_event(event);
                 }
                 AppEvent::ExitRequest => {
+                    self.close_transcript_view(terminal)?;
                     break;
                 }
                 AppEvent::CodexOp(op) => match &mut self.app_state {
@@ -323,14 +392,13 @@ impl App<'_> {
                     AppState::Chat { widget } => widget.update_latest_log(line),
                     AppState::Onboarding { .. } => {}
                 },
-                AppEvent::DispatchCommand(command) => match command {
+                AppEvent::DispatchCommand(command, args) => match command {
                     SlashCommand::New => {
                         // User accepted – switch to chat view.
                         let new_widget = Box::new(ChatWidget::new(
                             self.config.clone(),
                             self.app_event_tx.clone(),
                             None,
-                            Vec::new(),
                             self.enhanced_keys_supported,
                         ));
                         self.app_state = AppState::Chat { widget: new_widget };
@@ -349,6 +417,16 @@ impl App<'_> {
                             self.app_event_tx.send(AppEvent::CodexOp(Op::Compact));
                         }
                     }
+                    SlashCommand::Summarize => {
+                        if let AppState::Chat { widget } = &mut self.app_state {
+                            widget.summarize_session();
+                        }
+                    }
+                    SlashCommand::Continue => {
+                        if let AppState::Chat { widget } = &mut self.app_state {
+                            widget.continue_response();
+                        }
+                    }
                     SlashCommand::Quit => {
                         break;
                     }
@@ -389,11 +467,78 @@ impl App<'_> {
                             widget.add_status_output();
                         }
                     }
+                    SlashCommand::Stats => {
+                        if let AppState::Chat { widget } = &mut self.app_state {
+                            widget.add_stats_output();
+                        }
+                    }
+                    SlashCommand::Model => {
+                        if let AppState::Chat { widget } = &mut self.app_state {
+                            if let Some(config) = widget.switch_model(&args) {
+                                self.config = config;
+                                let new_widget = Box::new(ChatWidget::new(
+                                    self.config.clone(),
+                                    self.app_event_tx.clone(),
+                                    None,
+                                    self.enhanced_keys_supported,
+                                ));
+                                self.app_state = AppState::Chat { widget: new_widget };
+                                self.app_event_tx.send(AppEvent::RequestRedraw);
+                            }
+                        }
+                    }
                     SlashCommand::Prompts => {
                         if let AppState::Chat { widget } = &mut self.app_state {
                             widget.add_prompts_output();
                         }
                     }
+                    SlashCommand::Title => {
+                        if let AppState::Chat { widget } = &mut self.app_state {
+                            widget.set_title(&args);
+                        }
+                    }
+                    SlashCommand::Export => {
+                        if let AppState::Chat { widget } = &mut self.app_state {
+                            widget.export_transcript(&args);
+                        }
+                    }
+                    SlashCommand::ExportTurn => {
+                        if let AppState::Chat { widget } = &mut self.app_state {
+                            widget.export_turn(&args);
+                        }
+                    }
+                    SlashCommand::CopyCommands => {
+                        if let AppState::Chat { widget } = &mut self.app_state {
+                            widget.copy_commands(&args);
+                        }
+                    }
+                    SlashCommand::ShowRequest => {
+                        if let AppState::Chat { widget } = &mut self.app_state {
+                            widget.submit_op(Op::GetLastRequest);
+                        }
+                    }
+                    SlashCommand::Goto => {
+                        if let AppState::Chat { widget } = &mut self.app_state {
+                            if widget.goto_anchor(&args) {
+                                self.open_transcript_view(terminal)?;
+                            }
+                        }
+                    }
+                    SlashCommand::Theme => {
+                        if let AppState::Chat { widget } = &mut self.app_state {
+                            widget.set_theme(&args);
+                        }
+                    }
+                    SlashCommand::Wrap => {
+                        if let AppState::Chat { widget } = &mut self.app_state {
+                            widget.set_wrap(&args);
+                        }
+                    }
+                    SlashCommand::ExecOutput => {
+                        if let AppState::Chat { widget } = &mut self.app_state {
+                            widget.set_exec_output(&args);
+                        }
+                    }
                     #[cfg(debug_assertions)]
                     SlashCommand::TestApproval => {
                         use codex_core::protocol::EventMsg;
@@ -443,17 +588,19 @@ impl App<'_> {
                 AppEvent::OnboardingComplete(ChatWidgetArgs {
                     config,
                     enhanced_keys_supported,
-                    initial_images,
+                    initial_message,
                     initial_prompt,
                 }) => {
                     self.app_state = AppState::Chat {
-                        widget: Box::new(ChatWidget::new(
-                            config,
-                            app_event_tx.clone(),
-                            initial_prompt,
-                            initial_images,
-                            enhanced_keys_supported,
-                        )),
+                        widget: Box::new(
+                            ChatWidget::new(
+                                config,
+                                app_event_tx.clone(),
+                                initial_message,
+                                enhanced_keys_supported,
+                            )
+                            .with_initial_prompt(initial_prompt),
+                        ),
                     }
                 }
                 AppEvent::StartFileSearch(query) => {
@@ -468,6 +615,7 @@ impl App<'_> {
                 }
             }
         }
+        self.shutdown(terminal)?;
         terminal.clear()?;
 
         Ok(())
@@ -475,6 +623,7 @@ impl App<'_> {
 
     #[cfg(unix)]
     fn suspend(&mut self, terminal: &mut tui::Tui) -> Result<()> {
+        self.close_transcript_view(terminal)?;
         tui::restore()?;
         // SAFETY: Unix-only code path. We intentionally send SIGTSTP to the
         // current process group (pid 0) to trigger standard job-control
@@ -490,6 +639,26 @@ impl App<'_> {
         Ok(())
     }
 
+    /// Let the chat widget write out what it has not written yet, such as
+    /// the tail of a streaming answer, and insert it into the scrollback
+    /// before the app exits.
+    fn shutdown(&mut self, terminal: &mut tui::Tui) -> Result<()> {
+        let AppState::Chat { widget } = &mut self.app_state else {
+            return Ok(());
+        };
+        widget.shutdown();
+        self.close_transcript_view(terminal)?;
+        while let Ok(event) = self.app_event_rx.try_recv() {
+            if let AppEvent::InsertHistory(lines) = event {
+                self.pending_history_lines.extend(lines);
+            }
+        }
+        if self.pending_history_lines.is_empty() {
+            return Ok(());
+        }
+        std::io::stdout().sync_update(|_| self.draw_next_frame(terminal))?
+    }
+
     pub(crate) fn token_usage(&self) -> codex_core::protocol::TokenUsage {
         match &self.app_state {
             AppState::Chat { widget } => widget.token_usage().clone(),
@@ -497,7 +666,59 @@ impl App<'_> {
         }
     }
 
+    /// Switch to the alternate screen and show the full transcript there.
+    /// History keeps accumulating and is inserted into the normal scrollback
+    /// once the view closes.
+    fn open_transcript_view(&mut self, terminal: &mut tui::Tui) -> Result<()> {
+        if !matches!(self.app_state, AppState::Chat { .. }) {
+            return Ok(());
+        }
+        execute!(std::io::stdout(), EnterAlternateScreen)?;
+        self.transcript_view_saved_viewport = Some(terminal.viewport_area);
+        if let AppState::Chat { widget } = &mut self.app_state {
+            widget.set_transcript_view_open(true);
+        }
+        self.app_event_tx.send(AppEvent::RequestRedraw);
+        Ok(())
+    }
+
+    fn close_transcript_view(&mut self, terminal: &mut tui::Tui) -> Result<()> {
+        let Some(viewport) = self.transcript_view_saved_viewport.take() else {
+            return Ok(());
+        };
+        execute!(std::io::stdout(), LeaveAlternateScreen)?;
+        terminal.set_viewport_area(viewport);
+        if let AppState::Chat { widget } = &mut self.app_state {
+            widget.set_transcript_view_open(false);
+        }
+        terminal.clear()?;
+        self.app_event_tx.send(AppEvent::RequestRedraw);
+        Ok(())
+    }
+
+    fn draw_transcript_view(&mut self, terminal: &mut tui::Tui) -> Result<()> {
+        let size = terminal.size()?;
+        let area = Rect::new(0, 0, size.width, size.height);
+        if area != terminal.viewport_area {
+            terminal.set_viewport_area(area);
+            terminal.clear()?;
+        }
+        if let AppState::Chat { widget } = &mut self.app_state {
+            widget.set_viewport(size);
+        }
+        terminal.draw(|frame| {
+            if let AppState::Chat { widget } = &self.app_state {
+                let area = frame.area();
+                widget.render_transcript(area, frame.buffer_mut());
+            }
+        })?;
+        Ok(())
+    }
+
     fn draw_next_frame(&mut self, terminal: &mut tui::Tui) -> Result<()> {
+        if self.transcript_view_saved_viewport.is_some() {
+            return self.draw_transcript_view(terminal);
+        }
         let screen_size = terminal.size()?;
         let last_known_screen_size = terminal.last_known_screen_size;
         if screen_size != last_known_screen_size {
@@ -519,6 +740,9 @@ impl App<'_> {
         }
 
         let size = terminal.size()?;
+        if let AppState::Chat { widget } = &mut self.app_state {
+            widget.set_viewport(size);
+        }
         let desired_height = match &self.app_state {
             AppState::Chat { widget } => widget.desired_height(size.width),
             AppState::Onboarding { .. } => size.height,
@@ -537,22 +761,45 @@ impl App<'_> {
             terminal.clear()?;
             terminal.set_viewport_area(area);
         }
+        let mono = match &self.app_state {
+            AppState::Chat { widget } => widget.theme() == Theme::Mono,
+            AppState::Onboarding { .. } => self.config.tui.theme == Theme::Mono,
+        };
         if !self.pending_history_lines.is_empty() {
-            crate::insert_history::insert_history_lines(
-                terminal,
-                self.pending_history_lines.clone(),
-            );
-            self.pending_history_lines.clear();
+            let max_width = match &self.app_state {
+                AppState::Chat { widget } => widget.max_wrap_columns(),
+                AppState::Onboarding { .. } => None,
+            };
+            let mut lines = std::mem::take(&mut self.pending_history_lines);
+            if mono {
+                strip_colors(&mut lines);
+            }
+            let links = FileLinks::from_config(&self.config);
+            crate::insert_history::insert_history_lines(terminal, lines, max_width, links.as_ref());
         }
-        terminal.draw(|frame| match &mut self.app_state {
-            AppState::Chat { widget } => {
-                if let Some((x, y)) = widget.cursor_pos(frame.area()) {
-                    frame.set_cursor_position((x, y));
+        terminal.draw(|frame| {
+            match &mut self.app_state {
+                AppState::Chat { widget } => {
+                    if let Some((x, y)) = widget.cursor_pos(frame.area()) {
+                        frame.set_cursor_position((x, y));
+                    }
+                    frame.render_widget_ref(&**widget, frame.area())
+                }
+                AppState::Onboarding { screen } => frame.render_widget_ref(&*screen, frame.area()),
+            }
+            if mono {
+                for cell in &mut frame.buffer_mut().content {
+                    cell.set_fg(Color::Reset).set_bg(Color::Reset);
                 }
-                frame.render_widget_ref(&**widget, frame.area())
             }
-            AppState::Onboarding { screen } => frame.render_widget_ref(&*screen, frame.area()),
         })?;
+        let open_transcript = match &mut self.app_state {
+            AppState::Chat { widget } => widget.take_resume_scroll(),
+            AppState::Onboarding { .. } => false,
+        };
+        if open_transcript {
+            self.open_transcript_view(terminal)?;
+        }
         Ok(())
     }
 
@@ -33,8 +33,9 @@ pub(crate) enum AppEvent {
     LatestLog(String),
 
     /// Dispatch a recognized slash command from the UI (composer) to the app
-    /// layer so it can be handled centrally.
-    DispatchCommand(SlashCommand),
+    /// layer so it can be handled centrally, along with any text typed after
+    /// the command name (trimmed; empty when there is none).
+    DispatchCommand(SlashCommand, String),
 
     /// Kick off an asynchronous file search for the given query (text after
     /// the `@`). Previous searches may be cancelled by the app layer so there
@@ -51,6 +52,26 @@ pub(crate) enum AppEvent {
 
     InsertHistory(Vec<Line<'static>>),
 
+    /// Copy text to the system clipboard via the terminal.
+    CopyToClipboard(String),
+
+    /// The backoff after rate limit `limit` has elapsed; resend the last
+    /// message unless a newer prompt replaced it or the retry was cancelled.
+    RetryAfterRateLimit {
+        limit: u32,
+    },
+
+    /// The backoff of retry `attempt` after a transient error has elapsed;
+    /// resend the last message unless a newer prompt replaced it.
+    RetryAfterTransientError {
+        attempt: u32,
+    },
+
+    /// The first model output (reasoning or answer) of the running task
+    /// arrived. Sent once per task, after `TaskStarted` and before any of
+    /// that output is inserted into the history.
+    FirstToken,
+
     StartCommitAnimation,
     StopCommitAnimation,
     CommitTick,
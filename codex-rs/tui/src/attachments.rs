@@ -0,0 +1,204 @@
+//! Submitted prompts and their attachments.
+//!
+//! Any whitespace-delimited token of the form `@<path>` that resolves to a
+//! readable text file (relative to the session cwd) has its contents sent to
+//! the model alongside the prompt. A token naming nothing on disk is only
+//! reported when it reads like a path, so mentions like `@alice` stay plain
+//! text. Images given on the command line are sent
+//! as they are. History only shows a compact marker for each.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::protocol::InputItem;
+
+/// Files larger than this are not attached.
+const MAX_ATTACHMENT_BYTES: u64 = 256 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Attachment {
+    /// The file was read and will be sent to the model.
+    Attached { path: String, contents: String },
+    /// The token looked like an attachment but could not be attached.
+    Skipped { path: String, reason: &'static str },
+    /// An image file, sent to the model as an image.
+    Image { path: PathBuf },
+}
+
+impl Attachment {
+    /// Marker rendered in the user block in place of the file contents.
+    pub(crate) fn marker(&self) -> String {
+        match self {
+            Attachment::Attached { path, contents } => {
+                let lines = contents.lines().count();
+                let unit = if lines == 1 { "line" } else { "lines" };
+                format!("attached: {path} ({lines} {unit})")
+            }
+            Attachment::Skipped { path, reason } => format!("not attached: {path} ({reason})"),
+            Attachment::Image { path } => format!("image: {}", path.display()),
+        }
+    }
+
+    /// Request part sent to the model for this attachment, if any.
+    pub(crate) fn to_input_item(&self) -> Option<InputItem> {
+        match self {
+            Attachment::Attached { path, contents } => Some(InputItem::Text {
+                text: format!("<attached_file path=\"{path}\">\n{contents}\n</attached_file>"),
+            }),
+            Attachment::Skipped { .. } => None,
+            Attachment::Image { path } => Some(InputItem::LocalImage { path: path.clone() }),
+        }
+    }
+}
+
+/// A prompt as submitted: its text and what is sent along with it.
+#[derive(Debug, Clone)]
+pub(crate) struct UserMessage {
+    pub(crate) text: String,
+    pub(crate) attachments: Vec<Attachment>,
+}
+
+impl UserMessage {
+    pub(crate) fn new(text: String, images: Vec<PathBuf>) -> Self {
+        Self {
+            text,
+            attachments: images
+                .into_iter()
+                .map(|path| Attachment::Image { path })
+                .collect(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.text.is_empty() && self.attachments.is_empty()
+    }
+
+    /// Attach the files named by `@path` tokens in the text, resolved against
+    /// `cwd`, ahead of the attachments given with the message. Files are only
+    /// read here, once the prompt is actually submitted.
+    pub(crate) fn resolve_files(&mut self, cwd: &Path) {
+        let files = collect_attachments(&self.text, cwd);
+        self.attachments.splice(0..0, files);
+    }
+
+    /// The parts of the request: the text, then every attachment that is sent.
+    pub(crate) fn to_input_items(&self) -> Vec<InputItem> {
+        let text = (!self.text.is_empty()).then(|| InputItem::Text {
+            text: self.text.clone(),
+        });
+        text.into_iter()
+            .chain(
+                self.attachments
+                    .iter()
+                    .filter_map(Attachment::to_input_item),
+            )
+            .collect()
+    }
+}
+
+impl From<String> for UserMessage {
+    fn from(text: String) -> Self {
+        Self::new(text, Vec::new())
+    }
+}
+
+/// Resolve every `@path` token in `text` against `cwd`. Tokens are reported
+/// once each, in order of first appearance.
+pub(crate) fn collect_attachments(text: &str, cwd: &Path) -> Vec<Attachment> {
+    let mut seen: Vec<&str> = Vec::new();
+    let mut out = Vec::new();
+    for token in text.split_whitespace() {
+        let Some(path) = token.strip_prefix('@') else {
+            continue;
+        };
+        if path.is_empty() || seen.contains(&path) {
+            continue;
+        }
+        seen.push(path);
+        out.extend(read_attachment(path, cwd));
+    }
+    out
+}
+
+/// Whether a token that names nothing on disk was still meant as a path: it
+/// has a directory separator or a file extension.
+fn looks_like_path(path: &str) -> bool {
+    path.contains(['/', '\\'])
+        || Path::new(path)
+            .extension()
+            .is_some_and(|ext| !ext.is_empty())
+}
+
+fn read_attachment(path: &str, cwd: &Path) -> Option<Attachment> {
+    let skipped = |reason| {
+        Some(Attachment::Skipped {
+            path: path.to_string(),
+            reason,
+        })
+    };
+    let full = cwd.join(path);
+    let Ok(metadata) = std::fs::metadata(&full) else {
+        return if looks_like_path(path) {
+            skipped("not found")
+        } else {
+            None
+        };
+    };
+    if !metadata.is_file() {
+        return skipped("not a file");
+    }
+    if metadata.len() > MAX_ATTACHMENT_BYTES {
+        return skipped("too large");
+    }
+    let Ok(bytes) = std::fs::read(&full) else {
+        return skipped("unreadable");
+    };
+    if bytes.contains(&0) {
+        return skipped("binary");
+    }
+    match String::from_utf8(bytes) {
+        Ok(contents) => Some(Attachment::Attached {
+            path: path.to_string(),
+            contents,
+        }),
+        Err(_) => skipped("binary"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn collects_text_files_and_skips_missing_and_binary() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let dir = tmp.path();
+        std::fs::write(dir.join("notes.txt"), "one\ntwo\n").unwrap();
+        std::fs::write(dir.join("blob.bin"), [0u8, 159, 146, 150]).unwrap();
+
+        let got = collect_attachments(
+            "see @notes.txt and @missing.rs plus @blob.bin and @notes.txt again",
+            dir,
+        );
+        assert_eq!(
+            got.iter().map(Attachment::marker).collect::<Vec<_>>(),
+            vec![
+                "attached: notes.txt (2 lines)",
+                "not attached: missing.rs (not found)",
+                "not attached: blob.bin (binary)",
+            ]
+        );
+    }
+
+    #[test]
+    fn mentions_that_are_not_paths_are_left_alone() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let got = collect_attachments("thanks @alice, ping @bob. and @src/gone", tmp.path());
+        assert_eq!(
+            got.iter().map(Attachment::marker).collect::<Vec<_>>(),
+            vec!["not attached: src/gone (not found)"]
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use codex_core::config_types::EditingMode;
 use codex_core::protocol::TokenUsage;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
@@ -32,6 +33,7 @@ use codex_file_search::FileMatch;
 use std::cell::RefCell;
 
 const BASE_PLACEHOLDER_TEXT: &str = "Ask Codex to do anything";
+const READ_ONLY_PLACEHOLDER_TEXT: &str = "Following a session log; input is disabled";
 /// If the pasted content exceeds this number of characters, replace it with a
 /// placeholder in the UI.
 const LARGE_PASTE_CHAR_THRESHOLD: usize = 1000;
@@ -39,6 +41,8 @@ const LARGE_PASTE_CHAR_THRESHOLD: usize = 1000;
 /// Result returned when the user interacts with the text area.
 pub enum InputResult {
     Submitted(String),
+    /// Enter was pressed with nothing but whitespace in the composer.
+    SubmittedEmpty,
     None,
 }
 
@@ -61,6 +65,20 @@ pub(crate) struct ChatComposer {
     pending_pastes: Vec<(String, String)>,
     token_usage_info: Option<TokenUsageInfo>,
     has_focus: bool,
+    /// Set while following a session log, when nothing typed would be sent.
+    read_only: bool,
+    /// Modal editing state; `None` for the simple editor.
+    vim: Option<VimMode>,
+}
+
+/// Mode of the vim-like editor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VimMode {
+    Insert,
+    /// `pending_delete` is set after a first `d`, waiting for the second.
+    Normal {
+        pending_delete: bool,
+    },
 }
 
 /// Popup state – at most one can be visible at any time.
@@ -91,9 +109,18 @@ impl ChatComposer {
             pending_pastes: Vec::new(),
             token_usage_info: None,
             has_focus: has_input_focus,
+            read_only: false,
+            vim: None,
         }
     }
 
+    pub(crate) fn set_editing_mode(&mut self, mode: EditingMode) {
+        self.vim = match mode {
+            EditingMode::Simple => None,
+            EditingMode::Vim => Some(VimMode::Insert),
+        };
+    }
+
     pub fn desired_height(&self, width: u16) -> u16 {
         self.textarea.desired_height(width - 1)
             + match &self.active_popup {
@@ -193,6 +220,13 @@ impl ChatComposer {
         }
     }
 
+    /// Draw the composer unfocused, with a placeholder and hints saying it
+    /// takes no input.
+    pub(crate) fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+        self.set_has_focus(!read_only);
+    }
+
     pub fn set_ctrl_c_quit_hint(&mut self, show: bool, has_focus: bool) {
         self.ctrl_c_quit_hint = show;
         self.set_has_focus(has_focus);
@@ -265,8 +299,21 @@ impl ChatComposer {
                 ..
             } => {
                 if let Some(cmd) = popup.selected_command() {
+                    // Arguments are only taken when the command name was typed
+                    // in full, e.g. `/goto 1a2b3c`.
+                    let args = self
+                        .textarea
+                        .text()
+                        .trim_start()
+                        .strip_prefix('/')
+                        .and_then(|rest| rest.strip_prefix(cmd.command()))
+                        .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+                        .map(|rest| rest.trim().to_string())
+                        .unwrap_or_default();
+
                     // Send command to the app layer.
-                    self.app_event_tx.send(AppEvent::DispatchCommand(*cmd));
+                    self.app_event_tx
+                        .send(AppEvent::DispatchCommand(*cmd, args));
 
                     // Clear textarea so no residual text remains.
                     self.textarea.set_text("");
@@ -476,6 +523,20 @@ impl ChatComposer {
 
     /// Handle key event when no popup is visible.
     fn handle_key_event_without_popup(&mut self, key_event: KeyEvent) -> (InputResult, bool) {
+        match (self.vim, key_event.code) {
+            (Some(VimMode::Insert), KeyCode::Esc) => {
+                self.vim = Some(VimMode::Normal {
+                    pending_delete: false,
+                });
+                return (InputResult::None, true);
+            }
+            // Submission and history recall work the same in both modes.
+            (Some(VimMode::Normal { .. }), KeyCode::Up | KeyCode::Down | KeyCode::Enter) => {}
+            (Some(VimMode::Normal { pending_delete }), _) => {
+                return self.handle_vim_normal_key(key_event, pending_delete);
+            }
+            _ => {}
+        }
         match key_event {
             // -------------------------------------------------------------
             // History navigation (Up / Down) – only when the composer is not
@@ -519,9 +580,12 @@ impl ChatComposer {
                 }
                 self.pending_pastes.clear();
 
-                if text.is_empty() {
-                    (InputResult::None, true)
+                if text.trim().is_empty() {
+                    (InputResult::SubmittedEmpty, true)
                 } else {
+                    if self.vim.is_some() {
+                        self.vim = Some(VimMode::Insert);
+                    }
                     self.history.record_local_submission(&text);
                     (InputResult::Submitted(text), true)
                 }
@@ -530,6 +594,81 @@ impl ChatComposer {
         }
     }
 
+    /// Handle a key in vim normal mode: motions, `x`, `dd`, and the keys that
+    /// return to insert mode. Other keys are ignored rather than inserted.
+    fn handle_vim_normal_key(
+        &mut self,
+        key_event: KeyEvent,
+        pending_delete: bool,
+    ) -> (InputResult, bool) {
+        let mut next = VimMode::Normal {
+            pending_delete: false,
+        };
+        let KeyEvent {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
+            ..
+        } = key_event
+        else {
+            self.vim = Some(next);
+            return (InputResult::None, false);
+        };
+        let at_line_end = self.textarea.text()[self.textarea.cursor()..]
+            .chars()
+            .next()
+            .is_none_or(|ch| ch == '\n');
+        match (pending_delete, c) {
+            (true, 'd') => self.textarea.delete_current_line(),
+            (false, 'd') => {
+                next = VimMode::Normal {
+                    pending_delete: true,
+                }
+            }
+            (_, 'i') => next = VimMode::Insert,
+            (_, 'a') => {
+                if !at_line_end {
+                    self.textarea.move_cursor_right();
+                }
+                next = VimMode::Insert;
+            }
+            (_, 'I') => {
+                self.textarea.move_cursor_to_beginning_of_line(false);
+                next = VimMode::Insert;
+            }
+            (_, 'A') => {
+                self.textarea.move_cursor_to_end_of_line(false);
+                next = VimMode::Insert;
+            }
+            (_, 'h') => self.textarea.move_cursor_left(),
+            (_, 'l') => {
+                if !at_line_end {
+                    self.textarea.move_cursor_right();
+                }
+            }
+            (_, 'j') => self.textarea.move_cursor_down(),
+            (_, 'k') => self.textarea.move_cursor_up(),
+            (_, 'w') => self
+                .textarea
+                .set_cursor(self.textarea.beginning_of_next_word()),
+            (_, 'b') => self
+                .textarea
+                .set_cursor(self.textarea.beginning_of_previous_word()),
+            (_, '0') => self.textarea.move_cursor_to_beginning_of_line(false),
+            (_, '$') => self.textarea.move_cursor_to_end_of_line(false),
+            (_, 'x') => {
+                if !at_line_end {
+                    self.textarea.delete_forward(1);
+                }
+            }
+            _ => {}
+        }
+        self.vim = Some(next);
+        let text_after = self.textarea.text();
+        self.pending_pastes
+            .retain(|(placeholder, _)| text_after.contains(placeholder));
+        (InputResult::None, true)
+    }
+
     /// Handle generic Input events that modify the textarea content.
     fn handle_input_basic(&mut self, input: KeyEvent) -> (InputResult, bool) {
         // Special handling for backspace on placeholders
@@ -682,6 +821,12 @@ impl WidgetRef for &ChatComposer {
                         "Ctrl+C again".set_style(key_hint_style),
                         Span::from(" to quit"),
                     ]
+                } else if self.read_only {
+                    vec![
+                        Span::from(" "),
+                        "Ctrl+C".set_style(key_hint_style),
+                        Span::from(" quit"),
+                    ]
                 } else {
                     let newline_hint_key = if self.use_shift_enter_hint {
                         "Shift+⏎"
@@ -699,6 +844,15 @@ impl WidgetRef for &ChatComposer {
                     ]
                 };
 
+                let mode = match self.vim {
+                    Some(VimMode::Insert) => Some("INSERT"),
+                    Some(VimMode::Normal { .. }) => Some("NORMAL"),
+                    None => None,
+                };
+                if let Some(mode) = mode {
+                    hint.insert(0, Span::from(format!(" {mode} ")).bold());
+                }
+
                 // Append token/context usage info to the footer hints when available.
                 if let Some(token_usage_info) = &self.token_usage_info {
                     let token_usage = &token_usage_info.total_token_usage;
@@ -751,7 +905,12 @@ impl WidgetRef for &ChatComposer {
         let mut state = self.textarea_state.borrow_mut();
         StatefulWidgetRef::render_ref(&(&self.textarea), textarea_rect, buf, &mut state);
         if self.textarea.text().is_empty() {
-            Line::from(BASE_PLACEHOLDER_TEXT)
+            let placeholder = if self.read_only {
+                READ_ONLY_PLACEHOLDER_TEXT
+            } else {
+                BASE_PLACEHOLDER_TEXT
+            };
+            Line::from(placeholder)
                 .style(Style::default().dim())
                 .render_ref(textarea_rect.inner(Margin::new(1, 0)), buf);
         }
@@ -1070,13 +1229,15 @@ mod tests {
             InputResult::Submitted(text) => {
                 panic!("expected command dispatch, but composer submitted literal text: {text}")
             }
+            InputResult::SubmittedEmpty => panic!("expected command dispatch, got an empty submit"),
         }
         assert!(composer.textarea.is_empty(), "composer should be cleared");
 
         // Verify a DispatchCommand event for the "init" command was sent.
         match rx.try_recv() {
-            Ok(AppEvent::DispatchCommand(cmd)) => {
+            Ok(AppEvent::DispatchCommand(cmd, args)) => {
                 assert_eq!(cmd.command(), "init");
+                assert_eq!(args, "");
             }
             Ok(_other) => panic!("unexpected app event"),
             Err(TryRecvError::Empty) => panic!("expected a DispatchCommand event for '/init'"),
@@ -1107,11 +1268,12 @@ mod tests {
             InputResult::Submitted(text) => {
                 panic!("expected command dispatch, but composer submitted literal text: {text}")
             }
+            InputResult::SubmittedEmpty => panic!("expected command dispatch, got an empty submit"),
         }
         assert!(composer.textarea.is_empty(), "composer should be cleared");
 
         match rx.try_recv() {
-            Ok(AppEvent::DispatchCommand(cmd)) => {
+            Ok(AppEvent::DispatchCommand(cmd, _)) => {
                 assert_eq!(cmd.command(), "mention");
                 composer.insert_str("@");
             }
@@ -1307,4 +1469,51 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn vim_normal_mode_dd_clears_line_and_i_returns_to_insert() {
+        use codex_core::config_types::EditingMode;
+        use crossterm::event::KeyCode;
+        use crossterm::event::KeyEvent;
+        use crossterm::event::KeyModifiers;
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(true, sender, false);
+        composer.set_editing_mode(EditingMode::Vim);
+        let type_keys = |composer: &mut ChatComposer, keys: &str| {
+            for c in keys.chars() {
+                composer.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+            }
+        };
+        let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+
+        type_keys(&mut composer, "first draft");
+        composer.handle_key_event(esc);
+        // Motions in normal mode move the cursor without inserting.
+        type_keys(&mut composer, "0w");
+        assert_eq!(composer.textarea.cursor(), 6);
+        assert_eq!(composer.textarea.text(), "first draft");
+
+        type_keys(&mut composer, "dd");
+        assert_eq!(composer.textarea.text(), "");
+
+        type_keys(&mut composer, "ihello");
+        assert_eq!(composer.textarea.text(), "hello");
+
+        // Submitting from normal mode works and leaves the editor in insert
+        // mode for the next prompt.
+        composer.handle_key_event(esc);
+        let (result, _) = composer.handle_key_event(enter);
+        assert!(matches!(result, InputResult::Submitted(text) if text == "hello"));
+        type_keys(&mut composer, "x");
+        assert_eq!(composer.textarea.text(), "x");
+
+        // History recall still works from normal mode.
+        composer.handle_key_event(esc);
+        type_keys(&mut composer, "dd");
+        composer.handle_key_event(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE));
+        assert_eq!(composer.textarea.text(), "hello");
+    }
 }
@@ -4,6 +4,7 @@ use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
 use crate::user_approval_widget::ApprovalRequest;
 use bottom_pane_view::BottomPaneView;
+use codex_core::config_types::EditingMode;
 use codex_core::protocol::TokenUsage;
 use codex_file_search::FileMatch;
 use crossterm::event::KeyEvent;
@@ -86,6 +87,18 @@ impl BottomPane<'_> {
         }
     }
 
+    pub(crate) fn set_editing_mode(&mut self, mode: EditingMode) {
+        self.composer.set_editing_mode(mode);
+    }
+
+    /// Show the composer as taking no input, for a widget that only follows
+    /// a session log.
+    pub(crate) fn set_read_only(&mut self, read_only: bool) {
+        self.has_input_focus = !read_only;
+        self.composer.set_read_only(read_only);
+        self.request_redraw();
+    }
+
     pub fn desired_height(&self, width: u16) -> u16 {
         let overlay_status_h = self
             .live_status
@@ -399,6 +412,7 @@ mod tests {
             id: "1".to_string(),
             command: vec!["echo".into(), "ok".into()],
             reason: None,
+            dangerous_rule: None,
         }
     }
 
@@ -202,6 +202,28 @@ impl TextArea {
         }
     }
 
+    /// Start of the word after the one under the cursor (vim `w`).
+    pub(crate) fn beginning_of_next_word(&self) -> usize {
+        let rest = &self.text[self.cursor_pos..];
+        let after_word = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let next = rest[after_word..]
+            .find(|c: char| !c.is_whitespace())
+            .map_or(rest.len(), |i| after_word + i);
+        self.cursor_pos + next
+    }
+
+    /// Remove the line under the cursor together with its line break
+    /// (vim `dd`).
+    pub(crate) fn delete_current_line(&mut self) {
+        let bol = self.beginning_of_current_line();
+        let eol = self.end_of_current_line();
+        if eol < self.text.len() {
+            self.replace_range(bol..eol + 1, "");
+        } else {
+            self.replace_range(bol.saturating_sub(1)..eol, "");
+        }
+    }
+
     pub fn input(&mut self, event: KeyEvent) {
         match event {
             KeyEvent {
@@ -981,6 +1003,23 @@ mod tests {
         assert_eq!(t.cursor(), t.text().len()); // moves to end of next (last) line
     }
 
+    #[test]
+    fn next_word_and_delete_line_helpers() {
+        let mut t = ta_with("one two\nthree");
+        t.set_cursor(1);
+        assert_eq!(t.beginning_of_next_word(), 4);
+        t.set_cursor(4);
+        assert_eq!(t.beginning_of_next_word(), 8);
+        t.set_cursor(9);
+        assert_eq!(t.beginning_of_next_word(), 13);
+
+        t.set_cursor(2);
+        t.delete_current_line();
+        assert_eq!(t.text(), "three");
+        t.delete_current_line();
+        assert_eq!(t.text(), "");
+    }
+
     #[test]
     fn word_navigation_helpers() {
         let t = ta_with("  alpha  beta   gamma");
@@ -0,0 +1,325 @@
+//! The `bubbles` message layout: prompts and answers drawn in bordered
+//! bubbles, prompts on the right and answers on the left, with the `user` or
+//! `codex` header in the top border.
+//!
+//! Only the lines sent to the terminal are decorated; the transcript view
+//! records the plain lines, so its folds, search and exports are unaffected.
+//! A prompt arrives as one history cell and is drawn as a whole. An answer
+//! arrives in commits as it streams, so its bubble stays open, one bordered
+//! row per committed line, until the stream finishes.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use codex_core::config_types::HeaderGlyphs;
+use codex_core::config_types::MessageLayout;
+use codex_core::config_types::Theme;
+use codex_core::config_types::Tui;
+use ratatui::style::Style;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+
+use crate::insert_history::word_wrap_lines;
+use crate::render::line_utils::is_blank_line_trim;
+use crate::streaming::StreamKind;
+use crate::streaming::controller::AppEventHistorySink;
+use crate::streaming::controller::HistorySink;
+use crate::streaming::header_kind;
+
+/// Width of a bubble when `tui.bubble_max_width` is unset.
+const DEFAULT_MAX_WIDTH: u16 = 72;
+
+/// Narrowest bubble: the borders and a few columns of text.
+const MIN_WIDTH: u16 = 8;
+
+/// Columns taken by the borders and padding on each row: `│ ` and ` │`.
+const FRAME_COLUMNS: u16 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// Where a bubble sits on screen and how wide it is, borders included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BubbleFrame {
+    indent: u16,
+    width: u16,
+}
+
+impl BubbleFrame {
+    fn text_width(&self) -> u16 {
+        self.width.saturating_sub(FRAME_COLUMNS).max(1)
+    }
+
+    fn indent_spans(&self) -> Vec<Span<'static>> {
+        if self.indent == 0 {
+            Vec::new()
+        } else {
+            vec![Span::from(" ".repeat(usize::from(self.indent)))]
+        }
+    }
+
+    /// `╭─ label ──╮`, with `label` keeping its styles.
+    fn top(&self, label: &Line<'static>) -> Line<'static> {
+        let fill = usize::from(self.width)
+            .saturating_sub(label.width() + 5)
+            .max(1);
+        let mut spans = self.indent_spans();
+        spans.push("╭─ ".dim());
+        spans.extend(label.spans.iter().cloned());
+        spans.push(format!(" {}╮", "─".repeat(fill)).dim());
+        Line::from(spans)
+    }
+
+    /// `lines` wrapped to the bubble and bordered, one row per wrapped line.
+    fn rows(&self, lines: &[Line<'static>]) -> Vec<Line<'static>> {
+        let text_width = self.text_width();
+        word_wrap_lines(lines, text_width)
+            .into_iter()
+            .map(|row| {
+                let pad = usize::from(text_width).saturating_sub(row.width());
+                let mut spans = self.indent_spans();
+                spans.push("│ ".dim());
+                let style = row.style;
+                spans.extend(row.spans.into_iter().map(|mut span| {
+                    span.style = style.patch(span.style);
+                    span
+                }));
+                spans.push(Span::styled(
+                    format!("{} │", " ".repeat(pad)),
+                    Style::default().dim(),
+                ));
+                Line::from(spans)
+            })
+            .collect()
+    }
+
+    /// `╰──╯`.
+    fn bottom(&self) -> Line<'static> {
+        let mut spans = self.indent_spans();
+        spans.push(
+            format!(
+                "╰{}╯",
+                "─".repeat(usize::from(self.width.saturating_sub(2)))
+            )
+            .dim(),
+        );
+        Line::from(spans)
+    }
+}
+
+/// What a bubble is laid out against: the terminal width and the headers
+/// that open one.
+#[derive(Debug, Clone)]
+pub(crate) struct BubbleLayout {
+    max_width: u16,
+    screen_width: u16,
+    theme: Theme,
+    glyphs: HeaderGlyphs,
+}
+
+impl BubbleLayout {
+    /// The layout for `config`, or `None` unless `tui.message_layout` is
+    /// `bubbles`. A zero `screen_width` (no terminal size yet) lays bubbles
+    /// out at their maximum width.
+    pub(crate) fn new(config: &Tui, screen_width: u16) -> Option<Self> {
+        (config.message_layout == MessageLayout::Bubbles).then(|| Self {
+            max_width: config.bubble_max_width.unwrap_or(DEFAULT_MAX_WIDTH),
+            screen_width,
+            theme: config.theme,
+            glyphs: config.header_glyphs.clone(),
+        })
+    }
+
+    /// A frame on `side` fitting `content` columns of text, or as wide as
+    /// allowed when `content` is `None`.
+    fn frame(&self, side: Side, content: Option<usize>) -> BubbleFrame {
+        let available = match self.screen_width {
+            0 => self.max_width,
+            screen => screen.min(self.max_width),
+        }
+        .max(MIN_WIDTH);
+        let width = content.map_or(available, |content| {
+            u16::try_from(content)
+                .unwrap_or(u16::MAX)
+                .saturating_add(FRAME_COLUMNS)
+                .clamp(MIN_WIDTH, available)
+        });
+        let indent = match side {
+            Side::Left => 0,
+            Side::Right => self.screen_width.saturating_sub(width),
+        };
+        BubbleFrame { indent, width }
+    }
+
+    /// The lines of a prompt cell (its `user` header, the prompt and a
+    /// trailing blank line) as a right-aligned bubble shrunk to fit.
+    pub(crate) fn prompt(&self, lines: &[Line<'static>]) -> Vec<Line<'static>> {
+        let Some((header, rest)) = lines.split_first() else {
+            return Vec::new();
+        };
+        let end = rest
+            .iter()
+            .rposition(|line| !is_blank_line_trim(line))
+            .map_or(0, |i| i + 1);
+        let body = &rest[..end];
+        let content = body
+            .iter()
+            .map(Line::width)
+            .max()
+            .unwrap_or(0)
+            .max(header.width() + 2);
+        let frame = self.frame(Side::Right, Some(content));
+        let mut out = vec![frame.top(header)];
+        out.extend(frame.rows(body));
+        out.push(frame.bottom());
+        out.push(Line::default());
+        out
+    }
+}
+
+/// The answer bubble being streamed, if any.
+#[derive(Debug, Default)]
+pub(crate) struct OpenBubble {
+    frame: Option<BubbleFrame>,
+    /// Blank lines not yet drawn, so the last row of the answer is not
+    /// followed by empty rows inside the bubble.
+    held_blanks: usize,
+}
+
+impl OpenBubble {
+    /// Lay out committed stream `lines`. An answer header opens a bubble; a
+    /// reasoning header closes it and, like the reasoning, stays outside.
+    pub(crate) fn stream(
+        &mut self,
+        layout: &BubbleLayout,
+        lines: Vec<Line<'static>>,
+    ) -> Vec<Line<'static>> {
+        let mut out = Vec::new();
+        for line in lines {
+            match header_kind(&line, layout.theme, &layout.glyphs) {
+                Some(StreamKind::Answer) => {
+                    out.extend(self.close());
+                    let frame = layout.frame(Side::Left, None);
+                    out.push(frame.top(&line));
+                    self.frame = Some(frame);
+                }
+                Some(StreamKind::Reasoning) => {
+                    out.extend(self.close());
+                    out.push(line);
+                }
+                None => match self.frame {
+                    Some(_) if is_blank_line_trim(&line) => self.held_blanks += 1,
+                    Some(frame) => {
+                        let blanks = vec![Line::default(); std::mem::take(&mut self.held_blanks)];
+                        out.extend(frame.rows(&blanks));
+                        out.extend(frame.rows(std::slice::from_ref(&line)));
+                    }
+                    None => out.push(line),
+                },
+            }
+        }
+        out
+    }
+
+    /// Close the open bubble: its bottom border and a blank line, or nothing
+    /// when no bubble is open.
+    pub(crate) fn close(&mut self) -> Vec<Line<'static>> {
+        self.held_blanks = 0;
+        match self.frame.take() {
+            Some(frame) => vec![frame.bottom(), Line::default()],
+            None => Vec::new(),
+        }
+    }
+}
+
+/// [`HistorySink`] that draws answers in bubbles before they reach the
+/// terminal; a plain pass-through without a layout.
+pub(crate) struct BubbleHistorySink {
+    pub(crate) inner: AppEventHistorySink,
+    pub(crate) layout: Option<BubbleLayout>,
+    pub(crate) open: Rc<RefCell<OpenBubble>>,
+}
+
+impl HistorySink for BubbleHistorySink {
+    fn insert_history(&self, lines: Vec<Line<'static>>) {
+        let lines = match &self.layout {
+            Some(layout) => self.open.borrow_mut().stream(layout, lines),
+            None => lines,
+        };
+        if !lines.is_empty() {
+            self.inner.insert_history(lines);
+        }
+    }
+    fn start_commit_animation(&self) {
+        self.inner.start_commit_animation();
+    }
+    fn stop_commit_animation(&self) {
+        self.inner.stop_commit_animation();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::streaming::render_header_line;
+    use pretty_assertions::assert_eq;
+
+    fn layout(screen_width: u16) -> BubbleLayout {
+        let config = Tui {
+            message_layout: MessageLayout::Bubbles,
+            bubble_max_width: Some(20),
+            ..Default::default()
+        };
+        BubbleLayout::new(&config, screen_width).unwrap()
+    }
+
+    fn text(lines: &[Line<'_>]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn headers_layout_has_no_bubbles() {
+        assert!(BubbleLayout::new(&Tui::default(), 80).is_none());
+    }
+
+    #[test]
+    fn answer_rows_are_bordered_and_trailing_blanks_dropped() {
+        let layout = layout(40);
+        let mut open = OpenBubble::default();
+        let header = render_header_line(
+            StreamKind::Answer,
+            Theme::default(),
+            &HeaderGlyphs::default(),
+        );
+        let mut out = open.stream(
+            &layout,
+            vec![
+                header,
+                Line::from("one"),
+                Line::default(),
+                Line::from("two"),
+                Line::default(),
+            ],
+        );
+        out.extend(open.close());
+        assert_eq!(
+            text(&out),
+            vec![
+                "╭─ codex ──────────╮",
+                "│ one              │",
+                "│                  │",
+                "│ two              │",
+                "╰──────────────────╯",
+                "",
+            ]
+        );
+    }
+}
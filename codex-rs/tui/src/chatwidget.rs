@@ -1,36 +1,68 @@
+use std::cell::RefCell;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
+use std::ops::Range;
 use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Duration;
+use std::time::Instant;
 
 use codex_core::config::Config;
+use codex_core::config_types::CompactionDisplay;
+use codex_core::config_types::ConcurrentSubmitPolicy;
+use codex_core::config_types::CtrlCBehavior;
+use codex_core::config_types::EmptySubmit;
+use codex_core::config_types::ReasoningDisplay;
+use codex_core::config_types::ReasoningTransition;
+use codex_core::config_types::ResumeScroll;
+use codex_core::config_types::Retry;
+use codex_core::config_types::StreamMode;
+use codex_core::config_types::StreamingCaret;
+use codex_core::config_types::Theme;
+use codex_core::config_types::ToolArguments;
+use codex_core::config_types::Tui;
 use codex_core::parse_command::ParsedCommand;
 use codex_core::protocol::AgentMessageDeltaEvent;
 use codex_core::protocol::AgentMessageEvent;
+use codex_core::protocol::AgentMessagePreviewDeltaEvent;
 use codex_core::protocol::AgentReasoningDeltaEvent;
 use codex_core::protocol::AgentReasoningEvent;
 use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::ContextCompactedEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
+use codex_core::protocol::FileChange;
 use codex_core::protocol::InputItem;
+use codex_core::protocol::LastRequestEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
+use codex_core::protocol::RateLimitedEvent;
+use codex_core::protocol::ReviewDecision;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TurnDiffEvent;
+use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Constraint;
 use ratatui::layout::Layout;
 use ratatui::layout::Rect;
+use ratatui::layout::Size;
+use ratatui::style::Modifier;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
 use ratatui::widgets::Widget;
 use ratatui::widgets::WidgetRef;
 use tokio::sync::mpsc::UnboundedSender;
@@ -38,14 +70,47 @@ use tracing::debug;
 
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
+use crate::attachments::Attachment;
+use crate::attachments::UserMessage;
 use crate::bottom_pane::BottomPane;
 use crate::bottom_pane::BottomPaneParams;
 use crate::bottom_pane::CancellationEvent;
 use crate::bottom_pane::InputResult;
+use crate::bubble::BubbleHistorySink;
+use crate::bubble::BubbleLayout;
+use crate::bubble::OpenBubble;
+use crate::code_blocks::CodeBlock;
+use crate::code_blocks::CodeSelection;
+use crate::code_blocks::locate_code_blocks;
+use crate::details::locate_details;
+use crate::exec_command::commands_to_script;
 use crate::exec_command::strip_bash_lc_and_escape;
+use crate::exec_policy;
+use crate::exec_policy::ExecPolicyDecision;
 use crate::history_cell::CommandOutput;
 use crate::history_cell::HistoryCell;
 use crate::history_cell::PatchEventType;
+use crate::history_cell::SessionStats;
+use crate::history_cell::SystemNoteLevel;
+use crate::insert_history::word_wrap_lines;
+use crate::markdown::append_markdown;
+use crate::plain_log::PlainLog;
+use crate::plain_log::append_to_file;
+use crate::reasoning_cap::HeldReasoning;
+use crate::reasoning_cap::ReasoningCapSink;
+use crate::resume::ResumedMessage;
+use crate::resume::read_rollout_messages;
+use crate::retry::RetryClock;
+use crate::retry::SystemRetryClock;
+use crate::retry::backoff;
+use crate::scrollback::BlockKind;
+use crate::scrollback::RecordingHistorySink;
+use crate::scrollback::Scrollback;
+use crate::scrollback::TurnOutlineEntry;
+use crate::search::TranscriptSearch;
+use crate::search::find_matches;
+use crate::search::highlight;
+use crate::search::match_styles;
 // streaming internals are provided by crate::streaming and crate::markdown_stream
 use crate::user_approval_widget::ApprovalRequest;
 mod interrupts;
@@ -54,21 +119,131 @@ mod agent;
 use self::agent::spawn_agent;
 use crate::streaming::controller::AppEventHistorySink;
 use crate::streaming::controller::StreamController;
+use crate::streaming::render_header_line;
+use crate::text_formatting::truncate_text;
+use crate::transcript::ExecRecord;
+use crate::transcript::Transcript;
+use crate::truncation::TruncationIndicator;
 use codex_file_search::FileMatch;
 
+/// Sent by `/continue` to resume a truncated answer.
+const CONTINUE_PROMPT: &str = "Your previous response was cut off by the output length limit. \
+Continue exactly where it stopped, without repeating anything you already wrote.";
+
+/// Sent by `/summarize`, followed by the transcript.
+const SUMMARIZE_PROMPT: &str = "Summarize this session for someone picking it up: what was \
+asked, what was done and what is still open. Be brief. The transcript follows.";
+
+/// Most transcript characters `/summarize` sends; older turns are left out.
+const SUMMARY_MAX_TRANSCRIPT_CHARS: usize = 60_000;
+
+/// Window for the second Ctrl+C that quits when `tui.ctrl_c_quit_window_ms`
+/// is unset.
+const DEFAULT_CTRL_C_QUIT_WINDOW_MS: u64 = 2000;
+
+/// Below this size only [`TOO_SMALL_NOTICE`] is drawn; the composer and
+/// transcript view need more room to lay out.
+const MIN_RENDER_WIDTH: u16 = 20;
+const MIN_RENDER_HEIGHT: u16 = 3;
+const TOO_SMALL_NOTICE: &str = "terminal too small";
+
+/// The streaming caret toggles every this many commit ticks (50ms each).
+const CARET_BLINK_TICKS: usize = 10;
+const STREAMING_CARET: &str = "▌";
+
+/// Rows below the history in the transcript view, for the key hint.
+const TRANSCRIPT_HINT_HEIGHT: u16 = 1;
+/// Appended to the header row of a bookmarked turn in the transcript view.
+const BOOKMARK_MARKER: &str = "  ★";
+/// Rows of the answer preview shown at most; longer previews show their end.
+const ANSWER_PREVIEW_MAX_ROWS: usize = 3;
+/// Commit ticks the quiet reasoning summary settles above the composer
+/// before it moves into the history; it is dimmed for the first half.
+const REASONING_SETTLE_TICKS: usize = 4;
+/// Graphemes of the first prompt kept in a session title derived from it.
+const DERIVED_TITLE_MAX_GRAPHEMES: usize = 40;
+/// Characters of the session title kept in export file names.
+const EXPORT_STEM_MAX_CHARS: usize = 40;
+
+/// Finished commands kept for `/copy-commands`; older ones are dropped.
+/// `/stats` counts every command through `ExecTotals`.
+const EXEC_RESULTS_MAX: usize = 500;
+
+/// Outcome of a command the agent ran, as reported by its end event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ExecResult {
+    pub(crate) command: Vec<String>,
+    /// Index of the turn the command ran in, counted like `turns_started`.
+    pub(crate) turn: usize,
+    pub(crate) exit_code: i32,
+    pub(crate) duration: Duration,
+}
+
+/// Totals over every command that finished this session.
+#[derive(Debug, Clone, Copy, Default)]
+struct ExecTotals {
+    commands: usize,
+    failed: usize,
+    time: Duration,
+}
+
+/// Where a turn is in its life, as reported by `ChatWidget::turn_state`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TurnState {
+    /// Not started yet.
+    #[cfg(test)]
+    Pending,
+    /// Started by `TaskStarted` and still running.
+    Streaming,
+    /// Ended by `TaskComplete`.
+    Complete,
+    /// Ended by an error, including rate limiting.
+    Error,
+    /// Interrupted by the user.
+    Interrupted,
+}
+
+/// Timing and state of one turn, for `/stats` and `turn_state`.
+struct TurnMetrics {
+    state: TurnState,
+    started: Instant,
+    /// Wait from `TaskStarted` to the turn's first output.
+    first_output: Option<Duration>,
+    /// Set when the turn completes.
+    duration: Option<Duration>,
+}
+
 // Track information about an in-flight exec command.
 struct RunningCommand {
     command: Vec<String>,
     parsed_cmd: Vec<ParsedCommand>,
 }
 
+/// What happens to the initial prompt once the session is configured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum InitialPrompt {
+    /// Send it right away.
+    #[default]
+    Submit,
+    /// Put it in the composer, to be edited and sent with Enter.
+    Prefill,
+}
+
 pub(crate) struct ChatWidget<'a> {
     app_event_tx: AppEventSender,
     codex_op_tx: UnboundedSender<Op>,
+    // Set for `--follow`: events come from a log, so nothing typed is sent
+    // and approval requests are only recorded
+    read_only: bool,
     bottom_pane: BottomPane<'a>,
     active_exec_cell: Option<HistoryCell>,
     config: Config,
     initial_user_message: Option<UserMessage>,
+    // Whether the initial prompt is sent or left in the composer
+    initial_prompt: InitialPrompt,
+    // Attachments of an initial prompt left in the composer, sent with the
+    // next prompt
+    prefilled_attachments: Vec<Attachment>,
     total_token_usage: TokenUsage,
     last_token_usage: TokenUsage,
     // Stream lifecycle controller
@@ -81,32 +256,176 @@ pub(crate) struct ChatWidget<'a> {
     interrupts: InterruptManager,
     // Whether a redraw is needed after handling the current event
     needs_redraw: bool,
+    // Canonical source text of the conversation, used for export
+    transcript: Transcript,
+    // In-app copy of everything inserted into history, for the transcript view
+    scrollback: Rc<RefCell<Scrollback>>,
+    // Whether the last answer was cut short, so `/continue` can resume it
+    response_truncated: bool,
+    // The current turn answers `/summarize`; its answer becomes a summary block
+    summarizing: bool,
+    // Items of the last submitted message, resent after a rate limit or a
+    // transient error
+    last_input: Vec<InputItem>,
+    // Retries of the last message after transient errors so far
+    transient_retries: u32,
+    // Retry waiting for its backoff to elapse
+    pending_retry: Option<PendingRetry>,
+    // Rate limits so far this session; numbers their retries
+    rate_limits: u32,
+    // Fires retries once their backoff has elapsed
+    retry_clock: Box<dyn RetryClock>,
+    // Last Ctrl+C that showed the quit hint; a second one within the window quits
+    ctrl_c_pressed_at: Option<Instant>,
+    // Prompt held back by `tui.max_prompt_chars`; submitting the same text
+    // again sends it anyway
+    oversized_prompt: Option<String>,
+    // Reasoning held back in quiet mode until it is summarized in history
+    quiet_reasoning: Option<String>,
+    // Scroll target of a resumed session, until the first frame applies it
+    resume_scroll: Option<ResumeScroll>,
+    // Number of `TaskStarted` events seen; the latest turn is this minus one
+    turns_started: usize,
+    // Set when a delta arrived before `TaskStarted` and opened the turn, so
+    // the `TaskStarted` that follows joins it instead of opening another
+    implicit_turn: bool,
+    // Id of the running turn's events, from its `TaskStarted`
+    running_turn_id: Option<String>,
+    // Turn interrupted for a prompt submitted under `abort_and_start`; its
+    // late events, e.g. the interrupted error, are dropped
+    aborted_turn_id: Option<String>,
+    // Set once the running task produced its first output (`FirstToken`)
+    first_token_seen: bool,
+    // Anchor of this turn's plan checklist, which later updates replace
+    plan_anchor: Option<String>,
+    // The checklist as of the latest update, drawn above the composer once
+    // it differs from the copy already written to the terminal, and written
+    // below it when the turn ends
+    live_plan: Option<HistoryCell>,
+    // Set while the transcript view shows the raw Markdown source
+    raw_transcript: Option<RefCell<RawTranscript>>,
+    // Search in the transcript view, while one is active
+    search: Option<TranscriptSearch>,
+    // Events held back while rendering is paused (Ctrl+S), in arrival order
+    paused_events: Option<Vec<Event>>,
+    // Whether tool-call arguments are shown collapsed
+    tool_arguments_collapsed: bool,
+    // Anchors of tool-call blocks with a way to re-render them, so their
+    // arguments can be collapsed or expanded in the transcript view
+    tool_call_blocks: Vec<(String, ToolCallRender)>,
+    // Whether prompts over `collapse_prompt_lines` are shown collapsed
+    prompts_collapsed: bool,
+    // Anchors of collapsible prompt blocks with their text and attachments,
+    // so they can be expanded in the transcript view
+    prompt_blocks: Vec<(String, String, Vec<Attachment>)>,
+    // Whether patches show only their file summary, without the diffs
+    patch_details_collapsed: bool,
+    // Anchors of patch blocks that show diffs, with the event they render,
+    // so the diffs can be collapsed in the transcript view
+    patch_blocks: Vec<(String, PatchEventType, HashMap<PathBuf, FileChange>)>,
+    // Code block picked in the transcript view with Tab, if any
+    code_selection: Option<CodeSelection>,
+    // Dim every turn of the transcript view but the one in view
+    focus_mode: bool,
+    // Output held back until the turn completes in `wait-for-complete` mode
+    held_output: Vec<HeldBlock>,
+    // Provisional answer text shown dimmed until the answer starts
+    answer_preview: Option<String>,
+    // Latest status message of the agent ("Searching the web…"), shown
+    // dimmed until the answer starts
+    agent_status: Option<String>,
+    // Answer bubble of the `bubbles` layout still being streamed
+    open_bubble: Rc<RefCell<OpenBubble>>,
+    // Capped reasoning block held back from the terminal until it ends
+    held_reasoning: Rc<RefCell<HeldReasoning>>,
+    // Terminal size last passed to `set_viewport`
+    screen: Size,
+    // The screen is narrower than `full_layout_min_width`
+    compact_layout: bool,
+    // The full-screen transcript view is shown instead of the inline one
+    transcript_view_open: bool,
+    // Exit code and duration of the latest `EXEC_RESULTS_MAX` commands that
+    // finished, in order
+    exec_results: Vec<ExecResult>,
+    exec_totals: ExecTotals,
+    // Timing of every turn started, in order
+    turn_metrics: Vec<TurnMetrics>,
+    // Plain-text mirror of the conversation, when `plain_log_path` is set
+    plain_log: Option<PlainLog>,
+    // Commit ticks seen, for blinking the streaming caret
+    caret_ticks: usize,
+    // Name of the session, from `/title` or else the first prompt
+    session_title: Option<String>,
+    // Set by `/exec-output off`: commands are recorded but not rendered
+    exec_output_hidden: bool,
+    // Quiet reasoning summary settling above the composer, with the commit
+    // ticks left before it is written to history and the answer streams
+    settling_summary: Option<(Line<'static>, usize)>,
+    // Turns bookmarked in the transcript view, by index
+    bookmarks: BTreeSet<usize>,
 }
 
-struct UserMessage {
+/// A reasoning or answer block received while `stream_mode` is
+/// `wait-for-complete`.
+struct HeldBlock {
+    kind: StreamKind,
     text: String,
-    image_paths: Vec<PathBuf>,
+    /// The final text arrived; later deltas start a new block.
+    done: bool,
 }
 
-use crate::streaming::StreamKind;
+/// A resend of the last message waiting for its backoff to elapse. Only the
+/// one recorded here is sent; a timer whose retry was replaced or cancelled
+/// fires for nothing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PendingRetry {
+    /// Rate limit number `n` of the session.
+    RateLimit(u32),
+    /// Attempt `n` after a transient error.
+    Transient(u32),
+}
 
-impl From<String> for UserMessage {
-    fn from(text: String) -> Self {
-        Self {
-            text,
-            image_paths: Vec::new(),
-        }
-    }
+/// Builds the history cell of a tool call with its arguments collapsed or
+/// expanded.
+type ToolCallRender = Box<dyn Fn(bool) -> HistoryCell>;
+
+/// The canonical transcript as plain Markdown source lines, for debugging
+/// rendering. Rebuilt whenever the transcript changes.
+struct RawTranscript {
+    /// [`Transcript::revision`] the lines were built from.
+    revision: Option<u64>,
+    scrollback: Scrollback,
 }
 
-fn create_initial_user_message(text: String, image_paths: Vec<PathBuf>) -> Option<UserMessage> {
-    if text.is_empty() && image_paths.is_empty() {
-        None
-    } else {
-        Some(UserMessage { text, image_paths })
+impl RawTranscript {
+    fn new(config: &Config, transcript: &Transcript) -> Self {
+        let mut raw = Self {
+            revision: None,
+            scrollback: Scrollback::new(&config.tui),
+        };
+        raw.scrollback.push_block(BlockKind::Other, Vec::new());
+        raw.update(transcript);
+        raw
+    }
+
+    fn update(&mut self, transcript: &Transcript) {
+        if self.revision == Some(transcript.revision()) {
+            return;
+        }
+        let lines = transcript
+            .to_markdown()
+            .lines()
+            .map(|l| Line::from(l.to_string()))
+            .collect();
+        if let Some(anchor) = self.scrollback.last_anchor() {
+            self.scrollback.replace_block(&anchor, lines);
+        }
+        self.revision = Some(transcript.revision());
     }
 }
 
+use crate::streaming::StreamKind;
+
 impl ChatWidget<'_> {
     #[inline]
     fn mark_needs_redraw(&mut self) {
@@ -118,41 +437,175 @@ impl ChatWidget<'_> {
             .set_history_metadata(event.history_log_id, event.history_entry_count);
         self.add_to_history(HistoryCell::new_session_info(&self.config, event, true));
         if let Some(user_message) = self.initial_user_message.take() {
-            self.submit_user_message(user_message);
+            match self.initial_prompt {
+                InitialPrompt::Submit => self.submit_user_message(user_message),
+                InitialPrompt::Prefill => {
+                    self.bottom_pane.insert_str(&user_message.text);
+                    self.prefilled_attachments = user_message.attachments;
+                }
+            }
         }
         self.mark_needs_redraw();
     }
 
     fn on_agent_message(&mut self, message: String) {
-        let sink = AppEventHistorySink(self.app_event_tx.clone());
+        self.clear_answer_preview();
+        if std::mem::take(&mut self.summarizing) {
+            self.transcript.push_answer(&message);
+            self.add_to_history(HistoryCell::new_session_summary(&message, &self.config));
+            self.mark_needs_redraw();
+            return;
+        }
+        if self.waits_for_complete() {
+            self.transcript.push_answer(&message);
+            self.hold_output(StreamKind::Answer, message, true);
+            return;
+        }
+        self.flush_quiet_reasoning();
+        self.transcript.push_answer(&message);
+        let sink = self.history_sink();
         let finished = self.stream.apply_final_answer(&message, &sink);
         self.handle_if_stream_finished(finished);
         self.mark_needs_redraw();
     }
 
     fn on_agent_message_delta(&mut self, delta: String) {
+        // A summary is shown as one block once it is complete.
+        if self.summarizing {
+            return;
+        }
+        self.clear_answer_preview();
+        if self.waits_for_complete() {
+            self.hold_output(StreamKind::Answer, delta, false);
+            return;
+        }
+        self.settle_quiet_reasoning();
         self.handle_streaming_delta(StreamKind::Answer, delta);
     }
 
+    /// Show provisional answer text below the streaming reasoning. It is kept
+    /// out of the history and transcript, and dropped once the answer starts.
+    fn on_agent_message_preview_delta(&mut self, delta: String) {
+        if !self.config.tui.answer_preview
+            || self.summarizing
+            || self.waits_for_complete()
+            || self.stream.active_stream() == Some(StreamKind::Answer)
+        {
+            return;
+        }
+        self.answer_preview
+            .get_or_insert_with(String::new)
+            .push_str(&delta);
+        self.mark_needs_redraw();
+    }
+
+    /// Drop the answer preview and the agent status, which only stand in for
+    /// the answer until it starts.
+    fn clear_answer_preview(&mut self) {
+        let had_preview = self.answer_preview.take().is_some();
+        let had_status = self.agent_status.take().is_some();
+        if had_preview || had_status {
+            self.mark_needs_redraw();
+        }
+    }
+
+    /// The last rows of the answer preview wrapped at `width`, dimmed.
+    fn answer_preview_rows(&self, width: u16) -> Vec<Line<'static>> {
+        let Some(preview) = self.answer_preview.as_deref() else {
+            return Vec::new();
+        };
+        let lines: Vec<Line> = preview.trim().lines().map(Line::from).collect();
+        let mut rows = word_wrap_lines(&lines, width.max(1));
+        let skip = rows.len().saturating_sub(ANSWER_PREVIEW_MAX_ROWS);
+        rows.drain(..skip);
+        rows.into_iter().map(|row| row.dim()).collect()
+    }
+
     fn on_agent_reasoning_delta(&mut self, delta: String) {
+        // Only the completed text is logged.
+        if self.config.tui.reasoning_display == ReasoningDisplay::LogOnly {
+            return;
+        }
+        if self.waits_for_complete() {
+            self.hold_output(StreamKind::Reasoning, delta, false);
+            return;
+        }
+        if self.config.tui.reasoning_display == ReasoningDisplay::Quiet {
+            self.quiet_reasoning
+                .get_or_insert_with(String::new)
+                .push_str(&delta);
+            self.bottom_pane.update_status_text("thinking…".to_string());
+            self.mark_needs_redraw();
+            return;
+        }
         self.handle_streaming_delta(StreamKind::Reasoning, delta);
     }
 
-    fn on_agent_reasoning_final(&mut self) {
-        let sink = AppEventHistorySink(self.app_event_tx.clone());
+    fn on_agent_reasoning_final(&mut self, text: String) {
+        self.transcript.push_reasoning(&text);
+        if self.config.tui.reasoning_display == ReasoningDisplay::LogOnly {
+            self.log_reasoning(&text);
+            return;
+        }
+        if self.waits_for_complete() {
+            self.hold_output(StreamKind::Reasoning, text, true);
+            return;
+        }
+        if self.config.tui.reasoning_display == ReasoningDisplay::Quiet {
+            // The final text is authoritative over the buffered deltas.
+            self.quiet_reasoning = Some(text);
+            self.flush_quiet_reasoning();
+            self.mark_needs_redraw();
+            return;
+        }
+        let sink = self.history_sink();
         let finished = self.stream.finalize(StreamKind::Reasoning, false, &sink);
         self.handle_if_stream_finished(finished);
         self.mark_needs_redraw();
     }
 
     fn on_reasoning_section_break(&mut self) {
-        let sink = AppEventHistorySink(self.app_event_tx.clone());
+        if self.config.tui.reasoning_display == ReasoningDisplay::LogOnly {
+            return;
+        }
+        if self.waits_for_complete() {
+            self.hold_output(StreamKind::Reasoning, "\n\n".to_string(), false);
+            return;
+        }
+        if let Some(text) = self.quiet_reasoning.as_mut() {
+            text.push_str("\n\n");
+            return;
+        }
+        let sink = self.history_sink();
         self.stream.insert_reasoning_section_break(&sink);
     }
 
+    /// Write a completed reasoning block to `tui.reasoning_log_path`, or to
+    /// the TUI's log when it is unset.
+    fn log_reasoning(&self, text: &str) {
+        let Some(path) = &self.config.tui.reasoning_log_path else {
+            tracing::info!("reasoning: {text}");
+            return;
+        };
+        let text = format!("{}\n\n", text.trim_end_matches('\n'));
+        if let Err(e) = append_to_file(path, &text) {
+            tracing::error!("failed to write reasoning log: {e}");
+        }
+    }
+
     // Raw reasoning uses the same flow as summarized reasoning
 
     fn on_task_started(&mut self) {
+        self.turns_started += 1;
+        self.turn_metrics.push(TurnMetrics {
+            state: TurnState::Streaming,
+            started: Instant::now(),
+            first_output: None,
+            duration: None,
+        });
+        self.first_token_seen = false;
+        self.plan_anchor = None;
+        self.live_plan = None;
         self.bottom_pane.clear_ctrl_c_quit_hint();
         self.bottom_pane.set_task_running(true);
         self.set_waiting_for_model_status();
@@ -162,14 +615,39 @@ impl ChatWidget<'_> {
     }
 
     fn on_task_complete(&mut self) {
+        self.summarizing = false;
+        self.implicit_turn = false;
+        self.end_turn(TurnState::Complete);
+        if let Some(metrics) = self.turn_metrics.last_mut() {
+            let elapsed = metrics.started.elapsed();
+            metrics.duration.get_or_insert(elapsed);
+        }
+        self.flush_held_output();
+        self.flush_quiet_reasoning();
         // If a stream is currently active, finalize only that stream to flush any tail
         // without emitting stray headers for other streams.
         if self.stream.is_write_cycle_active() {
-            let sink = AppEventHistorySink(self.app_event_tx.clone());
+            let sink = self.history_sink();
             if let Some(kind) = self.last_stream_kind {
                 let _ = self.stream.finalize(kind, true, &sink);
             }
         }
+        self.close_open_blocks();
+        self.commit_live_plan();
+        if self.response_truncated {
+            self.add_to_history(HistoryCell::new_response_truncated());
+        }
+        if self.config.tui.auto_copy_answers {
+            if let Some(answer) = self.transcript.last_answer().map(str::to_string) {
+                self.app_event_tx.send(AppEvent::CopyToClipboard(answer));
+                self.add_to_history(HistoryCell::new_answer_copied());
+            }
+        }
+        if let Some(log) = &mut self.plain_log {
+            if let Err(e) = log.append_new_turns(self.transcript.turns()) {
+                tracing::error!("failed to write plain log: {e}");
+            }
+        }
         // Mark task stopped and request redraw now that all content is in history.
         self.bottom_pane.set_task_running(false);
         self.mark_needs_redraw();
@@ -185,15 +663,139 @@ impl ChatWidget<'_> {
         );
     }
 
-    fn on_error(&mut self, message: String) {
-        self.add_to_history(HistoryCell::new_error_event(message));
+    fn on_error(&mut self, message: String, transient: bool) {
+        self.end_turn(TurnState::Error);
+        self.flush_held_output();
+        self.stream.clear_all();
+        self.commit_live_plan();
+        let Retry {
+            max_attempts,
+            base_delay_ms,
+        } = self.config.tui.retry;
+        let attempt = self.transient_retries + 1;
+        if transient && attempt <= max_attempts && !self.summarizing && !self.last_input.is_empty()
+        {
+            self.transient_retries = attempt;
+            self.pending_retry = Some(PendingRetry::Transient(attempt));
+            let delay = backoff(Duration::from_millis(base_delay_ms), attempt);
+            self.discard_failed_attempt();
+            self.add_to_history(HistoryCell::new_transient_retry(
+                &message,
+                attempt,
+                max_attempts,
+                delay,
+            ));
+            self.retry_clock.schedule(
+                delay,
+                self.app_event_tx.clone(),
+                AppEvent::RetryAfterTransientError { attempt },
+            );
+        } else {
+            self.transcript.push_error(&message);
+            self.add_to_history(HistoryCell::new_error_event(message));
+        }
+        self.bottom_pane.set_task_running(false);
+        self.mark_needs_redraw();
+    }
+
+    /// Forget what the failed attempt of the current turn produced, so the
+    /// retry's output is recorded once. The terminal keeps the lines already
+    /// written to it; the transcript view and exports drop them.
+    fn discard_failed_attempt(&mut self) {
+        self.transcript.clear_turn_output();
+        self.scrollback.borrow_mut().truncate_turn_output();
+    }
+
+    fn on_rate_limited(&mut self, retry_after: Duration) {
+        self.end_turn(TurnState::Error);
+        self.flush_held_output();
+        self.commit_live_plan();
+        let auto_retry = self.config.tui.auto_retry_rate_limited && !self.last_input.is_empty();
+        self.transcript.push_error(&format!(
+            "rate limited; retry in {}s",
+            retry_after.as_secs()
+        ));
+        self.add_to_history(HistoryCell::new_rate_limited(retry_after, auto_retry));
         self.bottom_pane.set_task_running(false);
         self.stream.clear_all();
         self.mark_needs_redraw();
+        if auto_retry {
+            self.rate_limits += 1;
+            let limit = self.rate_limits;
+            self.pending_retry = Some(PendingRetry::RateLimit(limit));
+            self.retry_clock.schedule(
+                retry_after,
+                self.app_event_tx.clone(),
+                AppEvent::RetryAfterRateLimit { limit },
+            );
+        }
+    }
+
+    /// Resend the message that hit rate limit `limit`, unless a newer prompt
+    /// was submitted or the retry was cancelled during the backoff.
+    pub(crate) fn retry_after_rate_limit(&mut self, limit: u32) {
+        self.fire_retry(PendingRetry::RateLimit(limit));
+    }
+
+    /// Resend the message whose turn failed with a transient error, unless
+    /// a newer prompt was submitted or the retry was cancelled during the
+    /// backoff of retry `attempt`.
+    pub(crate) fn retry_after_transient_error(&mut self, attempt: u32) {
+        self.fire_retry(PendingRetry::Transient(attempt));
+    }
+
+    fn fire_retry(&mut self, retry: PendingRetry) {
+        if self.pending_retry == Some(retry) {
+            self.pending_retry = None;
+            self.resend_last_input();
+        }
+    }
+
+    /// Send the last message again. Its prompt is already in the history and
+    /// the transcript, so only the op is sent.
+    fn resend_last_input(&mut self) {
+        if self.last_input.is_empty() {
+            return;
+        }
+        self.codex_op_tx
+            .send(Op::UserInput {
+                items: self.last_input.clone(),
+            })
+            .unwrap_or_else(|e| {
+                tracing::error!("failed to resend message: {e}");
+            });
     }
 
     fn on_plan_update(&mut self, update: codex_core::plan_tool::UpdatePlanArgs) {
-        self.add_to_history(HistoryCell::new_plan_update(update));
+        let cell = HistoryCell::new_plan_update(update);
+        // Later updates in a turn rewrite the checklist in the transcript
+        // view and show it above the composer. The terminal keeps the first
+        // version instead of printing another one.
+        if let Some(anchor) = &self.plan_anchor {
+            let replaced = self
+                .scrollback
+                .borrow_mut()
+                .replace_block(anchor, cell.plain_lines());
+            if replaced {
+                self.live_plan = Some(cell);
+                self.mark_needs_redraw();
+                return;
+            }
+        }
+        self.add_to_history(cell);
+        self.plan_anchor = self.scrollback.borrow().last_anchor();
+    }
+
+    /// Write the checklist shown above the composer to the terminal once its
+    /// turn is over, so the final state is in the scrollback. The transcript
+    /// view already holds it in place of the first one.
+    fn commit_live_plan(&mut self) {
+        if let Some(cell) = self.live_plan.take() {
+            self.close_open_blocks();
+            self.app_event_tx
+                .send(AppEvent::InsertHistory(cell.plain_lines()));
+            self.mark_needs_redraw();
+        }
     }
 
     fn on_exec_approval_request(&mut self, id: String, ev: ExecApprovalRequestEvent) {
@@ -227,12 +829,12 @@ impl ChatWidget<'_> {
     }
 
     fn on_patch_apply_begin(&mut self, event: PatchApplyBeginEvent) {
-        self.add_to_history(HistoryCell::new_patch_event(
+        self.add_patch_to_history(
             PatchEventType::ApplyBegin {
                 auto_approved: event.auto_approved,
             },
             event.changes,
-        ));
+        );
     }
 
     fn on_patch_apply_end(&mut self, event: codex_core::protocol::PatchApplyEndEvent) {
@@ -271,6 +873,15 @@ impl ChatWidget<'_> {
             .on_history_entry_response(log_id, offset, entry.map(|e| e.text));
     }
 
+    fn on_last_request(&mut self, request: Option<serde_json::Value>) {
+        if let Some(request) = &request {
+            let text = serde_json::to_string_pretty(request).unwrap_or_default();
+            self.app_event_tx.send(AppEvent::CopyToClipboard(text));
+        }
+        self.add_to_history(HistoryCell::new_request_output(request.as_ref()));
+        self.mark_needs_redraw();
+    }
+
     fn on_shutdown_complete(&mut self) {
         self.app_event_tx.send(AppEvent::ExitRequest);
     }
@@ -279,15 +890,97 @@ impl ChatWidget<'_> {
         debug!("TurnDiffEvent: {unified_diff}");
     }
 
+    fn on_context_compacted(&mut self, summary: String) {
+        self.transcript.push_compaction(&summary);
+        let show_summary = self.config.tui.compaction_display == CompactionDisplay::Summary;
+        self.add_to_history(HistoryCell::new_context_compacted(&summary, show_summary));
+    }
+
+    /// Show a status message of the running turn in place of the previous
+    /// one. It is kept out of the history and transcript, and dropped once
+    /// the answer starts.
     fn on_background_event(&mut self, message: String) {
         debug!("BackgroundEvent: {message}");
+        if !self.bottom_pane.is_task_running() {
+            return;
+        }
+        let status = message.lines().map(str::trim).find(|l| !l.is_empty());
+        if let Some(status) = status {
+            self.agent_status = Some(status.to_string());
+            self.mark_needs_redraw();
+        }
     }
     /// Periodic tick to commit at most one queued line to history with a small delay,
     /// animating the output.
     pub(crate) fn on_commit_tick(&mut self) {
-        let sink = AppEventHistorySink(self.app_event_tx.clone());
+        if self.paused_events.is_some() {
+            return;
+        }
+        if let Some((_, ticks)) = self.settling_summary.as_mut() {
+            *ticks = ticks.saturating_sub(1);
+            if *ticks > 0 {
+                self.request_redraw();
+                return;
+            }
+            self.finish_settling_summary();
+        }
+        let sink = self.history_sink();
         let finished = self.stream.on_commit_tick(&sink);
         self.handle_if_stream_finished(finished);
+        self.update_reasoning_counter();
+        self.caret_ticks = self.caret_ticks.wrapping_add(1);
+        if self.shows_streaming_caret() && self.caret_ticks % CARET_BLINK_TICKS == 0 {
+            self.request_redraw();
+        }
+    }
+
+    /// Whether the caret row is laid out: an answer is streaming and the
+    /// caret is enabled. Its blink phase only affects what is drawn.
+    fn shows_streaming_caret(&self) -> bool {
+        self.config.tui.streaming_caret == StreamingCaret::Blink
+            && !self.compact_layout
+            && self.stream.active_stream() == Some(StreamKind::Answer)
+    }
+
+    fn shows_model_header(&self) -> bool {
+        self.config.tui.model_header && !self.compact_layout
+    }
+
+    /// Pause rendering of agent output, or resume it and render everything
+    /// that arrived in the meantime, in order.
+    pub(crate) fn toggle_pause(&mut self) {
+        match self.paused_events.take() {
+            Some(events) => {
+                for event in events {
+                    self.handle_codex_event(event);
+                }
+            }
+            None => self.paused_events = Some(Vec::new()),
+        }
+        self.request_redraw();
+    }
+
+    /// Hold `event` back if rendering is paused. Approval requests and
+    /// shutdown need the user, so they resume rendering first; history
+    /// lookups only feed the composer and pass through.
+    fn hold_while_paused(&mut self, event: Event) -> Option<Event> {
+        let Some(held) = self.paused_events.as_mut() else {
+            return Some(event);
+        };
+        match event.msg {
+            EventMsg::ExecApprovalRequest(_)
+            | EventMsg::ApplyPatchApprovalRequest(_)
+            | EventMsg::ShutdownComplete => {
+                self.toggle_pause();
+                Some(event)
+            }
+            EventMsg::GetHistoryEntryResponse(_) => Some(event),
+            _ => {
+                held.push(event);
+                self.request_redraw();
+                None
+            }
+        }
     }
     fn is_write_cycle_active(&self) -> bool {
         self.stream.is_write_cycle_active()
@@ -318,6 +1011,7 @@ impl ChatWidget<'_> {
     #[inline]
     fn handle_if_stream_finished(&mut self, finished: bool) {
         if finished {
+            self.close_open_blocks();
             if self.task_complete_pending {
                 self.bottom_pane.set_task_running(false);
                 self.task_complete_pending = false;
@@ -326,22 +1020,173 @@ impl ChatWidget<'_> {
         }
     }
 
+    fn waits_for_complete(&self) -> bool {
+        self.config.tui.stream_mode == StreamMode::WaitForComplete
+    }
+
+    /// Keep stream output for [`Self::flush_held_output`]. Deltas extend the
+    /// open block of the same kind; a final text replaces it.
+    fn hold_output(&mut self, kind: StreamKind, text: String, is_final: bool) {
+        match self.held_output.last_mut() {
+            Some(block) if block.kind == kind && !block.done => {
+                if is_final {
+                    block.text = text;
+                } else {
+                    block.text.push_str(&text);
+                }
+                block.done = is_final;
+            }
+            _ => self.held_output.push(HeldBlock {
+                kind,
+                text,
+                done: is_final,
+            }),
+        }
+        self.set_responding_status();
+        self.mark_needs_redraw();
+    }
+
+    /// Render everything held back this turn, in arrival order, each block
+    /// flushed at once with its header.
+    fn flush_held_output(&mut self) {
+        for block in std::mem::take(&mut self.held_output) {
+            let sink = self.history_sink();
+            let finished = match block.kind {
+                StreamKind::Answer => self.stream.apply_final_answer(&block.text, &sink),
+                StreamKind::Reasoning
+                    if self.config.tui.reasoning_display == ReasoningDisplay::Quiet =>
+                {
+                    self.quiet_reasoning = Some(block.text);
+                    self.flush_quiet_reasoning();
+                    false
+                }
+                StreamKind::Reasoning => {
+                    let mut text = block.text;
+                    if !text.ends_with('\n') {
+                        text.push('\n');
+                    }
+                    self.stream.begin(StreamKind::Reasoning, &sink);
+                    self.stream.push_and_maybe_commit(&text, &sink);
+                    self.stream.finalize(StreamKind::Reasoning, true, &sink)
+                }
+            };
+            self.handle_if_stream_finished(finished);
+        }
+    }
+
+    /// Write out everything not written yet before the app exits: output held
+    /// back, paused or still streaming goes to history and, when no final text will
+    /// arrive for it anymore, to the transcript; the plain log gets the
+    /// unfinished turn. A turn still running ends as interrupted.
+    pub(crate) fn shutdown(&mut self) {
+        // Output held back by Ctrl+S is rendered first.
+        if self.paused_events.is_some() {
+            self.toggle_pause();
+        }
+        let unfinished: Vec<(StreamKind, String)> = self
+            .held_output
+            .iter()
+            .filter(|block| !block.done)
+            .map(|block| (block.kind, block.text.clone()))
+            .chain(
+                self.quiet_reasoning
+                    .clone()
+                    .map(|text| (StreamKind::Reasoning, text)),
+            )
+            .chain(
+                self.stream
+                    .active_stream()
+                    .map(|kind| (kind, self.stream.unfinished_source(kind))),
+            )
+            .collect();
+        for (kind, text) in unfinished {
+            if text.trim().is_empty() {
+                continue;
+            }
+            match kind {
+                StreamKind::Answer => self.transcript.push_answer(&text),
+                StreamKind::Reasoning => self.transcript.push_reasoning(&text),
+            }
+        }
+
+        self.flush_held_output();
+        self.flush_quiet_reasoning();
+        if let Some(kind) = self.stream.active_stream() {
+            let sink = self.history_sink();
+            let finished = self.stream.finalize(kind, true, &sink);
+            self.handle_if_stream_finished(finished);
+        }
+        self.flush_active_exec_cell();
+        self.commit_live_plan();
+        self.end_turn(TurnState::Interrupted);
+        if let Some(log) = &mut self.plain_log {
+            if let Err(e) = log.append_new_turns(self.transcript.turns()) {
+                tracing::error!("failed to write plain log: {e}");
+            }
+        }
+    }
+
+    /// The task's first output arrived: the model is no longer being waited
+    /// for, so the status line says so before the output is handled.
+    fn on_first_token(&mut self) {
+        self.first_token_seen = true;
+        if let Some(metrics) = self.turn_metrics.last_mut() {
+            let elapsed = metrics.started.elapsed();
+            metrics.first_output.get_or_insert(elapsed);
+        }
+        self.app_event_tx.send(AppEvent::FirstToken);
+        self.set_responding_status();
+    }
+
+    #[inline]
+    fn set_responding_status(&mut self) {
+        self.bottom_pane
+            .update_status_text("responding".to_string());
+    }
+
     #[inline]
     fn set_waiting_for_model_status(&mut self) {
         self.bottom_pane
             .update_status_text("waiting for model".to_string());
     }
 
+    /// Show the length of the reasoning streamed so far in the status line
+    /// while reasoning is the active stream.
+    fn update_reasoning_counter(&mut self) {
+        if let Some(chars) = self.stream.reasoning_chars() {
+            self.bottom_pane
+                .update_status_text(reasoning_counter_text(chars));
+        }
+    }
+
     #[inline]
     fn handle_streaming_delta(&mut self, kind: StreamKind, delta: String) {
-        let sink = AppEventHistorySink(self.app_event_tx.clone());
-        self.set_waiting_for_model_status();
+        if kind == StreamKind::Reasoning {
+            self.finish_settling_summary();
+        }
+        let sink = self.history_sink();
         self.stream.begin(kind, &sink);
         self.last_stream_kind = Some(kind);
         self.stream.push_and_maybe_commit(&delta, &sink);
+        match kind {
+            StreamKind::Reasoning => self.update_reasoning_counter(),
+            StreamKind::Answer => self.set_responding_status(),
+        }
         self.mark_needs_redraw();
     }
 
+    fn record_exec_result(&mut self, result: ExecResult) {
+        self.exec_totals.commands += 1;
+        if result.exit_code != 0 {
+            self.exec_totals.failed += 1;
+        }
+        self.exec_totals.time += result.duration;
+        if self.exec_results.len() == EXEC_RESULTS_MAX {
+            self.exec_results.remove(0);
+        }
+        self.exec_results.push(result);
+    }
+
     pub(crate) fn handle_exec_end_now(&mut self, ev: ExecCommandEndEvent) {
         let running = self.running_commands.remove(&ev.call_id);
         self.active_exec_cell = None;
@@ -349,6 +1194,22 @@ impl ChatWidget<'_> {
             Some(rc) => (rc.command, rc.parsed_cmd),
             None => (vec![ev.call_id.clone()], Vec::new()),
         };
+        self.record_exec_result(ExecResult {
+            command: command.clone(),
+            turn: self.turns_started.saturating_sub(1),
+            exit_code: ev.exit_code,
+            duration: ev.duration,
+        });
+        self.transcript.push_exec(ExecRecord {
+            command: command.clone(),
+            exit_code: ev.exit_code,
+            stdout: ev.stdout.clone(),
+            stderr: ev.stderr.clone(),
+            duration: ev.duration,
+        });
+        if self.exec_output_hidden {
+            return;
+        }
         self.add_to_history(HistoryCell::new_completed_exec_command(
             command,
             parsed,
@@ -356,7 +1217,9 @@ impl ChatWidget<'_> {
                 exit_code: ev.exit_code,
                 stdout: ev.stdout.clone(),
                 stderr: ev.stderr.clone(),
+                duration: ev.duration,
             },
+            TruncationIndicator::new(&self.config.tui),
         ));
     }
 
@@ -367,13 +1230,49 @@ impl ChatWidget<'_> {
         if event.success {
             self.add_to_history(HistoryCell::new_patch_apply_success(event.stdout));
         } else {
-            self.add_to_history(HistoryCell::new_patch_apply_failure(event.stderr));
+            self.add_to_history(HistoryCell::new_patch_apply_failure(
+                event.stderr,
+                &TruncationIndicator::new(&self.config.tui),
+            ));
         }
     }
 
     pub(crate) fn handle_exec_approval_now(&mut self, id: String, ev: ExecApprovalRequestEvent) {
-        // Log a background summary immediately so the history is chronological.
         let cmdline = strip_bash_lc_and_escape(&ev.command);
+        if self.read_only {
+            self.add_to_history(HistoryCell::new_background_event(format!(
+                "command requires approval in the followed session:\n$ {cmdline}"
+            )));
+            self.mark_needs_redraw();
+            return;
+        }
+        match exec_policy::evaluate(&self.config.tui.exec_policy, &ev.command) {
+            ExecPolicyDecision::Allow => {
+                self.add_to_history(HistoryCell::new_background_event(format!(
+                    "auto-approved by exec_policy:\n$ {cmdline}"
+                )));
+                self.submit_op(Op::ExecApproval {
+                    id,
+                    decision: ReviewDecision::Approved,
+                });
+                self.mark_needs_redraw();
+                return;
+            }
+            ExecPolicyDecision::Deny(rule) => {
+                self.add_to_history(HistoryCell::new_error_event(format!(
+                    "denied by exec_policy rule `{rule}`: {cmdline}"
+                )));
+                self.submit_op(Op::ExecApproval {
+                    id,
+                    decision: ReviewDecision::Denied,
+                });
+                self.mark_needs_redraw();
+                return;
+            }
+            ExecPolicyDecision::Prompt => {}
+        }
+
+        // Log a background summary immediately so the history is chronological.
         let text = format!(
             "command requires approval:\n$ {cmdline}{reason}",
             reason = ev
@@ -386,6 +1285,7 @@ impl ChatWidget<'_> {
 
         let request = ApprovalRequest::Exec {
             id,
+            dangerous_rule: exec_policy::dangerous_rule(&self.config.tui.exec_policy, &ev.command),
             command: ev.command,
             reason: ev.reason,
         };
@@ -398,10 +1298,11 @@ impl ChatWidget<'_> {
         id: String,
         ev: ApplyPatchApprovalRequestEvent,
     ) {
-        self.add_to_history(HistoryCell::new_patch_event(
-            PatchEventType::ApprovalRequest,
-            ev.changes.clone(),
-        ));
+        self.add_patch_to_history(PatchEventType::ApprovalRequest, ev.changes.clone());
+        if self.read_only {
+            self.mark_needs_redraw();
+            return;
+        }
 
         let request = ApprovalRequest::ApplyPatch {
             id,
@@ -423,6 +1324,9 @@ impl ChatWidget<'_> {
                 parsed_cmd: ev.parsed_cmd.clone(),
             },
         );
+        if self.exec_output_hidden {
+            return;
+        }
         self.active_exec_cell = Some(HistoryCell::new_active_exec_command(
             ev.command,
             ev.parsed_cmd,
@@ -430,32 +1334,157 @@ impl ChatWidget<'_> {
     }
 
     pub(crate) fn handle_mcp_begin_now(&mut self, ev: McpToolCallBeginEvent) {
-        self.add_to_history(HistoryCell::new_active_mcp_tool_call(ev.invocation));
+        self.add_tool_call_to_history(Box::new(move |collapsed| {
+            HistoryCell::new_active_mcp_tool_call(ev.invocation.clone(), collapsed)
+        }));
     }
     pub(crate) fn handle_mcp_end_now(&mut self, ev: McpToolCallEndEvent) {
-        self.add_to_history(HistoryCell::new_completed_mcp_tool_call(
-            80,
-            ev.invocation,
-            ev.duration,
-            ev.result
-                .as_ref()
-                .map(|r| !r.is_error.unwrap_or(false))
-                .unwrap_or(false),
-            ev.result,
+        self.add_tool_call_to_history(Box::new(move |collapsed| {
+            HistoryCell::new_completed_mcp_tool_call(
+                80,
+                ev.invocation.clone(),
+                ev.duration,
+                ev.result
+                    .as_ref()
+                    .map(|r| !r.is_error.unwrap_or(false))
+                    .unwrap_or(false),
+                ev.result.clone(),
+                collapsed,
+            )
+        }));
+    }
+
+    fn add_tool_call_to_history(&mut self, render: ToolCallRender) {
+        self.add_to_history(render(self.tool_arguments_collapsed));
+        if let Some(anchor) = self.scrollback.borrow().last_anchor() {
+            self.tool_call_blocks.push((anchor, render));
+        }
+    }
+
+    fn user_prompt_cell(&self, text: &str, attachments: &[Attachment]) -> HistoryCell {
+        let max_lines = self
+            .prompts_collapsed
+            .then_some(self.config.tui.collapse_prompt_lines)
+            .flatten();
+        HistoryCell::new_user_prompt(
+            text.to_string(),
+            attachments,
+            &self.config.tui.header_glyphs,
+            max_lines,
+            &TruncationIndicator::new(&self.config.tui),
+        )
+    }
+
+    fn add_user_prompt_to_history(&mut self, text: &str, attachments: &[Attachment]) {
+        self.add_to_history(self.user_prompt_cell(text, attachments));
+        let collapsible = self
+            .config
+            .tui
+            .collapse_prompt_lines
+            .is_some_and(|max| text.lines().count() > max);
+        if collapsible {
+            if let Some(anchor) = self.scrollback.borrow().last_anchor() {
+                self.prompt_blocks
+                    .push((anchor, text.to_string(), attachments.to_vec()));
+            }
+        }
+    }
+
+    /// Collapse or expand every long prompt in the transcript view. Lines
+    /// already written to the terminal are left as they are.
+    pub(crate) fn toggle_prompts(&mut self) {
+        self.prompts_collapsed = !self.prompts_collapsed;
+        for (anchor, text, attachments) in &self.prompt_blocks {
+            let lines = self.user_prompt_cell(text, attachments).plain_lines();
+            self.scrollback.borrow_mut().replace_block(anchor, lines);
+        }
+    }
+
+    fn add_patch_to_history(
+        &mut self,
+        event_type: PatchEventType,
+        changes: HashMap<PathBuf, FileChange>,
+    ) {
+        self.add_to_history(HistoryCell::new_patch_event(
+            event_type,
+            changes.clone(),
+            self.patch_details_collapsed,
         ));
+        let shows_details = matches!(
+            event_type,
+            PatchEventType::ApprovalRequest
+                | PatchEventType::ApplyBegin {
+                    auto_approved: true
+                }
+        );
+        if shows_details {
+            if let Some(anchor) = self.scrollback.borrow().last_anchor() {
+                self.patch_blocks.push((anchor, event_type, changes));
+            }
+        }
+    }
+
+    /// Collapse every patch to its file summary in the transcript view, or
+    /// show the diffs again. Lines already written to the terminal are left
+    /// as they are.
+    pub(crate) fn toggle_patch_details(&mut self) {
+        self.patch_details_collapsed = !self.patch_details_collapsed;
+        let mut scrollback = self.scrollback.borrow_mut();
+        for (anchor, event_type, changes) in &self.patch_blocks {
+            let cell = HistoryCell::new_patch_event(
+                *event_type,
+                changes.clone(),
+                self.patch_details_collapsed,
+            );
+            scrollback.replace_block(anchor, cell.plain_lines());
+        }
+    }
+
+    /// Collapse or expand the arguments of every tool call in the transcript
+    /// view. Lines already written to the terminal are left as they are.
+    pub(crate) fn toggle_tool_arguments(&mut self) {
+        self.tool_arguments_collapsed = !self.tool_arguments_collapsed;
+        let mut scrollback = self.scrollback.borrow_mut();
+        for (anchor, render) in &self.tool_call_blocks {
+            scrollback.replace_block(anchor, render(self.tool_arguments_collapsed).plain_lines());
+        }
     }
     fn interrupt_running_task(&mut self) {
+        self.pending_retry = None;
         if self.bottom_pane.is_task_running() {
+            self.end_turn(TurnState::Interrupted);
             self.active_exec_cell = None;
             self.bottom_pane.clear_ctrl_c_quit_hint();
             self.submit_op(Op::Interrupt);
             self.bottom_pane.set_task_running(false);
+            self.flush_held_output();
             self.stream.clear_all();
+            self.close_open_blocks();
+            self.commit_live_plan();
             self.request_redraw();
         }
     }
-    fn layout_areas(&self, area: Rect) -> [Rect; 2] {
+    /// Height of the row above the active cell: the streaming caret or the
+    /// pause notice.
+    fn status_row_height(&self) -> u16 {
+        u16::from(
+            self.shows_streaming_caret()
+                || self.paused_events.is_some()
+                || self.agent_status.is_some(),
+        )
+    }
+
+    fn layout_areas(&self, area: Rect) -> [Rect; 7] {
         Layout::vertical([
+            Constraint::Length(u16::from(self.shows_model_header())),
+            Constraint::Length(self.settling_summary_rows().len() as u16),
+            Constraint::Length(self.status_row_height()),
+            Constraint::Length(self.answer_preview_rows(area.width).len() as u16),
+            Constraint::Max(
+                self.live_plan
+                    .as_ref()
+                    .map_or(0, |c| c.desired_height(area.width)),
+            ),
             Constraint::Max(
                 self.active_exec_cell
                     .as_ref()
@@ -469,26 +1498,85 @@ impl ChatWidget<'_> {
     pub(crate) fn new(
         config: Config,
         app_event_tx: AppEventSender,
-        initial_prompt: Option<String>,
-        initial_images: Vec<PathBuf>,
+        initial_message: Option<UserMessage>,
         enhanced_keys_supported: bool,
     ) -> Self {
         let codex_op_tx = spawn_agent(config.clone(), app_event_tx.clone());
+        Self::with_op_sender(
+            config,
+            app_event_tx,
+            codex_op_tx,
+            initial_message,
+            enhanced_keys_supported,
+        )
+    }
+
+    /// Choose whether the initial prompt is sent once the session is
+    /// configured or left in the composer for editing.
+    pub(crate) fn with_initial_prompt(mut self, initial_prompt: InitialPrompt) -> Self {
+        self.initial_prompt = initial_prompt;
+        self
+    }
+
+    /// A read-only widget for `--follow`, whose events come from a session
+    /// log. No agent is started: the composer takes no input and approval
+    /// requests are recorded in the history, answered by the followed
+    /// session.
+    pub(crate) fn new_follower(
+        config: Config,
+        app_event_tx: AppEventSender,
+        enhanced_keys_supported: bool,
+    ) -> Self {
+        let (codex_op_tx, _codex_op_rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut widget = Self::with_op_sender(
+            config,
+            app_event_tx,
+            codex_op_tx,
+            None,
+            enhanced_keys_supported,
+        );
+        widget.read_only = true;
+        widget.bottom_pane.set_read_only(true);
+        widget
+    }
 
-        Self {
+    fn with_op_sender(
+        config: Config,
+        app_event_tx: AppEventSender,
+        codex_op_tx: UnboundedSender<Op>,
+        initial_message: Option<UserMessage>,
+        enhanced_keys_supported: bool,
+    ) -> Self {
+        let scrollback = Rc::new(RefCell::new(Scrollback::new(&config.tui)));
+        let mut bottom_pane = BottomPane::new(BottomPaneParams {
             app_event_tx: app_event_tx.clone(),
+            has_input_focus: true,
+            enhanced_keys_supported,
+        });
+        bottom_pane.set_editing_mode(config.tui.editing_mode);
+        let plain_log = config.tui.plain_log_path.clone().map(PlainLog::new);
+        let export_blank_lines = config
+            .tui
+            .collapse_blank_lines
+            .filter(|_| config.tui.collapse_blank_lines_in_export);
+        let resumed = config
+            .experimental_resume
+            .as_deref()
+            .map(read_rollout_messages);
+        let resume_scroll = resumed.is_some().then_some(config.tui.resume_scroll);
+        let tool_arguments_collapsed = config.tui.tool_arguments == ToolArguments::Collapsed;
+        let focus_mode = config.tui.focus_mode;
+
+        let mut widget = Self {
+            app_event_tx,
             codex_op_tx,
-            bottom_pane: BottomPane::new(BottomPaneParams {
-                app_event_tx,
-                has_input_focus: true,
-                enhanced_keys_supported,
-            }),
+            read_only: false,
+            bottom_pane,
             active_exec_cell: None,
             config: config.clone(),
-            initial_user_message: create_initial_user_message(
-                initial_prompt.unwrap_or_default(),
-                initial_images,
-            ),
+            initial_user_message: initial_message.filter(|message| !message.is_empty()),
+            initial_prompt: InitialPrompt::default(),
+            prefilled_attachments: Vec::new(),
             total_token_usage: TokenUsage::default(),
             last_token_usage: TokenUsage::default(),
             stream: StreamController::new(config),
@@ -497,63 +1585,1025 @@ impl ChatWidget<'_> {
             task_complete_pending: false,
             interrupts: InterruptManager::new(),
             needs_redraw: false,
+            transcript: Transcript::with_max_blank_lines(export_blank_lines),
+            scrollback,
+            response_truncated: false,
+            summarizing: false,
+            last_input: Vec::new(),
+            transient_retries: 0,
+            pending_retry: None,
+            rate_limits: 0,
+            retry_clock: Box::new(SystemRetryClock),
+            ctrl_c_pressed_at: None,
+            oversized_prompt: None,
+            quiet_reasoning: None,
+            turns_started: 0,
+            implicit_turn: false,
+            running_turn_id: None,
+            aborted_turn_id: None,
+            first_token_seen: false,
+            plan_anchor: None,
+            live_plan: None,
+            raw_transcript: None,
+            search: None,
+            paused_events: None,
+            tool_arguments_collapsed,
+            tool_call_blocks: Vec::new(),
+            prompts_collapsed: true,
+            prompt_blocks: Vec::new(),
+            patch_details_collapsed: false,
+            patch_blocks: Vec::new(),
+            code_selection: None,
+            focus_mode,
+            held_output: Vec::new(),
+            answer_preview: None,
+            agent_status: None,
+            open_bubble: Rc::new(RefCell::new(OpenBubble::default())),
+            held_reasoning: Rc::new(RefCell::new(HeldReasoning::default())),
+            screen: Size::default(),
+            compact_layout: false,
+            transcript_view_open: false,
+            exec_results: Vec::new(),
+            exec_totals: ExecTotals::default(),
+            turn_metrics: Vec::new(),
+            plain_log,
+            caret_ticks: 0,
+            session_title: None,
+            exec_output_hidden: false,
+            settling_summary: None,
+            bookmarks: BTreeSet::new(),
+            resume_scroll,
+        };
+        match resumed {
+            Some(Ok(messages)) => widget.show_resumed_history(messages),
+            Some(Err(e)) => widget.add_to_history(HistoryCell::new_error_event(format!(
+                "failed to read the resumed session: {e}"
+            ))),
+            None => {}
+        }
+        widget
+    }
+
+    /// Show the prompts and answers of a resumed session as history, so the
+    /// conversation continues below them.
+    fn show_resumed_history(&mut self, messages: Vec<ResumedMessage>) {
+        for message in messages {
+            match message {
+                ResumedMessage::User(text) => {
+                    self.stream.reset_headers_for_new_turn();
+                    self.transcript.push_user(&text);
+                    self.add_user_prompt_to_history(&text, &[]);
+                    self.scrollback.borrow_mut().mark_turn_start(&text);
+                }
+                ResumedMessage::Assistant(text) => {
+                    self.transcript.push_answer(&text);
+                    let sink = self.history_sink();
+                    self.stream.apply_final_answer(&text, &sink);
+                }
+            }
+        }
+    }
+
+    /// Apply `tui.resume_scroll` once, after the first viewport is known.
+    /// Returns whether the transcript view should open to show the target.
+    pub(crate) fn take_resume_scroll(&mut self) -> bool {
+        let mut scrollback = self.scrollback.borrow_mut();
+        match self.resume_scroll.take() {
+            Some(ResumeScroll::LastUser) => scrollback.scroll_to_last_turn(),
+            Some(ResumeScroll::Top) => {
+                scrollback.scroll_to_top();
+                true
+            }
+            Some(ResumeScroll::Bottom) | None => false,
         }
     }
 
     pub fn desired_height(&self, width: u16) -> u16 {
-        self.bottom_pane.desired_height(width)
+        u16::from(self.shows_model_header())
+            + self.settling_summary_rows().len() as u16
+            + self.status_row_height()
+            + self.answer_preview_rows(width).len() as u16
+            + self.bottom_pane.desired_height(width)
+            + self
+                .live_plan
+                .as_ref()
+                .map_or(0, |c| c.desired_height(width))
             + self
                 .active_exec_cell
                 .as_ref()
                 .map_or(0, |c| c.desired_height(width))
     }
 
-    pub(crate) fn handle_key_event(&mut self, key_event: KeyEvent) {
-        if key_event.kind == KeyEventKind::Press {
-            self.bottom_pane.clear_ctrl_c_quit_hint();
+    /// Record the terminal size. The content viewport is derived from it and
+    /// the transcript is re-wrapped to fit, so callers never compute the
+    /// composer or status line height themselves.
+    pub(crate) fn set_viewport(&mut self, screen: Size) {
+        self.screen = screen;
+        let compact = self
+            .config
+            .tui
+            .full_layout_min_width
+            .is_some_and(|min| screen.width < min);
+        if compact != self.compact_layout {
+            self.compact_layout = compact;
+            self.request_redraw();
         }
-
+        let content = self.content_viewport();
+        self.scrollback
+            .borrow_mut()
+            .set_viewport(content.width, content.height);
+    }
+
+    /// Switch the content viewport between the inline and the full-screen
+    /// transcript view, so each view sizes the scrollback for itself.
+    pub(crate) fn set_transcript_view_open(&mut self, open: bool) {
+        self.transcript_view_open = open;
+        self.set_viewport(self.screen);
+    }
+
+    /// The part of the screen left for conversation content: everything
+    /// above the composer, status line and running command, or above the key
+    /// hint while the transcript view is open.
+    pub(crate) fn content_viewport(&self) -> Rect {
+        let Size { width, height } = self.screen;
+        let chrome = if self.transcript_view_open {
+            TRANSCRIPT_HINT_HEIGHT
+        } else {
+            self.desired_height(width)
+        };
+        Rect::new(0, 0, width, height.saturating_sub(chrome))
+    }
+
+    pub(crate) fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if key_event.kind == KeyEventKind::Press {
+            self.bottom_pane.clear_ctrl_c_quit_hint();
+        }
+        if key_event.kind == KeyEventKind::Press
+            && key_event.code == KeyCode::Char('s')
+            && key_event.modifiers == KeyModifiers::CONTROL
+        {
+            self.toggle_pause();
+            return;
+        }
+        if self.read_only {
+            return;
+        }
+
         match self.bottom_pane.handle_key_event(key_event) {
             InputResult::Submitted(text) => {
-                self.submit_user_message(text.into());
+                let mut user_message = UserMessage::from(text);
+                user_message
+                    .attachments
+                    .append(&mut self.prefilled_attachments);
+                self.submit_user_message(user_message);
             }
+            InputResult::SubmittedEmpty => self.on_empty_submit(),
             InputResult::None => {}
         }
     }
 
+    /// Enter on an empty composer: nothing, or with `tui.empty_submit =
+    /// "rerun-last"` the text of the latest prompt is sent again as a new
+    /// turn.
+    fn on_empty_submit(&mut self) {
+        if self.config.tui.empty_submit != EmptySubmit::RerunLast {
+            return;
+        }
+        let last = self
+            .transcript
+            .turns()
+            .iter()
+            .rev()
+            .map(|turn| turn.user.clone())
+            .find(|prompt| !prompt.trim().is_empty());
+        if let Some(prompt) = last {
+            self.submit_user_message(prompt.into());
+        }
+    }
+
     pub(crate) fn handle_paste(&mut self, text: String) {
+        if self.read_only {
+            return;
+        }
         self.bottom_pane.handle_paste(text);
     }
 
     fn flush_active_exec_cell(&mut self) {
         if let Some(active) = self.active_exec_cell.take() {
-            self.app_event_tx
-                .send(AppEvent::InsertHistory(active.plain_lines()));
+            self.insert_history_cell(BlockKind::Other, active.plain_lines());
+        }
+    }
+
+    /// Replace reasoning held back in quiet mode with its one-line summary.
+    fn flush_quiet_reasoning(&mut self) {
+        self.finish_settling_summary();
+        if let Some(summary) = self.take_quiet_summary() {
+            self.insert_summary(summary);
+        }
+    }
+
+    /// Like `flush_quiet_reasoning`, for the answer starting: with
+    /// `reasoning_transition = "settle"` the summary is first shown above the
+    /// composer, and the answer's lines wait until it is in history.
+    fn settle_quiet_reasoning(&mut self) {
+        if self.quiet_reasoning.is_some() {
+            // The answer replaces the thinking indicator.
+            self.set_responding_status();
+        }
+        if self.config.tui.reasoning_transition == ReasoningTransition::Off || self.compact_layout {
+            self.flush_quiet_reasoning();
+            return;
+        }
+        self.finish_settling_summary();
+        if let Some(summary) = self.take_quiet_summary() {
+            self.settling_summary = Some((summary, REASONING_SETTLE_TICKS));
+            self.app_event_tx.send(AppEvent::StartCommitAnimation);
+            self.mark_needs_redraw();
+        }
+    }
+
+    fn take_quiet_summary(&mut self) -> Option<Line<'static>> {
+        let text = self.quiet_reasoning.take()?;
+        if text.trim().is_empty() {
+            return None;
+        }
+        Some(quiet_reasoning_summary(&text, &self.config.tui))
+    }
+
+    /// Write the settling summary, if any, to history.
+    fn finish_settling_summary(&mut self) {
+        if let Some((summary, _)) = self.settling_summary.take() {
+            self.insert_summary(summary);
+            self.mark_needs_redraw();
+        }
+    }
+
+    fn insert_summary(&mut self, summary: Line<'static>) {
+        self.insert_history_cell(BlockKind::Reasoning, vec![summary, Line::from("")]);
+    }
+
+    /// The settling summary's row, dimmed while it is still settling.
+    fn settling_summary_rows(&self) -> Vec<Line<'static>> {
+        let Some((summary, ticks)) = &self.settling_summary else {
+            return Vec::new();
+        };
+        if *ticks * 2 > REASONING_SETTLE_TICKS {
+            vec![summary.clone().dim()]
+        } else {
+            vec![summary.clone()]
         }
     }
 
     fn add_to_history(&mut self, cell: HistoryCell) {
+        self.flush_quiet_reasoning();
         self.flush_active_exec_cell();
-        self.app_event_tx
-            .send(AppEvent::InsertHistory(cell.plain_lines()));
+        let kind = match cell {
+            HistoryCell::UserPrompt { .. } => BlockKind::User,
+            HistoryCell::PlanUpdate { .. } => BlockKind::Plan,
+            _ => BlockKind::Other,
+        };
+        self.insert_history_cell(kind, cell.plain_lines());
     }
 
-    fn submit_user_message(&mut self, user_message: UserMessage) {
-        let UserMessage { text, image_paths } = user_message;
-        let mut items: Vec<InputItem> = Vec::new();
+    /// Record `lines` for the transcript view and send them to the terminal,
+    /// a prompt drawn as a bubble in the `bubbles` layout.
+    fn insert_history_cell(&mut self, kind: BlockKind, lines: Vec<Line<'static>>) {
+        self.close_open_blocks();
+        self.scrollback.borrow_mut().push_block(kind, lines.clone());
+        let lines = match self.bubble_layout() {
+            Some(layout) if kind == BlockKind::User => layout.prompt(&lines),
+            _ => lines,
+        };
+        self.app_event_tx.send(AppEvent::InsertHistory(lines));
+    }
+
+    /// Sink for stream output that also records it for the transcript view.
+    fn history_sink(&self) -> RecordingHistorySink<ReasoningCapSink<BubbleHistorySink>> {
+        RecordingHistorySink {
+            inner: ReasoningCapSink {
+                inner: BubbleHistorySink {
+                    inner: AppEventHistorySink(self.app_event_tx.clone()),
+                    layout: self.bubble_layout(),
+                    open: Rc::clone(&self.open_bubble),
+                },
+                cap: self.config.tui.max_reasoning_lines,
+                theme: self.config.tui.theme,
+                glyphs: self.config.tui.header_glyphs.clone(),
+                held: Rc::clone(&self.held_reasoning),
+            },
+            scrollback: Rc::clone(&self.scrollback),
+        }
+    }
 
-        if !text.is_empty() {
-            items.push(InputItem::Text { text: text.clone() });
+    /// The bubble layout for the current screen, unless messages are laid
+    /// out under headers.
+    fn bubble_layout(&self) -> Option<BubbleLayout> {
+        BubbleLayout::new(&self.config.tui, self.screen.width)
+    }
+
+    /// Write what the terminal still holds open for the stream: a capped
+    /// reasoning block, then the bottom border of an answer bubble.
+    fn close_open_blocks(&mut self) {
+        let mut lines = self.held_reasoning.borrow_mut().close();
+        lines.extend(self.open_bubble.borrow_mut().close());
+        if !lines.is_empty() {
+            self.app_event_tx.send(AppEvent::InsertHistory(lines));
+        }
+    }
+
+    /// The user turns so far, with a short summary of each prompt and the row
+    /// of its header in the transcript view.
+    pub(crate) fn outline(&self) -> Vec<TurnOutlineEntry> {
+        self.scrollback.borrow().outline()
+    }
+
+    /// The turn at the top of the transcript view: the last one whose header
+    /// is at or above it.
+    fn turn_in_view(&self) -> Option<usize> {
+        let top = self.scrollback.borrow().visible_rows().start;
+        self.outline()
+            .iter()
+            .rev()
+            .find(|entry| entry.row <= top)
+            .map(|entry| entry.index)
+    }
+
+    /// Bookmark the turn in view of the transcript view, or remove its
+    /// bookmark.
+    fn toggle_bookmark(&mut self) {
+        if let Some(turn) = self.turn_in_view() {
+            if !self.bookmarks.remove(&turn) {
+                self.bookmarks.insert(turn);
+            }
+        }
+    }
+
+    /// Scroll to the next (`delta` 1) or previous (-1) bookmarked turn,
+    /// wrapping around at either end.
+    fn step_bookmark(&mut self, delta: isize) {
+        let current = self.turn_in_view();
+        let next = if delta > 0 {
+            let after = current.map_or(0, |turn| turn + 1);
+            self.bookmarks
+                .range(after..)
+                .next()
+                .or(self.bookmarks.first())
+        } else {
+            let before = current.unwrap_or(0);
+            self.bookmarks
+                .range(..before)
+                .next_back()
+                .or(self.bookmarks.last())
+        };
+        if let Some(&turn) = next {
+            self.scrollback.borrow_mut().scroll_to_turn(turn);
+        }
+    }
+
+    /// Scroll the transcript view to the block with `anchor`. Returns `false`
+    /// (after noting the problem in history) if there is no such block.
+    pub(crate) fn goto_anchor(&mut self, anchor: &str) -> bool {
+        if anchor.is_empty() {
+            self.add_to_history(HistoryCell::new_error_event(
+                "usage: /goto <anchor>".to_string(),
+            ));
+            return false;
+        }
+        if self.scrollback.borrow_mut().scroll_to_anchor(anchor) {
+            return true;
+        }
+        self.add_to_history(HistoryCell::new_error_event(format!(
+            "no block with anchor `{anchor}`"
+        )));
+        false
+    }
+
+    /// Ask the model to pick up a truncated answer where it stopped. The
+    /// continuation is recorded as part of the same answer rather than as a
+    /// new turn.
+    pub(crate) fn continue_response(&mut self) {
+        if !self.response_truncated {
+            self.add_to_history(HistoryCell::new_error_event(
+                "nothing to continue: the last response was not truncated".to_string(),
+            ));
+            return;
+        }
+        self.response_truncated = false;
+        self.transcript.continue_answer();
+        self.codex_op_tx
+            .send(Op::UserInput {
+                items: vec![InputItem::Text {
+                    text: CONTINUE_PROMPT.to_string(),
+                }],
+            })
+            .unwrap_or_else(|e| {
+                tracing::error!("failed to send continuation: {e}");
+            });
+    }
+
+    /// Ask the model to summarize the session. The transcript is sent along,
+    /// dropping the oldest turns if it is too long, and the answer is shown
+    /// as a summary block instead of a regular answer. The exchange is
+    /// recorded as a turn of its own, with `/summarize` as its prompt.
+    pub(crate) fn summarize_session(&mut self) {
+        if self.bottom_pane.is_task_running() {
+            self.add_to_history(HistoryCell::new_error_event(
+                "wait for the current turn to finish before /summarize".to_string(),
+            ));
+            return;
+        }
+        let (transcript, dropped) = self
+            .transcript
+            .to_markdown_within(SUMMARY_MAX_TRANSCRIPT_CHARS);
+        if transcript.is_empty() {
+            self.add_to_history(HistoryCell::new_error_event(
+                "nothing to summarize yet".to_string(),
+            ));
+            return;
         }
+        let mut text = format!("{SUMMARIZE_PROMPT}\n\n");
+        if dropped > 0 {
+            let note = format!("the {dropped} oldest turn(s) were left out to fit the context");
+            text.push_str(&format!("({note}.)\n\n"));
+            self.add_to_history(HistoryCell::new_background_event(format!(
+                "summarizing: {note}"
+            )));
+        }
+        text.push_str(&transcript);
+        self.transcript.push_user("/summarize");
+        self.summarizing = true;
+        self.codex_op_tx
+            .send(Op::UserInput {
+                items: vec![InputItem::Text { text }],
+            })
+            .unwrap_or_else(|e| {
+                tracing::error!("failed to send summary request: {e}");
+            });
+    }
+
+    /// Switch to the theme named `name` for new output and restyle the
+    /// transcript view with it. Lines already written to the terminal keep
+    /// the style they were written in.
+    pub(crate) fn set_theme(&mut self, name: &str) {
+        let theme = match name {
+            "dark" => Theme::Dark,
+            "light" => Theme::Light,
+            "mono" => Theme::Mono,
+            _ => {
+                self.add_to_history(HistoryCell::new_error_event(
+                    "usage: /theme dark|light|mono".to_string(),
+                ));
+                return;
+            }
+        };
+        self.config.tui.theme = theme;
+        self.stream.set_theme(theme);
+        self.scrollback.borrow_mut().set_theme(theme);
+        self.mark_needs_redraw();
+    }
 
-        for path in image_paths {
-            items.push(InputItem::LocalImage { path });
+    /// Wrap new output at `arg` columns, or at full width for `off`, and
+    /// re-wrap the transcript view to match. Lines already written to the
+    /// terminal keep their width.
+    pub(crate) fn set_wrap(&mut self, arg: &str) {
+        let columns = match arg {
+            "off" => None,
+            n => match n.parse::<u16>() {
+                Ok(n) if n > 0 => Some(n),
+                _ => {
+                    self.add_to_history(HistoryCell::new_error_event(
+                        "usage: /wrap <columns>|off".to_string(),
+                    ));
+                    return;
+                }
+            },
+        };
+        self.config.tui.max_wrap_columns = columns;
+        self.scrollback.borrow_mut().set_max_wrap_columns(columns);
+        self.mark_needs_redraw();
+    }
+
+    /// Show (`on`) or hide (`off`) the blocks of commands that run from now
+    /// on; without an argument, toggle. Hidden commands still count in
+    /// `/stats` and `/copy-commands`.
+    pub(crate) fn set_exec_output(&mut self, arg: &str) {
+        self.exec_output_hidden = match arg {
+            "on" => false,
+            "off" => true,
+            "" => !self.exec_output_hidden,
+            _ => {
+                self.add_to_history(HistoryCell::new_error_event(
+                    "usage: /exec-output on|off".to_string(),
+                ));
+                return;
+            }
+        };
+        if self.exec_output_hidden {
+            self.active_exec_cell = None;
         }
+        self.mark_needs_redraw();
+    }
+
+    /// Theme the history and the rest of the UI are drawn with.
+    pub(crate) fn theme(&self) -> Theme {
+        self.config.tui.theme
+    }
+
+    /// Column limit for wrapping history, if any.
+    pub(crate) fn max_wrap_columns(&self) -> Option<u16> {
+        self.config.tui.max_wrap_columns
+    }
+
+    /// Handle a key while the full-screen transcript view is open. Returns
+    /// `false` when the view should be closed.
+    pub(crate) fn handle_transcript_key_event(&mut self, key_event: KeyEvent) -> bool {
+        if let Some(search) = self.search.as_mut().filter(|s| s.editing) {
+            match key_event.code {
+                KeyCode::Char(c) => search.query.push(c),
+                KeyCode::Backspace => {
+                    search.query.pop();
+                }
+                KeyCode::Enter => {
+                    search.editing = false;
+                    self.step_search(0);
+                }
+                KeyCode::Esc => self.search = None,
+                _ => {}
+            }
+            self.request_redraw();
+            return true;
+        }
+        match key_event.code {
+            KeyCode::Char('/') => {
+                self.search = Some(TranscriptSearch {
+                    editing: true,
+                    ..TranscriptSearch::default()
+                });
+                self.request_redraw();
+                return true;
+            }
+            KeyCode::Char('n') if self.search.is_some() => {
+                self.step_search(1);
+                self.request_redraw();
+                return true;
+            }
+            KeyCode::Char('N') if self.search.is_some() => {
+                self.step_search(-1);
+                self.request_redraw();
+                return true;
+            }
+            KeyCode::Esc if self.search.is_some() => {
+                self.search = None;
+                self.request_redraw();
+                return true;
+            }
+            KeyCode::Tab => {
+                self.step_code_selection(1);
+                self.request_redraw();
+                return true;
+            }
+            KeyCode::BackTab => {
+                self.step_code_selection(-1);
+                self.request_redraw();
+                return true;
+            }
+            KeyCode::Char('c') if self.code_selection.is_some() => {
+                self.copy_selected_code_block();
+                self.request_redraw();
+                return true;
+            }
+            KeyCode::Esc if self.code_selection.is_some() => {
+                self.code_selection = None;
+                self.request_redraw();
+                return true;
+            }
+            _ => {}
+        }
+        if key_event.code == KeyCode::Char('a') {
+            self.toggle_tool_arguments();
+            self.request_redraw();
+            return true;
+        }
+        if key_event.code == KeyCode::Char('p') {
+            self.toggle_prompts();
+            self.request_redraw();
+            return true;
+        }
+        let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+        if key_event.code == KeyCode::Char('d') && !ctrl {
+            self.toggle_patch_details();
+            self.request_redraw();
+            return true;
+        }
+        if self.raw_transcript.is_none() {
+            let handled = match key_event.code {
+                KeyCode::Char('b') => {
+                    self.toggle_bookmark();
+                    true
+                }
+                KeyCode::Char(']') => {
+                    self.step_bookmark(1);
+                    true
+                }
+                KeyCode::Char('[') => {
+                    self.step_bookmark(-1);
+                    true
+                }
+                _ => false,
+            };
+            if handled {
+                self.request_redraw();
+                return true;
+            }
+        }
+        if key_event.code == KeyCode::Char('e') {
+            self.scrollback.borrow_mut().toggle_details();
+            self.request_redraw();
+            return true;
+        }
+        if key_event.code == KeyCode::Char('f') {
+            self.focus_mode = !self.focus_mode;
+            self.request_redraw();
+            return true;
+        }
+        if key_event.code == KeyCode::Char('r') {
+            self.toggle_raw_transcript();
+            self.request_redraw();
+            return true;
+        }
+        {
+            let mut raw = self.raw_transcript.as_ref().map(RefCell::borrow_mut);
+            let mut main = self.scrollback.borrow_mut();
+            let scrollback = match raw.as_deref_mut() {
+                Some(raw) => &mut raw.scrollback,
+                None => &mut *main,
+            };
+            let page = scrollback.page_height() as isize;
+            let half_page = (page / 2).max(1);
+            match key_event.code {
+                KeyCode::Up | KeyCode::Char('k') => scrollback.scroll_by(-1),
+                KeyCode::Down | KeyCode::Char('j') => scrollback.scroll_by(1),
+                KeyCode::PageUp => scrollback.scroll_by(-page),
+                KeyCode::PageDown => scrollback.scroll_by(page),
+                KeyCode::Char('u') if ctrl => scrollback.scroll_by(-half_page),
+                KeyCode::Char('d') if ctrl => scrollback.scroll_by(half_page),
+                KeyCode::Home => scrollback.scroll_to_top(),
+                KeyCode::End => scrollback.scroll_to_bottom(),
+                KeyCode::Esc | KeyCode::Char('q') => return false,
+                _ => return true,
+            }
+        }
+        self.request_redraw();
+        true
+    }
+
+    /// Move the current search match by `delta` (0 picks the first match at
+    /// or below the top of the view) and scroll it into view.
+    fn step_search(&mut self, delta: isize) {
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+        let mut raw = self.raw_transcript.as_ref().map(RefCell::borrow_mut);
+        let mut main = self.scrollback.borrow_mut();
+        let scrollback = match raw.as_deref_mut() {
+            Some(raw) => &mut raw.scrollback,
+            None => &mut *main,
+        };
+        let rows = scrollback.rows(0..scrollback.row_count());
+        let matches = find_matches(&rows, 0, &search.query);
+        if matches.is_empty() {
+            return;
+        }
+        search.current = if delta == 0 {
+            let top = scrollback.visible_rows().start;
+            matches.iter().position(|m| m.row >= top).unwrap_or(0)
+        } else {
+            (search.current as isize + delta).rem_euclid(matches.len() as isize) as usize
+        };
+        scrollback.reveal_row(matches[search.current].row);
+    }
+
+    /// Code blocks of the answers, located in the transcript view.
+    fn code_blocks(&self) -> Vec<CodeBlock> {
+        let scrollback = self.scrollback.borrow();
+        locate_code_blocks(
+            self.transcript
+                .turns()
+                .iter()
+                .map(|turn| turn.answer.as_str()),
+            scrollback.lines(),
+            &scrollback.answer_blocks(),
+        )
+    }
+
+    /// Lines of the `<details>` sections of the answers in the transcript
+    /// view, summary line first.
+    fn details_sections(&self) -> Vec<Range<usize>> {
+        let scrollback = self.scrollback.borrow();
+        locate_details(
+            self.transcript
+                .turns()
+                .iter()
+                .map(|turn| turn.answer.as_str()),
+            scrollback.lines(),
+            &scrollback.answer_blocks(),
+            |body| {
+                let mut lines = Vec::new();
+                append_markdown(body, &mut lines, &self.config);
+                lines.len()
+            },
+        )
+    }
+
+    /// Select the next (`delta` 1) or previous (-1) code block of the focused
+    /// answer: the last one that starts above the bottom of the view.
+    fn step_code_selection(&mut self, delta: isize) {
+        if self.raw_transcript.is_some() {
+            return;
+        }
+        let blocks = self.code_blocks();
+        let mut scrollback = self.scrollback.borrow_mut();
+        let bottom = scrollback.visible_rows().end;
+        let Some(focused) = scrollback
+            .answer_blocks()
+            .into_iter()
+            .rev()
+            .find(|lines| scrollback.rows_of_lines(lines.clone()).start < bottom.max(1))
+        else {
+            return;
+        };
+        let candidates: Vec<usize> = (0..blocks.len())
+            .filter(|&i| focused.contains(&blocks[i].lines.start))
+            .collect();
+        if candidates.is_empty() {
+            return;
+        }
+        let count = candidates.len() as isize;
+        let current = self
+            .code_selection
+            .and_then(|selection| candidates.iter().position(|&i| i == selection.index));
+        let position = match current {
+            Some(position) => (position as isize + delta).rem_euclid(count) as usize,
+            None if delta < 0 => candidates.len() - 1,
+            None => 0,
+        };
+        let index = candidates[position];
+        self.code_selection = Some(CodeSelection {
+            index,
+            position,
+            count: candidates.len(),
+            copied: false,
+        });
+        let rows = scrollback.rows_of_lines(blocks[index].lines.clone());
+        scrollback.reveal_row(rows.start);
+    }
+
+    fn copy_selected_code_block(&mut self) {
+        let blocks = self.code_blocks();
+        let Some(selection) = self.code_selection.as_mut() else {
+            return;
+        };
+        if let Some(block) = blocks.get(selection.index) {
+            self.app_event_tx
+                .send(AppEvent::CopyToClipboard(block.code.clone()));
+            selection.copied = true;
+        }
+    }
+
+    /// Switch the transcript view between the rendered history and the raw
+    /// Markdown source of the conversation.
+    pub(crate) fn toggle_raw_transcript(&mut self) {
+        self.raw_transcript = match self.raw_transcript.take() {
+            Some(_) => None,
+            None => Some(RefCell::new(RawTranscript::new(
+                &self.config,
+                &self.transcript,
+            ))),
+        };
+    }
+
+    /// Draw the full-screen transcript view: the recorded history above a
+    /// one-line key hint.
+    pub(crate) fn render_transcript(&self, area: Rect, buf: &mut Buffer) {
+        if too_small(area) {
+            render_too_small(area, buf);
+            return;
+        }
+        let [body, hint] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(TRANSCRIPT_HINT_HEIGHT),
+        ])
+        .areas(area);
+        let expand = self.config.tui.expand;
+        if !(expand.code && expand.prose) {
+            // Folding an answer needs to know which of its lines are code.
+            let code_lines = self.code_blocks().into_iter().map(|b| b.lines).collect();
+            self.scrollback
+                .borrow_mut()
+                .set_code_lines(code_lines, self.bottom_pane.is_task_running());
+        }
+        let details_lines = self.details_sections();
+        self.scrollback
+            .borrow_mut()
+            .set_details_lines(details_lines);
+        let selected_code = match (&self.raw_transcript, self.code_selection) {
+            (None, Some(selection)) => self.code_blocks().into_iter().nth(selection.index),
+            _ => None,
+        };
+        let mut raw = self.raw_transcript.as_ref().map(RefCell::borrow_mut);
+        if let Some(raw) = raw.as_deref_mut() {
+            raw.update(&self.transcript);
+        }
+        let (top, following) = {
+            let mut main = self.scrollback.borrow_mut();
+            let scrollback = match raw.as_deref_mut() {
+                Some(raw) => &mut raw.scrollback,
+                None => &mut *main,
+            };
+            scrollback.set_viewport(body.width, body.height);
+            let visible = scrollback.visible_rows();
+            let mut rows = scrollback.rows(visible.clone());
+            if self.focus_mode && self.raw_transcript.is_none() {
+                // The focused turn is the last one starting above the bottom
+                // of the view: the latest while following.
+                let starts: Vec<usize> = scrollback.outline().iter().map(|e| e.row).collect();
+                let focused = starts
+                    .iter()
+                    .rposition(|&row| row < visible.end.max(1))
+                    .map(|i| starts[i]..starts.get(i + 1).copied().unwrap_or(usize::MAX));
+                for (row, line) in visible.clone().zip(rows.iter_mut()) {
+                    if !focused.as_ref().is_some_and(|turn| turn.contains(&row)) {
+                        line.style = line.style.add_modifier(Modifier::DIM);
+                    }
+                }
+            }
+            if self.raw_transcript.is_none() {
+                for entry in scrollback.outline() {
+                    let row = entry.row.checked_sub(visible.start);
+                    if let Some(line) = row
+                        .filter(|_| self.bookmarks.contains(&entry.index))
+                        .and_then(|r| rows.get_mut(r))
+                    {
+                        line.spans.push(BOOKMARK_MARKER.yellow());
+                    }
+                }
+            }
+            if let Some(search) = &self.search {
+                let all = scrollback.rows(0..scrollback.row_count());
+                let (match_style, current_style) = match_styles(&self.config.tui);
+                for (i, m) in find_matches(&all, 0, &search.query).into_iter().enumerate() {
+                    if let Some(line) = m
+                        .row
+                        .checked_sub(visible.start)
+                        .and_then(|r| rows.get_mut(r))
+                    {
+                        let style = if i == search.current {
+                            current_style
+                        } else {
+                            match_style
+                        };
+                        *line = highlight(line, m.columns, style);
+                    }
+                }
+            }
+            if let Some(block) = &selected_code {
+                for row in scrollback.rows_of_lines(block.lines.clone()) {
+                    if let Some(line) = row.checked_sub(visible.start).and_then(|r| rows.get_mut(r))
+                    {
+                        for span in &mut line.spans {
+                            span.style = span.style.add_modifier(Modifier::REVERSED);
+                        }
+                    }
+                }
+            }
+            for (row, line) in (body.y..body.bottom()).zip(&rows) {
+                buf.set_line(body.x, row, line, body.width);
+            }
+            (visible.start, scrollback.is_following())
+        };
+        // Turn rows refer to the rendered history, so the raw source has no
+        // turn indicator.
+        let turn = if raw.is_some() {
+            String::new()
+        } else {
+            let outline = self.outline();
+            let total = outline.last().map_or(0, |entry| entry.index + 1);
+            match outline.iter().rev().find(|entry| entry.row <= top) {
+                Some(entry) => format!("turn {}/{total}   ", entry.index + 1),
+                None => String::new(),
+            }
+        };
+        let mode = if raw.is_some() { "r rendered" } else { "r raw" };
+        let state = if following {
+            "following"
+        } else {
+            "scroll locked"
+        };
+        let code_selection = self.code_selection.filter(|_| raw.is_none());
+        let hint_text = match (&self.search, code_selection) {
+            (None, Some(selection)) => {
+                let copied = if selection.copied { " · copied" } else { "" };
+                format!(
+                    " code block {}/{}{copied}   Tab next   c copy   Esc clear   {turn}{state}",
+                    selection.position + 1,
+                    selection.count
+                )
+            }
+            (Some(search), _) if search.editing => format!(" /{}", search.query),
+            (Some(search), _) => format!(
+                " /{}   n/N next/previous   Esc clear   {turn}{state}",
+                search.query
+            ),
+            (None, None) => {
+                let args = if self.tool_call_blocks.is_empty() {
+                    ""
+                } else {
+                    "a args   "
+                };
+                let prompts = match (self.prompt_blocks.is_empty(), self.prompts_collapsed) {
+                    (true, _) => "",
+                    (false, true) => "p expand prompts   ",
+                    (false, false) => "p collapse prompts   ",
+                };
+                let diffs = match (self.patch_blocks.is_empty(), self.patch_details_collapsed) {
+                    (true, _) => "",
+                    (false, true) => "d show diffs   ",
+                    (false, false) => "d hide diffs   ",
+                };
+                // Bookmarks only work in the rendered view.
+                let bookmarks = match (raw.is_some(), self.bookmarks.is_empty()) {
+                    (true, _) => "",
+                    (false, true) => "b bookmark   ",
+                    (false, false) => "b bookmark   ]/[ bookmarks   ",
+                };
+                let details = match self.scrollback.borrow().details_state() {
+                    None => "",
+                    Some(false) => "e expand details   ",
+                    Some(true) => "e collapse details   ",
+                };
+                let focus = if self.focus_mode {
+                    "f unfocus"
+                } else {
+                    "f focus"
+                };
+                format!(
+                    " ↑/↓ scroll   PgUp/PgDn page   Home/End jump   / search   {args}{prompts}{diffs}{details}{bookmarks}{focus}   {mode}   Esc close   {turn}{state}"
+                )
+            }
+        };
+        Line::from(hint_text).dim().render(hint, buf);
+    }
+
+    fn submit_user_message(&mut self, mut user_message: UserMessage) {
+        user_message.resolve_files(&self.config.cwd);
+        let items = user_message.to_input_items();
+        let UserMessage { text, attachments } = user_message;
 
         if items.is_empty() {
             return;
         }
 
+        if self.bottom_pane.is_task_running() {
+            match self.config.tui.concurrent_submit_policy {
+                ConcurrentSubmitPolicy::Queue => {}
+                ConcurrentSubmitPolicy::Reject => {
+                    self.push_system_note(
+                        "A turn is still running, so this prompt was not sent.\nWait for it to finish, or press Esc to interrupt it first.",
+                        SystemNoteLevel::Warn,
+                    );
+                    self.bottom_pane.insert_str(&text);
+                    return;
+                }
+                ConcurrentSubmitPolicy::AbortAndStart => {
+                    self.aborted_turn_id = self.running_turn_id.take();
+                    self.interrupt_running_task();
+                }
+            }
+        }
+
+        // The same text submitted right after the warning is sent as is.
+        let confirmed = self
+            .oversized_prompt
+            .take()
+            .is_some_and(|held| held == text);
+        if let Some(limit) = self.config.tui.max_prompt_chars {
+            let size = prompt_chars(&items);
+            if size > limit && !confirmed {
+                self.push_system_note(
+                    &format!(
+                        "This prompt is {size} characters, over the limit of {limit}, and was not sent.\nPress Enter to send it anyway, or shorten it first."
+                    ),
+                    SystemNoteLevel::Warn,
+                );
+                self.bottom_pane.insert_str(&text);
+                self.oversized_prompt = Some(text);
+                return;
+            }
+        }
+
+        self.last_input = items.clone();
+        self.transient_retries = 0;
+        self.pending_retry = None;
         self.codex_op_tx
             .send(Op::UserInput { items })
             .unwrap_or_else(|e| {
@@ -569,19 +2619,47 @@ impl ChatWidget<'_> {
                 });
         }
 
-        // Only show the text portion in conversation history.
-        if !text.is_empty() {
-            self.add_to_history(HistoryCell::new_user_prompt(text.clone()));
+        self.transcript.push_user(&text);
+        self.response_truncated = false;
+        if self.session_title.is_none() {
+            if let Some(first) = text.lines().map(str::trim).find(|line| !line.is_empty()) {
+                self.set_session_title(truncate_text(first, DERIVED_TITLE_MAX_GRAPHEMES));
+            }
+        }
+
+        // The prompt's text and a marker for each attachment; file contents
+        // and images are only sent to the model.
+        if !text.is_empty() || !attachments.is_empty() {
+            self.add_user_prompt_to_history(&text, &attachments);
+            self.scrollback.borrow_mut().mark_turn_start(&text);
         }
     }
 
     pub(crate) fn handle_codex_event(&mut self, event: Event) {
         // Reset redraw flag for this dispatch
         self.needs_redraw = false;
+        let Some(event) = self.hold_while_paused(event) else {
+            return;
+        };
         let Event { id, msg } = event;
+        // Core reports the interrupt after the new turn may have started;
+        // left through, it would end that turn instead.
+        if self.aborted_turn_id.as_ref() == Some(&id) {
+            return;
+        }
+        // A delta can overtake its `TaskStarted` (e.g. on reconnect); it
+        // opens the turn rather than streaming outside of one.
+        if is_content_delta(&msg) && !self.bottom_pane.is_task_running() {
+            self.on_task_started();
+            self.implicit_turn = true;
+        }
+        if !self.first_token_seen && is_model_output(&msg) {
+            self.on_first_token();
+        }
 
         match msg {
             EventMsg::AgentMessageDelta(_)
+            | EventMsg::AgentMessagePreviewDelta(_)
             | EventMsg::AgentReasoningDelta(_)
             | EventMsg::ExecCommandOutputDelta(_) => {}
             _ => {
@@ -595,19 +2673,31 @@ impl ChatWidget<'_> {
             EventMsg::AgentMessageDelta(AgentMessageDeltaEvent { delta }) => {
                 self.on_agent_message_delta(delta)
             }
+            EventMsg::AgentMessagePreviewDelta(AgentMessagePreviewDeltaEvent { delta }) => {
+                self.on_agent_message_preview_delta(delta)
+            }
             EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent { delta })
             | EventMsg::AgentReasoningRawContentDelta(AgentReasoningRawContentDeltaEvent {
                 delta,
             }) => self.on_agent_reasoning_delta(delta),
-            EventMsg::AgentReasoning(AgentReasoningEvent { .. })
-            | EventMsg::AgentReasoningRawContent(AgentReasoningRawContentEvent { .. }) => {
-                self.on_agent_reasoning_final()
+            EventMsg::AgentReasoning(AgentReasoningEvent { text })
+            | EventMsg::AgentReasoningRawContent(AgentReasoningRawContentEvent { text }) => {
+                self.on_agent_reasoning_final(text)
             }
             EventMsg::AgentReasoningSectionBreak(_) => self.on_reasoning_section_break(),
-            EventMsg::TaskStarted => self.on_task_started(),
+            EventMsg::ResponseTruncated(_) => self.response_truncated = true,
+            EventMsg::TaskStarted => {
+                self.running_turn_id = Some(id);
+                if !std::mem::take(&mut self.implicit_turn) {
+                    self.on_task_started();
+                }
+            }
             EventMsg::TaskComplete(TaskCompleteEvent { .. }) => self.on_task_complete(),
             EventMsg::TokenCount(token_usage) => self.on_token_count(token_usage),
-            EventMsg::Error(ErrorEvent { message }) => self.on_error(message),
+            EventMsg::Error(ErrorEvent { message, transient }) => self.on_error(message, transient),
+            EventMsg::RateLimited(RateLimitedEvent { retry_after }) => {
+                self.on_rate_limited(retry_after)
+            }
             EventMsg::PlanUpdate(update) => self.on_plan_update(update),
             EventMsg::ExecApprovalRequest(ev) => self.on_exec_approval_request(id, ev),
             EventMsg::ApplyPatchApprovalRequest(ev) => self.on_apply_patch_approval_request(id, ev),
@@ -619,6 +2709,10 @@ impl ChatWidget<'_> {
             EventMsg::McpToolCallBegin(ev) => self.on_mcp_tool_call_begin(ev),
             EventMsg::McpToolCallEnd(ev) => self.on_mcp_tool_call_end(ev),
             EventMsg::GetHistoryEntryResponse(ev) => self.on_get_history_entry_response(ev),
+            EventMsg::LastRequest(LastRequestEvent { request }) => self.on_last_request(request),
+            EventMsg::ContextCompacted(ContextCompactedEvent { summary }) => {
+                self.on_context_compacted(summary)
+            }
             EventMsg::ShutdownComplete => self.on_shutdown_complete(),
             EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => self.on_turn_diff(unified_diff),
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
@@ -647,6 +2741,114 @@ impl ChatWidget<'_> {
         self.add_to_history(HistoryCell::new_diff_output(diff_output.clone()));
     }
 
+    /// End the latest turn in `state`, unless it has already ended; a
+    /// `TaskComplete` after an error leaves the turn failed.
+    fn end_turn(&mut self, state: TurnState) {
+        self.clear_answer_preview();
+        if let Some(metrics) = self.turn_metrics.last_mut() {
+            if metrics.state == TurnState::Streaming {
+                metrics.state = state;
+            }
+        }
+    }
+
+    /// Show an app-level note such as "network restored" in the history. The
+    /// note is not sent to the model and does not start a turn; while output
+    /// is streaming it waits, like tool calls, until the stream has been
+    /// written.
+    pub(crate) fn push_system_note(&mut self, text: &str, level: SystemNoteLevel) {
+        let queued = text.to_string();
+        self.defer_or_handle(
+            |q| q.push_system_note(queued, level),
+            |s| s.push_system_note_now(text, level),
+        );
+    }
+
+    pub(crate) fn push_system_note_now(&mut self, text: &str, level: SystemNoteLevel) {
+        self.add_to_history(HistoryCell::new_system_note(text, level));
+        self.mark_needs_redraw();
+    }
+
+    /// The `model via provider` line shown with `tui.model_header`.
+    fn model_header(&self) -> Line<'static> {
+        let mut header = format!(
+            "{} via {}",
+            self.config.model, self.config.model_provider.name
+        );
+        if let Some(title) = &self.session_title {
+            header.push_str(&format!(" · {title}"));
+        }
+        Line::from(header).dim()
+    }
+
+    /// Handle `/title <text>`: name the session. Without text, show the
+    /// current name.
+    pub(crate) fn set_title(&mut self, args: &str) {
+        let title = args.trim();
+        if title.is_empty() {
+            let message = match &self.session_title {
+                Some(current) => format!("usage: /title <text> (current: {current})"),
+                None => "usage: /title <text>".to_string(),
+            };
+            self.add_to_history(HistoryCell::new_error_event(message));
+            return;
+        }
+        self.set_session_title(title.to_string());
+    }
+
+    fn set_session_title(&mut self, title: String) {
+        crate::session_log::log_session_title(&title);
+        self.session_title = Some(title);
+        self.mark_needs_redraw();
+    }
+
+    /// Start of export file names: the session title made file-name safe,
+    /// or `transcript` for an untitled session.
+    fn export_stem(&self) -> String {
+        let Some(title) = &self.session_title else {
+            return "transcript".to_string();
+        };
+        let mut stem = String::new();
+        for c in title.chars().flat_map(char::to_lowercase) {
+            if c.is_alphanumeric() {
+                stem.push(c);
+            } else if !stem.is_empty() && !stem.ends_with('-') {
+                stem.push('-');
+            }
+            if stem.chars().count() >= EXPORT_STEM_MAX_CHARS {
+                break;
+            }
+        }
+        let stem = stem.trim_end_matches('-');
+        if stem.is_empty() {
+            "transcript".to_string()
+        } else {
+            stem.to_string()
+        }
+    }
+
+    /// Handle `/model <name>`: the config a new session with that model is
+    /// started with, or `None` (after explaining why) when the model cannot
+    /// be switched now. The running session keeps its model, so switching
+    /// starts over.
+    pub(crate) fn switch_model(&mut self, args: &str) -> Option<Config> {
+        let model = args.trim();
+        if model.is_empty() {
+            self.add_to_history(HistoryCell::new_error_event(format!(
+                "usage: /model <name> (current: {} via {})",
+                self.config.model, self.config.model_provider.name
+            )));
+            return None;
+        }
+        if self.bottom_pane.is_task_running() {
+            self.add_to_history(HistoryCell::new_error_event(
+                "wait for the current task to finish before switching models".to_string(),
+            ));
+            return None;
+        }
+        Some(self.config.with_model(model))
+    }
+
     pub(crate) fn add_status_output(&mut self) {
         self.add_to_history(HistoryCell::new_status_output(
             &self.config,
@@ -654,6 +2856,165 @@ impl ChatWidget<'_> {
         ));
     }
 
+    /// Show turn, token, command and timing totals for the session so far.
+    pub(crate) fn add_stats_output(&mut self) {
+        let completed: Vec<Duration> = self
+            .turn_metrics
+            .iter()
+            .filter_map(|m| m.duration)
+            .collect();
+        let latencies: Vec<Duration> = self
+            .turn_metrics
+            .iter()
+            .filter_map(|m| m.first_output)
+            .collect();
+        let average_latency = (!latencies.is_empty())
+            .then(|| latencies.iter().sum::<Duration>() / latencies.len() as u32);
+        let stats = SessionStats {
+            turns: self.turns_started,
+            token_usage: self.total_token_usage.clone(),
+            commands: self.exec_totals.commands,
+            failed_commands: self.exec_totals.failed,
+            command_time: self.exec_totals.time,
+            turn_time: completed.iter().sum(),
+            completed_turns: completed.len(),
+            average_latency,
+        };
+        self.add_to_history(HistoryCell::new_stats_output(&stats));
+    }
+
+    /// Write the transcript as Markdown under `$CODEX_HOME/exports` and note
+    /// the destination in history. With `compact`, only the prompts and
+    /// answers are written; with `bookmarks`, only the bookmarked turns.
+    pub(crate) fn export_transcript(&mut self, args: &str) {
+        let stem = self.export_stem();
+        let (prefix, markdown) = match args.trim() {
+            "" => (stem, self.export_markdown()),
+            "compact" => (
+                format!("{stem}-compact"),
+                self.transcript.to_compact_markdown(),
+            ),
+            "bookmarks" if self.bookmarks.is_empty() => {
+                self.add_to_history(HistoryCell::new_error_event(
+                    "no bookmarked turns: press b in the transcript view (Ctrl+T) to bookmark one"
+                        .to_string(),
+                ));
+                return;
+            }
+            "bookmarks" => (format!("{stem}-bookmarks"), self.bookmarks_markdown()),
+            _ => {
+                self.add_to_history(HistoryCell::new_error_event(
+                    "usage: /export [compact|bookmarks]".to_string(),
+                ));
+                return;
+            }
+        };
+        let dir = self.config.codex_home.join("exports");
+        let name = chrono::Local::now().format("-%Y%m%d-%H%M%S.md");
+        let path = dir.join(format!("{prefix}{name}"));
+        let result = std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&path, &markdown));
+        match result {
+            Ok(()) => {
+                // The export keeps the original text, so point out anything
+                // that was marked or stripped on screen.
+                let hidden = match crate::text_formatting::count_control_chars(&markdown) {
+                    0 => String::new(),
+                    n => format!(" (warning: contains {n} bidi, zero-width or control characters)"),
+                };
+                self.add_to_history(HistoryCell::new_background_event(format!(
+                    "transcript exported to {}{hidden}",
+                    path.display()
+                )))
+            }
+            Err(e) => self.add_to_history(HistoryCell::new_error_event(format!(
+                "failed to export transcript: {e}"
+            ))),
+        }
+    }
+
+    /// Markdown rendering of the canonical transcript.
+    pub(crate) fn export_markdown(&self) -> String {
+        self.transcript.to_markdown()
+    }
+
+    /// Markdown rendering of the bookmarked turns only.
+    pub(crate) fn bookmarks_markdown(&self) -> String {
+        self.transcript
+            .turns_markdown(self.bookmarks.iter().copied())
+    }
+
+    /// Copy the commands run in the latest turn, or with `all` in the whole
+    /// session, to the clipboard as a shell script.
+    pub(crate) fn copy_commands(&mut self, args: &str) {
+        let latest_turn = self.turns_started.saturating_sub(1);
+        let all = match args.trim() {
+            "" => false,
+            "all" => true,
+            _ => {
+                self.add_to_history(HistoryCell::new_error_event(
+                    "usage: /copy-commands [all]".to_string(),
+                ));
+                return;
+            }
+        };
+        let commands: Vec<Vec<String>> = self
+            .exec_results
+            .iter()
+            .filter(|result| all || result.turn == latest_turn)
+            .map(|result| result.command.clone())
+            .collect();
+        if commands.is_empty() {
+            let scope = if all { "this session" } else { "this turn" };
+            self.add_to_history(HistoryCell::new_background_event(format!(
+                "no commands ran in {scope}"
+            )));
+            return;
+        }
+        let count = commands.len();
+        self.app_event_tx
+            .send(AppEvent::CopyToClipboard(commands_to_script(&commands)));
+        self.add_to_history(HistoryCell::new_background_event(format!(
+            "{count} command(s) copied to clipboard as a shell script"
+        )));
+    }
+
+    /// Handle `/export-turn <n> <path>`: write only turn `n` (zero-based) as
+    /// Markdown to `path`, resolved against the session's working directory.
+    pub(crate) fn export_turn(&mut self, args: &str) {
+        let Some((index, path)) = args
+            .split_once(char::is_whitespace)
+            .and_then(|(n, path)| Some((n.parse::<usize>().ok()?, path.trim())))
+            .filter(|(_, path)| !path.is_empty())
+        else {
+            self.add_to_history(HistoryCell::new_error_event(
+                "usage: /export-turn <n> <path>".to_string(),
+            ));
+            return;
+        };
+        let Some(markdown) = self.transcript.turn_markdown(index) else {
+            let count = self.transcript.turns().len();
+            self.add_to_history(HistoryCell::new_error_event(format!(
+                "no turn {index}: the transcript has {count} turn(s), numbered from 0"
+            )));
+            return;
+        };
+        let path = self.config.cwd.join(path);
+        let result = match path.parent() {
+            Some(dir) => std::fs::create_dir_all(dir),
+            None => Ok(()),
+        }
+        .and_then(|_| std::fs::write(&path, &markdown));
+        match result {
+            Ok(()) => self.add_to_history(HistoryCell::new_background_event(format!(
+                "turn {index} exported to {}",
+                path.display()
+            ))),
+            Err(e) => self.add_to_history(HistoryCell::new_error_event(format!(
+                "failed to export turn {index}: {e}"
+            ))),
+        }
+    }
+
     pub(crate) fn add_prompts_output(&mut self) {
         self.add_to_history(HistoryCell::new_prompts_output());
     }
@@ -663,26 +3024,61 @@ impl ChatWidget<'_> {
         self.bottom_pane.on_file_search_result(query, matches);
     }
 
-    /// Handle Ctrl-C key press.
-    /// Returns CancellationEvent::Handled if the event was consumed by the UI, or
-    /// CancellationEvent::Ignored if the caller should handle it (e.g. exit).
+    /// Handle Ctrl+C per `tui.ctrl_c`: quit at once, or cancel the running
+    /// turn (or close a dialog) and quit on a second press soon after.
+    /// Returns `Handled` when the press quit or closed a dialog, and
+    /// `Ignored` when it cancelled a turn or only armed the quit hint.
     pub(crate) fn on_ctrl_c(&mut self) -> CancellationEvent {
+        if self.config.tui.ctrl_c == CtrlCBehavior::Quit || self.read_only {
+            self.submit_op(Op::Shutdown);
+            return CancellationEvent::Handled;
+        }
         match self.bottom_pane.on_ctrl_c() {
-            CancellationEvent::Handled => return CancellationEvent::Handled,
+            CancellationEvent::Handled => {
+                // The pane shows the quit hint itself.
+                self.ctrl_c_pressed_at = Some(Instant::now());
+                return CancellationEvent::Handled;
+            }
             CancellationEvent::Ignored => {}
         }
         if self.bottom_pane.is_task_running() {
             self.interrupt_running_task();
+            self.arm_ctrl_c_quit();
             CancellationEvent::Ignored
-        } else if self.bottom_pane.ctrl_c_quit_hint_visible() {
+        } else if self.pending_retry.take().is_some() {
+            self.push_system_note("Automatic retry cancelled.", SystemNoteLevel::Info);
+            self.arm_ctrl_c_quit();
+            CancellationEvent::Ignored
+        } else if self.ctrl_c_quit_armed() {
             self.submit_op(Op::Shutdown);
             CancellationEvent::Handled
         } else {
-            self.bottom_pane.show_ctrl_c_quit_hint();
+            self.arm_ctrl_c_quit();
             CancellationEvent::Ignored
         }
     }
 
+    /// Show the `Ctrl+C again to quit` hint and start its window.
+    fn arm_ctrl_c_quit(&mut self) {
+        self.bottom_pane.show_ctrl_c_quit_hint();
+        self.ctrl_c_pressed_at = Some(Instant::now());
+    }
+
+    /// A Ctrl+C now quits: the hint is still shown and the previous press
+    /// was within `tui.ctrl_c_quit_window_ms`.
+    fn ctrl_c_quit_armed(&self) -> bool {
+        let window = Duration::from_millis(
+            self.config
+                .tui
+                .ctrl_c_quit_window_ms
+                .unwrap_or(DEFAULT_CTRL_C_QUIT_WINDOW_MS),
+        );
+        self.bottom_pane.ctrl_c_quit_hint_visible()
+            && self
+                .ctrl_c_pressed_at
+                .is_some_and(|at| at.elapsed() <= window)
+    }
+
     pub(crate) fn composer_is_empty(&self) -> bool {
         self.bottom_pane.composer_is_empty()
     }
@@ -692,6 +3088,14 @@ impl ChatWidget<'_> {
     }
     /// Forward an `Op` directly to codex.
     pub(crate) fn submit_op(&self, op: Op) {
+        // A followed log has no agent to send to; quitting is all that is
+        // left of a shutdown.
+        if self.read_only {
+            if matches!(op, Op::Shutdown) {
+                self.app_event_tx.send(AppEvent::ExitRequest);
+            }
+            return;
+        }
         // Record outbound operation for session replay fidelity.
         crate::session_log::log_outbound_op(&op);
         if let Err(e) = self.codex_op_tx.send(op) {
@@ -723,14 +3127,102 @@ impl ChatWidget<'_> {
     }
 
     pub fn cursor_pos(&self, area: Rect) -> Option<(u16, u16)> {
-        let [_, bottom_pane_area] = self.layout_areas(area);
+        if too_small(area) {
+            return None;
+        }
+        let [_, _, _, _, _, _, bottom_pane_area] = self.layout_areas(area);
         self.bottom_pane.cursor_pos(bottom_pane_area)
     }
 }
 
+#[cfg(test)]
+impl ChatWidget<'_> {
+    /// Whether the latest turn is still running.
+    pub(crate) fn is_streaming(&self) -> bool {
+        self.current_turn()
+            .is_some_and(|turn| self.turn_state(turn) == TurnState::Streaming)
+    }
+
+    /// State of turn `index`, counted like `current_turn`. Turns not started
+    /// yet are `Pending`.
+    pub(crate) fn turn_state(&self, index: usize) -> TurnState {
+        self.turn_metrics
+            .get(index)
+            .map_or(TurnState::Pending, |metrics| metrics.state)
+    }
+
+    /// Zero-based index of the latest turn, or `None` before the first
+    /// `TaskStarted`. It stays put after the turn completes.
+    pub(crate) fn current_turn(&self) -> Option<usize> {
+        self.turns_started.checked_sub(1)
+    }
+
+    /// Number of turns started so far.
+    pub(crate) fn turn_count(&self) -> usize {
+        self.turns_started
+    }
+
+    /// Commands that have finished this session, oldest first.
+    pub(crate) fn exec_results(&self) -> &[ExecResult] {
+        &self.exec_results
+    }
+
+    /// Source of every turn so far, oldest first: prompts, reasoning,
+    /// answers, commands and errors as they arrived, independent of how they
+    /// were rendered. The turns are copies, ready to serialize.
+    pub(crate) fn transcript_structured(&self) -> Vec<crate::transcript::TurnRecord> {
+        self.transcript.turns().to_vec()
+    }
+
+    /// Free the stored reasoning of transcript turn `turn` to bound memory in
+    /// long sessions. What was rendered, including the reasoning itself, stays
+    /// in the history, and the answer is kept; exports leave the reasoning
+    /// out. The latest turn is kept while its task runs, since reasoning may
+    /// still be appended. Returns whether the reasoning was dropped.
+    pub(crate) fn drop_reasoning(&mut self, turn: usize) -> bool {
+        let running = self.bottom_pane.is_task_running();
+        if running && turn + 1 == self.transcript.turns().len() {
+            return false;
+        }
+        self.transcript.drop_reasoning(turn)
+    }
+}
+
 impl WidgetRef for &ChatWidget<'_> {
     fn render_ref(&self, area: Rect, buf: &mut Buffer) {
-        let [active_cell_area, bottom_pane_area] = self.layout_areas(area);
+        if too_small(area) {
+            render_too_small(area, buf);
+            return;
+        }
+        let [
+            header_area,
+            settling_area,
+            caret_area,
+            preview_area,
+            plan_area,
+            active_cell_area,
+            bottom_pane_area,
+        ] = self.layout_areas(area);
+        if self.shows_model_header() {
+            self.model_header().render(header_area, buf);
+        }
+        Paragraph::new(self.settling_summary_rows()).render(settling_area, buf);
+        if let Some(held) = &self.paused_events {
+            Line::from(format!(
+                "paused · {} event(s) held · Ctrl+S to resume",
+                held.len()
+            ))
+            .dim()
+            .render(caret_area, buf);
+        } else if let Some(status) = &self.agent_status {
+            Line::from(status.clone()).dim().render(caret_area, buf);
+        } else if self.shows_streaming_caret() && (self.caret_ticks / CARET_BLINK_TICKS) % 2 == 0 {
+            Line::from(STREAMING_CARET).render(caret_area, buf);
+        }
+        Paragraph::new(self.answer_preview_rows(area.width)).render(preview_area, buf);
+        if let Some(cell) = &self.live_plan {
+            cell.render_ref(plan_area, buf);
+        }
         (&self.bottom_pane).render(bottom_pane_area, buf);
         if let Some(cell) = &self.active_exec_cell {
             cell.render_ref(active_cell_area, buf);
@@ -738,6 +3230,78 @@ impl WidgetRef for &ChatWidget<'_> {
     }
 }
 
+fn too_small(area: Rect) -> bool {
+    area.width < MIN_RENDER_WIDTH || area.height < MIN_RENDER_HEIGHT
+}
+
+fn render_too_small(area: Rect, buf: &mut Buffer) {
+    if area.is_empty() {
+        return;
+    }
+    let notice = truncate_text(TOO_SMALL_NOTICE, area.width as usize);
+    Line::from(notice)
+        .dim()
+        .render(Rect { height: 1, ..area }, buf);
+}
+
+/// The `thinking` header followed by the reasoning's first line (usually its
+/// bold title) and its length.
+fn quiet_reasoning_summary(text: &str, config: &Tui) -> Line<'static> {
+    let title = text
+        .lines()
+        .map(|line| line.trim().trim_matches(|c| c == '*' || c == '#').trim())
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    let chars = text.chars().count();
+    let mut line = render_header_line(StreamKind::Reasoning, config.theme, &config.header_glyphs);
+    line.spans
+        .push(format!(" · {} ({chars} chars)", truncate_text(title, 60)).dim());
+    line
+}
+
+/// Size of the text the model receives for a prompt: the prompt itself and
+/// the contents of its attached files.
+fn prompt_chars(items: &[InputItem]) -> usize {
+    items
+        .iter()
+        .map(|item| match item {
+            InputItem::Text { text } => text.chars().count(),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Whether `msg` carries model output: reasoning or answer text.
+fn is_model_output(msg: &EventMsg) -> bool {
+    matches!(
+        msg,
+        EventMsg::AgentMessage(_)
+            | EventMsg::AgentMessageDelta(_)
+            | EventMsg::AgentMessagePreviewDelta(_)
+            | EventMsg::AgentReasoning(_)
+            | EventMsg::AgentReasoningDelta(_)
+            | EventMsg::AgentReasoningRawContent(_)
+            | EventMsg::AgentReasoningRawContentDelta(_)
+    )
+}
+
+fn is_content_delta(msg: &EventMsg) -> bool {
+    matches!(
+        msg,
+        EventMsg::AgentMessageDelta(_)
+            | EventMsg::AgentMessagePreviewDelta(_)
+            | EventMsg::AgentReasoningDelta(_)
+            | EventMsg::AgentReasoningRawContentDelta(_)
+    )
+}
+
+/// Status line shown while reasoning streams, e.g. `thinking · 1200 chars (~300 tokens)`.
+/// Tokens are estimated at four characters each.
+fn reasoning_counter_text(chars: usize) -> String {
+    let tokens = chars.div_ceil(4);
+    format!("thinking · {chars} chars (~{tokens} tokens)")
+}
+
 fn add_token_usage(current_usage: &TokenUsage, new_usage: &TokenUsage) -> TokenUsage {
     let cached_input_tokens = match (
         current_usage.cached_input_tokens,
@@ -9,6 +9,7 @@ use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::PatchApplyEndEvent;
 
 use super::ChatWidget;
+use crate::history_cell::SystemNoteLevel;
 
 #[derive(Debug)]
 pub(crate) enum QueuedInterrupt {
@@ -19,6 +20,7 @@ pub(crate) enum QueuedInterrupt {
     McpBegin(McpToolCallBeginEvent),
     McpEnd(McpToolCallEndEvent),
     PatchEnd(PatchApplyEndEvent),
+    SystemNote(String, SystemNoteLevel),
 }
 
 #[derive(Default)]
@@ -71,6 +73,11 @@ impl InterruptManager {
         self.queue.push_back(QueuedInterrupt::PatchEnd(ev));
     }
 
+    pub(crate) fn push_system_note(&mut self, text: String, level: SystemNoteLevel) {
+        self.queue
+            .push_back(QueuedInterrupt::SystemNote(text, level));
+    }
+
     pub(crate) fn flush_all(&mut self, chat: &mut ChatWidget<'_>) {
         while let Some(q) = self.queue.pop_front() {
             match q {
@@ -83,6 +90,7 @@ impl InterruptManager {
                 QueuedInterrupt::McpBegin(ev) => chat.handle_mcp_begin_now(ev),
                 QueuedInterrupt::McpEnd(ev) => chat.handle_mcp_end_now(ev),
                 QueuedInterrupt::PatchEnd(ev) => chat.handle_patch_apply_end_now(ev),
+                QueuedInterrupt::SystemNote(text, level) => chat.push_system_note_now(&text, level),
             }
         }
     }
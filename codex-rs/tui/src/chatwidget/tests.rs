@@ -3,6 +3,8 @@
 use super::*;
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
+use crate::follow::LogFollower;
+use crate::transcript::TurnRecord;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::config::ConfigToml;
@@ -12,33 +14,45 @@ use codex_core::plan_tool::UpdatePlanArgs;
 use codex_core::protocol::AgentMessageDeltaEvent;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningDeltaEvent;
+use codex_core::protocol::AgentReasoningEvent;
+use codex_core::protocol::AgentReasoningSectionBreakEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::FileChange;
+use codex_core::protocol::LastRequestEvent;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::ReviewDecision;
 use codex_core::protocol::TaskCompleteEvent;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyModifiers;
 use pretty_assertions::assert_eq;
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::BufRead;
 use std::io::BufReader;
 use std::io::Read;
+use std::io::Write;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::mpsc::channel;
 use tokio::sync::mpsc::unbounded_channel;
 
 fn test_config() -> Config {
     // Use base defaults to avoid depending on host state.
-    codex_core::config::Config::load_from_base_config_with_overrides(
+    let mut config = codex_core::config::Config::load_from_base_config_with_overrides(
         ConfigToml::default(),
         ConfigOverrides::default(),
         std::env::temp_dir(),
     )
-    .expect("config")
+    .expect("config");
+    // The caret blinks with commit ticks; keep renders deterministic unless a
+    // test turns it back on.
+    config.tui.streaming_caret = StreamingCaret::Off;
+    config
 }
 
 #[test]
@@ -102,7 +116,7 @@ async fn helpers_are_available_and_do_not_panic() {
     let (tx_raw, _rx) = channel::<AppEvent>();
     let tx = AppEventSender::new(tx_raw);
     let cfg = test_config();
-    let mut w = ChatWidget::new(cfg, tx, None, Vec::new(), false);
+    let mut w = ChatWidget::new(cfg, tx, None, false);
     // Basic construction sanity.
     let _ = &mut w;
 }
@@ -117,6 +131,8 @@ fn make_chatwidget_manual() -> (
     let app_event_tx = AppEventSender::new(tx_raw);
     let (op_tx, op_rx) = unbounded_channel::<Op>();
     let cfg = test_config();
+    let focus_mode = cfg.tui.focus_mode;
+    let scrollback = Rc::new(RefCell::new(Scrollback::new(&cfg.tui)));
     let bottom = BottomPane::new(BottomPaneParams {
         app_event_tx: app_event_tx.clone(),
         has_input_focus: true,
@@ -125,10 +141,13 @@ fn make_chatwidget_manual() -> (
     let widget = ChatWidget {
         app_event_tx,
         codex_op_tx: op_tx,
+        read_only: false,
         bottom_pane: bottom,
         active_exec_cell: None,
         config: cfg.clone(),
         initial_user_message: None,
+        initial_prompt: InitialPrompt::default(),
+        prefilled_attachments: Vec::new(),
         total_token_usage: TokenUsage::default(),
         last_token_usage: TokenUsage::default(),
         stream: StreamController::new(cfg),
@@ -137,6 +156,54 @@ fn make_chatwidget_manual() -> (
         task_complete_pending: false,
         interrupts: InterruptManager::new(),
         needs_redraw: false,
+        transcript: Transcript::new(),
+        scrollback,
+        response_truncated: false,
+        summarizing: false,
+        last_input: Vec::new(),
+        transient_retries: 0,
+        pending_retry: None,
+        rate_limits: 0,
+        retry_clock: Box::new(SystemRetryClock),
+        ctrl_c_pressed_at: None,
+        oversized_prompt: None,
+        quiet_reasoning: None,
+        turns_started: 0,
+        implicit_turn: false,
+        running_turn_id: None,
+        aborted_turn_id: None,
+        first_token_seen: false,
+        plan_anchor: None,
+        live_plan: None,
+        raw_transcript: None,
+        search: None,
+        paused_events: None,
+        tool_arguments_collapsed: false,
+        tool_call_blocks: Vec::new(),
+        prompts_collapsed: true,
+        prompt_blocks: Vec::new(),
+        patch_details_collapsed: false,
+        patch_blocks: Vec::new(),
+        code_selection: None,
+        focus_mode,
+        held_output: Vec::new(),
+        answer_preview: None,
+        agent_status: None,
+        open_bubble: Rc::new(RefCell::new(OpenBubble::default())),
+        held_reasoning: Rc::new(RefCell::new(HeldReasoning::default())),
+        screen: ratatui::layout::Size::default(),
+        compact_layout: false,
+        transcript_view_open: false,
+        exec_results: Vec::new(),
+        exec_totals: ExecTotals::default(),
+        turn_metrics: Vec::new(),
+        plain_log: None,
+        caret_ticks: 0,
+        session_title: None,
+        exec_output_hidden: false,
+        settling_summary: None,
+        bookmarks: std::collections::BTreeSet::new(),
+        resume_scroll: None,
     };
     (widget, rx, op_rx)
 }
@@ -188,12 +255,47 @@ fn open_fixture(name: &str) -> std::fs::File {
     File::open(name).expect("open fixture file")
 }
 
-#[tokio::test(flavor = "current_thread")]
-async fn binary_size_transcript_matches_ideal_fixture() {
+/// Feed the `codex_event` records of a session log fixture to `chat`, but
+/// only for the turns whose zero-based index satisfies `keep`. A turn starts
+/// at each `task_started` event; events before the first one (e.g.
+/// `session_configured`) are always replayed. Start/stop bounds are
+/// expressed as a range, e.g. `|turn| (1..3).contains(&turn)`.
+fn replay_fixture_turns(chat: &mut ChatWidget<'_>, name: &str, keep: impl Fn(usize) -> bool) {
+    let reader = BufReader::new(open_fixture(name));
+    let mut turns_seen = 0;
+    let mut current: Option<usize> = None;
+    for line in reader.lines() {
+        let line = line.expect("read line");
+        let v: serde_json::Value = serde_json::from_str(&line).expect("parse line");
+        if v.get("kind").and_then(|k| k.as_str()) != Some("codex_event") {
+            continue;
+        }
+        let payload = v.get("payload").cloned().expect("payload");
+        let ev: Event = serde_json::from_value(payload).expect("parse event");
+        if matches!(ev.msg, EventMsg::TaskStarted) {
+            current = Some(turns_seen);
+            turns_seen += 1;
+        }
+        if current.is_none_or(&keep) {
+            chat.handle_codex_event(ev);
+        }
+    }
+}
+
+/// What replaying a session log leaves behind: the terminal screen the
+/// history was written to (rows trimmed, trailing blank rows dropped) and the
+/// text of every inserted line.
+struct ReplayOutput {
+    screen: Vec<String>,
+    transcript: String,
+}
+
+/// Replay the codex events and commit ticks sent to the TUI in the fixture
+/// `name` into a fresh widget, writing the history to a VT100 terminal
+/// `width` columns wide.
+fn replay_log(name: &str, width: u16) -> ReplayOutput {
     let (mut chat, rx, _op_rx) = make_chatwidget_manual();
 
-    // Set up a VT100 test terminal to capture ANSI visual output
-    let width: u16 = 80;
     let height: u16 = 2000;
     let viewport = ratatui::layout::Rect::new(0, height - 1, width, 1);
     let backend = ratatui::backend::TestBackend::new(width, height);
@@ -201,11 +303,21 @@ async fn binary_size_transcript_matches_ideal_fixture() {
         .expect("failed to construct terminal");
     terminal.set_viewport_area(viewport);
 
-    // Replay the recorded session into the widget and collect transcript
-    let file = open_fixture("binary-size-log.jsonl");
-    let reader = BufReader::new(file);
+    let reader = BufReader::new(open_fixture(name));
     let mut transcript = String::new();
     let mut ansi: Vec<u8> = Vec::new();
+    let mut insert_pending = || {
+        while let Ok(app_ev) = rx.try_recv() {
+            if let AppEvent::InsertHistory(lines) = app_ev {
+                transcript.push_str(&lines_to_single_string(&lines));
+                crate::insert_history::insert_history_lines_to_writer(
+                    &mut terminal,
+                    &mut ansi,
+                    lines,
+                );
+            }
+        }
+    };
 
     for line in reader.lines() {
         let line = line.expect("read line");
@@ -215,54 +327,282 @@ async fn binary_size_transcript_matches_ideal_fixture() {
         let Ok(v): Result<serde_json::Value, _> = serde_json::from_str(&line) else {
             continue;
         };
-        let Some(dir) = v.get("dir").and_then(|d| d.as_str()) else {
-            continue;
-        };
-        if dir != "to_tui" {
+        if v.get("dir").and_then(|d| d.as_str()) != Some("to_tui") {
             continue;
         }
-        let Some(kind) = v.get("kind").and_then(|k| k.as_str()) else {
-            continue;
-        };
-
-        match kind {
-            "codex_event" => {
+        match v.get("kind").and_then(|k| k.as_str()) {
+            Some("codex_event") => {
                 if let Some(payload) = v.get("payload") {
                     let ev: Event = serde_json::from_value(payload.clone()).expect("parse");
                     chat.handle_codex_event(ev);
-                    while let Ok(app_ev) = rx.try_recv() {
-                        if let AppEvent::InsertHistory(lines) = app_ev {
-                            transcript.push_str(&lines_to_single_string(&lines));
-                            crate::insert_history::insert_history_lines_to_writer(
-                                &mut terminal,
-                                &mut ansi,
-                                lines,
-                            );
-                        }
-                    }
+                    insert_pending();
                 }
             }
-            "app_event" => {
-                if let Some(variant) = v.get("variant").and_then(|s| s.as_str()) {
-                    if variant == "CommitTick" {
-                        chat.on_commit_tick();
-                        while let Ok(app_ev) = rx.try_recv() {
-                            if let AppEvent::InsertHistory(lines) = app_ev {
-                                transcript.push_str(&lines_to_single_string(&lines));
-                                crate::insert_history::insert_history_lines_to_writer(
-                                    &mut terminal,
-                                    &mut ansi,
-                                    lines,
-                                );
-                            }
-                        }
-                    }
+            Some("app_event") => {
+                if v.get("variant").and_then(|s| s.as_str()) == Some("CommitTick") {
+                    chat.on_commit_tick();
+                    insert_pending();
                 }
             }
             _ => {}
         }
     }
 
+    // Build the final VT100 visual by parsing the ANSI stream.
+    let mut parser = vt100::Parser::new(height, width, 0);
+    parser.process(&ansi);
+    let mut screen: Vec<String> = (0..height)
+        .map(|row| {
+            let s: String = (0..width)
+                .map(|col| {
+                    parser
+                        .screen()
+                        .cell(row, col)
+                        .and_then(|cell| cell.contents().chars().next())
+                        .unwrap_or(' ')
+                })
+                .collect();
+            s.trim_end().to_string()
+        })
+        .collect();
+    while screen.last().is_some_and(|l| l.is_empty()) {
+        screen.pop();
+    }
+    ReplayOutput { screen, transcript }
+}
+
+/// Where two replays first differ: a screen row or a transcript line, with
+/// the text on each side (`None` past the end).
+#[derive(Debug, PartialEq)]
+enum Divergence {
+    Screen {
+        row: usize,
+        left: Option<String>,
+        right: Option<String>,
+    },
+    Transcript {
+        line: usize,
+        left: Option<String>,
+        right: Option<String>,
+    },
+}
+
+/// The first divergence between two replays, checking the screen first.
+fn first_divergence(left: &ReplayOutput, right: &ReplayOutput) -> Option<Divergence> {
+    fn first_difference<S: AsRef<str>>(
+        left: &[S],
+        right: &[S],
+    ) -> Option<(usize, Option<String>, Option<String>)> {
+        (0..left.len().max(right.len())).find_map(|i| {
+            let l = left.get(i).map(|s| s.as_ref().to_string());
+            let r = right.get(i).map(|s| s.as_ref().to_string());
+            (l != r).then_some((i, l, r))
+        })
+    }
+    if let Some((row, left, right)) = first_difference(&left.screen, &right.screen) {
+        return Some(Divergence::Screen { row, left, right });
+    }
+    let left_lines: Vec<&str> = left.transcript.lines().collect();
+    let right_lines: Vec<&str> = right.transcript.lines().collect();
+    first_difference(&left_lines, &right_lines).map(|(line, left, right)| Divergence::Transcript {
+        line,
+        left,
+        right,
+    })
+}
+
+/// Replay two logs and report where their output first differs.
+fn compare_logs(left: &str, right: &str, width: u16) -> Option<Divergence> {
+    first_divergence(&replay_log(left, width), &replay_log(right, width))
+}
+
+/// What one record of a session log made the widget insert into history.
+#[derive(Debug)]
+struct EventRender {
+    /// `type` of the codex event (e.g. `agent_message_delta`), or
+    /// `CommitTick`.
+    event: String,
+    /// Text of every line inserted while handling the record, in order.
+    inserted: Vec<String>,
+}
+
+/// Replay the codex events and commit ticks of the fixture `name` into a
+/// fresh widget, recording which history lines each record produced. Keys
+/// are line numbers in the fixture, counting from 1.
+fn replay_log_by_event(name: &str) -> BTreeMap<usize, EventRender> {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    let reader = BufReader::new(open_fixture(name));
+    let mut renders = BTreeMap::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.expect("read line");
+        let Ok(v): Result<serde_json::Value, _> = serde_json::from_str(&line) else {
+            continue;
+        };
+        if v.get("dir").and_then(|d| d.as_str()) != Some("to_tui") {
+            continue;
+        }
+        let event = match v.get("kind").and_then(|k| k.as_str()) {
+            Some("codex_event") => {
+                let Some(payload) = v.get("payload") else {
+                    continue;
+                };
+                let ev: Event = serde_json::from_value(payload.clone()).expect("parse");
+                let kind = payload
+                    .pointer("/msg/type")
+                    .and_then(|t| t.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                chat.handle_codex_event(ev);
+                kind
+            }
+            Some("app_event")
+                if v.get("variant").and_then(|s| s.as_str()) == Some("CommitTick") =>
+            {
+                chat.on_commit_tick();
+                "CommitTick".to_string()
+            }
+            _ => continue,
+        };
+        let inserted = drain_insert_history(&rx)
+            .iter()
+            .flatten()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        renders.insert(index + 1, EventRender { event, inserted });
+    }
+    renders
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn each_history_line_is_attributed_to_the_record_that_produced_it() {
+    let renders = replay_log_by_event("longer-markdown-session.jsonl");
+    let turn_starts: Vec<usize> = renders
+        .iter()
+        .filter(|(_, r)| r.event == "task_started")
+        .map(|(line, _)| *line)
+        .collect();
+    assert_eq!(turn_starts.len(), 3);
+    let turn_two = turn_starts[1]..turn_starts[2];
+
+    let is_header = |text: &String| text.trim() == "codex";
+    let (header_line, header_render) = renders
+        .range(turn_two.clone())
+        .find(|(_, r)| r.inserted.iter().any(is_header))
+        .expect("turn 2 has a codex header");
+    // Nothing of turn 2 is inserted before its header, and the header is
+    // inserted together with the first line of the answer, not on its own.
+    assert!(
+        renders
+            .range(turn_two.start..*header_line)
+            .all(|(_, r)| r.inserted.is_empty()),
+        "{renders:#?}"
+    );
+    assert!(
+        header_render.event.starts_with("agent_message") || header_render.event == "CommitTick",
+        "{header_render:?}"
+    );
+    let header_at = header_render
+        .inserted
+        .iter()
+        .position(is_header)
+        .expect("header");
+    assert!(
+        header_render.inserted[header_at + 1..]
+            .iter()
+            .any(|text| text.contains("Second turn")),
+        "{header_render:?}"
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn a_log_replayed_against_itself_has_no_divergence() {
+    assert_eq!(
+        compare_logs("binary-size-log.jsonl", "binary-size-log.jsonl", 80),
+        None
+    );
+    // Different sessions do diverge, at the first row that differs.
+    match compare_logs("tool-call.jsonl", "truncated-answer.jsonl", 80) {
+        Some(Divergence::Screen { left, right, .. }) => assert_ne!(left, right),
+        other => panic!("expected a screen divergence, got {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn box_art_keeps_its_alignment_and_is_clipped_rather_than_wrapped() {
+    const BOXES: [&[&str]; 2] = [
+        &[
+            "+--------+     +---------+     +----------+",
+            "| client | --> | gateway | --> | service  |",
+            "+--------+     +---------+     +----------+",
+        ],
+        &[
+            "┌──────────┬───────┐",
+            "│ cache    │  hit  │",
+            "│ database │  miss │",
+            "└──────────┴───────┘",
+        ],
+    ];
+
+    // Wide enough: every row comes out exactly as the model drew it.
+    let screen = replay_log("box-art.jsonl", 80).screen;
+    for rows in BOXES {
+        let start = screen
+            .iter()
+            .position(|row| row == rows[0])
+            .unwrap_or_else(|| panic!("missing {:?} in {screen:#?}", rows[0]));
+        assert_eq!(&screen[start..start + rows.len()], rows);
+    }
+
+    // Too narrow: each row is clipped to one screen row, while prose still
+    // wraps.
+    let width = 20;
+    let screen = replay_log("box-art.jsonl", width).screen;
+    for rows in BOXES {
+        let clipped: Vec<String> = rows
+            .iter()
+            .map(|row| row.chars().take(usize::from(width)).collect())
+            .collect();
+        let start = screen
+            .iter()
+            .position(|row| *row == clipped[0])
+            .unwrap_or_else(|| panic!("missing {:?} in {screen:#?}", clipped[0]));
+        assert_eq!(screen[start..start + clipped.len()], clipped[..]);
+    }
+    assert!(
+        screen.iter().any(|row| row == "Both paths log the"),
+        "{screen:#?}"
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn each_agent_message_of_a_turn_renders_under_its_own_header_in_order() {
+    let screen = replay_log("two-messages.jsonl", 80).screen;
+    let position = |needle: &str| {
+        screen
+            .iter()
+            .position(|row| row.contains(needle))
+            .unwrap_or_else(|| panic!("missing {needle:?} in {screen:#?}"))
+    };
+    let headers: Vec<usize> = screen
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| row.as_str() == "codex")
+        .map(|(i, _)| i)
+        .collect();
+    assert_eq!(headers.len(), 2, "{screen:#?}");
+
+    let first = position("I'll check whether Cargo.lock matches the manifests.");
+    let command = position("cargo metadata --locked --format-version 1");
+    let second = position("The lockfile is up to date");
+    assert!(headers[0] < first, "{screen:#?}");
+    assert!(first < command && command < headers[1], "{screen:#?}");
+    assert!(headers[1] < second, "{screen:#?}");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn binary_size_transcript_matches_ideal_fixture() {
+    let lines = replay_log("binary-size-log.jsonl", 80).screen;
+
     // Read the ideal fixture as-is
     let mut f = open_fixture("ideal-binary-response.txt");
     let mut ideal = String::new();
@@ -271,30 +611,6 @@ async fn binary_size_transcript_matches_ideal_fixture() {
     // Normalize line endings for Windows vs. Unix checkouts
     let ideal = ideal.replace("\r\n", "\n");
 
-    // Build the final VT100 visual by parsing the ANSI stream. Trim trailing spaces per line
-    // and drop trailing empty lines so the shape matches the ideal fixture exactly.
-    let mut parser = vt100::Parser::new(height, width, 0);
-    parser.process(&ansi);
-    let mut lines: Vec<String> = Vec::with_capacity(height as usize);
-    for row in 0..height {
-        let mut s = String::with_capacity(width as usize);
-        for col in 0..width {
-            if let Some(cell) = parser.screen().cell(row, col) {
-                if let Some(ch) = cell.contents().chars().next() {
-                    s.push(ch);
-                } else {
-                    s.push(' ');
-                }
-            } else {
-                s.push(' ');
-            }
-        }
-        // Trim trailing spaces to match plain text fixture
-        lines.push(s.trim_end().to_string());
-    }
-    while lines.last().is_some_and(|l| l.is_empty()) {
-        lines.pop();
-    }
     // Compare only after the last session banner marker, and start at the next 'thinking' line.
     const MARKER_PREFIX: &str = ">_ You are using OpenAI Codex in ";
     let last_marker_line_idx = lines
@@ -440,6 +756,205 @@ fn apply_patch_events_emit_history_cells() {
     );
 }
 
+fn render_contains(chat: &ChatWidget<'_>, needle: &str) -> bool {
+    let area = ratatui::layout::Rect::new(0, 0, 80, 12);
+    let mut buf = ratatui::buffer::Buffer::empty(area);
+    chat.render_ref(area, &mut buf);
+    (0..area.height).any(|y| {
+        let row: String = (0..area.width)
+            .map(|x| buf[(x, y)].symbol().chars().next().unwrap_or(' '))
+            .collect();
+        row.contains(needle)
+    })
+}
+
+fn exec_approval_request(command: &[&str]) -> EventMsg {
+    EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
+        call_id: "call-exec".into(),
+        command: command.iter().map(|s| s.to_string()).collect(),
+        cwd: PathBuf::from("/tmp"),
+        reason: None,
+    })
+}
+
+#[test]
+fn exec_policy_allowlisted_command_auto_approves_and_unknown_prompts() {
+    let (mut chat, rx, mut op_rx) = make_chatwidget_manual();
+    chat.config.tui.exec_policy.allow = vec!["git status".to_string()];
+
+    chat.handle_codex_event(Event {
+        id: "sub-allow".into(),
+        msg: exec_approval_request(&["bash", "-lc", "git status --short"]),
+    });
+    match op_rx.try_recv() {
+        Ok(Op::ExecApproval { id, decision }) => {
+            assert_eq!(id, "sub-allow");
+            assert!(matches!(decision, ReviewDecision::Approved));
+        }
+        other => panic!("expected auto-approval op, got {other:?}"),
+    }
+    assert!(
+        !render_contains(&chat, "Allow command?"),
+        "allowlisted command should not show the approval modal"
+    );
+    assert!(
+        std::iter::from_fn(|| rx.try_recv().ok()).any(|e| matches!(e, AppEvent::RequestRedraw)),
+        "auto-approval should request a redraw"
+    );
+
+    chat.handle_codex_event(Event {
+        id: "sub-unknown".into(),
+        msg: exec_approval_request(&["bash", "-lc", "curl example.com"]),
+    });
+    assert!(
+        op_rx.try_recv().is_err(),
+        "unknown command must not be answered"
+    );
+    assert!(
+        render_contains(&chat, "Allow command?"),
+        "unknown command should show the approval modal"
+    );
+}
+
+#[test]
+fn dangerous_command_escalates_approval_to_typed_confirmation() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.exec_policy.dangerous = vec!["rm -rf".to_string()];
+
+    chat.handle_codex_event(Event {
+        id: "sub-danger".into(),
+        msg: exec_approval_request(&["bash", "-lc", "cd /tmp && rm -rf build"]),
+    });
+    assert!(render_contains(&chat, "Dangerous command"));
+    assert!(render_contains(&chat, "Type yes to run this command"));
+    assert!(!render_contains(&chat, "Allow command?"));
+
+    // The y/n shortcut does not apply: `y` followed by Enter denies.
+    chat.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    let decisions: Vec<ReviewDecision> = rx
+        .try_iter()
+        .filter_map(|ev| match ev {
+            AppEvent::CodexOp(Op::ExecApproval { decision, .. }) => Some(decision),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(decisions, vec![ReviewDecision::Denied]);
+}
+
+#[test]
+fn exec_policy_denied_command_renders_error_note() {
+    let (mut chat, rx, mut op_rx) = make_chatwidget_manual();
+    chat.config.tui.exec_policy.deny = vec!["rm -rf".to_string()];
+
+    chat.handle_codex_event(Event {
+        id: "sub-deny".into(),
+        msg: exec_approval_request(&["rm", "-rf", "/"]),
+    });
+    match op_rx.try_recv() {
+        Ok(Op::ExecApproval { id, decision }) => {
+            assert_eq!(id, "sub-deny");
+            assert!(matches!(decision, ReviewDecision::Denied));
+        }
+        other => panic!("expected auto-denial op, got {other:?}"),
+    }
+    let cells = drain_insert_history(&rx);
+    let blob = lines_to_single_string(cells.last().expect("error cell"));
+    assert!(
+        blob.contains("denied by exec_policy rule `rm -rf`"),
+        "missing denial note: {blob:?}"
+    );
+}
+
+#[test]
+fn show_request_renders_model_and_user_prompt_and_copies() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+
+    let request = serde_json::json!({
+        "model": "gpt-5",
+        "instructions": "You are Codex.",
+        "input": [{
+            "type": "message",
+            "role": "user",
+            "content": [{ "type": "input_text", "text": "what does main.rs do?" }],
+        }],
+        "reasoning": { "effort": "medium" },
+    });
+    chat.handle_codex_event(Event {
+        id: "sub-req".into(),
+        msg: EventMsg::LastRequest(LastRequestEvent {
+            request: Some(request),
+        }),
+    });
+
+    let mut copied = None;
+    let mut cells = Vec::new();
+    while let Ok(ev) = rx.try_recv() {
+        match ev {
+            AppEvent::CopyToClipboard(text) => copied = Some(text),
+            AppEvent::InsertHistory(lines) => cells.push(lines),
+            _ => {}
+        }
+    }
+    let blob = lines_to_single_string(cells.last().expect("request cell"));
+    assert!(blob.contains("/show-request"), "{blob:?}");
+    assert!(blob.contains("model: \"gpt-5\""), "{blob:?}");
+    assert!(blob.contains("what does main.rs do?"), "{blob:?}");
+    let copied = copied.expect("request copied to clipboard");
+    assert!(copied.contains("what does main.rs do?"));
+}
+
+#[test]
+fn auto_copy_copies_full_answer_when_turn_completes() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.auto_copy_answers = true;
+    let answer = "Run the tests with:\n\n```\ncargo test\n```\n\nThen commit.";
+
+    chat.handle_codex_event(Event {
+        id: "c".into(),
+        msg: EventMsg::TaskStarted,
+    });
+    chat.handle_codex_event(Event {
+        id: "c".into(),
+        msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+            delta: answer.into(),
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "c".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: answer.into(),
+        }),
+    });
+
+    // Nothing is copied until the turn is over.
+    let mut copied = Vec::new();
+    while let Ok(ev) = rx.try_recv() {
+        if let AppEvent::CopyToClipboard(text) = ev {
+            copied.push(text);
+        }
+    }
+    assert!(copied.is_empty());
+
+    chat.handle_codex_event(Event {
+        id: "c".into(),
+        msg: EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: Some(answer.into()),
+        }),
+    });
+    let mut cells = Vec::new();
+    while let Ok(ev) = rx.try_recv() {
+        match ev {
+            AppEvent::CopyToClipboard(text) => copied.push(text),
+            AppEvent::InsertHistory(lines) => cells.push(lines),
+            _ => {}
+        }
+    }
+    assert_eq!(copied, vec![answer.to_string()]);
+    let blob = lines_to_single_string(cells.last().expect("indicator cell"));
+    assert!(blob.contains("answer copied to clipboard"), "{blob:?}");
+}
+
 #[test]
 fn apply_patch_approval_sends_op_with_submission_id() {
     let (mut chat, rx, _op_rx) = make_chatwidget_manual();
@@ -832,3 +1347,3679 @@ fn multiple_agent_messages_in_single_turn_emit_multiple_headers() {
     let second_idx = combined.find("Second message").unwrap();
     assert!(first_idx < second_idx, "messages out of order: {combined}");
 }
+
+#[test]
+fn reasoning_counter_tracks_aggregated_length_until_answer_starts() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::TaskStarted,
+    });
+
+    for delta in ["abc", "de\nf", "gh"] {
+        chat.handle_codex_event(Event {
+            id: "t".into(),
+            msg: EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
+                delta: delta.into(),
+            }),
+        });
+    }
+    assert_eq!(chat.stream.reasoning_chars(), Some(9));
+    chat.on_commit_tick();
+    assert_eq!(chat.stream.reasoning_chars(), Some(9));
+    assert_eq!(reasoning_counter_text(9), "thinking · 9 chars (~3 tokens)");
+
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+            delta: "Answer".into(),
+        }),
+    });
+    assert_eq!(chat.stream.reasoning_chars(), None);
+}
+
+#[test]
+fn quiet_reasoning_shows_indicator_then_summary_at_answer_start() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.reasoning_display = ReasoningDisplay::Quiet;
+    chat.config.tui.reasoning_transition = ReasoningTransition::Off;
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::TaskStarted,
+    });
+
+    for delta in [
+        "**Planning the fix**\n\n",
+        "First I will read ",
+        "the parser.\n",
+    ] {
+        chat.handle_codex_event(Event {
+            id: "t".into(),
+            msg: EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
+                delta: delta.into(),
+            }),
+        });
+        chat.on_commit_tick();
+    }
+    assert!(drain_insert_history(&rx).is_empty());
+    assert!(chat.bottom_pane.is_task_running());
+    // The status line types its text out, a few characters per frame.
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(2);
+    while !render_contains(&chat, "thinking…") {
+        assert!(
+            std::time::Instant::now() < deadline,
+            "no thinking indicator"
+        );
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    assert!(!render_contains(&chat, "Planning the fix"));
+
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+            delta: "Done.\n".into(),
+        }),
+    });
+    assert!(!render_contains(&chat, "thinking…"));
+    let cells = drain_insert_history(&rx);
+    let first = lines_to_single_string(cells.first().expect("summary cell"));
+    assert_eq!(first, "thinking · Planning the fix (52 chars)\n\n");
+    let all: String = cells.iter().map(|c| lines_to_single_string(c)).collect();
+    assert!(!all.contains("read the parser"), "{all}");
+}
+
+/// What a quiet-reasoning turn leaves on screen after each step: the history
+/// lines inserted by the step and whether the live area shows the summary.
+fn quiet_turn_steps(transition: ReasoningTransition) -> Vec<(String, bool)> {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.reasoning_display = ReasoningDisplay::Quiet;
+    chat.config.tui.reasoning_transition = transition;
+    let mut steps = Vec::new();
+    let mut record = |chat: &ChatWidget<'_>| {
+        let inserted: String = drain_insert_history(&rx)
+            .iter()
+            .map(|lines| lines_to_single_string(lines))
+            .collect();
+        steps.push((inserted, render_contains(chat, "Planning the fix")));
+    };
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::TaskStarted,
+    });
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
+            delta: "**Planning the fix**\n\nRead the parser.\n".into(),
+        }),
+    });
+    record(&chat);
+    for delta in ["Done.\n", "Tests pass.\n"] {
+        chat.handle_codex_event(Event {
+            id: "t".into(),
+            msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+                delta: delta.into(),
+            }),
+        });
+        record(&chat);
+    }
+    for _ in 0..8 {
+        chat.on_commit_tick();
+        record(&chat);
+    }
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: None,
+        }),
+    });
+    record(&chat);
+    steps
+}
+
+#[test]
+fn reasoning_transition_off_matches_the_unanimated_baseline() {
+    let summary = "thinking · Planning the fix (39 chars)\n\n";
+    let steps = quiet_turn_steps(ReasoningTransition::Off);
+    assert!(steps.iter().all(|(_, live)| !live), "{steps:?}");
+    let inserted: Vec<&str> = steps.iter().map(|(text, _)| text.as_str()).collect();
+    assert_eq!(inserted[0], "");
+    assert_eq!(inserted[1], summary);
+    let answer: String = inserted[2..].concat();
+    assert!(
+        answer.contains("Done.") && answer.contains("Tests pass."),
+        "{answer}"
+    );
+
+    // Settling only delays the history; what ends up there is the same.
+    let settled = quiet_turn_steps(ReasoningTransition::Settle);
+    assert!(settled[1].0.is_empty() && settled[1].1, "{settled:?}");
+    let history = |steps: &[(String, bool)]| -> String {
+        steps.iter().map(|(text, _)| text.as_str()).collect()
+    };
+    assert_eq!(history(&settled), history(&steps));
+    assert!(history(&steps).starts_with(summary));
+}
+
+/// Assert that the newest block is a `kind` stream and that it fills the
+/// transcript view: its last row is on screen and nothing above it is.
+fn assert_active_block_in_view(chat: &ChatWidget<'_>, kind: BlockKind) {
+    let scrollback = chat.scrollback.borrow();
+    let (last_kind, rows) = scrollback.last_block().unwrap();
+    assert_eq!(last_kind, kind);
+    let visible = scrollback.visible_rows();
+    assert!(
+        visible.start >= rows.start && visible.end == rows.end,
+        "active {kind:?} block {rows:?} not in view {visible:?}"
+    );
+}
+
+#[test]
+fn transcript_view_follows_reasoning_then_answer() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    let area = Rect::new(0, 0, 40, 6);
+    let mut buf = Buffer::empty(area);
+    chat.render_transcript(area, &mut buf);
+
+    chat.submit_text_message("one\ntwo\nthree\nfour\nfive\nsix".to_string());
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::TaskStarted,
+    });
+
+    let thoughts: String = (0..8).map(|i| format!("thought {i}\n")).collect();
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent { delta: thoughts }),
+    });
+    for _ in 0..10 {
+        chat.on_commit_tick();
+        assert_active_block_in_view(&chat, BlockKind::Reasoning);
+    }
+
+    let answer: String = (0..8).map(|i| format!("answer {i}\n")).collect();
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent { delta: answer }),
+    });
+    for _ in 0..10 {
+        chat.on_commit_tick();
+        assert_active_block_in_view(&chat, BlockKind::Answer);
+    }
+    assert!(chat.scrollback.borrow().is_following());
+
+    // Scrolling up locks the view while the answer keeps streaming.
+    chat.handle_transcript_key_event(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE));
+    let locked_top = chat.scrollback.borrow().visible_rows().start;
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+            delta: "more\nand more\n".into(),
+        }),
+    });
+    for _ in 0..3 {
+        chat.on_commit_tick();
+    }
+    assert_eq!(chat.scrollback.borrow().visible_rows().start, locked_top);
+    assert!(!chat.scrollback.borrow().is_following());
+}
+
+#[test]
+fn outline_lists_each_turn_with_its_prompt_summary() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    chat.submit_text_message("How do I list files?\nIncluding hidden ones.".to_string());
+    chat.handle_codex_event(Event {
+        id: "t1".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: "Use `ls -a`.".into(),
+        }),
+    });
+    chat.submit_text_message(format!("Now explain {} please", "x".repeat(80)));
+
+    let outline = chat.outline();
+    assert_eq!(
+        outline
+            .iter()
+            .map(|entry| (entry.index, entry.summary.clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            (0, "How do I list files?".to_string()),
+            (1, format!("Now explain {}...", "x".repeat(45))),
+        ]
+    );
+    assert_eq!(outline[0].row, 0);
+    assert!(outline[1].row > outline[0].row);
+}
+
+#[test]
+fn goto_scrolls_referenced_block_header_into_view() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.show_anchors = true;
+    chat.scrollback = Rc::new(RefCell::new(Scrollback::new(&chat.config.tui)));
+    let area = Rect::new(0, 0, 60, 6);
+    let mut buf = Buffer::empty(area);
+    chat.render_transcript(area, &mut buf);
+
+    chat.submit_text_message("the first question".to_string());
+    for i in 0..10 {
+        chat.submit_text_message(format!("follow-up {i}"));
+    }
+    let anchor = chat.scrollback.borrow().anchors()[0].clone();
+
+    assert!(chat.goto_anchor(&anchor));
+    let mut buf = Buffer::empty(area);
+    chat.render_transcript(area, &mut buf);
+    let screen: String = buf.content().iter().map(|c| c.symbol()).collect();
+    assert!(screen.contains(&format!("user  #{anchor}")), "{screen}");
+    assert!(!chat.scrollback.borrow().is_following());
+
+    assert!(!chat.goto_anchor("nope"));
+}
+
+#[test]
+fn raw_toggle_shows_markdown_source_in_transcript_view() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    chat.submit_text_message("format it".to_string());
+    chat.handle_codex_event(Event {
+        id: "r".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: "This is **bold** text.".into(),
+        }),
+    });
+    let area = Rect::new(0, 0, 100, 10);
+    let render = |chat: &ChatWidget| {
+        let mut buf = Buffer::empty(area);
+        chat.render_transcript(area, &mut buf);
+        buf.content().iter().map(|c| c.symbol()).collect::<String>()
+    };
+
+    let screen = render(&chat);
+    assert!(screen.contains("This is bold text."), "{screen}");
+    assert!(!screen.contains("**"), "{screen}");
+
+    let r = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE);
+    assert!(chat.handle_transcript_key_event(r));
+    let screen = render(&chat);
+    assert!(screen.contains("This is **bold** text."), "{screen}");
+    assert!(screen.contains("r rendered"), "{screen}");
+
+    assert!(chat.handle_transcript_key_event(r));
+    let screen = render(&chat);
+    assert!(!screen.contains("**"), "{screen}");
+}
+
+#[test]
+fn resizing_across_the_full_layout_width_toggles_the_compact_layout() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.model_header = true;
+    chat.config.tui.full_layout_min_width = Some(60);
+
+    chat.set_viewport(ratatui::layout::Size::new(80, 30));
+    assert!(!chat.compact_layout);
+    let full = chat.desired_height(80);
+
+    chat.set_viewport(ratatui::layout::Size::new(59, 30));
+    assert!(chat.compact_layout);
+    // The model header row is left out.
+    assert_eq!(chat.desired_height(80), full - 1);
+    assert!(
+        std::iter::from_fn(|| rx.try_recv().ok()).any(|e| matches!(e, AppEvent::RequestRedraw))
+    );
+
+    chat.set_viewport(ratatui::layout::Size::new(60, 30));
+    assert!(!chat.compact_layout);
+    assert_eq!(chat.desired_height(80), full);
+
+    // Without a threshold the layout stays full at any width.
+    chat.config.tui.full_layout_min_width = None;
+    chat.set_viewport(ratatui::layout::Size::new(40, 30));
+    assert!(!chat.compact_layout);
+}
+
+#[test]
+fn smaller_viewport_shows_fewer_rows() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    for i in 0..40 {
+        chat.submit_text_message(format!("question {i}"));
+    }
+    let chrome = chat.desired_height(80);
+
+    chat.set_viewport(ratatui::layout::Size::new(80, 30));
+    let tall = chat.content_viewport();
+    assert_eq!(tall, Rect::new(0, 0, 80, 30 - chrome));
+    assert_eq!(
+        chat.scrollback.borrow().visible_rows().len(),
+        usize::from(tall.height)
+    );
+
+    chat.set_viewport(ratatui::layout::Size::new(80, 15));
+    let short = chat.content_viewport();
+    assert_eq!(short.height, tall.height - 15);
+    assert_eq!(
+        chat.scrollback.borrow().visible_rows().len(),
+        usize::from(short.height)
+    );
+}
+
+#[test]
+fn transcript_view_keeps_its_own_viewport() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    for i in 0..40 {
+        chat.submit_text_message(format!("question {i}"));
+    }
+    chat.set_viewport(ratatui::layout::Size::new(80, 30));
+    chat.set_transcript_view_open(true);
+    assert_eq!(chat.content_viewport(), Rect::new(0, 0, 80, 29));
+
+    // Drawing the view and a later resize agree on the rows shown.
+    let area = Rect::new(0, 0, 80, 30);
+    chat.render_transcript(area, &mut Buffer::empty(area));
+    chat.set_viewport(ratatui::layout::Size::new(80, 30));
+    assert_eq!(chat.scrollback.borrow().visible_rows().len(), 29);
+
+    chat.set_transcript_view_open(false);
+    let chrome = chat.desired_height(80);
+    assert_eq!(
+        chat.scrollback.borrow().visible_rows().len(),
+        usize::from(30 - chrome)
+    );
+}
+
+#[test]
+fn reasoning_cap_hides_earlier_lines_but_export_keeps_them() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.max_reasoning_lines = Some(2);
+    chat.scrollback = Rc::new(RefCell::new(Scrollback::new(&chat.config.tui)));
+    let area = Rect::new(0, 0, 60, 12);
+    let mut buf = Buffer::empty(area);
+    chat.render_transcript(area, &mut buf);
+
+    chat.submit_text_message("think".to_string());
+    let thoughts: String = (0..5).map(|i| format!("thought {i}\n")).collect();
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
+            delta: thoughts.clone(),
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::AgentReasoning(AgentReasoningEvent { text: thoughts }),
+    });
+    // Committed lines reach history one per tick.
+    for _ in 0..10 {
+        chat.on_commit_tick();
+    }
+
+    let mut buf = Buffer::empty(area);
+    chat.render_transcript(area, &mut buf);
+    let screen: String = buf.content().iter().map(|c| c.symbol()).collect();
+    assert!(
+        screen.contains("…earlier reasoning hidden (3 lines)"),
+        "{screen}"
+    );
+    assert!(
+        screen.contains("thought 3") && screen.contains("thought 4"),
+        "{screen}"
+    );
+    assert!(!screen.contains("thought 2"), "{screen}");
+
+    // The terminal gets the same last lines, written once the block ends.
+    let terminal = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(
+        terminal.contains("…earlier reasoning hidden (3 lines)\nthought 3\nthought 4\n"),
+        "{terminal:?}"
+    );
+    assert!(!terminal.contains("thought 2"), "{terminal:?}");
+
+    let export = chat.export_markdown();
+    for i in 0..5 {
+        assert!(export.contains(&format!("thought {i}")), "{export}");
+    }
+}
+
+#[test]
+fn theme_switch_restyles_already_rendered_codex_header() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: "Hello".into(),
+        }),
+    });
+    let header_fg = |lines: &[ratatui::text::Line<'static>]| {
+        lines
+            .iter()
+            .flat_map(|l| &l.spans)
+            .find(|span| span.content == "codex")
+            .and_then(|span| span.style.fg)
+    };
+    let inserted: Vec<_> = drain_insert_history(&rx).concat();
+    assert_eq!(header_fg(&inserted), Some(ratatui::style::Color::Magenta));
+
+    chat.set_theme("light");
+    // The terminal scrollback is left alone; the transcript view is restyled.
+    assert!(drain_insert_history(&rx).is_empty());
+    let scrollback = chat.scrollback.borrow();
+    assert_eq!(
+        header_fg(scrollback.lines()),
+        Some(ratatui::style::Color::Blue)
+    );
+    assert!(lines_to_single_string(scrollback.lines()).contains("Hello"));
+}
+
+#[test]
+fn wrap_command_reflows_existing_paragraph_to_the_limit() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    let paragraph = "The quick brown fox jumps over the lazy dog while the patient \
+                     cat watches from the windowsill and wonders why anyone would \
+                     bother jumping over a dog at all when there is a perfectly good \
+                     path around it.";
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: paragraph.into(),
+        }),
+    });
+    let _ = drain_insert_history(&rx);
+
+    chat.set_wrap("60");
+    assert_eq!(chat.max_wrap_columns(), Some(60));
+    // The terminal scrollback is left alone; the transcript view re-wraps.
+    assert!(drain_insert_history(&rx).is_empty());
+    let replaced = chat.scrollback.borrow().lines().to_vec();
+
+    let width: u16 = 120;
+    let height: u16 = 20;
+    let backend = ratatui::backend::TestBackend::new(width, height);
+    let mut terminal = crate::custom_terminal::Terminal::with_options(backend)
+        .expect("failed to construct terminal");
+    terminal.set_viewport_area(Rect::new(0, height - 1, width, 1));
+    let mut ansi: Vec<u8> = Vec::new();
+    crate::insert_history::insert_wrapped_history_lines_to_writer(
+        &mut terminal,
+        &mut ansi,
+        replaced,
+        chat.max_wrap_columns(),
+        None,
+    );
+    let mut parser = vt100::Parser::new(height, width, 0);
+    parser.process(&ansi);
+    let rows: Vec<String> = parser
+        .screen()
+        .contents()
+        .lines()
+        .map(|l| l.trim_end().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+
+    // The header plus at least four rows of paragraph; at the terminal's
+    // full width it would fit in two.
+    assert!(rows.len() >= 5, "{rows:?}");
+    for row in &rows {
+        assert!(row.chars().count() <= 60, "{row:?}");
+    }
+    assert!(rows.iter().any(|row| row.chars().count() > 50), "{rows:?}");
+
+    chat.set_wrap("off");
+    assert_eq!(chat.max_wrap_columns(), None);
+}
+
+#[test]
+fn tiny_terminal_shows_notice_and_recovers_when_enlarged() {
+    let (chat, _rx, _op_rx) = make_chatwidget_manual();
+    let row = |buf: &Buffer, y: u16| -> String {
+        (0..buf.area.width)
+            .map(|x| buf[(x, y)].symbol().to_string())
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    };
+
+    for (width, height) in [(5, 1), (80, 1), (10, 12), (0, 0)] {
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        (&chat).render_ref(area, &mut buf);
+        chat.render_transcript(area, &mut buf);
+        assert_eq!(chat.cursor_pos(area), None);
+        if height > 0 {
+            let expected = crate::text_formatting::truncate_text(TOO_SMALL_NOTICE, width as usize);
+            assert_eq!(row(&buf, 0), expected);
+        }
+    }
+
+    let area = Rect::new(0, 0, 80, 12);
+    let mut buf = Buffer::empty(area);
+    (&chat).render_ref(area, &mut buf);
+    assert!((0..area.height).all(|y| !row(&buf, y).contains(TOO_SMALL_NOTICE)));
+}
+
+#[test]
+fn replaying_only_the_second_turn_still_emits_its_codex_header() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    replay_fixture_turns(&mut chat, "longer-markdown-session.jsonl", |turn| turn == 1);
+
+    let lines: Vec<ratatui::text::Line<'static>> =
+        drain_insert_history(&rx).into_iter().flatten().collect();
+    let text = lines_to_single_string(&lines);
+    assert!(
+        lines.iter().any(|l| {
+            l.spans
+                .iter()
+                .map(|s| s.content.as_ref())
+                .collect::<String>()
+                == "codex"
+        }),
+        "missing codex header:\n{text}"
+    );
+    assert!(text.contains("sudo apt install build-essential"), "{text}");
+    assert!(
+        !text.contains("linker."),
+        "first turn was replayed:\n{text}"
+    );
+    assert!(
+        !text.contains("Third turn"),
+        "third turn was replayed:\n{text}"
+    );
+    assert_eq!(chat.turn_count(), 1);
+}
+
+#[test]
+fn header_glyphs_render_before_labels_and_are_not_exported() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.header_glyphs = codex_core::config_types::HeaderGlyphs {
+        user: Some("🧑".into()),
+        codex: Some("🤖".into()),
+        thinking: None,
+    };
+    chat.stream = StreamController::new(chat.config.clone());
+    chat.scrollback = Rc::new(RefCell::new(Scrollback::new(&chat.config.tui)));
+
+    chat.submit_text_message("Hi".to_string());
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
+            delta: "**Greeting**".into(),
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::AgentReasoning(AgentReasoningEvent {
+            text: "**Greeting**".into(),
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: "Hello!".into(),
+        }),
+    });
+    let lines: Vec<ratatui::text::Line<'static>> =
+        drain_insert_history(&rx).into_iter().flatten().collect();
+
+    let header = |label: &str| {
+        lines
+            .iter()
+            .find(|l| l.spans.len() == 2 && l.spans[1].content == label)
+            .unwrap_or_else(|| panic!("no {label} header in {lines:?}"))
+            .spans[0]
+            .clone()
+    };
+    let (user, codex, thinking) = (header("user"), header("codex"), header("thinking"));
+    assert_eq!(user.content, "🧑 ");
+    assert_eq!(codex.content, "🤖 ");
+    // No thinking glyph, so the label is indented to line up with the others.
+    assert_eq!(thinking.content, "   ");
+    assert_eq!(user.width(), thinking.width());
+
+    let export = chat.transcript.to_markdown();
+    assert!(export.contains("### codex"), "{export}");
+    assert!(!export.contains('🧑') && !export.contains('🤖'), "{export}");
+}
+
+#[test]
+fn exec_results_keep_the_latest_commands_and_stats_count_all() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    for i in 0..=EXEC_RESULTS_MAX {
+        chat.handle_codex_event(Event {
+            id: "t".into(),
+            msg: EventMsg::ExecCommandEnd(ExecCommandEndEvent {
+                call_id: format!("call-{i}"),
+                stdout: String::new(),
+                stderr: String::new(),
+                exit_code: i32::from(i % 2 == 0),
+                duration: std::time::Duration::from_secs(1),
+            }),
+        });
+    }
+    let _ = drain_insert_history(&rx);
+
+    let results = chat.exec_results();
+    assert_eq!(results.len(), EXEC_RESULTS_MAX);
+    assert_eq!(results[0].command, vec!["call-1".to_string()]);
+
+    chat.add_stats_output();
+    let text = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(
+        text.contains("Commands: 501 in 8m21s, 251 failed"),
+        "{text}"
+    );
+}
+
+#[test]
+fn exec_footer_shows_exit_code_and_duration_and_results_are_recorded() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    replay_fixture_turns(&mut chat, "failing-command.jsonl", |_| true);
+    let lines: Vec<ratatui::text::Line<'static>> =
+        drain_insert_history(&rx).into_iter().flatten().collect();
+
+    let footer = |text: &str| {
+        lines
+            .iter()
+            .find(|l| lines_to_single_string(std::slice::from_ref(*l)).trim() == text)
+            .unwrap_or_else(|| panic!("no {text:?} footer in {lines:?}"))
+            .spans[0]
+            .style
+    };
+    let failed = footer("exit 101 · 2.34s");
+    assert_eq!(failed.fg, Some(ratatui::style::Color::Red));
+    let succeeded = footer("exit 0 · 48ms");
+    assert_ne!(succeeded.fg, Some(ratatui::style::Color::Red));
+    assert!(
+        succeeded
+            .add_modifier
+            .contains(ratatui::style::Modifier::DIM)
+    );
+
+    let results: Vec<(String, i32, std::time::Duration)> = chat
+        .exec_results()
+        .iter()
+        .map(|r| (r.command.join(" "), r.exit_code, r.duration))
+        .collect();
+    assert_eq!(
+        results,
+        vec![
+            (
+                "bash -lc cargo test -p demo".to_string(),
+                101,
+                std::time::Duration::from_millis(2340)
+            ),
+            (
+                "bash -lc git status --short".to_string(),
+                0,
+                std::time::Duration::from_nanos(48_211_000)
+            ),
+        ]
+    );
+}
+
+#[test]
+fn compaction_marker_and_summary_render_and_are_exported() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.submit_text_message("Why does the build fail?".to_string());
+    replay_fixture_turns(&mut chat, "context-compacted.jsonl", |_| true);
+    let lines: Vec<ratatui::text::Line<'static>> =
+        drain_insert_history(&rx).into_iter().flatten().collect();
+    let text = lines_to_single_string(&lines);
+
+    let marker = lines
+        .iter()
+        .find(|l| lines_to_single_string(std::slice::from_ref(*l)).trim() == "context compacted")
+        .unwrap_or_else(|| panic!("no marker in:\n{text}"));
+    assert!(
+        marker.spans[0]
+            .style
+            .add_modifier
+            .contains(ratatui::style::Modifier::DIM)
+    );
+    assert!(text.contains("it was renamed to `total`."), "{text}");
+    assert!(text.contains("Remaining: re-run the tests"), "{text}");
+
+    let export = chat.transcript.to_markdown();
+    assert!(
+        export.contains(
+            "### context compacted\n\nThe user is fixing a failing `cargo test` in the demo crate."
+        ),
+        "{export}"
+    );
+    assert!(
+        export.contains("### user\n\nWhy does the build fail?"),
+        "{export}"
+    );
+}
+
+#[test]
+fn log_only_reasoning_is_written_to_its_file_and_never_rendered() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    let dir = tempfile::TempDir::new().expect("temp dir");
+    let path = dir.path().join("reasoning.log");
+    chat.config.tui.reasoning_display = ReasoningDisplay::LogOnly;
+    chat.config.tui.reasoning_log_path = Some(path.clone());
+
+    chat.submit_text_message("Fix the parser".to_string());
+    drain_insert_history(&rx);
+    for msg in [
+        EventMsg::TaskStarted,
+        EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
+            delta: "**Planning the fix**\n\nRead the parser.\n".into(),
+        }),
+        EventMsg::AgentReasoningSectionBreak(AgentReasoningSectionBreakEvent {}),
+        EventMsg::AgentReasoning(AgentReasoningEvent {
+            text: "**Planning the fix**\n\nRead the parser.".into(),
+        }),
+        EventMsg::AgentMessage(AgentMessageEvent {
+            message: "Fixed.".into(),
+        }),
+        EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: None,
+        }),
+    ] {
+        chat.handle_codex_event(Event {
+            id: "t".into(),
+            msg,
+        });
+        chat.on_commit_tick();
+    }
+
+    let shown = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(shown.contains("Fixed."), "{shown}");
+    assert!(!shown.contains("thinking"), "{shown}");
+    assert!(!shown.contains("Read the parser"), "{shown}");
+    assert_eq!(
+        std::fs::read_to_string(&path).expect("read reasoning log"),
+        "**Planning the fix**\n\nRead the parser.\n\n"
+    );
+    assert_eq!(
+        chat.transcript.turns()[0].reasoning,
+        "**Planning the fix**\n\nRead the parser."
+    );
+}
+
+#[test]
+fn plain_log_gets_prompt_and_answer_when_the_turn_completes() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    let dir = tempfile::TempDir::new().expect("temp dir");
+    let path = dir.path().join("conversation.log");
+    chat.plain_log = Some(crate::plain_log::PlainLog::new(path.clone()));
+
+    chat.submit_text_message("What is **2 + 2**?".to_string());
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::TaskStarted,
+    });
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: "It is **4**.".into(),
+        }),
+    });
+    assert!(
+        !path.exists(),
+        "nothing is written before the turn completes"
+    );
+
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: None,
+        }),
+    });
+    let log = std::fs::read_to_string(&path).expect("read plain log");
+    assert_eq!(
+        log,
+        "== user ==\nWhat is **2 + 2**?\n\n== codex ==\nIt is **4**.\n\n"
+    );
+
+    // A continued answer belongs to the turn already written; only what it
+    // added is appended.
+    chat.response_truncated = true;
+    chat.continue_response();
+    chat.handle_codex_event(Event {
+        id: "c".into(),
+        msg: EventMsg::TaskStarted,
+    });
+    chat.handle_codex_event(Event {
+        id: "c".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: " Always.".into(),
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "c".into(),
+        msg: EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: None,
+        }),
+    });
+    let log = std::fs::read_to_string(&path).expect("read plain log");
+    assert_eq!(
+        log,
+        "== user ==\nWhat is **2 + 2**?\n\n== codex ==\nIt is **4**.\n\n\
+         == codex (continued) ==\n Always.\n\n"
+    );
+}
+
+#[test]
+fn streaming_caret_shows_while_answer_streams_and_never_reaches_history() {
+    let stream_answer = |caret: StreamingCaret| {
+        let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+        chat.config.tui.streaming_caret = caret;
+        let area = Rect::new(0, 0, 40, 10);
+        // Only the status row is searched: the composer's left border is
+        // drawn with the same glyph.
+        let caret_rows = |chat: &ChatWidget<'_>| {
+            let mut buf = Buffer::empty(area);
+            (&chat).render_ref(area, &mut buf);
+            let status = chat.layout_areas(area)[2];
+            (status.top()..status.bottom())
+                .filter(|&y| buf[(status.x, y)].symbol() == STREAMING_CARET)
+                .collect::<Vec<_>>()
+        };
+
+        chat.handle_codex_event(Event {
+            id: "t".into(),
+            msg: EventMsg::TaskStarted,
+        });
+        let idle_height = chat.desired_height(area.width);
+        for delta in ["Line one\n", "Line two\n", "Line th"] {
+            chat.handle_codex_event(Event {
+                id: "t".into(),
+                msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+                    delta: delta.into(),
+                }),
+            });
+        }
+        let streaming = (
+            caret_rows(&chat),
+            chat.desired_height(area.width) - idle_height,
+        );
+
+        chat.handle_codex_event(Event {
+            id: "t".into(),
+            msg: EventMsg::AgentMessage(AgentMessageEvent {
+                message: "Line one\nLine two\nLine three".into(),
+            }),
+        });
+        chat.handle_codex_event(Event {
+            id: "t".into(),
+            msg: EventMsg::TaskComplete(TaskCompleteEvent {
+                last_agent_message: None,
+            }),
+        });
+        assert_eq!(caret_rows(&chat), Vec::<u16>::new());
+        let history = lines_to_single_string(&drain_insert_history(&rx).concat());
+        (streaming, history)
+    };
+
+    let (off_streaming, off_history) = stream_answer(StreamingCaret::Off);
+    assert_eq!(off_streaming, (vec![], 0));
+
+    let (on_streaming, on_history) = stream_answer(StreamingCaret::Blink);
+    assert_eq!(on_streaming, (vec![0], 1));
+
+    assert!(!off_history.contains(STREAMING_CARET));
+    assert_eq!(on_history, off_history);
+}
+
+#[test]
+fn rate_limit_shows_backoff_and_auto_retry_resends_the_message() {
+    let (mut chat, rx, mut op_rx) = make_chatwidget_manual();
+    chat.submit_text_message("Summarize the open issues".to_string());
+    while op_rx.try_recv().is_ok() {}
+    replay_fixture_turns(&mut chat, "rate-limited.jsonl", |_| true);
+
+    let text = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(
+        text.contains("rate limited · the provider asked to wait 42.00s"),
+        "{text}"
+    );
+    assert!(text.contains("send the message again to retry"), "{text}");
+    assert!(!text.contains('🖐'), "{text}");
+    assert!(!chat.bottom_pane.is_task_running());
+
+    chat.config.tui.auto_retry_rate_limited = true;
+    let delays = Rc::new(RefCell::new(Vec::new()));
+    chat.retry_clock = Box::new(MockRetryClock(Rc::clone(&delays)));
+    replay_fixture_turns(&mut chat, "rate-limited.jsonl", |_| true);
+    assert_eq!(*delays.borrow(), vec![Duration::from_secs(42)]);
+    let mut limits = Vec::new();
+    let mut history = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        match event {
+            AppEvent::RetryAfterRateLimit { limit } => limits.push(limit),
+            AppEvent::InsertHistory(lines) => history.extend(lines),
+            _ => {}
+        }
+    }
+    let text = lines_to_single_string(&history);
+    assert!(text.contains("retrying automatically in 42.00s"), "{text}");
+
+    chat.retry_after_rate_limit(limits[0]);
+    let Ok(Op::UserInput { items }) = op_rx.try_recv() else {
+        panic!("expected the message to be resent");
+    };
+    assert!(
+        matches!(&items[..], [InputItem::Text { text }] if text == "Summarize the open issues"),
+        "{items:?}"
+    );
+}
+
+#[test]
+fn prompt_submitted_during_rate_limit_backoff_is_sent_once() {
+    let (mut chat, rx, mut op_rx) = make_chatwidget_manual();
+    chat.config.tui.auto_retry_rate_limited = true;
+    chat.retry_clock = Box::new(MockRetryClock(Rc::new(RefCell::new(Vec::new()))));
+    chat.submit_text_message("Summarize the open issues".to_string());
+    while op_rx.try_recv().is_ok() {}
+    replay_fixture_turns(&mut chat, "rate-limited.jsonl", |_| true);
+    let limits: Vec<u32> = std::iter::from_fn(|| rx.try_recv().ok())
+        .filter_map(|event| match event {
+            AppEvent::RetryAfterRateLimit { limit } => Some(limit),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(limits.len(), 1);
+
+    // A new prompt during the backoff replaces the retry.
+    chat.submit_text_message("List the stale branches instead".to_string());
+    chat.retry_after_rate_limit(limits[0]);
+
+    let sent: Vec<Vec<InputItem>> = std::iter::from_fn(|| op_rx.try_recv().ok())
+        .filter_map(|op| match op {
+            Op::UserInput { items } => Some(items),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(sent.len(), 1, "{sent:?}");
+    assert!(
+        matches!(&sent[0][..], [InputItem::Text { text }] if text == "List the stale branches instead"),
+        "{sent:?}"
+    );
+}
+
+/// Records each backoff and fires the retry at once.
+struct MockRetryClock(Rc<RefCell<Vec<Duration>>>);
+
+impl RetryClock for MockRetryClock {
+    fn schedule(&self, delay: Duration, tx: AppEventSender, event: AppEvent) {
+        self.0.borrow_mut().push(delay);
+        tx.send(event);
+    }
+}
+
+#[test]
+fn transient_error_is_retried_after_backoff_without_duplicating_the_answer() {
+    let (mut chat, rx, mut op_rx) = make_chatwidget_manual();
+    chat.config.tui.retry = Retry {
+        max_attempts: 3,
+        base_delay_ms: 500,
+    };
+    let delays = Rc::new(RefCell::new(Vec::new()));
+    chat.retry_clock = Box::new(MockRetryClock(Rc::clone(&delays)));
+    let send = |chat: &mut ChatWidget<'_>, msg: EventMsg| {
+        chat.handle_codex_event(Event {
+            id: "s1".into(),
+            msg,
+        });
+    };
+    let answer = |text: &str| {
+        EventMsg::AgentMessage(AgentMessageEvent {
+            message: text.into(),
+        })
+    };
+    let complete = || {
+        EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: None,
+        })
+    };
+
+    chat.submit_text_message("Which tool builds this?".to_string());
+    while op_rx.try_recv().is_ok() {}
+
+    // The first attempt answers in part, then the stream drops.
+    send(&mut chat, EventMsg::TaskStarted);
+    send(&mut chat, answer("Let me look at the manifest first."));
+    send(
+        &mut chat,
+        EventMsg::Error(ErrorEvent {
+            message: "stream disconnected before completion: connection reset".into(),
+            transient: true,
+        }),
+    );
+    send(&mut chat, complete());
+
+    assert_eq!(*delays.borrow(), vec![Duration::from_millis(500)]);
+    let mut attempts = Vec::new();
+    let mut history = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        match event {
+            AppEvent::RetryAfterTransientError { attempt } => attempts.push(attempt),
+            AppEvent::InsertHistory(lines) => history.extend(lines),
+            _ => {}
+        }
+    }
+    assert_eq!(attempts, vec![1]);
+    let text = lines_to_single_string(&history);
+    assert!(
+        text.contains("retrying (attempt 1/3)… · stream disconnected"),
+        "{text}"
+    );
+    assert!(!text.contains('🖐'), "{text}");
+
+    // The clock fired: the message is sent again, and this attempt succeeds.
+    chat.retry_after_transient_error(1);
+    let Ok(Op::UserInput { items }) = op_rx.try_recv() else {
+        panic!("expected the message to be resent");
+    };
+    assert!(
+        matches!(&items[..], [InputItem::Text { text }] if text == "Which tool builds this?"),
+        "{items:?}"
+    );
+    send(&mut chat, EventMsg::TaskStarted);
+    send(&mut chat, answer("Cargo builds the whole workspace."));
+    send(&mut chat, complete());
+
+    // A stale timer does not send it a third time.
+    chat.retry_after_transient_error(1);
+    assert!(op_rx.try_recv().is_err());
+
+    assert_eq!(
+        chat.transcript.turns()[0].answer,
+        "Cargo builds the whole workspace."
+    );
+    let scrollback = chat.scrollback.borrow();
+    let recorded = lines_to_single_string(scrollback.lines());
+    assert_eq!(
+        scrollback
+            .lines()
+            .iter()
+            .filter(|line| line.spans.iter().any(|span| span.content == "codex"))
+            .count(),
+        1,
+        "{recorded}"
+    );
+    assert!(
+        !recorded.contains("Let me look at the manifest"),
+        "{recorded}"
+    );
+    assert!(recorded.contains("retrying (attempt 1/3)…"), "{recorded}");
+}
+
+#[test]
+fn model_header_shows_the_configured_model_and_follows_a_model_switch() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.model_header = true;
+    let header = |chat: &ChatWidget<'_>| -> String {
+        let area = Rect::new(0, 0, 60, chat.desired_height(60));
+        let mut buf = Buffer::empty(area);
+        chat.render_ref(area, &mut buf);
+        (0..area.width)
+            .map(|x| buf[(x, 0)].symbol())
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    };
+    let provider = chat.config.model_provider.name.clone();
+    assert_eq!(
+        header(&chat),
+        format!("{} via {provider}", chat.config.model)
+    );
+
+    assert!(chat.switch_model("  ").is_none());
+    let config = chat.switch_model("gpt-4.1").unwrap();
+    assert_eq!(config.model, "gpt-4.1");
+    assert_eq!(config.model_family.slug, "gpt-4.1");
+    // The app starts a new session with the returned config.
+    chat.config = config;
+    assert_eq!(header(&chat), format!("gpt-4.1 via {provider}"));
+}
+
+#[test]
+fn session_title_shows_in_the_header_and_names_exports() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.model_header = true;
+    let home = tempfile::TempDir::new().expect("temp dir");
+    chat.config.codex_home = home.path().to_path_buf();
+    let header = |chat: &ChatWidget<'_>| -> String {
+        let area = Rect::new(0, 0, 80, chat.desired_height(80));
+        let mut buf = Buffer::empty(area);
+        chat.render_ref(area, &mut buf);
+        (0..area.width)
+            .map(|x| buf[(x, 0)].symbol())
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    };
+    let model = format!(
+        "{} via {}",
+        chat.config.model, chat.config.model_provider.name
+    );
+
+    // The first prompt names an untitled session.
+    chat.submit_text_message("\nFix the flaky login test\nIt fails on CI.".to_string());
+    assert_eq!(header(&chat), format!("{model} · Fix the flaky login test"));
+
+    chat.set_title("  Release 1.2: notes & checks ");
+    assert_eq!(
+        header(&chat),
+        format!("{model} · Release 1.2: notes & checks")
+    );
+    chat.export_transcript("");
+    let exported: Vec<String> = std::fs::read_dir(home.path().join("exports"))
+        .expect("exports dir")
+        .map(|entry| {
+            let entry = entry.expect("entry");
+            entry.file_name().to_string_lossy().into_owned()
+        })
+        .collect();
+    assert_eq!(exported.len(), 1);
+    assert!(
+        exported[0].starts_with("release-1-2-notes-checks-"),
+        "{exported:?}"
+    );
+
+    // A later prompt keeps the title; `/title` alone shows it.
+    chat.submit_text_message("And the docs?".to_string());
+    drain_insert_history(&rx);
+    chat.set_title("");
+    let text = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(
+        text.contains("usage: /title <text> (current: Release 1.2: notes & checks)"),
+        "{text}"
+    );
+}
+
+#[test]
+fn compact_export_keeps_answers_and_leaves_out_reasoning() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    replay_fixture_turns(&mut chat, "binary-size-log.jsonl", |_| true);
+    let home = tempfile::TempDir::new().expect("temp dir");
+    chat.config.codex_home = home.path().to_path_buf();
+
+    chat.export_transcript("compact");
+    let exported: Vec<PathBuf> = std::fs::read_dir(home.path().join("exports"))
+        .expect("exports dir")
+        .map(|entry| entry.expect("entry").path())
+        .collect();
+    assert_eq!(exported.len(), 1);
+    let name = exported[0].file_name().expect("name").to_string_lossy();
+    assert!(name.starts_with("transcript-compact-"), "{name}");
+    let compact = std::fs::read_to_string(&exported[0]).expect("compact export");
+    assert!(compact.contains("### codex"), "{compact}");
+    assert!(
+        compact.contains("I resolved the Rust-side merge conflict"),
+        "{compact}"
+    );
+    assert!(compact.contains("what’s driving size"), "{compact}");
+    assert!(!compact.contains("### thinking"), "{compact}");
+    assert!(
+        !compact.contains("Planning merge conflict resolution"),
+        "{compact}"
+    );
+    // The full export still has the reasoning.
+    assert!(
+        chat.export_markdown()
+            .contains("Planning merge conflict resolution")
+    );
+
+    drain_insert_history(&rx);
+    chat.export_transcript("short");
+    let text = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(
+        text.contains("usage: /export [compact|bookmarks]"),
+        "{text}"
+    );
+}
+
+#[test]
+fn export_turn_writes_only_that_turn_and_rejects_bad_indices() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.transcript.push_user("How do I build?");
+    chat.transcript.push_reasoning("**Checking the justfile**");
+    chat.transcript.push_answer("Run `cargo build`.");
+    chat.transcript.push_user("And test?");
+    chat.transcript.push_answer("Run `cargo test`.");
+
+    let dir = tempfile::TempDir::new().expect("temp dir");
+    let path = dir.path().join("turn.md");
+    chat.export_turn(&format!("0 {}", path.display()));
+    let written = std::fs::read_to_string(&path).expect("turn written");
+    assert_eq!(
+        written,
+        "### user\n\nHow do I build?\n\n### thinking\n\n**Checking the justfile**\n\n\
+         ### codex\n\nRun `cargo build`.\n"
+    );
+    let text = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(text.contains("turn 0 exported to"), "{text}");
+
+    chat.export_turn(&format!("2 {}", path.display()));
+    chat.export_turn("1");
+    let text = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(
+        text.contains("no turn 2: the transcript has 2 turn(s)"),
+        "{text}"
+    );
+    assert!(text.contains("usage: /export-turn <n> <path>"), "{text}");
+    assert_eq!(std::fs::read_to_string(&path).expect("unchanged"), written);
+}
+
+#[test]
+fn resuming_with_last_user_puts_the_final_prompt_at_the_top_of_the_view() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    let rollout = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("resume-rollout.jsonl");
+    let messages = crate::resume::read_rollout_messages(&rollout).expect("read rollout");
+    assert_eq!(messages.len(), 4, "{messages:?}");
+    chat.show_resumed_history(messages);
+    chat.resume_scroll = Some(ResumeScroll::LastUser);
+
+    let area = Rect::new(0, 0, 60, 14);
+    chat.set_viewport(Size {
+        width: area.width,
+        height: area.height,
+    });
+    assert!(chat.take_resume_scroll());
+    // The target is applied once; later frames leave the view alone.
+    assert!(!chat.take_resume_scroll());
+
+    let mut buf = Buffer::empty(area);
+    chat.render_transcript(area, &mut buf);
+    let row = |y: u16| {
+        (0..area.width)
+            .map(|x| buf[(x, y)].symbol())
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    };
+    assert_eq!(row(0), "user");
+    assert_eq!(row(1), "List the steps of the release checklist");
+
+    let text = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(text.contains("What does the build script do?"), "{text}");
+    assert!(text.contains("20. Step 20 of the release"), "{text}");
+    assert!(!text.contains("environment_context"), "{text}");
+}
+
+#[test]
+fn summarize_sends_the_transcript_and_renders_the_answer_as_a_summary() {
+    let (mut chat, rx, mut op_rx) = make_chatwidget_manual();
+    chat.transcript.push_user("Why is CI red?");
+    chat.transcript
+        .push_answer("The lint job fails on an unused import.");
+
+    chat.summarize_session();
+    let Ok(Op::UserInput { items }) = op_rx.try_recv() else {
+        panic!("expected a summary request");
+    };
+    let [InputItem::Text { text }] = &items[..] else {
+        panic!("unexpected items: {items:?}");
+    };
+    assert!(text.starts_with(SUMMARIZE_PROMPT), "{text}");
+    assert!(
+        text.contains("### user\n\nWhy is CI red?\n\n### codex\n\nThe lint job fails"),
+        "{text}"
+    );
+
+    for msg in [
+        EventMsg::TaskStarted,
+        EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+            delta: "CI fails on lint.\n".into(),
+        }),
+        EventMsg::AgentMessage(AgentMessageEvent {
+            message: "CI fails on lint.\n".into(),
+        }),
+        EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: None,
+        }),
+    ] {
+        chat.handle_codex_event(Event {
+            id: "s".into(),
+            msg,
+        });
+    }
+    let lines = drain_insert_history(&rx).concat();
+    let rows: Vec<String> = lines
+        .iter()
+        .map(|l| {
+            lines_to_single_string(std::slice::from_ref(l))
+                .trim()
+                .to_string()
+        })
+        .collect();
+    let header = rows
+        .iter()
+        .position(|r| r == "summary")
+        .unwrap_or_else(|| panic!("no summary header in {rows:?}"));
+    assert_eq!(rows[header + 1], "CI fails on lint.");
+    assert!(!rows.iter().any(|r| r == "codex"), "{rows:?}");
+    // The summary is recorded as a turn of its own, not as an answer of the
+    // summarized turn.
+    let turns = chat.transcript.turns();
+    assert_eq!(turns.len(), 2);
+    assert_eq!(turns[0].answer, "The lint job fails on an unused import.");
+    assert_eq!(turns[1].user, "/summarize");
+    assert_eq!(turns[1].answer.trim_end(), "CI fails on lint.");
+}
+
+#[test]
+fn search_highlights_a_match_inside_a_code_block_without_breaking_its_style() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.code_block_style = codex_core::config_types::CodeBlockStyle::Background;
+    chat.stream = StreamController::new(chat.config.clone());
+    chat.handle_codex_event(Event {
+        id: "s".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: "Here:\n\n```\nlet needle = 1;\n```\n".into(),
+        }),
+    });
+
+    let area = Rect::new(0, 0, 40, 10);
+    let mut buf = Buffer::empty(area);
+    chat.render_transcript(area, &mut buf);
+    for c in "/NEEDLE".chars() {
+        assert!(
+            chat.handle_transcript_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+        );
+    }
+    chat.handle_transcript_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    let mut buf = Buffer::empty(area);
+    chat.render_transcript(area, &mut buf);
+
+    let (y, x) = (0..area.height)
+        .find_map(|y| {
+            let row: String = (0..area.width).map(|x| buf[(x, y)].symbol()).collect();
+            row.find("let needle").map(|x| (y, x as u16))
+        })
+        .unwrap();
+    let (_, current) = crate::search::match_styles(&chat.config.tui);
+    let code_bg = ratatui::style::Color::Indexed(236);
+    // "let " and " = 1;" keep the code background; "needle" is the current
+    // match.
+    for dx in 0..4 {
+        assert_eq!(buf[(x + dx, y)].bg, code_bg, "column {dx}");
+    }
+    for dx in 4..10 {
+        assert_eq!(buf[(x + dx, y)].bg, current.bg.unwrap(), "column {dx}");
+        assert_eq!(buf[(x + dx, y)].fg, current.fg.unwrap(), "column {dx}");
+    }
+    for dx in 10..15 {
+        assert_eq!(buf[(x + dx, y)].bg, code_bg, "column {dx}");
+    }
+    let hint: String = (0..area.width)
+        .map(|x| buf[(x, area.height - 1)].symbol())
+        .collect();
+    assert!(hint.contains("/NEEDLE"), "{hint}");
+
+    // Esc clears the search before it closes the view.
+    assert!(chat.handle_transcript_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)));
+    assert!(chat.search.is_none());
+}
+
+#[test]
+fn events_held_while_paused_render_in_order_with_a_single_header_on_resume() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.handle_codex_event(Event {
+        id: "p".into(),
+        msg: EventMsg::TaskStarted,
+    });
+    let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+    chat.handle_key_event(ctrl_s);
+
+    for delta in ["Alpha ", "beta\n", "gamma ", "delta\n"] {
+        chat.handle_codex_event(Event {
+            id: "p".into(),
+            msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+                delta: delta.into(),
+            }),
+        });
+    }
+    // The turn completes while paused; finalizing waits for the resume.
+    chat.handle_codex_event(Event {
+        id: "p".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: "Alpha beta\ngamma delta\n".into(),
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "p".into(),
+        msg: EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: None,
+        }),
+    });
+    chat.on_commit_tick();
+    assert!(drain_insert_history(&rx).is_empty());
+    assert_eq!(chat.paused_events.as_ref().map(Vec::len), Some(6));
+
+    chat.handle_key_event(ctrl_s);
+    assert!(chat.paused_events.is_none());
+    let cells = drain_insert_history(&rx);
+    let headers = cells
+        .iter()
+        .flatten()
+        .flat_map(|line| &line.spans)
+        .filter(|span| span.content == "codex")
+        .count();
+    assert_eq!(headers, 1);
+    let text: String = cells
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect();
+    let alpha = text.find("Alpha beta").unwrap();
+    let gamma = text.find("gamma delta").unwrap();
+    assert!(alpha < gamma, "{text}");
+    assert_eq!(text.matches("Alpha beta").count(), 1, "{text}");
+    assert!(!chat.bottom_pane.is_task_running());
+}
+
+#[test]
+fn tool_call_arguments_are_pretty_printed_and_collapse_in_the_transcript_view() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    replay_fixture_turns(&mut chat, "tool-call.jsonl", |_| true);
+
+    let history: String = drain_insert_history(&rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect();
+    assert!(history.contains("github.create_issue(\n"), "{history}");
+    assert!(history.contains("  \"repo\": \"acme/web\",\n"), "{history}");
+    assert!(history.contains("    \"flaky\",\n"), "{history}");
+    // Arguments that are not valid JSON are shown as they arrived.
+    assert!(
+        history.contains("github.add_comment({\"issue\": 412, \"body\": \"see CI)"),
+        "{history}"
+    );
+
+    let a = KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE);
+    assert!(chat.handle_transcript_key_event(a));
+    let transcript = lines_to_single_string(chat.scrollback.borrow().lines());
+    assert!(
+        !transcript.contains("\"repo\": \"acme/web\""),
+        "{transcript}"
+    );
+    let call = transcript
+        .lines()
+        .find(|line| line.starts_with("github.create_issue({"))
+        .unwrap();
+    assert!(call.ends_with("...) · 8 lines"), "{call}");
+    assert!(transcript.contains("Created issue #412"), "{transcript}");
+
+    assert!(chat.handle_transcript_key_event(a));
+    let transcript = lines_to_single_string(chat.scrollback.borrow().lines());
+    assert_eq!(transcript.matches("  \"repo\": \"acme/web\",").count(), 2);
+}
+
+#[test]
+fn drop_reasoning_frees_the_turns_reasoning_but_keeps_its_answer_and_history() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    for (prompt, reasoning, answer) in [
+        ("first", "**Weighing options** at length", "First answer"),
+        ("second", "**Checking** again", "Second answer"),
+    ] {
+        chat.submit_text_message(prompt.to_string());
+        chat.handle_codex_event(Event {
+            id: prompt.into(),
+            msg: EventMsg::TaskStarted,
+        });
+        chat.handle_codex_event(Event {
+            id: prompt.into(),
+            msg: EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
+                delta: reasoning.into(),
+            }),
+        });
+        chat.handle_codex_event(Event {
+            id: prompt.into(),
+            msg: EventMsg::AgentReasoning(AgentReasoningEvent {
+                text: reasoning.into(),
+            }),
+        });
+        chat.handle_codex_event(Event {
+            id: prompt.into(),
+            msg: EventMsg::AgentMessage(AgentMessageEvent {
+                message: answer.into(),
+            }),
+        });
+        if prompt == "first" {
+            chat.handle_codex_event(Event {
+                id: prompt.into(),
+                msg: EventMsg::TaskComplete(TaskCompleteEvent {
+                    last_agent_message: None,
+                }),
+            });
+        }
+    }
+    drain_insert_history(&rx);
+
+    // The second turn is still running.
+    assert!(!chat.drop_reasoning(1));
+    assert!(chat.drop_reasoning(0));
+    assert!(!chat.drop_reasoning(5));
+
+    let turns = chat.transcript.turns();
+    assert_eq!(turns[0].reasoning, "");
+    assert_eq!(turns[0].answer, "First answer");
+    assert_eq!(turns[1].reasoning, "**Checking** again");
+    let export = chat.export_markdown();
+    assert!(!export.contains("Weighing options"), "{export}");
+    assert!(export.contains("First answer"), "{export}");
+    let history = lines_to_single_string(chat.scrollback.borrow().lines());
+    assert!(history.contains("Weighing options"), "{history}");
+}
+
+#[test]
+fn tab_cycles_through_an_answers_code_blocks_and_c_copies_the_selected_one() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.submit_text_message("show me".to_string());
+    chat.handle_codex_event(Event {
+        id: "c".into(),
+        msg: EventMsg::TaskStarted,
+    });
+    chat.handle_codex_event(Event {
+        id: "c".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: "Set it:\n\n```rust\nlet a = 1;\n```\n\nThen run:\n\n```sh\ncargo test\n```\n"
+                .into(),
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "c".into(),
+        msg: EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: None,
+        }),
+    });
+    let area = Rect::new(0, 0, 60, 20);
+    chat.render_transcript(area, &mut Buffer::empty(area));
+    while rx.try_recv().is_ok() {}
+
+    let copied = |rx: &std::sync::mpsc::Receiver<AppEvent>| -> Vec<String> {
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .filter_map(|event| match event {
+                AppEvent::CopyToClipboard(text) => Some(text),
+                _ => None,
+            })
+            .collect()
+    };
+    let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+    let reversed_row = |chat: &ChatWidget<'_>| -> Option<String> {
+        let mut buf = Buffer::empty(area);
+        chat.render_transcript(area, &mut buf);
+        (0..area.height).find_map(|y| {
+            let cells: Vec<_> = (0..area.width).map(|x| &buf[(x, y)]).collect();
+            cells
+                .iter()
+                .any(|cell| cell.modifier.contains(Modifier::REVERSED))
+                .then(|| cells.iter().map(|cell| cell.symbol()).collect::<String>())
+        })
+    };
+    assert_eq!(reversed_row(&chat), None);
+
+    assert!(chat.handle_transcript_key_event(key(KeyCode::Tab)));
+    assert!(reversed_row(&chat).unwrap().contains("let a = 1;"));
+    assert!(chat.handle_transcript_key_event(key(KeyCode::Char('c'))));
+    assert_eq!(copied(&rx), vec!["let a = 1;".to_string()]);
+
+    assert!(chat.handle_transcript_key_event(key(KeyCode::Tab)));
+    assert!(reversed_row(&chat).unwrap().contains("cargo test"));
+    assert!(chat.handle_transcript_key_event(key(KeyCode::Char('c'))));
+    assert_eq!(copied(&rx), vec!["cargo test".to_string()]);
+
+    // Tab wraps around; Esc clears the selection before closing the view.
+    assert!(chat.handle_transcript_key_event(key(KeyCode::Tab)));
+    assert!(reversed_row(&chat).unwrap().contains("let a = 1;"));
+    assert!(chat.handle_transcript_key_event(key(KeyCode::Esc)));
+    assert_eq!(reversed_row(&chat), None);
+}
+
+#[test]
+fn expand_settings_keep_code_in_full_and_fold_prose_to_its_first_line() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.expand.prose = false;
+    // The prompt stays on screen above the answer.
+    chat.config.tui.scroll_policy = codex_core::config_types::ScrollPolicy::FollowBottom;
+    chat.scrollback = Rc::new(RefCell::new(Scrollback::new(&chat.config.tui)));
+    chat.submit_text_message("how do I test?".to_string());
+    chat.handle_codex_event(Event {
+        id: "e".into(),
+        msg: EventMsg::TaskStarted,
+    });
+    chat.handle_codex_event(Event {
+        id: "e".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: "Build first.\n\nThen run the tests.\n\n```sh\ncargo build\ncargo test\n```\n"
+                .into(),
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "e".into(),
+        msg: EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: None,
+        }),
+    });
+
+    let area = Rect::new(0, 0, 60, 20);
+    let mut buf = Buffer::empty(area);
+    chat.render_transcript(area, &mut buf);
+    let screen: String = (0..area.height)
+        .map(|y| {
+            let row: String = (0..area.width).map(|x| buf[(x, y)].symbol()).collect();
+            row.trim_end().to_string() + "\n"
+        })
+        .collect();
+    assert!(screen.contains("Build first."), "{screen}");
+    assert!(!screen.contains("Then run the tests."), "{screen}");
+    assert!(screen.contains("more lines"), "{screen}");
+    assert!(screen.contains("cargo build"), "{screen}");
+    assert!(screen.contains("cargo test"), "{screen}");
+    // The prompt is not an answer and is never folded.
+    assert!(screen.contains("how do I test?"), "{screen}");
+}
+
+#[test]
+fn system_notes_render_with_their_level_style_and_do_not_start_a_turn() {
+    let (mut chat, rx, mut op_rx) = make_chatwidget_manual();
+    chat.submit_text_message("hello".to_string());
+    chat.handle_codex_event(Event {
+        id: "n".into(),
+        msg: EventMsg::TaskStarted,
+    });
+    chat.handle_codex_event(Event {
+        id: "n".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: "Hi".into(),
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "n".into(),
+        msg: EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: None,
+        }),
+    });
+    drain_insert_history(&rx);
+    while op_rx.try_recv().is_ok() {}
+
+    chat.push_system_note("network restored", SystemNoteLevel::Warn);
+    let lines = drain_insert_history(&rx).concat();
+    let note = lines
+        .iter()
+        .find(|line| line.spans.iter().any(|s| s.content == "network restored"))
+        .unwrap();
+    assert_eq!(note.spans[0].content, "⚠ ");
+    assert!(
+        note.spans
+            .iter()
+            .all(|s| s.style.fg == Some(ratatui::style::Color::Yellow))
+    );
+
+    chat.push_system_note("synced", SystemNoteLevel::Info);
+    chat.push_system_note("sync failed\nretrying later", SystemNoteLevel::Error);
+    let text = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(text.contains("ℹ synced"), "{text}");
+    assert!(text.contains("✖ sync failed\n  retrying later"), "{text}");
+
+    // Notes are neither turns nor input for the model.
+    assert_eq!(chat.outline().len(), 1);
+    assert_eq!(chat.transcript.turns().len(), 1);
+    assert!(op_rx.try_recv().is_err());
+}
+
+#[test]
+fn unclosed_fence_is_closed_at_completion_so_later_text_is_not_code() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.submit_text_message("How do I run the tests?".to_string());
+    replay_fixture_turns(&mut chat, "unclosed-fence.jsonl", |_| true);
+
+    let text = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(text.contains("cargo test"), "{text}");
+    assert!(text.contains("All tests pass now."), "{text}");
+    assert!(!text.contains("```"), "{text}");
+
+    // The next answer starts after the closed fence, so it is prose and the
+    // code block holds only the code.
+    assert!(
+        chat.transcript
+            .to_markdown()
+            .contains("cargo test\n```\n\nAll tests pass now.")
+    );
+    let blocks = chat.code_blocks();
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0].code, "cargo test");
+}
+
+#[test]
+fn focus_mode_dims_older_turns_and_f_toggles_it() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    chat.focus_mode = true;
+    // Both turns stay on screen.
+    chat.config.tui.scroll_policy = codex_core::config_types::ScrollPolicy::FollowBottom;
+    chat.scrollback = Rc::new(RefCell::new(Scrollback::new(&chat.config.tui)));
+    for (prompt, answer) in [("first", "First answer"), ("second", "Second answer")] {
+        chat.submit_text_message(prompt.to_string());
+        chat.handle_codex_event(Event {
+            id: prompt.into(),
+            msg: EventMsg::TaskStarted,
+        });
+        chat.handle_codex_event(Event {
+            id: prompt.into(),
+            msg: EventMsg::AgentMessage(AgentMessageEvent {
+                message: answer.into(),
+            }),
+        });
+        chat.handle_codex_event(Event {
+            id: prompt.into(),
+            msg: EventMsg::TaskComplete(TaskCompleteEvent {
+                last_agent_message: None,
+            }),
+        });
+    }
+
+    let area = Rect::new(0, 0, 60, 30);
+    // Whether the first cell of `text` on screen is dimmed.
+    let dimmed = |chat: &ChatWidget<'_>, text: &str| -> bool {
+        let mut buf = Buffer::empty(area);
+        chat.render_transcript(area, &mut buf);
+        let (x, y) = (0..area.height)
+            .find_map(|y| {
+                let row: String = (0..area.width).map(|x| buf[(x, y)].symbol()).collect();
+                row.find(text).map(|i| (row[..i].chars().count() as u16, y))
+            })
+            .unwrap();
+        buf[(x, y)].modifier.contains(Modifier::DIM)
+    };
+    assert!(dimmed(&chat, "First answer"));
+    assert!(!dimmed(&chat, "Second answer"));
+
+    let f = KeyEvent::new(KeyCode::Char('f'), KeyModifiers::NONE);
+    assert!(chat.handle_transcript_key_event(f));
+    assert!(!dimmed(&chat, "First answer"));
+    // Focus mode only restyles the view.
+    assert!(chat.transcript.to_markdown().contains("First answer"));
+}
+
+#[test]
+fn first_token_fires_once_per_task_before_any_output_is_inserted() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    replay_fixture_turns(&mut chat, "unclosed-fence.jsonl", |_| true);
+    let events: Vec<AppEvent> = std::iter::from_fn(|| rx.try_recv().ok()).collect();
+    let first_tokens: Vec<usize> = events
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| matches!(e, AppEvent::FirstToken))
+        .map(|(i, _)| i)
+        .collect();
+    let first_insert = events
+        .iter()
+        .position(|e| matches!(e, AppEvent::InsertHistory(_)))
+        .unwrap();
+    assert_eq!(first_tokens.len(), 1);
+    assert!(first_tokens[0] < first_insert);
+
+    // The next task fires it again.
+    replay_fixture_turns(&mut chat, "unclosed-fence.jsonl", |_| true);
+    let again = std::iter::from_fn(|| rx.try_recv().ok())
+        .filter(|e| matches!(e, AppEvent::FirstToken))
+        .count();
+    assert_eq!(again, 1);
+}
+
+#[test]
+fn sign_off_patterns_dim_or_strip_the_last_sentence_of_an_answer() {
+    use codex_core::config_types::SignOffAction;
+    use codex_core::config_types::SignOffs;
+
+    let answer = "The fix is in place. Let me know if you need anything else!";
+    for action in [SignOffAction::Dim, SignOffAction::Strip] {
+        let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+        chat.config.tui.sign_offs = SignOffs {
+            patterns: vec!["let me know if .*".to_string()],
+            action,
+        };
+        chat.stream = StreamController::new(chat.config.clone());
+        chat.submit_text_message("fix it".to_string());
+        chat.handle_codex_event(Event {
+            id: "s".into(),
+            msg: EventMsg::TaskStarted,
+        });
+        for delta in [
+            "The fix is in place. ",
+            "Let me know if you need anything else!",
+        ] {
+            chat.handle_codex_event(Event {
+                id: "s".into(),
+                msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+                    delta: delta.into(),
+                }),
+            });
+        }
+        chat.handle_codex_event(Event {
+            id: "s".into(),
+            msg: EventMsg::AgentMessage(AgentMessageEvent {
+                message: answer.into(),
+            }),
+        });
+
+        let lines = drain_insert_history(&rx).concat();
+        let text_of = |line: &ratatui::text::Line<'_>| -> String {
+            line.spans.iter().map(|s| s.content.as_ref()).collect()
+        };
+        let body = lines
+            .iter()
+            .find(|l| text_of(l).contains("The fix is in place."))
+            .unwrap();
+        assert_eq!(text_of(body).trim_end(), "The fix is in place.");
+        assert!(!body.style.add_modifier.contains(Modifier::DIM));
+        let sign_off = lines.iter().find(|l| text_of(l).contains("Let me know"));
+        match action {
+            SignOffAction::Dim => {
+                assert!(sign_off.unwrap().style.add_modifier.contains(Modifier::DIM));
+            }
+            SignOffAction::Strip => assert!(sign_off.is_none()),
+        }
+        // The transcript, and so `/export`, keeps the whole answer.
+        assert_eq!(chat.transcript.last_answer(), Some(answer));
+    }
+}
+
+#[test]
+fn empty_enter_is_ignored_unless_configured_to_rerun_the_last_prompt() {
+    let (mut chat, _rx, mut op_rx) = make_chatwidget_manual();
+    let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+    let submitted_texts = |op_rx: &mut tokio::sync::mpsc::UnboundedReceiver<Op>| {
+        std::iter::from_fn(|| op_rx.try_recv().ok())
+            .filter_map(|op| match op {
+                Op::UserInput { items } => Some(items),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|item| match item {
+                InputItem::Text { text } => Some(text),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Nothing to rerun yet, and whitespace counts as empty.
+    chat.config.tui.empty_submit = EmptySubmit::RerunLast;
+    chat.handle_key_event(key(KeyCode::Char(' ')));
+    chat.handle_key_event(key(KeyCode::Enter));
+    assert!(submitted_texts(&mut op_rx).is_empty());
+    assert!(chat.transcript.turns().is_empty());
+
+    chat.submit_text_message("run the tests".to_string());
+    assert_eq!(
+        submitted_texts(&mut op_rx),
+        vec!["run the tests".to_string()]
+    );
+
+    chat.config.tui.empty_submit = EmptySubmit::Ignore;
+    chat.handle_key_event(key(KeyCode::Enter));
+    assert!(submitted_texts(&mut op_rx).is_empty());
+    assert_eq!(chat.transcript.turns().len(), 1);
+
+    chat.config.tui.empty_submit = EmptySubmit::RerunLast;
+    chat.handle_key_event(key(KeyCode::Char(' ')));
+    chat.handle_key_event(key(KeyCode::Enter));
+    assert_eq!(
+        submitted_texts(&mut op_rx),
+        vec!["run the tests".to_string()]
+    );
+    assert_eq!(chat.transcript.turns().len(), 2);
+}
+
+/// Submit "first", start its turn, then submit "second" under `policy`.
+/// Returns the ops sent for "second".
+fn submit_during_turn(
+    policy: ConcurrentSubmitPolicy,
+) -> (
+    ChatWidget<'static>,
+    std::sync::mpsc::Receiver<AppEvent>,
+    Vec<Op>,
+) {
+    let (mut chat, rx, mut op_rx) = make_chatwidget_manual();
+    chat.config.tui.concurrent_submit_policy = policy;
+    chat.submit_text_message("first".to_string());
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::TaskStarted,
+    });
+    while op_rx.try_recv().is_ok() {}
+    drain_insert_history(&rx);
+
+    chat.submit_text_message("second".to_string());
+    let ops = std::iter::from_fn(|| op_rx.try_recv().ok())
+        .filter(|op| !matches!(op, Op::AddToHistory { .. }))
+        .collect();
+    (chat, rx, ops)
+}
+
+#[test]
+fn reject_policy_keeps_a_prompt_submitted_during_a_turn_in_the_composer() {
+    let (chat, rx, ops) = submit_during_turn(ConcurrentSubmitPolicy::Reject);
+    assert!(ops.is_empty(), "{ops:?}");
+    assert_eq!(chat.transcript.turns().len(), 1);
+    assert!(!chat.bottom_pane.composer_is_empty());
+    let text = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(text.contains("this prompt was not sent"), "{text}");
+}
+
+#[test]
+fn queue_policy_sends_a_prompt_submitted_during_a_turn_without_interrupting() {
+    let (chat, _rx, ops) = submit_during_turn(ConcurrentSubmitPolicy::Queue);
+    assert!(matches!(ops.as_slice(), [Op::UserInput { .. }]), "{ops:?}");
+    assert!(chat.bottom_pane.is_task_running());
+    assert_eq!(chat.transcript.turns().len(), 2);
+}
+
+#[test]
+fn abort_policy_interrupts_the_running_turn_then_starts_the_new_one() {
+    let (chat, _rx, ops) = submit_during_turn(ConcurrentSubmitPolicy::AbortAndStart);
+    assert!(
+        matches!(ops.as_slice(), [Op::Interrupt, Op::UserInput { .. }]),
+        "{ops:?}"
+    );
+    assert_eq!(chat.turn_state(0), TurnState::Interrupted);
+    assert_eq!(chat.transcript.turns().len(), 2);
+    assert_eq!(chat.transcript.turns()[1].user, "second");
+}
+
+#[test]
+fn abort_policy_keeps_the_new_turn_when_the_interrupt_is_reported_late() {
+    let (mut chat, _rx, _ops) = submit_during_turn(ConcurrentSubmitPolicy::AbortAndStart);
+    chat.handle_codex_event(Event {
+        id: "u".into(),
+        msg: EventMsg::TaskStarted,
+    });
+    // Core sends the abandoned turn's error from a task of its own.
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::Error(ErrorEvent {
+            message: " Turn interrupted".into(),
+            transient: false,
+        }),
+    });
+    assert_eq!(chat.turn_state(0), TurnState::Interrupted);
+    assert_eq!(chat.turn_state(1), TurnState::Streaming);
+    assert!(chat.bottom_pane.is_task_running());
+}
+
+#[test]
+fn prompt_over_the_size_limit_is_held_back_until_submitted_again() {
+    let (mut chat, rx, mut op_rx) = make_chatwidget_manual();
+    chat.config.tui.max_prompt_chars = Some(20);
+    let sent = |op_rx: &mut tokio::sync::mpsc::UnboundedReceiver<Op>| {
+        std::iter::from_fn(|| op_rx.try_recv().ok())
+            .filter(|op| matches!(op, Op::UserInput { .. }))
+            .count()
+    };
+
+    chat.submit_text_message("short enough".to_string());
+    assert_eq!(sent(&mut op_rx), 1);
+    drain_insert_history(&rx);
+
+    let long = "explain every module in this workspace";
+    chat.submit_text_message(long.to_string());
+    assert_eq!(sent(&mut op_rx), 0);
+    assert_eq!(chat.transcript.turns().len(), 1);
+    let text = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(
+        text.contains("This prompt is 38 characters, over the limit of 20"),
+        "{text}"
+    );
+    // The text is back in the composer; Enter sends it unchanged.
+    assert!(!chat.bottom_pane.composer_is_empty());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert_eq!(sent(&mut op_rx), 1);
+    assert_eq!(chat.transcript.turns().len(), 2);
+    assert_eq!(chat.transcript.turns()[1].user, long);
+}
+
+#[test]
+fn collapsed_prompt_shows_the_configured_truncation_indicator() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.collapse_prompt_lines = Some(2);
+    chat.config.tui.truncation_indicator = Some("(show {n} hidden {lines})".to_string());
+    let prompt: String = (1..=5).map(|i| format!("pasted line {i}\n")).collect();
+
+    chat.submit_text_message(prompt.trim_end().to_string());
+    let text = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(text.contains("pasted line 2"), "{text}");
+    assert!(text.contains("(show 3 hidden lines)"), "{text}");
+    assert!(!text.contains("more lines"), "{text}");
+}
+
+#[test]
+fn long_prompt_renders_collapsed_and_expands_in_the_transcript_view() {
+    let (mut chat, rx, mut op_rx) = make_chatwidget_manual();
+    chat.config.tui.collapse_prompt_lines = Some(3);
+    let prompt: String = (1..=10).map(|i| format!("pasted line {i}\n")).collect();
+    let prompt = prompt.trim_end().to_string();
+
+    chat.submit_text_message(prompt.clone());
+    let text = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(text.contains("pasted line 3"), "{text}");
+    assert!(!text.contains("pasted line 4"), "{text}");
+    assert!(text.contains("… 7 more lines"), "{text}");
+    // The model and the transcript get every line.
+    let sent: Vec<String> = std::iter::from_fn(|| op_rx.try_recv().ok())
+        .filter_map(|op| match op {
+            Op::UserInput { items } => Some(items),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|item| match item {
+            InputItem::Text { text } => Some(text),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(sent, vec![prompt.clone()]);
+    assert_eq!(chat.transcript.turns()[0].user, prompt);
+
+    let view = |chat: &ChatWidget<'_>| lines_to_single_string(chat.scrollback.borrow().lines());
+    assert!(!view(&chat).contains("pasted line 10"));
+    let p = KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE);
+    assert!(chat.handle_transcript_key_event(p));
+    let expanded = view(&chat);
+    assert!(expanded.contains("pasted line 10"), "{expanded}");
+    assert!(!expanded.contains("more lines"), "{expanded}");
+    assert!(chat.handle_transcript_key_event(p));
+    assert!(!view(&chat).contains("pasted line 10"));
+}
+
+#[test]
+fn copy_commands_copies_the_turns_commands_as_a_quoted_script() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    let run = |chat: &mut ChatWidget<'_>, call_id: &str, command: &[&str]| {
+        chat.handle_codex_event(Event {
+            id: "t".into(),
+            msg: EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
+                call_id: call_id.into(),
+                command: command.iter().map(|s| s.to_string()).collect(),
+                cwd: PathBuf::from("/tmp/project"),
+                parsed_cmd: Vec::new(),
+            }),
+        });
+        chat.handle_codex_event(Event {
+            id: "t".into(),
+            msg: EventMsg::ExecCommandEnd(ExecCommandEndEvent {
+                call_id: call_id.into(),
+                stdout: String::new(),
+                stderr: String::new(),
+                exit_code: 0,
+                duration: std::time::Duration::from_millis(5),
+            }),
+        });
+    };
+    let copied = |rx: &std::sync::mpsc::Receiver<AppEvent>| -> Vec<String> {
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .filter_map(|event| match event {
+                AppEvent::CopyToClipboard(text) => Some(text),
+                _ => None,
+            })
+            .collect()
+    };
+
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::TaskStarted,
+    });
+    run(&mut chat, "1", &["ls", "-la"]);
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::TaskStarted,
+    });
+    run(&mut chat, "2", &["grep", "-rn", "fn main", "src dir"]);
+    run(&mut chat, "3", &["bash", "-lc", "cargo test && echo ok"]);
+    while rx.try_recv().is_ok() {}
+
+    chat.copy_commands("");
+    assert_eq!(
+        copied(&rx),
+        vec!["grep -rn 'fn main' 'src dir'\ncargo test && echo ok\n".to_string()]
+    );
+    chat.copy_commands("all");
+    assert_eq!(
+        copied(&rx),
+        vec!["ls -la\ngrep -rn 'fn main' 'src dir'\ncargo test && echo ok\n".to_string()]
+    );
+}
+
+#[test]
+fn exec_output_off_hides_new_commands_but_not_the_answer() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    let run = |chat: &mut ChatWidget<'_>, call_id: &str, stdout: &str| {
+        chat.handle_codex_event(Event {
+            id: "t".into(),
+            msg: EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
+                call_id: call_id.into(),
+                command: vec!["echo".into(), stdout.into()],
+                cwd: PathBuf::from("/tmp/project"),
+                parsed_cmd: Vec::new(),
+            }),
+        });
+        chat.handle_codex_event(Event {
+            id: "t".into(),
+            msg: EventMsg::ExecCommandEnd(ExecCommandEndEvent {
+                call_id: call_id.into(),
+                stdout: format!("{stdout}\n"),
+                stderr: String::new(),
+                exit_code: 0,
+                duration: std::time::Duration::from_millis(5),
+            }),
+        });
+    };
+    let history = |rx: &std::sync::mpsc::Receiver<AppEvent>| -> String {
+        drain_insert_history(rx)
+            .iter()
+            .map(|lines| lines_to_single_string(lines))
+            .collect()
+    };
+
+    chat.set_exec_output("off");
+    run(&mut chat, "1", "hidden-output");
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: "The answer.".into(),
+        }),
+    });
+    let hidden = history(&rx);
+    assert!(!hidden.contains("hidden-output"), "{hidden}");
+    assert!(hidden.contains("The answer."), "{hidden}");
+    assert_eq!(chat.exec_results().len(), 1);
+
+    chat.set_exec_output("on");
+    run(&mut chat, "2", "shown-output");
+    let shown = history(&rx);
+    assert!(shown.contains("shown-output"), "{shown}");
+}
+
+#[test]
+fn reasoning_sections_are_numbered_or_lose_their_titles_per_config() {
+    use codex_core::config_types::ReasoningSectionTitles;
+
+    let render = |titles: ReasoningSectionTitles| -> Vec<String> {
+        let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+        chat.config.tui.reasoning_section_titles = titles;
+        chat.stream = StreamController::new(chat.config.clone());
+        let reasoning_delta = |delta: &str| {
+            EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
+                delta: delta.into(),
+            })
+        };
+        for msg in [
+            EventMsg::TaskStarted,
+            reasoning_delta("**Checking the build**\n\nLooking at Cargo.toml.\n"),
+            EventMsg::AgentReasoningSectionBreak(AgentReasoningSectionBreakEvent {}),
+            reasoning_delta("**Running"),
+            reasoning_delta(" tests**\n\nAll green.\n"),
+            EventMsg::AgentReasoning(AgentReasoningEvent {
+                text: "done".into(),
+            }),
+            EventMsg::TaskComplete(TaskCompleteEvent {
+                last_agent_message: None,
+            }),
+        ] {
+            chat.handle_codex_event(Event {
+                id: "r".into(),
+                msg,
+            });
+        }
+        drain_insert_history(&rx)
+            .concat()
+            .iter()
+            .map(|line| {
+                lines_to_single_string(std::slice::from_ref(line))
+                    .trim_end()
+                    .to_string()
+            })
+            .filter(|line| !line.is_empty() && line != "thinking")
+            .collect()
+    };
+
+    assert_eq!(
+        render(ReasoningSectionTitles::Separator),
+        vec![
+            "Checking the build",
+            "Looking at Cargo.toml.",
+            "Running tests",
+            "All green."
+        ]
+    );
+    assert_eq!(
+        render(ReasoningSectionTitles::Numbered),
+        vec![
+            "Reasoning 1",
+            "Checking the build",
+            "Looking at Cargo.toml.",
+            "Reasoning 2",
+            "Running tests",
+            "All green."
+        ]
+    );
+    assert_eq!(
+        render(ReasoningSectionTitles::Hidden),
+        vec!["Looking at Cargo.toml.", "All green."]
+    );
+}
+
+#[test]
+fn stats_aggregate_turns_tokens_and_commands_of_replayed_turns() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    replay_fixture_turns(&mut chat, "binary-size-log.jsonl", |_| true);
+    let _ = drain_insert_history(&rx);
+
+    chat.add_stats_output();
+    let text = lines_to_single_string(&drain_insert_history(&rx).concat());
+
+    assert!(text.contains("Turns: 2"), "{text}");
+    // Non-cached input plus output over every token_count event of the log.
+    assert!(text.contains("Tokens: 68885"), "{text}");
+    // 31 commands ran for 294.6s in total; 4 of them exited non-zero.
+    assert!(text.contains("Commands: 31 in 4m54s, 4 failed"), "{text}");
+    assert!(text.contains("Duration: "), "{text}");
+    assert!(text.contains("Average Turn: "), "{text}");
+    assert!(text.contains("Average Latency: "), "{text}");
+}
+
+#[test]
+fn delta_before_task_started_opens_the_turn_that_task_started_then_joins() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    let send = |chat: &mut ChatWidget<'_>, msg: EventMsg| {
+        chat.handle_codex_event(Event {
+            id: "r".into(),
+            msg,
+        });
+    };
+    // After a reconnect the first delta overtakes `TaskStarted`.
+    send(
+        &mut chat,
+        EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+            delta: "Early ".into(),
+        }),
+    );
+    assert!(chat.bottom_pane.is_task_running());
+    assert_eq!(chat.turn_count(), 1);
+    send(&mut chat, EventMsg::TaskStarted);
+    send(
+        &mut chat,
+        EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+            delta: "and late.\n".into(),
+        }),
+    );
+    send(
+        &mut chat,
+        EventMsg::AgentMessage(AgentMessageEvent {
+            message: "Early and late.\n".into(),
+        }),
+    );
+    send(
+        &mut chat,
+        EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: None,
+        }),
+    );
+
+    assert_eq!(chat.turn_count(), 1);
+    let cells = drain_insert_history(&rx);
+    let headers = cells
+        .iter()
+        .flatten()
+        .flat_map(|line| &line.spans)
+        .filter(|span| span.content == "codex")
+        .count();
+    assert_eq!(headers, 1);
+    let text: String = cells
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect();
+    assert_eq!(text.matches("Early and late.").count(), 1, "{text}");
+    assert_eq!(chat.transcript.turns().len(), 1);
+    assert_eq!(chat.transcript.turns()[0].answer, "Early and late.\n");
+
+    // The next task opens a turn of its own.
+    send(&mut chat, EventMsg::TaskStarted);
+    assert_eq!(chat.turn_count(), 2);
+}
+
+#[test]
+fn multi_file_patch_lists_a_file_tree_above_collapsible_diffs() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    replay_fixture_turns(&mut chat, "multi-file-patch.jsonl", |_| true);
+    let text = lines_to_single_string(&drain_insert_history(&rx).concat());
+
+    assert!(text.contains("Applying patch to 3 files (+7 -4)"), "{text}");
+    let summary = [
+        "README.md (+1 -1)",
+        "src/",
+        "  lib.rs (+3 -3)",
+        "  tokenizer.rs (+3 -0)",
+    ];
+    let positions: Vec<usize> = summary
+        .iter()
+        .map(|row| {
+            text.find(row)
+                .unwrap_or_else(|| panic!("missing {row:?}: {text}"))
+        })
+        .collect();
+    assert!(positions.windows(2).all(|w| w[0] < w[1]), "{text}");
+    let detail = text
+        .find("pub use tokenizer::tokenize;")
+        .expect("diff detail");
+    assert!(positions[3] < detail, "{text}");
+
+    let view = |chat: &ChatWidget<'_>| lines_to_single_string(chat.scrollback.borrow().lines());
+    let d = KeyEvent::new(KeyCode::Char('d'), KeyModifiers::NONE);
+    assert!(chat.handle_transcript_key_event(d));
+    let collapsed = view(&chat);
+    assert!(collapsed.contains("  tokenizer.rs (+3 -0)"), "{collapsed}");
+    assert!(
+        !collapsed.contains("pub use tokenizer::tokenize;"),
+        "{collapsed}"
+    );
+    assert!(chat.handle_transcript_key_event(d));
+    assert!(view(&chat).contains("pub use tokenizer::tokenize;"));
+}
+
+#[test]
+fn turn_state_follows_task_started_complete_error_and_interrupt() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    let send = |chat: &mut ChatWidget<'_>, msg: EventMsg| {
+        chat.handle_codex_event(Event {
+            id: "s1".into(),
+            msg,
+        });
+    };
+    let complete = || {
+        EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: None,
+        })
+    };
+    assert!(!chat.is_streaming());
+    assert_eq!(chat.turn_state(0), TurnState::Pending);
+
+    send(&mut chat, EventMsg::TaskStarted);
+    assert!(chat.is_streaming());
+    assert_eq!(chat.turn_state(0), TurnState::Streaming);
+    send(
+        &mut chat,
+        EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+            delta: "Working on it.\n".into(),
+        }),
+    );
+    assert_eq!(chat.turn_state(0), TurnState::Streaming);
+    assert_eq!(chat.turn_state(1), TurnState::Pending);
+    send(&mut chat, complete());
+    assert!(!chat.is_streaming());
+    assert_eq!(chat.turn_state(0), TurnState::Complete);
+
+    // A completion after an error leaves the turn failed.
+    send(&mut chat, EventMsg::TaskStarted);
+    send(
+        &mut chat,
+        EventMsg::Error(ErrorEvent {
+            message: "stream disconnected".into(),
+            transient: false,
+        }),
+    );
+    assert_eq!(chat.turn_state(1), TurnState::Error);
+    send(&mut chat, complete());
+    assert_eq!(chat.turn_state(1), TurnState::Error);
+
+    send(&mut chat, EventMsg::TaskStarted);
+    chat.on_ctrl_c();
+    assert!(!chat.is_streaming());
+    assert_eq!(chat.turn_state(2), TurnState::Interrupted);
+    assert_eq!(chat.turn_state(0), TurnState::Complete);
+}
+
+#[test]
+fn math_regions_keep_their_source_in_the_math_style() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.render_math = true;
+    chat.stream = StreamController::new(chat.config.clone());
+    replay_fixture_turns(&mut chat, "math-answer.jsonl", |_| true);
+    let lines = drain_insert_history(&rx).concat();
+    let math_spans: Vec<String> = lines
+        .iter()
+        .flat_map(|line| &line.spans)
+        .filter(|span| span.style == crate::math::math_style())
+        .map(|span| span.content.to_string())
+        .collect();
+    assert_eq!(
+        math_spans,
+        vec![
+            "$n_k = 2^k$",
+            "$k$",
+            "$n$",
+            "$$",
+            "\\sum_{k=0}^{\\log_2 n} 2^k = 2n - 1",
+            "$$",
+            "$O(n)$",
+            "$O(1)$",
+        ]
+    );
+    let text = lines_to_single_string(&lines);
+    assert!(
+        text.contains("Doubling the table at each resize copies $n_k = 2^k$ entries"),
+        "{text}"
+    );
+}
+
+#[test]
+fn shutdown_mid_stream_flushes_the_partial_answer() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.handle_codex_event(Event {
+        id: "s1".into(),
+        msg: EventMsg::TaskStarted,
+    });
+    for delta in ["The fix is in place.\n", "Remaining work: rerun"] {
+        chat.handle_codex_event(Event {
+            id: "s1".into(),
+            msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+                delta: delta.into(),
+            }),
+        });
+    }
+    let before = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(!before.contains("Remaining work"), "{before}");
+    assert_eq!(chat.transcript.last_answer(), None);
+
+    chat.shutdown();
+    let after = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(after.contains("Remaining work: rerun"), "{after}");
+    assert_eq!(
+        chat.transcript.last_answer(),
+        Some("The fix is in place.\nRemaining work: rerun")
+    );
+    assert_eq!(chat.turn_state(0), TurnState::Interrupted);
+}
+
+#[test]
+fn reference_links_number_per_message_with_a_references_section() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.link_style = codex_core::config_types::LinkStyle::Reference;
+    chat.stream = StreamController::new(chat.config.clone());
+    let answers = [
+        "Read [the RFC](https://example.com/rfc) and [the errata](https://example.com/errata), then [the RFC](https://example.com/rfc) again.",
+        "Also see [the FAQ](https://example.com/faq).",
+    ];
+    let mut texts = Vec::new();
+    for answer in answers {
+        chat.handle_codex_event(Event {
+            id: "s1".into(),
+            msg: EventMsg::TaskStarted,
+        });
+        chat.handle_codex_event(Event {
+            id: "s1".into(),
+            msg: EventMsg::AgentMessage(AgentMessageEvent {
+                message: answer.into(),
+            }),
+        });
+        chat.handle_codex_event(Event {
+            id: "s1".into(),
+            msg: EventMsg::TaskComplete(TaskCompleteEvent {
+                last_agent_message: None,
+            }),
+        });
+        texts.push(lines_to_single_string(&drain_insert_history(&rx).concat()));
+    }
+
+    assert!(
+        texts[0].contains("Read the RFC[1] and the errata[2], then the RFC[1] again."),
+        "{}",
+        texts[0]
+    );
+    assert!(
+        texts[0]
+            .contains("References\n[1] https://example.com/rfc\n[2] https://example.com/errata\n"),
+        "{}",
+        texts[0]
+    );
+    // Numbers start over in the next message.
+    assert!(texts[1].contains("Also see the FAQ[1]."), "{}", texts[1]);
+    assert!(
+        texts[1].contains("References\n[1] https://example.com/faq\n"),
+        "{}",
+        texts[1]
+    );
+    // The transcript keeps the links as written.
+    assert!(
+        chat.transcript.turns()[0]
+            .answer
+            .contains("[the RFC](https://example.com/rfc)")
+    );
+}
+
+#[test]
+fn answer_preview_renders_dim_until_the_answer_replaces_it() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.answer_preview = true;
+    let events: Vec<Event> = BufReader::new(open_fixture("answer-preview.jsonl"))
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(&line.expect("read line")))
+        .map(|v| v.expect("parse line"))
+        .filter(|v| v.get("kind").and_then(|k| k.as_str()) == Some("codex_event"))
+        .map(|v| serde_json::from_value(v["payload"].clone()).expect("parse event"))
+        .collect();
+    let answer_start = events
+        .iter()
+        .position(|ev| matches!(ev.msg, EventMsg::AgentMessageDelta(_)))
+        .expect("answer delta");
+    let area = Rect::new(0, 0, 80, 12);
+    // Whether `text` is on screen, and if so whether its first cell is dimmed.
+    let shown_dimmed = |chat: &ChatWidget<'_>, text: &str| -> Option<bool> {
+        let mut buf = Buffer::empty(area);
+        chat.render_ref(area, &mut buf);
+        (0..area.height).find_map(|y| {
+            let row: String = (0..area.width).map(|x| buf[(x, y)].symbol()).collect();
+            let x = row.find(text)?;
+            let x = row[..x].chars().count() as u16;
+            Some(buf[(x, y)].modifier.contains(Modifier::DIM))
+        })
+    };
+
+    let mut events = events.into_iter();
+    for ev in events.by_ref().take(answer_start) {
+        chat.handle_codex_event(ev);
+    }
+    assert_eq!(
+        shown_dimmed(&chat, "Probably yes, via the triple."),
+        Some(true)
+    );
+
+    for ev in events {
+        chat.handle_codex_event(ev);
+    }
+    assert_eq!(shown_dimmed(&chat, "Probably yes"), None);
+    let history = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(!history.contains("Probably yes"), "{history}");
+    assert_eq!(history.matches("Yes:").count(), 1, "{history}");
+    assert!(!chat.transcript.to_markdown().contains("Probably yes"));
+}
+
+#[test]
+fn text_and_image_submission_renders_both_and_sends_both_parts() {
+    let (mut chat, rx, mut op_rx) = make_chatwidget_manual();
+    chat.submit_user_message(UserMessage::new(
+        "What does this screenshot show?".to_string(),
+        vec![PathBuf::from("shot.png")],
+    ));
+
+    let history = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(
+        history.contains("What does this screenshot show?"),
+        "{history}"
+    );
+    assert!(history.contains("image: shot.png"), "{history}");
+
+    let Ok(Op::UserInput { items }) = op_rx.try_recv() else {
+        panic!("expected the prompt to be sent");
+    };
+    assert!(
+        matches!(
+            &items[..],
+            [InputItem::Text { text }, InputItem::LocalImage { path }]
+                if text == "What does this screenshot show?" && path == Path::new("shot.png")
+        ),
+        "{items:?}"
+    );
+}
+
+#[test]
+fn followed_log_renders_lines_as_they_are_appended() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    let lines: Vec<String> = BufReader::new(open_fixture("two-messages.jsonl"))
+        .lines()
+        .collect::<Result<_, _>>()
+        .expect("read fixture");
+    let dir = tempfile::TempDir::new().expect("temp dir");
+    let path = dir.path().join("session.jsonl");
+    let mut log = File::create(&path).expect("create log");
+    let mut follower = LogFollower::open(&path).expect("open log");
+    let mut follow = |chat: &mut ChatWidget<'_>| {
+        for event in follower.read_events().expect("read log") {
+            chat.handle_codex_event(event);
+        }
+    };
+
+    // Up to the first answer, then half of the command that follows it.
+    let (written, rest) = lines.split_at(10);
+    let (head, tail) = rest[0].split_at(rest[0].len() / 2);
+    for line in written {
+        writeln!(log, "{line}").expect("append");
+    }
+    write!(log, "{head}").expect("append");
+    follow(&mut chat);
+    let transcript = chat.transcript.to_markdown();
+    assert!(
+        transcript.contains("I'll check whether `Cargo.lock` matches"),
+        "{transcript}"
+    );
+    assert!(
+        !transcript.contains("The lockfile is up to date"),
+        "{transcript}"
+    );
+
+    writeln!(log, "{tail}").expect("append");
+    for line in &rest[1..] {
+        writeln!(log, "{line}").expect("append");
+    }
+    follow(&mut chat);
+    let transcript = chat.transcript.to_markdown();
+    assert!(
+        transcript.contains("The lockfile is up to date"),
+        "{transcript}"
+    );
+    assert_eq!(chat.exec_results().len(), 1);
+}
+
+#[test]
+fn follower_records_approval_requests_and_takes_no_input() {
+    let (mut chat, rx, mut op_rx) = make_chatwidget_manual();
+    chat.read_only = true;
+    chat.bottom_pane.set_read_only(true);
+
+    chat.handle_codex_event(Event {
+        id: "sub-exec".into(),
+        msg: exec_approval_request(&["bash", "-lc", "curl example.com"]),
+    });
+    let mut changes = HashMap::new();
+    changes.insert(
+        PathBuf::from("a.rs"),
+        FileChange::Add {
+            content: "fn main() {}\n".into(),
+        },
+    );
+    chat.handle_codex_event(Event {
+        id: "sub-patch".into(),
+        msg: EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
+            call_id: "call-patch".into(),
+            changes,
+            reason: None,
+            grant_root: None,
+        }),
+    });
+    assert!(!render_contains(&chat, "Allow command?"));
+    assert!(!render_contains(&chat, "Apply changes?"));
+    let text = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(
+        text.contains("command requires approval in the followed session"),
+        "{text}"
+    );
+    assert!(text.contains("a.rs"), "{text}");
+
+    for c in "hello".chars() {
+        chat.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert!(chat.bottom_pane.composer_is_empty());
+    assert!(chat.transcript.turns().is_empty());
+    assert!(op_rx.try_recv().is_err());
+}
+
+#[test]
+fn escaped_markdown_renders_literal_characters_without_styling() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    replay_fixture_turns(&mut chat, "escaped-markdown.jsonl", |_| true);
+    let lines = drain_insert_history(&rx).concat();
+
+    for expected in [
+        "Call it as f(*args, **kwargs) from my_module.py, not `eval`.",
+        "Keep [brackets](literal) and __init__ as typed.",
+    ] {
+        let line = lines
+            .iter()
+            .find(|l| lines_to_single_string(std::slice::from_ref(*l)).contains(expected))
+            .unwrap_or_else(|| panic!("no {expected:?} in {lines:?}"));
+        for span in &line.spans {
+            assert_eq!(span.style, ratatui::style::Style::default(), "{span:?}");
+        }
+    }
+    // The transcript keeps the escapes as the model wrote them.
+    assert!(
+        chat.transcript
+            .to_markdown()
+            .contains(r"f(\*args, \*\*kwargs)")
+    );
+}
+
+#[test]
+fn inline_changes_render_struck_out_red_and_replacement_green() {
+    use crate::inline_diff::added_style;
+    use crate::inline_diff::removed_style;
+
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.inline_diffs = true;
+    chat.stream = StreamController::new(chat.config.clone());
+    replay_fixture_turns(&mut chat, "inline-diff.jsonl", |_| true);
+    let lines = drain_insert_history(&rx).concat();
+    let styled = |style: ratatui::style::Style| -> Vec<String> {
+        lines
+            .iter()
+            .flat_map(|line| &line.spans)
+            .filter(|span| span.style == style)
+            .map(|span| span.content.to_string())
+            .collect()
+    };
+    assert_eq!(styled(removed_style()), vec!["parse_args", "10"]);
+    assert_eq!(styled(added_style()), vec!["parse_cli", "20"]);
+    let text = lines_to_single_string(&lines);
+    assert!(
+        text.contains(
+            "I renamed the helper parse_args → parse_cli and bumped the limit from 10 20."
+        ),
+        "{text}"
+    );
+    // The transcript keeps the change as the model wrote it.
+    assert!(
+        chat.transcript
+            .to_markdown()
+            .contains("~~parse_args~~ → parse_cli")
+    );
+}
+
+#[test]
+fn truncated_answer_shows_marker_and_continue_resumes_it() {
+    let (mut chat, rx, mut op_rx) = make_chatwidget_manual();
+    chat.submit_text_message("How do I release?".to_string());
+    while op_rx.try_recv().is_ok() {}
+
+    let reader = BufReader::new(open_fixture("truncated-answer.jsonl"));
+    for line in reader.lines() {
+        let line = line.expect("read line");
+        let v: serde_json::Value = serde_json::from_str(&line).expect("parse line");
+        if v.get("kind").and_then(|k| k.as_str()) != Some("codex_event") {
+            continue;
+        }
+        let payload = v.get("payload").cloned().expect("payload");
+        let ev: Event = serde_json::from_value(payload).expect("parse event");
+        chat.handle_codex_event(ev);
+    }
+
+    let screen: String = drain_insert_history(&rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect();
+    let answer_at = screen.find("2. Tag the com").expect("answer rendered");
+    let marker_at = screen
+        .find("(response truncated)")
+        .expect("truncation marker rendered");
+    assert!(answer_at < marker_at, "{screen}");
+
+    chat.continue_response();
+    let Ok(Op::UserInput { items }) = op_rx.try_recv() else {
+        panic!("expected a continuation turn");
+    };
+    assert!(
+        matches!(&items[..], [InputItem::Text { text }] if text == CONTINUE_PROMPT),
+        "{items:?}"
+    );
+
+    chat.handle_codex_event(Event {
+        id: "2".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: "mit and push it".into(),
+        }),
+    });
+    let export = chat.export_markdown();
+    assert!(export.contains("2. Tag the commit and push it"), "{export}");
+    assert_eq!(export.matches("### user").count(), 1, "{export}");
+
+    // Nothing is left to continue.
+    chat.continue_response();
+    assert!(op_rx.try_recv().is_err());
+}
+
+#[test]
+fn code_block_border_marks_every_row_but_export_excludes_it() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.code_block_style = codex_core::config_types::CodeBlockStyle::Border;
+    chat.stream = StreamController::new(chat.config.clone());
+
+    chat.handle_codex_event(Event {
+        id: "b".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message:
+                "```\nfn main() {\n    println!(\"a line long enough to wrap around\");\n}\n```\n"
+                    .into(),
+        }),
+    });
+
+    let lines: Vec<Line<'static>> = drain_insert_history(&rx).into_iter().flatten().collect();
+    let rows = crate::insert_history::word_wrap_lines(&lines, 24);
+    let code_rows: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            row.spans
+                .iter()
+                .map(|s| s.content.as_ref())
+                .collect::<String>()
+        })
+        .filter(|text| text.starts_with(crate::markdown::CODE_BLOCK_BORDER))
+        .collect();
+    // Three code lines, the middle one wrapped onto several rows.
+    assert!(code_rows.len() > 3, "{code_rows:?}");
+    let code: String = code_rows
+        .iter()
+        .map(|row| &row[crate::markdown::CODE_BLOCK_BORDER.len()..])
+        .collect::<Vec<_>>()
+        .join(" ");
+    assert!(code.starts_with("fn main() {"), "{code:?}");
+    assert!(code.contains("wrap around"), "{code:?}");
+    assert!(code.ends_with('}'), "{code:?}");
+
+    let export = chat.export_markdown();
+    assert!(export.contains("println!"), "{export:?}");
+    assert!(!export.contains(crate::markdown::CODE_BLOCK_BORDER.trim_end()));
+}
+
+#[test]
+fn turn_accessors_match_task_started_count_during_replay() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    assert_eq!(chat.current_turn(), None);
+    assert_eq!(chat.turn_count(), 0);
+
+    let mut current_turn_index: Option<usize> = None;
+    let reader = BufReader::new(open_fixture("binary-size-log.jsonl"));
+    for line in reader.lines() {
+        let line = line.expect("read line");
+        let Ok(v): Result<serde_json::Value, _> = serde_json::from_str(&line) else {
+            continue;
+        };
+        if v.get("dir").and_then(|d| d.as_str()) != Some("to_tui")
+            || v.get("kind").and_then(|k| k.as_str()) != Some("codex_event")
+        {
+            continue;
+        }
+        let Some(payload) = v.get("payload") else {
+            continue;
+        };
+        let ev: Event = serde_json::from_value(payload.clone()).expect("parse");
+        if matches!(ev.msg, EventMsg::TaskStarted) {
+            current_turn_index = Some(current_turn_index.map_or(0, |i| i + 1));
+        }
+        chat.handle_codex_event(ev);
+        assert_eq!(chat.current_turn(), current_turn_index);
+        assert_eq!(chat.turn_count(), current_turn_index.map_or(0, |i| i + 1));
+    }
+    assert!(current_turn_index.is_some(), "fixture should contain turns");
+}
+
+#[test]
+fn plan_updates_rewrite_one_checklist_in_place() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+
+    let reader = BufReader::new(open_fixture("plan-updates.jsonl"));
+    let mut events = Vec::new();
+    for line in reader.lines() {
+        let line = line.expect("read line");
+        let v: serde_json::Value = serde_json::from_str(&line).expect("parse line");
+        if v.get("kind").and_then(|k| k.as_str()) != Some("codex_event") {
+            continue;
+        }
+        let payload = v.get("payload").cloned().expect("payload");
+        let ev: Event = serde_json::from_value(payload).expect("parse event");
+        events.push(ev);
+    }
+    let complete = events.pop().expect("task_complete");
+    assert!(matches!(complete.msg, EventMsg::TaskComplete(_)));
+    for ev in events {
+        chat.handle_codex_event(ev);
+    }
+
+    // Apply history events the way `App` does, below what the terminal
+    // already shows.
+    let drain = |rx: &std::sync::mpsc::Receiver<AppEvent>| {
+        let mut screen: Vec<Line<'static>> = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::InsertHistory(lines) = ev {
+                screen.extend(lines);
+            }
+        }
+        lines_to_single_string(&screen)
+    };
+    let live_text = |chat: &ChatWidget<'_>| -> String {
+        let area = Rect::new(0, 0, 80, chat.desired_height(80));
+        let mut buf = Buffer::empty(area);
+        chat.render_ref(area, &mut buf);
+        (0..area.height)
+            .map(|y| {
+                let row: String = (0..area.width).map(|x| buf[(x, y)].symbol()).collect();
+                row + "\n"
+            })
+            .collect()
+    };
+
+    // While the turn runs, the terminal keeps the first checklist, written
+    // once, and the live area above the composer shows the latest one.
+    let screen = drain(&rx);
+    assert_eq!(screen.matches("Update plan").count(), 1, "{screen}");
+    assert!(!screen.contains("3/3"), "{screen}");
+    let plan_at = screen.find("Update plan").expect("plan");
+    let answer_at = screen.find("trailing quote").expect("answer");
+    assert!(plan_at < answer_at, "{screen}");
+    let live = live_text(&chat);
+    assert!(live.contains("3/3"), "{live}");
+
+    // The transcript view holds the latest checklist in its place.
+    let recorded = lines_to_single_string(chat.scrollback.borrow().lines());
+    assert_eq!(recorded.matches("Update plan").count(), 1, "{recorded}");
+    assert!(recorded.contains("3/3"), "{recorded}");
+    assert!(!recorded.contains('□'), "{recorded}");
+
+    // Once the turn completes, the final checklist is written below the
+    // answer and the live area lets go of it.
+    chat.handle_codex_event(complete);
+    let screen = drain(&rx);
+    assert_eq!(screen.matches("Update plan").count(), 1, "{screen}");
+    assert!(screen.contains("3/3"), "{screen}");
+    assert!(!screen.contains('□'), "{screen}");
+    let live = live_text(&chat);
+    assert!(!live.contains("Update plan"), "{live}");
+    let recorded = lines_to_single_string(chat.scrollback.borrow().lines());
+    assert_eq!(recorded.matches("Update plan").count(), 1, "{recorded}");
+}
+
+#[test]
+fn updated_checklist_is_written_to_history_when_the_turn_is_interrupted() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    let plan = |statuses: [StepStatus; 2]| {
+        EventMsg::PlanUpdate(UpdatePlanArgs {
+            explanation: None,
+            plan: ["Write the fix", "Run the tests"]
+                .into_iter()
+                .zip(statuses)
+                .map(|(step, status)| PlanItemArg {
+                    step: step.to_string(),
+                    status,
+                })
+                .collect(),
+        })
+    };
+    chat.submit_text_message("fix it".to_string());
+    for msg in [
+        EventMsg::TaskStarted,
+        plan([StepStatus::InProgress, StepStatus::Pending]),
+        plan([StepStatus::Completed, StepStatus::InProgress]),
+    ] {
+        chat.handle_codex_event(Event {
+            id: "1".into(),
+            msg,
+        });
+    }
+    drain_insert_history(&rx);
+    assert!(chat.live_plan.is_some());
+
+    chat.on_ctrl_c();
+    assert!(chat.live_plan.is_none());
+    let text = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(text.contains("1/2"), "{text}");
+}
+
+#[test]
+fn html_entities_decode_on_screen_and_in_export() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+
+    let reader = BufReader::new(open_fixture("html-entities.jsonl"));
+    for line in reader.lines() {
+        let line = line.expect("read line");
+        let v: serde_json::Value = serde_json::from_str(&line).expect("parse line");
+        if v.get("kind").and_then(|k| k.as_str()) != Some("codex_event") {
+            continue;
+        }
+        let payload = v.get("payload").cloned().expect("payload");
+        let ev: Event = serde_json::from_value(payload).expect("parse event");
+        chat.handle_codex_event(ev);
+    }
+
+    let screen: String = drain_insert_history(&rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect();
+    assert!(screen.contains("Salt & pepper"), "{screen}");
+    assert!(screen.contains("seasonings — unlike"), "{screen}");
+    assert!(screen.contains("&bogus;"), "{screen}");
+    assert!(screen.contains("&& │ and"), "{screen}");
+    assert!(screen.contains("<= │ at most"), "{screen}");
+    // Code is shown as written.
+    assert!(screen.contains("Write &amp; for"), "{screen}");
+    assert!(
+        screen.contains("if a &lt; b &amp;&amp; c &gt; d {"),
+        "{screen}"
+    );
+    assert!(screen.contains("println!(&quot;ok&quot;);"), "{screen}");
+
+    let export = chat.export_markdown();
+    assert!(export.contains("Salt & pepper"), "{export}");
+    assert!(export.contains("&bogus;"), "{export}");
+    assert!(export.contains("| && | and |"), "{export}");
+    assert!(export.contains("Write `&amp;` for"), "{export}");
+    assert!(
+        export.contains("if a &lt; b &amp;&amp; c &gt; d {"),
+        "{export}"
+    );
+}
+
+#[test]
+fn rlo_in_answer_is_marked_and_does_not_reverse_following_text() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    let message = "Open invoice\u{202e}fdp.exe to continue.";
+    chat.handle_codex_event(Event {
+        id: "b".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: message.into(),
+        }),
+    });
+
+    let screen: String = drain_insert_history(&rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect();
+    assert!(!screen.contains('\u{202e}'), "{screen:?}");
+    assert!(
+        screen.contains("Open invoice⟨U+202E⟩fdp.exe to continue."),
+        "{screen:?}"
+    );
+    // The canonical transcript keeps the original character.
+    assert!(chat.export_markdown().contains(message));
+
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.control_chars = codex_core::config_types::ControlChars::Strip;
+    chat.stream = StreamController::new(chat.config.clone());
+    chat.handle_codex_event(Event {
+        id: "c".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: message.into(),
+        }),
+    });
+    let screen: String = drain_insert_history(&rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect();
+    assert!(
+        screen.contains("Open invoicefdp.exe to continue."),
+        "{screen:?}"
+    );
+}
+
+#[test]
+fn wait_for_complete_flushes_held_output_on_interrupt() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.stream_mode = codex_core::config_types::StreamMode::WaitForComplete;
+
+    chat.handle_codex_event(Event {
+        id: "w".into(),
+        msg: EventMsg::TaskStarted,
+    });
+    chat.handle_codex_event(Event {
+        id: "w".into(),
+        msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+            delta: "Partial answer.\n".into(),
+        }),
+    });
+    chat.on_commit_tick();
+    assert!(drain_insert_history(&rx).is_empty());
+
+    chat.on_ctrl_c();
+    chat.on_commit_tick();
+    let screen: String = drain_insert_history(&rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect();
+    assert!(screen.contains("Partial answer."), "{screen}");
+}
+
+#[test]
+fn wait_for_complete_inserts_nothing_until_the_turn_ends() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.stream_mode = codex_core::config_types::StreamMode::WaitForComplete;
+
+    let answer = "First line.\nSecond line.\nThird line.";
+    let send = |chat: &mut ChatWidget, msg: EventMsg| {
+        chat.handle_codex_event(Event {
+            id: "w".into(),
+            msg,
+        })
+    };
+    send(&mut chat, EventMsg::TaskStarted);
+    send(
+        &mut chat,
+        EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
+            delta: "**Checking**\n".into(),
+        }),
+    );
+    send(
+        &mut chat,
+        EventMsg::AgentReasoning(AgentReasoningEvent {
+            text: "**Checking**\n".into(),
+        }),
+    );
+    for part in ["First line.\nSec", "ond line.\n", "Third line."] {
+        send(
+            &mut chat,
+            EventMsg::AgentMessageDelta(AgentMessageDeltaEvent { delta: part.into() }),
+        );
+        chat.on_commit_tick();
+    }
+    send(
+        &mut chat,
+        EventMsg::AgentMessage(AgentMessageEvent {
+            message: answer.into(),
+        }),
+    );
+    chat.on_commit_tick();
+    assert!(
+        drain_insert_history(&rx).is_empty(),
+        "nothing should be inserted before the turn completes"
+    );
+
+    send(
+        &mut chat,
+        EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: Some(answer.into()),
+        }),
+    );
+    chat.on_commit_tick();
+    let screen: String = drain_insert_history(&rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect();
+    assert_eq!(screen.matches("thinking\n").count(), 1, "{screen}");
+    assert_eq!(screen.matches("codex\n").count(), 1, "{screen}");
+    assert_eq!(screen.matches("Second line.").count(), 1, "{screen}");
+    let reasoning_at = screen.find("Checking").expect("reasoning");
+    let answer_at = screen
+        .find("First line.\nSecond line.\nThird line.\n")
+        .expect("complete answer");
+    assert!(reasoning_at < answer_at, "{screen}");
+}
+
+#[test]
+fn file_references_render_as_links_with_configured_url() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.file_reference_url = Some("myeditor://open?file={path}&line={line}".into());
+    chat.stream = StreamController::new(chat.config.clone());
+
+    let reader = BufReader::new(open_fixture("file-references.jsonl"));
+    for line in reader.lines() {
+        let line = line.expect("read line");
+        let v: serde_json::Value = serde_json::from_str(&line).expect("parse line");
+        if v.get("kind").and_then(|k| k.as_str()) != Some("codex_event") {
+            continue;
+        }
+        let payload = v.get("payload").cloned().expect("payload");
+        let ev: Event = serde_json::from_value(payload).expect("parse event");
+        chat.handle_codex_event(ev);
+    }
+    let lines: Vec<Line<'static>> = drain_insert_history(&rx).into_iter().flatten().collect();
+
+    let references: Vec<&str> = lines
+        .iter()
+        .flat_map(|l| l.spans.iter())
+        .filter(|s| {
+            s.style
+                .add_modifier
+                .contains(ratatui::style::Modifier::UNDERLINED)
+        })
+        .map(|s| s.content.as_ref())
+        .collect();
+    assert_eq!(references, vec!["src/frobnicate.rs:42", "src/defrob.rs:7"]);
+
+    let width: u16 = 100;
+    let height: u16 = 20;
+    let backend = ratatui::backend::TestBackend::new(width, height);
+    let mut terminal = crate::custom_terminal::Terminal::with_options(backend)
+        .expect("failed to construct terminal");
+    terminal.set_viewport_area(Rect::new(0, height - 1, width, 1));
+    let mut ansi: Vec<u8> = Vec::new();
+    let links = crate::file_reference::FileLinks::from_config(&chat.config);
+    crate::insert_history::insert_wrapped_history_lines_to_writer(
+        &mut terminal,
+        &mut ansi,
+        lines,
+        None,
+        links.as_ref(),
+    );
+    let ansi = String::from_utf8(ansi).expect("utf8");
+
+    let cwd = chat.config.cwd.to_string_lossy().replace('\\', "/");
+    let expected = format!(
+        "\x1b]8;;myeditor://open?file={cwd}/src/frobnicate.rs&line=42\x1b\\src/frobnicate.rs:42\x1b]8;;\x1b\\"
+    );
+    assert!(ansi.contains(&expected), "{ansi:?}");
+    assert!(
+        ansi.contains(&format!("file={cwd}/src/defrob.rs&line=7")),
+        "{ansi:?}"
+    );
+}
+
+#[test]
+fn attached_file_contents_are_sent_but_only_marker_is_shown() {
+    let (mut chat, rx, mut op_rx) = make_chatwidget_manual();
+    let dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("main.rs"),
+        "fn main() {\n    secret_body();\n}\n",
+    )
+    .unwrap();
+    chat.config.cwd = dir.path().to_path_buf();
+
+    chat.submit_text_message("review @main.rs please".to_string());
+
+    let Ok(Op::UserInput { items }) = op_rx.try_recv() else {
+        panic!("expected UserInput op");
+    };
+    let sent: Vec<String> = items
+        .into_iter()
+        .filter_map(|item| match item {
+            InputItem::Text { text } => Some(text),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(sent[0], "review @main.rs please");
+    assert!(
+        sent[1].contains("secret_body();") && sent[1].contains("path=\"main.rs\""),
+        "attachment not sent: {sent:?}"
+    );
+
+    let cells = drain_insert_history(&rx);
+    let blob = lines_to_single_string(cells.last().expect("user cell"));
+    assert!(blob.contains("attached: main.rs (3 lines)"), "{blob:?}");
+    assert!(!blob.contains("secret_body"), "{blob:?}");
+}
+
+#[test]
+fn tabs_expand_on_screen_but_export_preserves_them() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.tab_width = 4;
+    chat.stream = StreamController::new(chat.config.clone());
+
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: "```\nfn main() {\n\tprintln!();\n}\n```\n".into(),
+        }),
+    });
+
+    let cells = drain_insert_history(&rx);
+    let screen: String = cells.iter().map(|c| lines_to_single_string(c)).collect();
+    assert!(screen.contains("\n    println!();\n"), "{screen:?}");
+    assert!(!screen.contains('\t'), "{screen:?}");
+
+    assert!(
+        chat.export_markdown().contains("\tprintln!();"),
+        "export should keep the original tab"
+    );
+}
+
+#[test]
+fn details_sections_fold_under_their_summary_until_expanded() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    replay_fixture_turns(&mut chat, "details-section.jsonl", |_| true);
+    let history = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(history.contains("▸ Full compiler output"), "{history}");
+    assert!(!history.contains("<details>"), "{history}");
+
+    let area = Rect::new(0, 0, 80, 20);
+    let screen = |chat: &ChatWidget<'_>| -> String {
+        let mut buf = Buffer::empty(area);
+        chat.render_transcript(area, &mut buf);
+        (0..area.height)
+            .map(|y| {
+                let row: String = (0..area.width).map(|x| buf[(x, y)].symbol()).collect();
+                row.trim_end().to_string() + "\n"
+            })
+            .collect()
+    };
+    let collapsed = screen(&chat);
+    assert!(collapsed.contains("▸ Full compiler output"), "{collapsed}");
+    assert!(collapsed.contains("more lines"), "{collapsed}");
+    assert!(!collapsed.contains("error[E0433]"), "{collapsed}");
+    assert!(
+        collapsed.contains("Add features = [\"json\"]"),
+        "{collapsed}"
+    );
+    assert!(collapsed.contains("e expand details"), "{collapsed}");
+
+    chat.handle_transcript_key_event(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+    let expanded = screen(&chat);
+    assert!(expanded.contains("▾ Full compiler output"), "{expanded}");
+    assert!(expanded.contains("error[E0433]"), "{expanded}");
+    assert!(
+        expanded.contains("note: enable the json feature"),
+        "{expanded}"
+    );
+    assert!(!expanded.contains("more lines"), "{expanded}");
+}
+
+#[test]
+fn status_events_show_transiently_and_stay_out_of_the_answer() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    let reader = BufReader::new(open_fixture("status-events.jsonl"));
+    let mut shown = Vec::new();
+    for line in reader.lines() {
+        let v: serde_json::Value =
+            serde_json::from_str(&line.expect("read line")).expect("parse line");
+        if v["kind"] != "codex_event" {
+            continue;
+        }
+        let ev: Event = serde_json::from_value(v["payload"].clone()).expect("parse event");
+        let kind = ev.msg.to_string();
+        chat.handle_codex_event(ev);
+        shown.push((
+            kind,
+            render_contains(&chat, "Searching the web…"),
+            render_contains(&chat, "Reading 3 results…"),
+        ));
+    }
+    let step = |kind: &str, searching: bool, reading: bool| (kind.to_string(), searching, reading);
+    assert_eq!(
+        shown,
+        vec![
+            step("task_started", false, false),
+            step("background_event", true, false),
+            // Each status replaces the previous one.
+            step("background_event", false, true),
+            // The answer clears it.
+            step("agent_message_delta", false, false),
+            step("agent_message", false, false),
+            step("task_complete", false, false),
+        ]
+    );
+
+    let history = lines_to_single_string(&drain_insert_history(&rx).concat());
+    assert!(history.contains("Rust 1.89 was released"), "{history}");
+    assert!(!history.contains("Searching the web"), "{history}");
+    let transcript = chat.transcript.to_markdown();
+    assert!(!transcript.contains("Searching the web"), "{transcript}");
+    assert!(!transcript.contains("Reading 3 results"), "{transcript}");
+}
+
+#[test]
+fn bookmarked_turn_is_reached_by_next_bookmark_and_exported_alone() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    for question in ["first question", "second question", "third question"] {
+        chat.submit_text_message(question.to_string());
+        let answer: String = (1..=10)
+            .map(|i| format!("Step {i} for the {question}.\n\n"))
+            .collect();
+        chat.handle_codex_event(Event {
+            id: "t".into(),
+            msg: EventMsg::AgentMessage(AgentMessageEvent { message: answer }),
+        });
+    }
+    let area = Rect::new(0, 0, 60, 10);
+    let screen = |chat: &ChatWidget<'_>| -> String {
+        let mut buf = Buffer::empty(area);
+        chat.render_transcript(area, &mut buf);
+        (0..area.height)
+            .map(|y| {
+                let row: String = (0..area.width).map(|x| buf[(x, y)].symbol()).collect();
+                row.trim_end().to_string() + "\n"
+            })
+            .collect()
+    };
+    let key = |chat: &mut ChatWidget<'_>, code: KeyCode| {
+        chat.handle_transcript_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+    };
+    screen(&chat);
+    key(&mut chat, KeyCode::Home);
+    key(&mut chat, KeyCode::Char('b'));
+    assert!(screen(&chat).contains("★"));
+
+    key(&mut chat, KeyCode::End);
+    assert_ne!(chat.turn_in_view(), Some(0));
+    key(&mut chat, KeyCode::Char(']'));
+    assert_eq!(chat.turn_in_view(), Some(0));
+    let outline = chat.outline();
+    assert_eq!(
+        chat.scrollback.borrow().visible_rows().start,
+        outline[0].row
+    );
+
+    let exported = chat.bookmarks_markdown();
+    assert!(exported.contains("first question"), "{exported}");
+    assert!(
+        exported.contains("Step 10 for the first question."),
+        "{exported}"
+    );
+    assert!(!exported.contains("second question"), "{exported}");
+    assert!(!exported.contains("third question"), "{exported}");
+
+    // The raw view has no bookmarks, so its hint leaves them out.
+    let wide = Rect::new(0, 0, 120, 10);
+    let hint = |chat: &ChatWidget<'_>| -> String {
+        let mut buf = Buffer::empty(wide);
+        chat.render_transcript(wide, &mut buf);
+        (0..wide.width)
+            .map(|x| buf[(x, wide.height - 1)].symbol())
+            .collect()
+    };
+    assert!(hint(&chat).contains("]/[ bookmarks"));
+    key(&mut chat, KeyCode::Char('r'));
+    assert!(!hint(&chat).contains("bookmark"));
+}
+
+#[test]
+fn prefilled_initial_prompt_waits_in_the_composer_until_enter() {
+    let (chat, _rx, mut op_rx) = make_chatwidget_manual();
+    let mut chat = chat.with_initial_prompt(InitialPrompt::Prefill);
+    chat.initial_user_message = Some(UserMessage::from("fix the flaky test".to_string()));
+    let user_inputs =
+        |op_rx: &mut tokio::sync::mpsc::UnboundedReceiver<Op>| -> Vec<Vec<InputItem>> {
+            std::iter::from_fn(|| op_rx.try_recv().ok())
+                .filter_map(|op| match op {
+                    Op::UserInput { items } => Some(items),
+                    _ => None,
+                })
+                .collect()
+        };
+
+    // Only the events before the first turn, among them `SessionConfigured`.
+    replay_fixture_turns(&mut chat, "longer-markdown-session.jsonl", |_| false);
+    assert!(user_inputs(&mut op_rx).is_empty());
+    assert!(render_contains(&chat, "fix the flaky test"));
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    let sent = user_inputs(&mut op_rx);
+    assert!(
+        matches!(&sent[..], [items] if matches!(&items[..], [InputItem::Text { text }] if text == "fix the flaky test")),
+        "{sent:?}"
+    );
+}
+
+#[test]
+fn bubble_layout_puts_prompts_right_and_answers_left() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.message_layout = codex_core::config_types::MessageLayout::Bubbles;
+    chat.config.tui.bubble_max_width = Some(40);
+    chat.set_viewport(Size::new(100, 30));
+
+    chat.submit_text_message("Which tool builds this?".to_string());
+    chat.handle_codex_event(Event {
+        id: "s1".into(),
+        msg: EventMsg::TaskStarted,
+    });
+    chat.handle_codex_event(Event {
+        id: "s1".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: "Cargo builds the whole workspace.".into(),
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "s1".into(),
+        msg: EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: None,
+        }),
+    });
+
+    let history = lines_to_single_string(&drain_insert_history(&rx).concat());
+    let rows: Vec<&str> = history.lines().collect();
+    let row = |needle: &str| {
+        rows.iter()
+            .position(|row| row.contains(needle))
+            .unwrap_or_else(|| panic!("no row with {needle:?} in\n{history}"))
+    };
+
+    // The prompt: a bubble shrunk to its text, flush with the right edge.
+    let prompt = row("Which tool builds this?");
+    assert_eq!(
+        rows[prompt],
+        format!("{}│ Which tool builds this? │", " ".repeat(73))
+    );
+    assert!(
+        rows[prompt - 1].trim_start().starts_with("╭─ user "),
+        "{history}"
+    );
+    assert!(rows[prompt - 1].ends_with('╮'), "{history}");
+    assert_eq!(
+        rows[prompt + 1],
+        format!("{}╰{}╯", " ".repeat(73), "─".repeat(25))
+    );
+
+    // The answer: a bubble of the maximum width at the left edge.
+    let answer = row("Cargo builds the whole workspace.");
+    assert_eq!(
+        rows[answer],
+        format!("│ Cargo builds the whole workspace.{} │", " ".repeat(3))
+    );
+    assert!(rows[answer - 1].starts_with("╭─ codex "), "{history}");
+    assert_eq!(rows[answer + 1], format!("╰{}╯", "─".repeat(38)));
+
+    // The transcript view keeps the header layout.
+    let transcript = lines_to_single_string(chat.scrollback.borrow().lines());
+    assert!(!transcript.contains('╭'), "{transcript}");
+}
+
+#[test]
+fn structured_transcript_of_a_replayed_session_round_trips_through_serde() {
+    let (mut chat, _rx, mut op_rx) = make_chatwidget_manual();
+    chat.submit_text_message("Why do the tests fail?".to_string());
+    replay_fixture_turns(&mut chat, "structured-session.jsonl", |turn| turn == 0);
+    chat.submit_text_message("Show the diff.".to_string());
+    replay_fixture_turns(&mut chat, "structured-session.jsonl", |turn| turn == 1);
+    while op_rx.try_recv().is_ok() {}
+
+    let turns = chat.transcript_structured();
+    let prompts: Vec<&str> = turns.iter().map(|turn| turn.user.as_str()).collect();
+    assert_eq!(prompts, vec!["Why do the tests fail?", "Show the diff."]);
+    assert!(
+        turns[0].reasoning.contains("Checking the tests"),
+        "{turns:?}"
+    );
+    assert_eq!(
+        turns[0].answer,
+        "`parses` fails: the parser rejects a trailing comma."
+    );
+    let commands: Vec<Vec<(String, i32)>> = turns
+        .iter()
+        .map(|turn| {
+            turn.exec
+                .iter()
+                .map(|exec| (exec.command.join(" "), exec.exit_code))
+                .collect()
+        })
+        .collect();
+    assert_eq!(
+        commands,
+        vec![
+            vec![("bash -lc cargo test -p demo".to_string(), 101)],
+            vec![("bash -lc git diff --stat".to_string(), 0)],
+        ]
+    );
+    assert_eq!(
+        turns[0].exec[0].stderr,
+        "error: test failed, to rerun pass `--lib`\n"
+    );
+    assert!(turns[0].errors.is_empty());
+    assert_eq!(
+        turns[1].errors,
+        vec!["unexpected status 400 Bad Request: context too long".to_string()]
+    );
+    assert!(turns[1].answer.is_empty());
+
+    let json = serde_json::to_string(&turns).expect("serialize");
+    let back: Vec<TurnRecord> = serde_json::from_str(&json).expect("deserialize");
+    assert_eq!(back, turns);
+}
+
+#[test]
+fn longer_and_tilde_fences_keep_inner_backtick_fences_as_code() {
+    let (mut chat, rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui.code_block_style = codex_core::config_types::CodeBlockStyle::Border;
+    chat.stream = StreamController::new(chat.config.clone());
+    replay_fixture_turns(&mut chat, "nested-fence.jsonl", |_| true);
+
+    let history = lines_to_single_string(&drain_insert_history(&rx).concat());
+    let rows: Vec<&str> = history.lines().map(str::trim_end).collect();
+    let border = crate::markdown::CODE_BLOCK_BORDER;
+    let code = |text: &str| format!("{border}{text}");
+    let start = rows
+        .iter()
+        .position(|row| row.ends_with("```rust"))
+        .unwrap_or_else(|| panic!("no inner fence in\n{history}"));
+    assert_eq!(
+        rows[start..start + 3],
+        [code("```rust"), code("fn main() {}"), code("```")]
+    );
+    // The outer fences are not drawn, and what follows them is prose again.
+    assert!(!rows.contains(&"````"), "{history}");
+    assert!(!rows.contains(&"~~~"), "{history}");
+    assert!(rows.contains(&"A tilde fence works too:"), "{history}");
+    assert!(rows.contains(&code("```").as_str()), "{history}");
+    assert!(rows.contains(&"Done."), "{history}");
+
+    let blocks: Vec<String> = chat.code_blocks().into_iter().map(|b| b.code).collect();
+    assert_eq!(blocks, vec!["```rust\nfn main() {}\n```", "```"]);
+}
+
+/// A widget with a turn running for "first", its ops so far drained.
+fn chat_with_running_turn(
+    ctrl_c: CtrlCBehavior,
+) -> (
+    ChatWidget<'static>,
+    tokio::sync::mpsc::UnboundedReceiver<Op>,
+) {
+    let (mut chat, _rx, mut op_rx) = make_chatwidget_manual();
+    chat.config.tui.ctrl_c = ctrl_c;
+    chat.submit_text_message("first".to_string());
+    chat.handle_codex_event(Event {
+        id: "t".into(),
+        msg: EventMsg::TaskStarted,
+    });
+    while op_rx.try_recv().is_ok() {}
+    (chat, op_rx)
+}
+
+#[test]
+fn ctrl_c_cancels_the_turn_then_quits_on_a_second_quick_press() {
+    let (mut chat, mut op_rx) = chat_with_running_turn(CtrlCBehavior::CancelThenQuit);
+
+    assert_eq!(chat.on_ctrl_c(), CancellationEvent::Ignored);
+    assert!(matches!(op_rx.try_recv(), Ok(Op::Interrupt)));
+    assert!(op_rx.try_recv().is_err());
+    assert!(!chat.bottom_pane.is_task_running());
+    assert!(chat.bottom_pane.ctrl_c_quit_hint_visible());
+    assert_eq!(chat.turn_state(0), TurnState::Interrupted);
+
+    assert_eq!(chat.on_ctrl_c(), CancellationEvent::Handled);
+    assert!(matches!(op_rx.try_recv(), Ok(Op::Shutdown)));
+}
+
+#[test]
+fn ctrl_c_outside_the_quit_window_only_shows_the_hint_again() {
+    let (mut chat, mut op_rx) = chat_with_running_turn(CtrlCBehavior::CancelThenQuit);
+    chat.config.tui.ctrl_c_quit_window_ms = Some(0);
+
+    chat.on_ctrl_c();
+    assert!(matches!(op_rx.try_recv(), Ok(Op::Interrupt)));
+    std::thread::sleep(Duration::from_millis(5));
+    assert_eq!(chat.on_ctrl_c(), CancellationEvent::Ignored);
+    assert!(op_rx.try_recv().is_err());
+    assert!(chat.bottom_pane.ctrl_c_quit_hint_visible());
+}
+
+#[test]
+fn quit_mode_shuts_down_on_the_first_ctrl_c() {
+    let (mut chat, mut op_rx) = chat_with_running_turn(CtrlCBehavior::Quit);
+    assert_eq!(chat.on_ctrl_c(), CancellationEvent::Handled);
+    assert!(matches!(op_rx.try_recv(), Ok(Op::Shutdown)));
+}
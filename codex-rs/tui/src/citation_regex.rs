@@ -20,3 +20,29 @@ lazy_static::lazy_static! {
         r"【F:([^†]+)†L(\d+)(?:-L(\d+|\?))?】"
     ).expect("failed to compile citation regex");
 }
+
+lazy_static::lazy_static! {
+    /// Regular expression that matches plain `path:line` references such as
+    /// `src/main.rs:42` or `/tmp/x.py:7`, when they start a word.
+    ///
+    /// Capture groups:
+    /// 1. the character before the reference (empty at the start of input)
+    /// 2. file path, which must have an extension starting with a letter
+    /// 3. line number
+    pub(crate) static ref FILE_REFERENCE_REGEX: Regex = Regex::new(
+        r#"(^|[\s(\[`'"])(/?(?:[A-Za-z0-9_.\-]+/)*[A-Za-z0-9_\-][A-Za-z0-9_.\-]*\.[A-Za-z][A-Za-z0-9]*):(\d+)"#
+    ).expect("failed to compile file reference regex");
+}
+
+lazy_static::lazy_static! {
+    /// Regular expression that matches inline markdown links and images such
+    /// as `[text](https://example.com "title")`.
+    ///
+    /// Capture groups:
+    /// 1. `!` for an image, otherwise empty
+    /// 2. link text
+    /// 3. destination URL
+    pub(crate) static ref MARKDOWN_LINK_REGEX: Regex = Regex::new(
+        r#"(!?)\[([^\]\n]+)\]\(([^)\s]+)(?:\s+"[^"\n]*")?\)"#
+    ).expect("failed to compile markdown link regex");
+}
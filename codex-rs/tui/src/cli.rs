@@ -13,6 +13,10 @@ pub struct Cli {
     #[arg(long = "image", short = 'i', value_name = "FILE", value_delimiter = ',', num_args = 1..)]
     pub images: Vec<PathBuf>,
 
+    /// Put the prompt in the composer to be edited, instead of sending it.
+    #[arg(long = "edit", default_value_t = false)]
+    pub edit: bool,
+
     /// Model the agent should use.
     #[arg(long, short = 'm')]
     pub model: Option<String>,
@@ -54,6 +58,11 @@ pub struct Cli {
     #[clap(long = "cd", short = 'C', value_name = "DIR")]
     pub cwd: Option<PathBuf>,
 
+    /// Render a session log recorded with `CODEX_TUI_RECORD_SESSION=1` live
+    /// as it is written, like `tail -f`, instead of starting a session.
+    #[arg(long = "follow", value_name = "LOG", conflicts_with_all = ["prompt", "images", "edit"])]
+    pub follow: Option<PathBuf>,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 }
@@ -0,0 +1,27 @@
+//! Copy text to the system clipboard using the OSC 52 terminal escape. This
+//! works over SSH and inside tmux (with `set-clipboard on`) without linking a
+//! native clipboard library.
+
+use base64::Engine;
+use std::io::Write;
+
+pub(crate) fn copy_to_clipboard(text: &str) -> std::io::Result<()> {
+    let mut stdout = std::io::stdout();
+    stdout.write_all(osc52_sequence(text).as_bytes())?;
+    stdout.flush()
+}
+
+fn osc52_sequence(text: &str) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    format!("\x1b]52;c;{encoded}\x07")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn osc52_sequence_encodes_text() {
+        assert_eq!(osc52_sequence("hi"), "\x1b]52;c;aGk=\x07");
+    }
+}
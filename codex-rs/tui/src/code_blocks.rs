@@ -0,0 +1,123 @@
+//! Code blocks of the answers in the transcript view, so they can be picked
+//! with Tab / Shift+Tab and copied with `c`.
+//!
+//! Rendered lines carry no trace of where a fence was, so each code block of
+//! the answers' source is located by finding its lines, in order, among the
+//! lines of the answer blocks. The copied text is the source, not what was
+//! drawn (no expanded tabs, borders or whitespace markers).
+
+use std::ops::Range;
+
+use ratatui::text::Line;
+
+use crate::markdown::CODE_BLOCK_BORDER;
+use crate::markdown::code_block_contents;
+
+/// A code block found in the transcript view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CodeBlock {
+    /// Lines of the block in the transcript view.
+    pub(crate) lines: Range<usize>,
+    /// The code as the model wrote it, without the fences.
+    pub(crate) code: String,
+}
+
+/// The selected code block of the focused answer.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CodeSelection {
+    /// Index among all located code blocks.
+    pub(crate) index: usize,
+    /// Position among the code blocks of the focused answer, and their count.
+    pub(crate) position: usize,
+    pub(crate) count: usize,
+    /// The block was copied since it was selected.
+    pub(crate) copied: bool,
+}
+
+/// Locate the code blocks of `answers` (the source of each answer, oldest
+/// first) in `lines`, searching only within `answer_ranges` (the lines of
+/// each answer block, in order). Blocks that cannot be found are skipped.
+pub(crate) fn locate_code_blocks<'a>(
+    answers: impl IntoIterator<Item = &'a str>,
+    lines: &[Line<'_>],
+    answer_ranges: &[Range<usize>],
+) -> Vec<CodeBlock> {
+    let rendered: Vec<String> = lines.iter().map(rendered_code_text).collect();
+    let mut found = Vec::new();
+    // Blocks appear in the same order as in the source, so each search
+    // starts after the previous match.
+    let mut cursor = 0;
+    for code in answers.into_iter().flat_map(code_block_contents) {
+        let wanted: Vec<String> = code.lines().map(normalize).collect();
+        if wanted.is_empty() {
+            continue;
+        }
+        let start = answer_ranges.iter().find_map(|range| {
+            (range.start.max(cursor)..range.end)
+                .take_while(|start| start + wanted.len() <= range.end)
+                .find(|&start| rendered[start..start + wanted.len()] == wanted[..])
+        });
+        if let Some(start) = start {
+            let end = start + wanted.len();
+            cursor = end;
+            found.push(CodeBlock {
+                lines: start..end,
+                code: code.strip_suffix('\n').unwrap_or(&code).to_string(),
+            });
+        }
+    }
+    found
+}
+
+/// Text of a rendered line, compared the same way as the source: without the
+/// code border and trailing whitespace markers.
+fn rendered_code_text(line: &Line<'_>) -> String {
+    let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+    let text = text.strip_prefix(CODE_BLOCK_BORDER).unwrap_or(&text);
+    normalize(text.trim_end_matches('·'))
+}
+
+/// Whitespace differs between source and screen (tabs are expanded), so
+/// lines are compared word by word.
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn blocks_are_found_in_order_within_answers_only() {
+        let lines: Vec<Line<'static>> = [
+            "user",
+            "cargo test",
+            "codex",
+            "Run:",
+            "",
+            "▎ cargo test",
+            "",
+            "then",
+            "    x\t= 1",
+        ]
+        .into_iter()
+        .map(Line::from)
+        .collect();
+        let answer = "Run:\n\n```\ncargo test\n```\n\nthen\n\n```\n\tx = 1\n```\n";
+        let blocks = locate_code_blocks([answer], &lines, std::slice::from_ref(&(2..9)));
+        assert_eq!(
+            blocks,
+            vec![
+                CodeBlock {
+                    lines: 5..6,
+                    code: "cargo test".to_string(),
+                },
+                CodeBlock {
+                    lines: 8..9,
+                    code: "\tx = 1".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Collapsible sections in answers, written as HTML `<details>` with an
+//! optional `<summary>`.
+//!
+//! A section is rendered as a `▸ summary` line followed by its body. The
+//! transcript view folds the body under the summary line, like the other
+//! folds of `tui.expand`, until `e` expands it. As with code blocks, the
+//! rendered lines carry no trace of the section, so each one is located by
+//! finding its summary line among the lines of the answer blocks.
+
+use std::ops::Range;
+
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+
+use crate::render::markdown_utils::fence_after;
+
+/// Drawn before the summary of a folded section, and of an expanded one.
+const COLLAPSED_GLYPH: &str = "▸ ";
+const EXPANDED_GLYPH: &str = "▾ ";
+
+/// Summary of a section without a `<summary>` element.
+const DEFAULT_SUMMARY: &str = "Details";
+
+/// Part of an answer's source: markdown outside any section, or a section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum DetailsPiece<'a> {
+    Markdown(&'a str),
+    Details {
+        /// `None` until the line after `<details>` has arrived, so a
+        /// streaming section does not show a summary it would later change.
+        summary: Option<String>,
+        /// The markdown between the summary and `</details>`, or the rest of
+        /// the source while the section is still open.
+        body: &'a str,
+    },
+}
+
+/// Split `source` into markdown and `<details>` sections. Tags inside fenced
+/// code blocks are left alone, and a section nested in another is part of
+/// its body.
+pub(crate) fn split_details(source: &str) -> Vec<DetailsPiece<'_>> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        lines.push((offset, line));
+        offset += line.len();
+    }
+    let mut pieces = Vec::new();
+    let mut text_start = 0;
+    let mut fence = None;
+    let mut i = 0;
+    while i < lines.len() {
+        let (start, line) = lines[i];
+        i += 1;
+        fence = fence_after(fence, line);
+        if fence.is_some() || !is_details_open(line) {
+            continue;
+        }
+        if text_start < start {
+            pieces.push(DetailsPiece::Markdown(&source[text_start..start]));
+        }
+        let mut summary = summary_in(line);
+        if summary.is_none() {
+            while lines.get(i).is_some_and(|(_, l)| l.trim().is_empty()) {
+                i += 1;
+            }
+            if let Some((_, next)) = lines.get(i) {
+                summary = summary_in(next);
+                if summary.is_some() {
+                    i += 1;
+                } else {
+                    summary = Some(DEFAULT_SUMMARY.to_string());
+                }
+            }
+        }
+        let body_start = lines.get(i).map_or(source.len(), |(s, _)| *s);
+        let mut body_end = source.len();
+        let mut end = source.len();
+        let mut depth = 1;
+        let mut body_fence = None;
+        while let Some(&(s, l)) = lines.get(i) {
+            i += 1;
+            let in_fence = body_fence.is_some();
+            body_fence = fence_after(body_fence, l);
+            if in_fence || body_fence.is_some() {
+                continue;
+            }
+            if is_details_open(l) {
+                depth += 1;
+            } else if l.trim_start().starts_with("</details>") {
+                depth -= 1;
+                if depth == 0 {
+                    body_end = s;
+                    end = s + l.len();
+                    break;
+                }
+            }
+        }
+        pieces.push(DetailsPiece::Details {
+            summary,
+            body: &source[body_start..body_end],
+        });
+        text_start = end;
+    }
+    if text_start < source.len() {
+        pieces.push(DetailsPiece::Markdown(&source[text_start..]));
+    }
+    pieces
+}
+
+/// The line a section's summary is drawn as.
+pub(crate) fn summary_line(summary: &str) -> Line<'static> {
+    Line::from(vec![COLLAPSED_GLYPH.dim(), summary.to_string().bold()])
+}
+
+/// `line`, a summary line, with the glyph of an expanded section.
+pub(crate) fn expanded_summary_line(line: &Line<'static>) -> Line<'static> {
+    let mut line = line.clone();
+    if let Some(glyph) = line
+        .spans
+        .first_mut()
+        .filter(|span| span.content == COLLAPSED_GLYPH)
+    {
+        glyph.content = EXPANDED_GLYPH.into();
+    }
+    line
+}
+
+/// Locate the sections of `answers` (the source of each answer, oldest
+/// first) in `lines`, searching only within `answer_ranges`. Each range
+/// covers the summary line and the `body_len` lines its body renders to.
+/// Sections with an empty body, or that cannot be found, are skipped.
+pub(crate) fn locate_details<'a>(
+    answers: impl IntoIterator<Item = &'a str>,
+    lines: &[Line<'_>],
+    answer_ranges: &[Range<usize>],
+    body_len: impl Fn(&str) -> usize,
+) -> Vec<Range<usize>> {
+    let mut found = Vec::new();
+    let mut cursor = 0;
+    for piece in answers.into_iter().flat_map(split_details) {
+        let DetailsPiece::Details {
+            summary: Some(summary),
+            body,
+        } = piece
+        else {
+            continue;
+        };
+        let wanted = format!("{COLLAPSED_GLYPH}{summary}");
+        let start = answer_ranges.iter().find_map(|range| {
+            (range.start.max(cursor)..range.end).find(|&i| {
+                lines[i]
+                    .spans
+                    .iter()
+                    .map(|s| s.content.as_ref())
+                    .collect::<String>()
+                    == wanted
+            })
+        });
+        let Some(start) = start else {
+            continue;
+        };
+        let len = body_len(body);
+        cursor = start + 1 + len;
+        if len > 0 {
+            found.push(start..cursor);
+        }
+    }
+    found
+}
+
+fn is_details_open(line: &str) -> bool {
+    line.trim_start()
+        .strip_prefix("<details")
+        .is_some_and(|rest| rest.starts_with('>') || rest.starts_with(char::is_whitespace))
+}
+
+/// The text of a `<summary>…</summary>` element in `line`, if any.
+fn summary_in(line: &str) -> Option<String> {
+    let start = line.find("<summary>")? + "<summary>".len();
+    let len = line[start..].find("</summary>")?;
+    let summary = line[start..start + len].trim();
+    (!summary.is_empty()).then(|| summary.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn sections_are_split_from_the_markdown_around_them() {
+        let source =
+            "Intro\n\n<details>\n<summary>Why</summary>\n\nBecause.\n</details>\n\nAfter\n";
+        assert_eq!(
+            split_details(source),
+            vec![
+                DetailsPiece::Markdown("Intro\n\n"),
+                DetailsPiece::Details {
+                    summary: Some("Why".to_string()),
+                    body: "\nBecause.\n",
+                },
+                DetailsPiece::Markdown("\nAfter\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn summary_is_pending_until_the_next_line_arrives() {
+        assert_eq!(
+            split_details("<details>\n"),
+            vec![DetailsPiece::Details {
+                summary: None,
+                body: "",
+            }]
+        );
+        assert_eq!(
+            split_details("<details open>\nBody\n"),
+            vec![DetailsPiece::Details {
+                summary: Some("Details".to_string()),
+                body: "Body\n",
+            }]
+        );
+    }
+
+    #[test]
+    fn tags_in_code_blocks_are_not_sections() {
+        let source = "```html\n<details>\n```\n";
+        assert_eq!(split_details(source), vec![DetailsPiece::Markdown(source)]);
+    }
+}
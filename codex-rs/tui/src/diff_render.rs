@@ -25,9 +25,11 @@ pub(crate) fn create_diff_summary(
     title: &str,
     changes: &HashMap<PathBuf, FileChange>,
     event_type: PatchEventType,
+    collapse_details: bool,
 ) -> Vec<RtLine<'static>> {
     struct FileSummary {
-        display_path: String,
+        path: PathBuf,
+        move_path: Option<PathBuf>,
         added: usize,
         removed: usize,
     }
@@ -62,15 +64,17 @@ pub(crate) fn create_diff_summary(
     };
 
     let mut files: Vec<FileSummary> = Vec::new();
-    for (path, change) in changes.iter() {
+    for (path, change) in sorted_changes(changes) {
         match change {
             FileChange::Add { content } => files.push(FileSummary {
-                display_path: path.display().to_string(),
+                path: path.clone(),
+                move_path: None,
                 added: content.lines().count(),
                 removed: 0,
             }),
             FileChange::Delete => files.push(FileSummary {
-                display_path: path.display().to_string(),
+                path: path.clone(),
+                move_path: None,
                 added: 0,
                 removed: std::fs::read_to_string(path)
                     .ok()
@@ -82,13 +86,9 @@ pub(crate) fn create_diff_summary(
                 move_path,
             } => {
                 let (added, removed) = count_from_unified(unified_diff);
-                let display_path = if let Some(new_path) = move_path {
-                    format!("{} → {}", path.display(), new_path.display())
-                } else {
-                    path.display().to_string()
-                };
                 files.push(FileSummary {
-                    display_path,
+                    path: path.clone(),
+                    move_path: move_path.clone(),
                     added,
                     removed,
                 });
@@ -126,37 +126,75 @@ pub(crate) fn create_diff_summary(
     header_spans.push(RtSpan::raw(")"));
     out.push(RtLine::from(header_spans));
 
-    // Dimmed per-file lines with prefix
-    for (idx, f) in files.iter().enumerate() {
-        let mut spans: Vec<RtSpan<'static>> = Vec::new();
-        spans.push(RtSpan::raw(f.display_path.clone()));
-        spans.push(RtSpan::raw(" ("));
-        spans.push(RtSpan::styled(
-            format!("+{}", f.added),
-            Style::default().fg(Color::Green),
-        ));
-        spans.push(RtSpan::raw(" "));
-        spans.push(RtSpan::styled(
-            format!("-{}", f.removed),
-            Style::default().fg(Color::Red),
+    // Dimmed per-file lines with prefix. Several files are grouped under
+    // their directory, so the summary reads as a small file tree.
+    let grouped = files.len() > 1;
+    let mut current_dir: Option<PathBuf> = None;
+    let mut rows: Vec<(bool, Vec<RtSpan<'static>>)> = Vec::new();
+    for f in &files {
+        let counts = |mut spans: Vec<RtSpan<'static>>| {
+            spans.push(RtSpan::raw(" ("));
+            spans.push(RtSpan::styled(
+                format!("+{}", f.added),
+                Style::default().fg(Color::Green),
+            ));
+            spans.push(RtSpan::raw(" "));
+            spans.push(RtSpan::styled(
+                format!("-{}", f.removed),
+                Style::default().fg(Color::Red),
+            ));
+            spans.push(RtSpan::raw(")"));
+            spans
+        };
+        let moved = f
+            .move_path
+            .as_ref()
+            .map(|new_path| format!(" → {}", new_path.display()))
+            .unwrap_or_default();
+        let dir = f
+            .path
+            .parent()
+            .filter(|dir| grouped && !dir.as_os_str().is_empty());
+        let Some(dir) = dir else {
+            let name = format!("{}{moved}", f.path.display());
+            rows.push((false, counts(vec![RtSpan::raw(name)])));
+            continue;
+        };
+        if current_dir.as_deref() != Some(dir) {
+            rows.push((false, vec![RtSpan::raw(format!("{}/", dir.display()))]));
+            current_dir = Some(dir.to_path_buf());
+        }
+        let file_name = f
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        rows.push((
+            true,
+            counts(vec![RtSpan::raw(format!("{file_name}{moved}"))]),
         ));
-        spans.push(RtSpan::raw(")"));
+    }
 
+    for (idx, (nested, spans)) in rows.into_iter().enumerate() {
         let mut line = RtLine::from(spans);
         let prefix = if idx == 0 { "  ⎿ " } else { "    " };
         line.spans.insert(0, prefix.into());
+        if nested {
+            line.spans.insert(1, "  ".into());
+        }
         line.spans
             .iter_mut()
             .for_each(|span| span.style = span.style.add_modifier(Modifier::DIM));
         out.push(line);
     }
 
-    let show_details = matches!(
-        event_type,
-        PatchEventType::ApplyBegin {
-            auto_approved: true
-        } | PatchEventType::ApprovalRequest
-    );
+    let show_details = !collapse_details
+        && matches!(
+            event_type,
+            PatchEventType::ApplyBegin {
+                auto_approved: true
+            } | PatchEventType::ApprovalRequest
+        );
 
     if show_details {
         out.extend(render_patch_details(changes));
@@ -165,13 +203,21 @@ pub(crate) fn create_diff_summary(
     out
 }
 
+/// Changes ordered by directory, then path, so the files of a directory are
+/// adjacent and a patch always renders in the same order.
+fn sorted_changes(changes: &HashMap<PathBuf, FileChange>) -> Vec<(&PathBuf, &FileChange)> {
+    let mut sorted: Vec<_> = changes.iter().collect();
+    sorted.sort_by(|(a, _), (b, _)| (a.parent(), a).cmp(&(b.parent(), b)));
+    sorted
+}
+
 fn render_patch_details(changes: &HashMap<PathBuf, FileChange>) -> Vec<RtLine<'static>> {
     let mut out: Vec<RtLine<'static>> = Vec::new();
     let term_cols: usize = terminal::size()
         .map(|(w, _)| w as usize)
         .unwrap_or(DEFAULT_WRAP_COLS.into());
 
-    for (index, (path, change)) in changes.iter().enumerate() {
+    for (index, (path, change)) in sorted_changes(changes).into_iter().enumerate() {
         let is_first_file = index == 0;
         // Add separator only between files (not at the very start)
         if !is_first_file {
@@ -379,8 +425,12 @@ mod tests {
             },
         );
 
-        let lines =
-            create_diff_summary("proposed patch", &changes, PatchEventType::ApprovalRequest);
+        let lines = create_diff_summary(
+            "proposed patch",
+            &changes,
+            PatchEventType::ApprovalRequest,
+            false,
+        );
 
         snapshot_lines("add_details", lines, 80, 10);
     }
@@ -401,8 +451,12 @@ mod tests {
             },
         );
 
-        let lines =
-            create_diff_summary("proposed patch", &changes, PatchEventType::ApprovalRequest);
+        let lines = create_diff_summary(
+            "proposed patch",
+            &changes,
+            PatchEventType::ApprovalRequest,
+            false,
+        );
 
         snapshot_lines("update_details_with_rename", lines, 80, 12);
     }
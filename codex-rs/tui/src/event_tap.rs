@@ -0,0 +1,276 @@
+//! Live copy of the session event log over a local unix socket.
+//!
+//! Every JSON line the session logger produces is also offered to the tap,
+//! which fans it out to all connected clients. The UI thread only ever does
+//! a non-blocking `try_send`; if the writer falls behind, lines are dropped
+//! and a `dropped` meta record tells clients how many they missed.
+
+use std::io::Write;
+use std::os::unix::fs::DirBuilderExt;
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::fs::MetadataExt;
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::UnixListener;
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::SyncSender;
+use std::sync::mpsc::TrySendError;
+use std::sync::mpsc::sync_channel;
+use std::thread;
+use std::time::Duration;
+
+use serde_json::json;
+
+/// Lines buffered between the UI and the writer thread.
+const QUEUE_CAPACITY: usize = 1024;
+
+/// A client that cannot take a line within this long is disconnected.
+const CLIENT_WRITE_TIMEOUT: Duration = Duration::from_millis(200);
+
+pub(crate) struct EventTap {
+    tx: SyncSender<String>,
+    dropped: AtomicU64,
+    path: PathBuf,
+}
+
+impl EventTap {
+    /// Listen on `path`, replacing a stale socket left by an earlier run.
+    /// A socket another session still listens on, or anything that is not a
+    /// socket, is left alone and is an error. The socket is readable and
+    /// writable by the current user only from the moment it is created.
+    pub(crate) fn bind(path: &Path) -> std::io::Result<Self> {
+        match std::fs::symlink_metadata(path) {
+            Ok(meta) if meta.file_type().is_socket() => match UnixStream::connect(path) {
+                Ok(_) => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::AddrInUse,
+                        format!("another session is listening on {}", path.display()),
+                    ));
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => {
+                    std::fs::remove_file(path)?
+                }
+                Err(e) => return Err(e),
+            },
+            Ok(_) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::AlreadyExists,
+                    format!("{} exists and is not a socket", path.display()),
+                ));
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        let listener = bind_private(path)?;
+        let clients: Arc<Mutex<Vec<UnixStream>>> = Arc::new(Mutex::new(Vec::new()));
+
+        let accept_clients = Arc::clone(&clients);
+        thread::spawn(move || accept_loop(listener, accept_clients));
+
+        let (tx, rx) = sync_channel(QUEUE_CAPACITY);
+        thread::spawn(move || write_loop(rx, clients));
+
+        Ok(Self {
+            tx,
+            dropped: AtomicU64::new(0),
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Remove the socket file. The tap lives in a static logger whose `Drop`
+    /// never runs, so this is called when the session ends.
+    pub(crate) fn close(&self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+
+    /// Queue one serialized event line. Never blocks.
+    pub(crate) fn send(&self, line: String) {
+        let dropped = self.dropped.load(Ordering::Relaxed);
+        if dropped > 0 {
+            let note = json!({
+                "ts": crate::session_log::now_ts(),
+                "dir": "meta",
+                "kind": "dropped",
+                "count": dropped,
+            });
+            match self.tx.try_send(note.to_string()) {
+                Ok(()) => {
+                    self.dropped.fetch_sub(dropped, Ordering::Relaxed);
+                }
+                Err(_) => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+            }
+        }
+        match self.tx.try_send(line) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(TrySendError::Disconnected(_)) => {}
+        }
+    }
+}
+
+impl Drop for EventTap {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// Bind `path` with a umask that leaves the socket to the current user, so
+/// it is never reachable by others, not even before its mode could be set.
+fn bind_private(path: &Path) -> std::io::Result<UnixListener> {
+    // SAFETY: `umask` only swaps the process file mode mask.
+    let previous = unsafe { libc::umask(0o177) };
+    let listener = UnixListener::bind(path);
+    // SAFETY: as above; restores the mask saved before binding.
+    unsafe { libc::umask(previous) };
+    listener
+}
+
+/// Where the tap listens when no log directory is available:
+/// `$XDG_RUNTIME_DIR`, or else a directory of the current user's own under
+/// the temp directory.
+pub(crate) fn fallback_socket_path() -> std::io::Result<PathBuf> {
+    const NAME: &str = "codex-tui-events.sock";
+    if let Some(dir) = std::env::var_os("XDG_RUNTIME_DIR").filter(|d| !d.is_empty()) {
+        return Ok(PathBuf::from(dir).join(NAME));
+    }
+    // SAFETY: `getuid` cannot fail and has no side effects.
+    let uid = unsafe { libc::getuid() };
+    let dir = std::env::temp_dir().join(format!("codex-{uid}"));
+    match std::fs::DirBuilder::new().mode(0o700).create(&dir) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+        Err(e) => return Err(e),
+    }
+    // Someone else may have made the directory first; only use it if it is
+    // ours and private.
+    let meta = std::fs::symlink_metadata(&dir)?;
+    if !meta.is_dir() || meta.uid() != uid || meta.permissions().mode() & 0o077 != 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!("{} is not a private directory", dir.display()),
+        ));
+    }
+    Ok(dir.join(NAME))
+}
+
+fn accept_loop(listener: UnixListener, clients: Arc<Mutex<Vec<UnixStream>>>) {
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                tracing::warn!("event tap accept error: {e}");
+                continue;
+            }
+        };
+        if let Err(e) = stream.set_write_timeout(Some(CLIENT_WRITE_TIMEOUT)) {
+            tracing::warn!("event tap client setup error: {e}");
+            continue;
+        }
+        let mut guard = match clients.lock() {
+            Ok(g) => g,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        guard.push(stream);
+    }
+}
+
+fn write_loop(rx: Receiver<String>, clients: Arc<Mutex<Vec<UnixStream>>>) {
+    while let Ok(mut line) = rx.recv() {
+        line.push('\n');
+        let mut guard = match clients.lock() {
+            Ok(g) => g,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        // Slow or closed clients are dropped rather than allowed to stall
+        // everyone else.
+        guard.retain_mut(|client| client.write_all(line.as_bytes()).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use std::io::BufRead;
+    use std::io::BufReader;
+    use std::time::Instant;
+
+    #[test]
+    fn client_reads_well_formed_event_lines_during_replay() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("tap.sock");
+        let tap = EventTap::bind(&path).unwrap();
+        let client = UnixStream::connect(&path).unwrap();
+        client
+            .set_read_timeout(Some(Duration::from_millis(50)))
+            .unwrap();
+        let mut reader = BufReader::new(client);
+
+        let fixture = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/truncated-answer.jsonl"
+        ))
+        .unwrap();
+
+        // The client is registered asynchronously, so keep replaying until a
+        // line arrives.
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut received = String::new();
+        while !received.ends_with('\n') && Instant::now() < deadline {
+            for line in fixture.lines() {
+                tap.send(line.to_string());
+            }
+            let _ = reader.read_line(&mut received);
+        }
+
+        let value: serde_json::Value = serde_json::from_str(received.trim_end()).unwrap();
+        assert!(
+            value.get("dir").and_then(|d| d.as_str()).is_some(),
+            "{value}"
+        );
+        assert!(
+            value.get("kind").and_then(|k| k.as_str()).is_some(),
+            "{value}"
+        );
+
+        drop(tap);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn socket_is_private_and_only_a_stale_socket_is_replaced() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("tap.sock");
+        let stale = UnixListener::bind(&path).unwrap();
+        drop(stale);
+
+        let tap = EventTap::bind(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        // A live socket belongs to another session and is kept.
+        let err = EventTap::bind(&path).err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+        assert!(path.exists());
+        tap.close();
+        assert!(!path.exists());
+        drop(tap);
+
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, "keep me").unwrap();
+        let err = EventTap::bind(&file).err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "keep me");
+    }
+}
@@ -20,6 +20,16 @@ pub(crate) fn strip_bash_lc_and_escape(command: &[String]) -> String {
     }
 }
 
+/// `commands` as a shell script, one command per line, each quoted so the
+/// script runs the same argv. `bash -lc` wrappers are dropped in favor of the
+/// script they run.
+pub(crate) fn commands_to_script(commands: &[Vec<String>]) -> String {
+    commands
+        .iter()
+        .map(|command| format!("{}\n", strip_bash_lc_and_escape(command)))
+        .collect()
+}
+
 /// If `path` is absolute and inside $HOME, return the part *after* the home
 /// directory; otherwise, return the path as-is. Note if `path` is the homedir,
 /// this will return and empty path.
@@ -53,6 +63,23 @@ mod tests {
         assert_eq!(cmdline, "foo 'bar baz' 'weird&stuff'");
     }
 
+    #[test]
+    fn commands_to_script_quotes_each_command_on_its_own_line() {
+        let commands = vec![
+            vec!["bash".into(), "-lc".into(), "cargo test --all".into()],
+            vec![
+                "git".into(),
+                "commit".into(),
+                "-m".into(),
+                "fix the build".into(),
+            ],
+        ];
+        assert_eq!(
+            commands_to_script(&commands),
+            "cargo test --all\ngit commit -m 'fix the build'\n"
+        );
+    }
+
     #[test]
     fn test_strip_bash_lc_and_escape() {
         let args = vec!["bash".into(), "-lc".into(), "echo hello".into()];
@@ -0,0 +1,203 @@
+//! Evaluation of the `[tui.exec_policy]` allow/deny lists against commands the
+//! agent asks to run.
+
+use codex_core::config_types::ExecPolicy;
+
+/// Outcome of checking a command against the configured [`ExecPolicy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ExecPolicyDecision {
+    /// The command matched an `allow` rule and can run without prompting.
+    Allow,
+    /// The command matched the contained `deny` rule.
+    Deny(String),
+    /// No rule applies; ask the user.
+    Prompt,
+}
+
+/// Characters that make a shell script more than a single simple command. A
+/// script containing any of these never matches an `allow` rule, otherwise
+/// `git status && rm -rf ~` would be approved by a `git status` rule.
+const SHELL_CONTROL_CHARS: &[char] = &[';', '&', '|', '<', '>', '`', '$'];
+
+/// Characters that separate or group commands in a `bash -lc` script but
+/// that splitting into words drops (newlines are read as whitespace), so
+/// they are looked for in the script itself: `git status\nrm -rf ~` splits
+/// into words an allow rule for `git status` would match.
+const SCRIPT_CONTROL_CHARS: &[char] = &['\n', '\r', '(', ')', '{', '}'];
+
+pub(crate) fn evaluate(policy: &ExecPolicy, command: &[String]) -> ExecPolicyDecision {
+    let Some(words) = command_words(command) else {
+        return ExecPolicyDecision::Prompt;
+    };
+
+    if let Some(rule) = policy.deny.iter().find(|rule| rule_matches(rule, &words)) {
+        return ExecPolicyDecision::Deny(rule.clone());
+    }
+
+    let is_simple = !words.iter().any(|w| w.contains(SHELL_CONTROL_CHARS))
+        && !bash_script(command).is_some_and(|script| script.contains(SCRIPT_CONTROL_CHARS));
+    if is_simple
+        && dangerous_rule(policy, command).is_none()
+        && policy.allow.iter().any(|rule| rule_matches(rule, &words))
+    {
+        return ExecPolicyDecision::Allow;
+    }
+
+    ExecPolicyDecision::Prompt
+}
+
+/// The `dangerous` rule matched by `command`, if any. Matching approval
+/// requests need the user to type `yes` instead of a single keypress.
+pub(crate) fn dangerous_rule(policy: &ExecPolicy, command: &[String]) -> Option<String> {
+    let words = command_words(command)?;
+    policy
+        .dangerous
+        .iter()
+        .find(|rule| rule_matches_anywhere(rule, &words))
+        .cloned()
+}
+
+/// Split `command` into words, unwrapping `bash -lc "<script>"` so rules can
+/// be written in terms of the script the model actually asked for.
+fn command_words(command: &[String]) -> Option<Vec<String>> {
+    match bash_script(command) {
+        Some(script) => shlex::split(script),
+        None => Some(command.to_vec()),
+    }
+}
+
+/// The script of a `bash -lc "<script>"` invocation.
+fn bash_script(command: &[String]) -> Option<&str> {
+    match command {
+        [first, second, script] if first == "bash" && second == "-lc" => Some(script),
+        _ => None,
+    }
+}
+
+fn rule_matches(rule: &str, words: &[String]) -> bool {
+    let Some(rule_words) = shlex::split(rule) else {
+        return false;
+    };
+    !rule_words.is_empty()
+        && rule_words.len() <= words.len()
+        && rule_words.iter().zip(words).all(|(r, w)| r == w)
+}
+
+/// Like [`rule_matches`], but the rule may start at any word. Shell operators
+/// glued to a word (`build;`) are stripped so chained commands still match.
+fn rule_matches_anywhere(rule: &str, words: &[String]) -> bool {
+    let Some(rule_words) = shlex::split(rule) else {
+        return false;
+    };
+    if rule_words.is_empty() {
+        return false;
+    }
+    let words: Vec<&str> = words
+        .iter()
+        .map(|w| w.trim_matches(SHELL_CONTROL_CHARS))
+        .collect();
+    words.windows(rule_words.len()).any(|window| {
+        window
+            .iter()
+            .zip(&rule_words)
+            .all(|(w, r)| *w == r.as_str())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(allow: &[&str], deny: &[&str]) -> ExecPolicy {
+        ExecPolicy {
+            allow: allow.iter().map(|s| s.to_string()).collect(),
+            deny: deny.iter().map(|s| s.to_string()).collect(),
+            dangerous: Vec::new(),
+        }
+    }
+
+    fn cmd(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn allow_matches_on_word_prefix() {
+        let p = policy(&["git status"], &[]);
+        assert_eq!(
+            evaluate(&p, &cmd(&["git", "status", "--short"])),
+            ExecPolicyDecision::Allow
+        );
+        assert_eq!(
+            evaluate(&p, &cmd(&["git", "statusx"])),
+            ExecPolicyDecision::Prompt
+        );
+        assert_eq!(evaluate(&p, &cmd(&["git"])), ExecPolicyDecision::Prompt);
+    }
+
+    #[test]
+    fn bash_lc_scripts_are_unwrapped() {
+        let p = policy(&["cargo test"], &[]);
+        assert_eq!(
+            evaluate(&p, &cmd(&["bash", "-lc", "cargo test -p codex-tui"])),
+            ExecPolicyDecision::Allow
+        );
+    }
+
+    #[test]
+    fn compound_scripts_are_never_auto_allowed() {
+        let p = policy(&["git status"], &[]);
+        assert_eq!(
+            evaluate(&p, &cmd(&["bash", "-lc", "git status && rm -rf ~"])),
+            ExecPolicyDecision::Prompt
+        );
+    }
+
+    #[test]
+    fn newline_chained_and_grouped_scripts_need_approval() {
+        let p = policy(&["git status"], &[]);
+        for script in [
+            "git status\nrm -rf ~",
+            "git status\r\nrm -rf ~",
+            "git status {rm,-rf,~}",
+            "git status (rm -rf ~)",
+        ] {
+            assert_eq!(
+                evaluate(&p, &cmd(&["bash", "-lc", script])),
+                ExecPolicyDecision::Prompt,
+                "{script:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn deny_takes_precedence_over_allow() {
+        let p = policy(&["rm"], &["rm -rf"]);
+        assert_eq!(
+            evaluate(&p, &cmd(&["rm", "-rf", "/tmp/x"])),
+            ExecPolicyDecision::Deny("rm -rf".to_string())
+        );
+        assert_eq!(
+            evaluate(&p, &cmd(&["rm", "/tmp/x"])),
+            ExecPolicyDecision::Allow
+        );
+    }
+
+    #[test]
+    fn dangerous_rules_match_anywhere_in_the_script() {
+        let p = ExecPolicy {
+            allow: vec!["rm".to_string()],
+            dangerous: vec!["rm -rf".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            dangerous_rule(&p, &cmd(&["bash", "-lc", "cd /tmp && rm -rf build"])),
+            Some("rm -rf".to_string())
+        );
+        assert_eq!(dangerous_rule(&p, &cmd(&["rm", "-r", "build"])), None);
+        // A dangerous command is never auto-approved by a broader allow rule.
+        assert_eq!(
+            evaluate(&p, &cmd(&["rm", "-rf", "build"])),
+            ExecPolicyDecision::Prompt
+        );
+    }
+}
@@ -0,0 +1,47 @@
+//! Renderers for fenced code blocks of a given language, registered by the
+//! host embedding the TUI, e.g. to pretty-print or validate JSON.
+//!
+//! Markdown rendering is a free function reached from many places (streams,
+//! history cells, the transcript view), so the renderers live in a
+//! process-wide table rather than being threaded through `Config`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use ratatui::text::Line;
+
+/// Turns the content of a fenced block (without the fences) into the lines
+/// shown in its place.
+pub type FenceRenderer = Box<dyn Fn(&str) -> Vec<Line<'static>> + Send + Sync>;
+
+/// Renderer for each language, keyed by its lowercase name.
+static RENDERERS: LazyLock<Mutex<HashMap<String, Arc<FenceRenderer>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Render fenced blocks tagged `lang` (matched case-insensitively against
+/// the first word of the fence's info string) with `renderer` instead of as
+/// plain code. Registering a language again replaces its renderer.
+pub fn register_fence_renderer(lang: &str, renderer: FenceRenderer) {
+    let mut renderers = match RENDERERS.lock() {
+        Ok(renderers) => renderers,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    renderers.insert(lang.to_lowercase(), Arc::new(renderer));
+}
+
+/// Lines for a fenced block with info string `info`, if a renderer is
+/// registered for its language.
+pub(crate) fn render_fence(info: &str, content: &str) -> Option<Vec<Line<'static>>> {
+    let lang = info.split_whitespace().next()?.to_lowercase();
+    let renderer = {
+        let renderers = match RENDERERS.lock() {
+            Ok(renderers) => renderers,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        Arc::clone(renderers.get(&lang)?)
+    };
+    // Called without the lock held, so a renderer may register others.
+    Some(renderer(content))
+}
@@ -0,0 +1,202 @@
+//! Plain `path:line` references in agent output, e.g. `src/foo.rs:42`.
+//!
+//! References are styled when markdown is rendered. Ratatui spans cannot carry
+//! a link target, so when history is written to the terminal the target of a
+//! styled reference is worked out again from its text by [`FileLinks`] and
+//! the span is wrapped in an OSC 8 hyperlink.
+
+use std::ops::Range;
+use std::path::Path;
+use std::path::PathBuf;
+
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+
+use codex_core::config::Config;
+use codex_core::config_types::UriBasedFileOpener;
+
+use crate::citation_regex::FILE_REFERENCE_REGEX;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FileReference {
+    pub(crate) path: String,
+    pub(crate) line: u32,
+}
+
+impl FileReference {
+    /// Fill in `template`, replacing `{path}` with the absolute path
+    /// (resolved against `cwd`, with forward slashes) and `{line}` with the
+    /// line number.
+    pub(crate) fn url(&self, template: &str, cwd: &Path) -> String {
+        let p = Path::new(&self.path);
+        let absolute = if p.is_absolute() {
+            path_clean::clean(p)
+        } else {
+            path_clean::clean(cwd.join(p))
+        };
+        let absolute = absolute.to_string_lossy().replace('\\', "/");
+        template
+            .replace("{path}", &absolute)
+            .replace("{line}", &self.line.to_string())
+    }
+}
+
+/// Every reference in `text`, with the byte range of its `path:line` text.
+pub(crate) fn find_file_references(text: &str) -> Vec<(Range<usize>, FileReference)> {
+    FILE_REFERENCE_REGEX
+        .captures_iter(text)
+        .filter_map(|caps| {
+            let path = caps.get(2)?;
+            let line = caps.get(3)?;
+            let reference = FileReference {
+                path: path.as_str().to_string(),
+                line: line.as_str().parse().ok()?,
+            };
+            Some((path.start()..line.end(), reference))
+        })
+        .collect()
+}
+
+/// Where rendered references link to: a URL template and the directory
+/// relative paths are resolved against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FileLinks {
+    template: String,
+    cwd: PathBuf,
+}
+
+impl FileLinks {
+    /// Links for `config`: `tui.file_reference_url`, or the file opener's
+    /// scheme when none is set. `None` when neither gives a URL.
+    pub(crate) fn from_config(config: &Config) -> Option<Self> {
+        let template = url_template(config.tui.file_reference_url.as_deref(), config.file_opener)?;
+        Some(Self {
+            template,
+            cwd: config.cwd.clone(),
+        })
+    }
+
+    /// URL to open for `span`, if it is a rendered file reference.
+    pub(crate) fn url_for(&self, span: &Span<'_>) -> Option<String> {
+        if !span.style.add_modifier.contains(Modifier::UNDERLINED) {
+            return None;
+        }
+        let mut found = find_file_references(&span.content);
+        match found.pop() {
+            Some((range, reference)) if found.is_empty() && range == (0..span.content.len()) => {
+                Some(reference.url(&self.template, &self.cwd))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The URL template for references: `configured`, or one built from the file
+/// opener's scheme.
+pub(crate) fn url_template(
+    configured: Option<&str>,
+    file_opener: UriBasedFileOpener,
+) -> Option<String> {
+    match configured {
+        Some(template) => Some(template.to_string()),
+        None => file_opener
+            .get_scheme()
+            .map(|scheme| format!("{scheme}://file{{path}}:{{line}}")),
+    }
+}
+
+/// Style the references in `lines` so [`FileLinks::url_for`] picks them up.
+pub(crate) fn link_file_references(lines: &mut [Line<'static>]) {
+    for line in lines {
+        let spans = std::mem::take(&mut line.spans);
+        for span in spans {
+            let found = find_file_references(&span.content);
+            if found.is_empty() {
+                line.spans.push(span);
+                continue;
+            }
+            let mut pos = 0;
+            for (range, _) in found {
+                if range.start > pos {
+                    line.spans.push(Span::styled(
+                        span.content[pos..range.start].to_string(),
+                        span.style,
+                    ));
+                }
+                let text = span.content[range.clone()].to_string();
+                line.spans
+                    .push(Span::styled(text, span.style.patch(reference_style())));
+                pos = range.end;
+            }
+            if pos < span.content.len() {
+                line.spans
+                    .push(Span::styled(span.content[pos..].to_string(), span.style));
+            }
+        }
+    }
+}
+
+fn reference_style() -> Style {
+    Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::UNDERLINED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn references_are_detected_at_word_starts_only() {
+        let found = find_file_references(
+            "See src/foo.rs:42, (lib.rs:7) and `/tmp/x.py:3`; not http://h.com:80 or 10.5:30.",
+        );
+        let refs: Vec<(String, u32)> = found.into_iter().map(|(_, r)| (r.path, r.line)).collect();
+        assert_eq!(
+            refs,
+            vec![
+                ("src/foo.rs".to_string(), 42),
+                ("lib.rs".to_string(), 7),
+                ("/tmp/x.py".to_string(), 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn url_template_gets_absolute_path_and_line() {
+        let reference = FileReference {
+            path: "src/../src/foo.rs".to_string(),
+            line: 42,
+        };
+        assert_eq!(
+            reference.url(
+                "myeditor://open?file={path}&line={line}",
+                Path::new("/work")
+            ),
+            "myeditor://open?file=/work/src/foo.rs&line=42"
+        );
+    }
+
+    #[test]
+    fn only_whole_underlined_references_get_a_url() {
+        let links = FileLinks {
+            template: "ed://{path}:{line}".to_string(),
+            cwd: PathBuf::from("/work"),
+        };
+        let underlined = Style::default().add_modifier(Modifier::UNDERLINED);
+        assert_eq!(
+            links.url_for(&Span::styled("src/foo.rs:42", underlined)),
+            Some("ed:///work/src/foo.rs:42".to_string())
+        );
+        assert_eq!(links.url_for(&Span::raw("src/foo.rs:42")), None);
+        assert_eq!(
+            links.url_for(&Span::styled("see src/foo.rs:42", underlined)),
+            None
+        );
+        assert_eq!(links.url_for(&Span::styled("important", underlined)), None);
+    }
+}
@@ -0,0 +1,103 @@
+//! `--follow <log>`: render a session log live while another TUI writes it.
+//!
+//! The log is the JSONL file written with `CODEX_TUI_RECORD_SESSION=1`. Its
+//! codex events are fed to the chat widget as if they came from the agent,
+//! so they go through the same rendering as a live session. Like `tail -f`,
+//! the file is polled for appended lines; a line still being written is
+//! held back until its newline arrives.
+
+use std::fs::File;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use codex_core::protocol::Event;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+
+/// How often the log is checked for new lines.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub(crate) struct LogFollower {
+    file: File,
+    /// Bytes read so far.
+    offset: u64,
+    /// The start of a line whose newline has not been written yet.
+    partial: Vec<u8>,
+}
+
+impl LogFollower {
+    pub(crate) fn open(path: &Path) -> std::io::Result<Self> {
+        Ok(Self {
+            file: File::open(path)?,
+            offset: 0,
+            partial: Vec::new(),
+        })
+    }
+
+    /// The codex events of the lines completed since the last call. A log
+    /// that shrank was rewritten by a new session, which is read from the
+    /// start.
+    pub(crate) fn read_events(&mut self) -> std::io::Result<Vec<Event>> {
+        if self.file.metadata()?.len() < self.offset {
+            self.file.seek(SeekFrom::Start(0))?;
+            self.offset = 0;
+            self.partial.clear();
+        }
+        let read = self.file.read_to_end(&mut self.partial)?;
+        self.offset += read as u64;
+        let Some(end) = self.partial.iter().rposition(|&b| b == b'\n') else {
+            return Ok(Vec::new());
+        };
+        let rest = self.partial.split_off(end + 1);
+        let complete = std::mem::replace(&mut self.partial, rest);
+        Ok(complete
+            .split(|&b| b == b'\n')
+            .filter(|line| !line.is_empty())
+            .filter_map(parse_codex_event)
+            .collect())
+    }
+
+    /// Poll the log on a background thread and send its events to the app.
+    pub(crate) fn spawn(mut self, app_event_tx: AppEventSender) {
+        thread::spawn(move || {
+            loop {
+                match self.read_events() {
+                    Ok(events) => {
+                        for event in events {
+                            app_event_tx.send(AppEvent::CodexEvent(event));
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("failed to read the followed log: {e}");
+                        return;
+                    }
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+        });
+    }
+}
+
+/// The event of a `codex_event` record; other records, such as app events
+/// and the session's own ops, are not replayed.
+fn parse_codex_event(line: &[u8]) -> Option<Event> {
+    let record: serde_json::Value = match serde_json::from_slice(line) {
+        Ok(record) => record,
+        Err(e) => {
+            tracing::warn!("skipping malformed log line: {e}");
+            return None;
+        }
+    };
+    if record.get("kind").and_then(|kind| kind.as_str()) != Some("codex_event") {
+        return None;
+    }
+    let payload = record.get("payload")?.clone();
+    serde_json::from_value(payload)
+        .inspect_err(|e| tracing::warn!("skipping unknown codex event: {e}"))
+        .ok()
+}
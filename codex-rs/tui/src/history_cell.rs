@@ -1,15 +1,22 @@
+use crate::attachments::Attachment;
 use crate::colors::LIGHT_BLUE;
 use crate::diff_render::create_diff_summary;
 use crate::exec_command::relativize_to_home;
 use crate::exec_command::strip_bash_lc_and_escape;
+use crate::markdown::append_markdown;
+use crate::markdown::link_references;
 use crate::slash_command::SlashCommand;
+use crate::streaming::header_glyph_span;
 use crate::text_block::TextBlock;
 use crate::text_formatting::format_and_truncate_tool_result;
+use crate::text_formatting::truncate_text;
+use crate::truncation::TruncationIndicator;
 use base64::Engine;
 use codex_ansi_escape::ansi_escape_line;
 use codex_common::create_config_summary_entries;
 use codex_common::elapsed::format_duration;
 use codex_core::config::Config;
+use codex_core::config_types::HeaderGlyphs;
 use codex_core::parse_command::ParsedCommand;
 use codex_core::plan_tool::PlanItemArg;
 use codex_core::plan_tool::StepStatus;
@@ -43,8 +50,24 @@ pub(crate) struct CommandOutput {
     pub(crate) exit_code: i32,
     pub(crate) stdout: String,
     pub(crate) stderr: String,
+    pub(crate) duration: Duration,
 }
 
+/// Aggregates shown by `/stats`, gathered from the per-turn metrics.
+pub(crate) struct SessionStats {
+    pub(crate) turns: usize,
+    pub(crate) token_usage: TokenUsage,
+    pub(crate) commands: usize,
+    pub(crate) failed_commands: usize,
+    pub(crate) command_time: Duration,
+    /// Time spent in completed turns, from `TaskStarted` to `TaskComplete`.
+    pub(crate) turn_time: Duration,
+    pub(crate) completed_turns: usize,
+    /// Mean wait for the first output of a turn, over turns that produced one.
+    pub(crate) average_latency: Option<Duration>,
+}
+
+#[derive(Clone, Copy)]
 pub(crate) enum PatchEventType {
     ApprovalRequest,
     ApplyBegin { auto_approved: bool },
@@ -65,10 +88,22 @@ fn line_to_static(line: &Line) -> Line<'static> {
     }
 }
 
+/// Severity of a note from [`HistoryCell::new_system_note`], which picks its
+/// glyph and color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SystemNoteLevel {
+    Info,
+    Warn,
+    #[cfg(test)]
+    Error,
+}
+
 pub(crate) struct ExecCell {
     pub(crate) command: Vec<String>,
     pub(crate) parsed: Vec<ParsedCommand>,
     pub(crate) output: Option<CommandOutput>,
+    /// Stands in for the middle of long output.
+    pub(crate) truncation: TruncationIndicator,
 }
 
 /// Represents an event to display in the conversation history. Returns its
@@ -129,11 +164,21 @@ pub(crate) enum HistoryCell {
         view: TextBlock,
     },
 
+    /// Output from the `/show-request` command.
+    RequestOutput {
+        view: TextBlock,
+    },
+
     /// Error event from the backend.
     ErrorEvent {
         view: TextBlock,
     },
 
+    /// Note pushed by the embedding application, not by the model.
+    SystemNote {
+        view: TextBlock,
+    },
+
     /// Info describing the newly-initialized session.
     SessionInfo {
         view: TextBlock,
@@ -159,6 +204,8 @@ pub(crate) enum HistoryCell {
 }
 
 const TOOL_CALL_MAX_LINES: usize = 5;
+/// Longest arguments, in graphemes, shown on the call line when collapsed.
+const TOOL_CALL_COLLAPSED_ARGS_MAX_CHARS: usize = 60;
 
 fn title_case(s: &str) -> String {
     if s.is_empty() {
@@ -194,7 +241,9 @@ impl HistoryCell {
             | HistoryCell::GitDiffOutput { view }
             | HistoryCell::StatusOutput { view }
             | HistoryCell::PromptsOutput { view }
+            | HistoryCell::RequestOutput { view }
             | HistoryCell::ErrorEvent { view }
+            | HistoryCell::SystemNote { view }
             | HistoryCell::SessionInfo { view }
             | HistoryCell::CompletedMcpToolCall { view }
             | HistoryCell::PendingPatch { view }
@@ -207,7 +256,8 @@ impl HistoryCell {
                 command,
                 parsed,
                 output,
-            }) => HistoryCell::exec_command_lines(command, parsed, output.as_ref()),
+                truncation,
+            }) => HistoryCell::exec_command_lines(command, parsed, output.as_ref(), truncation),
             HistoryCell::CompletedMcpToolCallWithImageOutput { .. } => vec![
                 Line::from("tool result (image output omitted)"),
                 Line::from(""),
@@ -289,10 +339,32 @@ impl HistoryCell {
         }
     }
 
-    pub(crate) fn new_user_prompt(message: String) -> Self {
+    pub(crate) fn new_user_prompt(
+        message: String,
+        attachments: &[Attachment],
+        glyphs: &HeaderGlyphs,
+        max_lines: Option<usize>,
+        truncation: &TruncationIndicator,
+    ) -> Self {
         let mut lines: Vec<Line<'static>> = Vec::new();
-        lines.push(Line::from("user".cyan().bold()));
-        lines.extend(message.lines().map(|l| Line::from(l.to_string())));
+        let mut header = Line::default();
+        header
+            .spans
+            .extend(header_glyph_span(glyphs.user.as_deref(), glyphs));
+        header.spans.push("user".cyan().bold());
+        lines.push(header);
+        let total = message.lines().count();
+        let shown = max_lines.map_or(total, |max| total.min(max));
+        lines.extend(
+            message
+                .lines()
+                .take(shown)
+                .map(|l| Line::from(l.to_string())),
+        );
+        if shown < total {
+            lines.push(truncation.line(total - shown));
+        }
+        lines.extend(attachments.iter().map(|a| Line::from(a.marker().dim())));
         lines.push(Line::from(""));
 
         HistoryCell::UserPrompt {
@@ -304,26 +376,29 @@ impl HistoryCell {
         command: Vec<String>,
         parsed: Vec<ParsedCommand>,
     ) -> Self {
-        HistoryCell::new_exec_cell(command, parsed, None)
+        HistoryCell::new_exec_cell(command, parsed, None, TruncationIndicator::default())
     }
 
     pub(crate) fn new_completed_exec_command(
         command: Vec<String>,
         parsed: Vec<ParsedCommand>,
         output: CommandOutput,
+        truncation: TruncationIndicator,
     ) -> Self {
-        HistoryCell::new_exec_cell(command, parsed, Some(output))
+        HistoryCell::new_exec_cell(command, parsed, Some(output), truncation)
     }
 
     fn new_exec_cell(
         command: Vec<String>,
         parsed: Vec<ParsedCommand>,
         output: Option<CommandOutput>,
+        truncation: TruncationIndicator,
     ) -> Self {
         HistoryCell::Exec(ExecCell {
             command,
             parsed,
             output,
+            truncation,
         })
     }
 
@@ -331,16 +406,18 @@ impl HistoryCell {
         command: &[String],
         parsed: &[ParsedCommand],
         output: Option<&CommandOutput>,
+        truncation: &TruncationIndicator,
     ) -> Vec<Line<'static>> {
         match parsed.is_empty() {
-            true => HistoryCell::new_exec_command_generic(command, output),
-            false => HistoryCell::new_parsed_command(parsed, output),
+            true => HistoryCell::new_exec_command_generic(command, output, truncation),
+            false => HistoryCell::new_parsed_command(parsed, output, truncation),
         }
     }
 
     fn new_parsed_command(
         parsed_commands: &[ParsedCommand],
         output: Option<&CommandOutput>,
+        truncation: &TruncationIndicator,
     ) -> Vec<Line<'static>> {
         let mut lines: Vec<Line> = vec![Line::from("⚙︎ Working")];
 
@@ -373,7 +450,8 @@ impl HistoryCell {
             }
         }
 
-        lines.extend(output_lines(output, true, false));
+        lines.extend(output_lines(output, true, false, truncation));
+        lines.extend(output.map(exec_footer_line));
         lines.push(Line::from(""));
 
         lines
@@ -382,6 +460,7 @@ impl HistoryCell {
     fn new_exec_command_generic(
         command: &[String],
         output: Option<&CommandOutput>,
+        truncation: &TruncationIndicator,
     ) -> Vec<Line<'static>> {
         let mut lines: Vec<Line<'static>> = Vec::new();
         let command_escaped = strip_bash_lc_and_escape(command);
@@ -398,18 +477,17 @@ impl HistoryCell {
             lines.push(Line::from(cont.to_string()));
         }
 
-        lines.extend(output_lines(output, false, true));
+        lines.extend(output_lines(output, false, true, truncation));
+        lines.extend(output.map(exec_footer_line));
 
         lines
     }
 
-    pub(crate) fn new_active_mcp_tool_call(invocation: McpInvocation) -> Self {
+    pub(crate) fn new_active_mcp_tool_call(invocation: McpInvocation, collapsed: bool) -> Self {
         let title_line = Line::from(vec!["tool".magenta(), " running...".dim()]);
-        let lines: Vec<Line> = vec![
-            title_line,
-            format_mcp_invocation(invocation.clone()),
-            Line::from(""),
-        ];
+        let mut lines: Vec<Line> = vec![title_line];
+        lines.extend(format_mcp_invocation(&invocation, collapsed));
+        lines.push(Line::from(""));
 
         HistoryCell::ActiveMcpToolCall {
             view: TextBlock::new(lines),
@@ -464,6 +542,7 @@ impl HistoryCell {
         duration: Duration,
         success: bool,
         result: Result<mcp_types::CallToolResult, String>,
+        collapsed: bool,
     ) -> Self {
         if let Some(cell) = Self::try_new_completed_mcp_tool_call_with_image_output(&result) {
             return cell;
@@ -484,7 +563,7 @@ impl HistoryCell {
 
         let mut lines: Vec<Line<'static>> = Vec::new();
         lines.push(title_line);
-        lines.push(format_mcp_invocation(invocation));
+        lines.extend(format_mcp_invocation(&invocation, collapsed));
 
         match result {
             Ok(mcp_types::CallToolResult { content, .. }) => {
@@ -690,6 +769,56 @@ impl HistoryCell {
         }
     }
 
+    pub(crate) fn new_stats_output(stats: &SessionStats) -> Self {
+        let mut lines: Vec<Line<'static>> = Vec::new();
+        lines.push(Line::from("/stats".magenta()));
+
+        lines.push(Line::from(vec!["📈 ".into(), "Session".bold()]));
+        lines.push(Line::from(vec![
+            "  • Turns: ".into(),
+            stats.turns.to_string().into(),
+        ]));
+        let tokens = stats.token_usage.blended_total();
+        let tokens = if tokens > 0 {
+            tokens.to_string()
+        } else {
+            "not reported".to_string()
+        };
+        lines.push(Line::from(vec!["  • Tokens: ".into(), tokens.into()]));
+        let mut commands = format!(
+            "{} in {}",
+            stats.commands,
+            format_duration(stats.command_time)
+        );
+        if stats.failed_commands > 0 {
+            commands.push_str(&format!(", {} failed", stats.failed_commands));
+        }
+        lines.push(Line::from(vec!["  • Commands: ".into(), commands.into()]));
+        lines.push(Line::from(vec![
+            "  • Duration: ".into(),
+            format_duration(stats.turn_time).into(),
+        ]));
+        if stats.completed_turns > 0 {
+            let average = stats.turn_time / stats.completed_turns as u32;
+            lines.push(Line::from(vec![
+                "  • Average Turn: ".into(),
+                format_duration(average).into(),
+            ]));
+        }
+        if let Some(latency) = stats.average_latency {
+            lines.push(Line::from(vec![
+                "  • Average Latency: ".into(),
+                format_duration(latency).into(),
+                " to first output".dim(),
+            ]));
+        }
+
+        lines.push(Line::from(""));
+        HistoryCell::StatusOutput {
+            view: TextBlock::new(lines),
+        }
+    }
+
     pub(crate) fn new_prompts_output() -> Self {
         let lines: Vec<Line<'static>> = vec![
             Line::from("/prompts".magenta()),
@@ -707,6 +836,157 @@ impl HistoryCell {
         }
     }
 
+    pub(crate) fn new_request_output(request: Option<&serde_json::Value>) -> Self {
+        let mut lines: Vec<Line<'static>> = Vec::new();
+        lines.push(Line::from("/show-request".magenta()));
+
+        match request {
+            None => lines.push(Line::from("No request has been sent yet.".italic())),
+            Some(request) => {
+                let field = |name: &str| request.get(name).map(|v| v.to_string());
+                if let Some(model) = field("model") {
+                    lines.push(Line::from(vec!["model: ".dim(), model.into()]));
+                }
+                if let Some(reasoning) = field("reasoning") {
+                    lines.push(Line::from(vec!["reasoning: ".dim(), reasoning.into()]));
+                }
+                lines.push(Line::from(""));
+                let pretty = serde_json::to_string_pretty(request).unwrap_or_default();
+                lines.extend(pretty.lines().map(|l| Line::from(l.to_string())));
+                lines.push(Line::from(""));
+                lines.push(Line::from("copied to clipboard".dim()));
+            }
+        }
+
+        lines.push(Line::from(""));
+        HistoryCell::RequestOutput {
+            view: TextBlock::new(lines),
+        }
+    }
+
+    /// Note shown after the answer was copied to the clipboard.
+    pub(crate) fn new_answer_copied() -> Self {
+        let lines: Vec<Line<'static>> = vec!["answer copied to clipboard".dim().into(), "".into()];
+        HistoryCell::BackgroundEvent {
+            view: TextBlock::new(lines),
+        }
+    }
+
+    /// Marker for older context that the core replaced with a summary,
+    /// followed by the summary unless only the marker is wanted.
+    pub(crate) fn new_context_compacted(summary: &str, show_summary: bool) -> Self {
+        let mut lines: Vec<Line<'static>> = vec!["context compacted".dim().italic().into()];
+        if show_summary {
+            lines.extend(
+                summary
+                    .lines()
+                    .map(|line| Line::from(format!("  {line}").dim())),
+            );
+        }
+        lines.push("".into());
+        HistoryCell::BackgroundEvent {
+            view: TextBlock::new(lines),
+        }
+    }
+
+    /// Session summary requested with `/summarize`, rendered as Markdown
+    /// under its own header.
+    pub(crate) fn new_session_summary(summary: &str, config: &Config) -> Self {
+        let mut lines: Vec<Line<'static>> = vec!["summary".magenta().bold().into()];
+        append_markdown(summary, &mut lines, config);
+        lines.extend(link_references(summary, config));
+        lines.push("".into());
+        HistoryCell::BackgroundEvent {
+            view: TextBlock::new(lines),
+        }
+    }
+
+    /// Notice shown when the provider rate limited the turn and the client
+    /// ran out of retries.
+    pub(crate) fn new_rate_limited(retry_after: Duration, auto_retry: bool) -> Self {
+        let wait = format_duration(retry_after);
+        let hint = if auto_retry {
+            format!("  retrying automatically in {wait}")
+        } else {
+            "  send the message again to retry".to_string()
+        };
+        let lines: Vec<Line<'static>> = vec![
+            Line::from(vec![
+                "rate limited".yellow().bold(),
+                format!(" · the provider asked to wait {wait}").dim(),
+            ]),
+            Line::from(hint.dim()),
+            "".into(),
+        ];
+        HistoryCell::BackgroundEvent {
+            view: TextBlock::new(lines),
+        }
+    }
+
+    /// Notice that a turn failed with a transient error and is sent again,
+    /// as retry `attempt` of `max_attempts`, after `delay`.
+    pub(crate) fn new_transient_retry(
+        message: &str,
+        attempt: u32,
+        max_attempts: u32,
+        delay: Duration,
+    ) -> Self {
+        let lines: Vec<Line<'static>> = vec![
+            Line::from(vec![
+                format!("retrying (attempt {attempt}/{max_attempts})…")
+                    .yellow()
+                    .bold(),
+                format!(" · {message}").dim(),
+            ]),
+            Line::from(format!("  sending the message again in {}", format_duration(delay)).dim()),
+            "".into(),
+        ];
+        HistoryCell::BackgroundEvent {
+            view: TextBlock::new(lines),
+        }
+    }
+
+    /// Marker shown after an answer that was cut short by a length limit.
+    pub(crate) fn new_response_truncated() -> Self {
+        let lines: Vec<Line<'static>> = vec![
+            Line::from(vec![
+                "(response truncated)".dim().italic(),
+                " type /continue to resume".dim(),
+            ]),
+            "".into(),
+        ];
+        HistoryCell::BackgroundEvent {
+            view: TextBlock::new(lines),
+        }
+    }
+
+    /// App-level note such as "network restored". Continuation lines are
+    /// indented under the first.
+    pub(crate) fn new_system_note(text: &str, level: SystemNoteLevel) -> Self {
+        let (glyph, style) = match level {
+            SystemNoteLevel::Info => ("ℹ ", Style::default().fg(Color::Cyan)),
+            SystemNoteLevel::Warn => ("⚠ ", Style::default().fg(Color::Yellow)),
+            #[cfg(test)]
+            SystemNoteLevel::Error => ("✖ ", Style::default().fg(Color::Red)),
+        };
+        let mut lines: Vec<Line<'static>> = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                let prefix = if i == 0 {
+                    Span::styled(glyph, style.add_modifier(Modifier::BOLD))
+                } else {
+                    Span::raw("  ")
+                };
+                Line::from(vec![prefix, Span::styled(line.to_string(), style)])
+            })
+            .collect();
+        lines.push("".into());
+        HistoryCell::SystemNote {
+            view: TextBlock::new(lines),
+        }
+    }
+
     pub(crate) fn new_error_event(message: String) -> Self {
         let lines: Vec<Line<'static>> =
             vec![vec!["🖐 ".red().bold(), message.into()].into(), "".into()];
@@ -824,10 +1104,12 @@ impl HistoryCell {
 
     /// Create a new `PendingPatch` cell that lists the file‑level summary of
     /// a proposed patch. The summary lines should already be formatted (e.g.
-    /// "A path/to/file.rs").
+    /// "A path/to/file.rs"). With `collapse_details` only the summary is
+    /// shown, without the diff of each file.
     pub(crate) fn new_patch_event(
         event_type: PatchEventType,
         changes: HashMap<PathBuf, FileChange>,
+        collapse_details: bool,
     ) -> Self {
         let title = match &event_type {
             PatchEventType::ApprovalRequest => "proposed patch",
@@ -847,7 +1129,8 @@ impl HistoryCell {
             }
         };
 
-        let mut lines: Vec<Line<'static>> = create_diff_summary(title, &changes, event_type);
+        let mut lines: Vec<Line<'static>> =
+            create_diff_summary(title, &changes, event_type, collapse_details);
 
         lines.push(Line::from(""));
 
@@ -856,7 +1139,10 @@ impl HistoryCell {
         }
     }
 
-    pub(crate) fn new_patch_apply_failure(stderr: String) -> Self {
+    pub(crate) fn new_patch_apply_failure(
+        stderr: String,
+        truncation: &TruncationIndicator,
+    ) -> Self {
         let mut lines: Vec<Line<'static>> = Vec::new();
 
         // Failure title
@@ -868,9 +1154,11 @@ impl HistoryCell {
                     exit_code: 1,
                     stdout: String::new(),
                     stderr,
+                    duration: Duration::ZERO,
                 }),
                 true,
                 true,
+                truncation,
             ));
         }
 
@@ -921,11 +1209,13 @@ fn output_lines(
     output: Option<&CommandOutput>,
     only_err: bool,
     include_angle_pipe: bool,
+    truncation: &TruncationIndicator,
 ) -> Vec<Line<'static>> {
     let CommandOutput {
         exit_code,
         stdout,
         stderr,
+        ..
     } = match output {
         Some(output) if only_err && output.exit_code == 0 => return vec![],
         Some(output) => output,
@@ -957,8 +1247,7 @@ fn output_lines(
     // If we will ellipsize less than the limit, just show it.
     let show_ellipsis = total > 2 * limit;
     if show_ellipsis {
-        let omitted = total - 2 * limit;
-        out.push(Line::from(format!("… +{omitted} lines")));
+        out.push(truncation.line(total - 2 * limit));
     }
 
     let tail_start = if show_ellipsis {
@@ -978,25 +1267,74 @@ fn output_lines(
     out
 }
 
-fn format_mcp_invocation<'a>(invocation: McpInvocation) -> Line<'a> {
-    let args_str = invocation
-        .arguments
-        .as_ref()
-        .map(|v| {
-            // Use compact form to keep things short but readable.
-            serde_json::to_string(v).unwrap_or_else(|_| v.to_string())
-        })
-        .unwrap_or_default();
+/// `exit N · duration` line closing a completed command, in the error style
+/// when the command failed.
+fn exec_footer_line(output: &CommandOutput) -> Line<'static> {
+    let text = format!(
+        "    exit {} · {}",
+        output.exit_code,
+        format_duration(output.duration)
+    );
+    if output.exit_code == 0 {
+        Line::from(text.dim())
+    } else {
+        Line::from(text.red())
+    }
+}
 
-    let invocation_spans = vec![
+/// The `server.tool(...)` call. Expanded, arguments that span several lines
+/// when pretty-printed go on their own indented lines; collapsed, they stay
+/// compact on one line, truncated. Arguments that arrive as a string are
+/// parsed as JSON when possible and shown as they are otherwise.
+fn format_mcp_invocation(invocation: &McpInvocation, collapsed: bool) -> Vec<Line<'static>> {
+    let mut call = vec![
         Span::styled(invocation.server.clone(), Style::default().fg(Color::Blue)),
         Span::raw("."),
         Span::styled(invocation.tool.clone(), Style::default().fg(Color::Blue)),
         Span::raw("("),
-        Span::styled(args_str, Style::default().fg(Color::Gray)),
-        Span::raw(")"),
     ];
-    Line::from(invocation_spans)
+    let (compact, pretty) = match &invocation.arguments {
+        None => (String::new(), Vec::new()),
+        Some(serde_json::Value::String(raw)) => {
+            match serde_json::from_str::<serde_json::Value>(raw) {
+                Ok(value) => json_forms(&value),
+                Err(_) => (
+                    raw.lines().collect::<Vec<_>>().join(" "),
+                    raw.lines().map(str::to_string).collect(),
+                ),
+            }
+        }
+        Some(value) => json_forms(value),
+    };
+    let args_style = Style::default().fg(Color::Gray);
+    if collapsed || pretty.len() <= 1 {
+        call.push(Span::styled(
+            truncate_text(&compact, TOOL_CALL_COLLAPSED_ARGS_MAX_CHARS),
+            args_style,
+        ));
+        call.push(Span::raw(")"));
+        if pretty.len() > 1 {
+            call.push(format!(" · {} lines", pretty.len()).dim());
+        }
+        return vec![Line::from(call)];
+    }
+    let mut lines = vec![Line::from(call)];
+    lines.extend(
+        pretty
+            .into_iter()
+            .map(|line| Line::styled(format!("  {line}"), args_style)),
+    );
+    lines.push(Line::from(")"));
+    lines
+}
+
+/// `value` as compact JSON and as pretty-printed lines.
+fn json_forms(value: &serde_json::Value) -> (String, Vec<String>) {
+    let pretty = serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string());
+    (
+        value.to_string(),
+        pretty.lines().map(str::to_string).collect(),
+    )
 }
 
 fn shlex_join_safe(command: &[String]) -> String {
@@ -1015,7 +1353,8 @@ mod tests {
         let parsed = vec![ParsedCommand::Unknown {
             cmd: vec!["printf".into(), "foo\nbar".into()],
         }];
-        let lines = HistoryCell::exec_command_lines(&[], &parsed, None);
+        let lines =
+            HistoryCell::exec_command_lines(&[], &parsed, None, &TruncationIndicator::default());
         assert!(lines.len() >= 3);
         assert_eq!(lines[1].spans[0].content, "  L ");
         assert_eq!(lines[2].spans[0].content, "    ");
@@ -0,0 +1,202 @@
+//! Inline changes in agent prose, written as `~~old~~ new` or
+//! `~~old~~ → new`.
+//!
+//! With `tui.inline_diffs`, the struck-out text is shown in red and the word
+//! replacing it in green, like a one-word diff. As with inline math, each
+//! change is swapped for a placeholder before the prose goes through
+//! `tui_markdown` and put back, styled, once the prose is rendered.
+
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+
+/// Delimits the index of a change in a placeholder; distinct from the
+/// placeholders of inline math.
+const PLACEHOLDER_START: char = '\u{E002}';
+const PLACEHOLDER_END: char = '\u{E003}';
+
+/// Arrows accepted between the struck-out text and its replacement.
+const ARROWS: [&str; 2] = ["→", "->"];
+
+/// A change taken out of the prose: the struck-out text, what separates it
+/// from the replacement as written (e.g. ` → `), and the replacement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct InlineDiff {
+    old: String,
+    separator: String,
+    new: String,
+}
+
+pub(crate) fn removed_style() -> Style {
+    Style::default()
+        .fg(Color::Red)
+        .add_modifier(Modifier::CROSSED_OUT)
+}
+
+pub(crate) fn added_style() -> Style {
+    Style::default().fg(Color::Green)
+}
+
+/// Replace every inline change of `prose` with a placeholder. Returns the
+/// rewritten prose and the changes, in order.
+pub(crate) fn protect_inline_diffs(prose: &str) -> (String, Vec<InlineDiff>) {
+    let mut out = String::with_capacity(prose.len());
+    let mut diffs = Vec::new();
+    for line in prose.split_inclusive('\n') {
+        let mut pos = 0;
+        for (range, diff) in inline_diffs(line) {
+            out.push_str(&line[pos..range.start]);
+            out.push(PLACEHOLDER_START);
+            out.push_str(&diffs.len().to_string());
+            out.push(PLACEHOLDER_END);
+            diffs.push(diff);
+            pos = range.end;
+        }
+        out.push_str(&line[pos..]);
+    }
+    (out, diffs)
+}
+
+/// Put the changes taken out by [`protect_inline_diffs`] back into the
+/// rendered `lines`.
+pub(crate) fn restore_inline_diffs(lines: &mut [Line<'static>], diffs: &[InlineDiff]) {
+    if diffs.is_empty() {
+        return;
+    }
+    for line in lines {
+        let spans = std::mem::take(&mut line.spans);
+        for span in spans {
+            let mut rest: &str = &span.content;
+            while let Some(start) = rest.find(PLACEHOLDER_START) {
+                let Some(len) = rest[start..].find(PLACEHOLDER_END) else {
+                    break;
+                };
+                let index = &rest[start + PLACEHOLDER_START.len_utf8()..start + len];
+                let Some(diff) = index.parse::<usize>().ok().and_then(|i| diffs.get(i)) else {
+                    break;
+                };
+                if start > 0 {
+                    line.spans
+                        .push(Span::styled(rest[..start].to_string(), span.style));
+                }
+                line.spans.extend([
+                    Span::styled(diff.old.clone(), span.style.patch(removed_style())),
+                    Span::styled(diff.separator.clone(), span.style),
+                    Span::styled(diff.new.clone(), span.style.patch(added_style())),
+                ]);
+                rest = &rest[start + len + PLACEHOLDER_END.len_utf8()..];
+            }
+            if !rest.is_empty() {
+                line.spans.push(Span::styled(rest.to_string(), span.style));
+            }
+        }
+    }
+}
+
+/// The inline changes of `line` outside inline code, with their byte ranges.
+fn inline_diffs(line: &str) -> Vec<(std::ops::Range<usize>, InlineDiff)> {
+    let bytes = line.as_bytes();
+    let mut found = Vec::new();
+    let mut in_code = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'`' => in_code = !in_code,
+            b'\\' if !in_code => i += 1,
+            b'~' if !in_code && line[i..].starts_with("~~") => {
+                if let Some((end, diff)) = inline_diff_at(line, i) {
+                    found.push((i..end, diff));
+                    i = end;
+                    continue;
+                }
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    found
+}
+
+/// The change starting with the `~~` at `open`, if it is one: struck-out
+/// text on one line, then spaces, an optional arrow, and a replacement word.
+/// Returns where the change ends. A replacement that is itself markdown,
+/// such as inline code or a link, is left to the renderer.
+fn inline_diff_at(line: &str, open: usize) -> Option<(usize, InlineDiff)> {
+    let body = open + 2;
+    let close = body + line[body..].find("~~")?;
+    let old = &line[body..close];
+    if old.is_empty() || old.starts_with(char::is_whitespace) || old.ends_with(char::is_whitespace)
+    {
+        return None;
+    }
+    let after = close + 2;
+    let mut new_start = after + spaces(&line[after..]);
+    if new_start == after {
+        return None;
+    }
+    if let Some(arrow) = ARROWS.iter().find(|a| line[new_start..].starts_with(**a)) {
+        let past_arrow = new_start + arrow.len();
+        let gap = spaces(&line[past_arrow..]);
+        if gap == 0 {
+            return None;
+        }
+        new_start = past_arrow + gap;
+    }
+    let word_len = line[new_start..]
+        .find(char::is_whitespace)
+        .unwrap_or(line.len() - new_start);
+    let word =
+        line[new_start..new_start + word_len].trim_end_matches([',', '.', ';', ':', '!', '?', ')']);
+    if word.is_empty() || word.starts_with(['`', '[', '*', '_', '~']) {
+        return None;
+    }
+    let end = new_start + word.len();
+    Some((
+        end,
+        InlineDiff {
+            old: old.to_string(),
+            separator: line[after..new_start].to_string(),
+            new: word.to_string(),
+        },
+    ))
+}
+
+/// Length of the run of spaces and tabs at the start of `text`.
+fn spaces(text: &str) -> usize {
+    text.len() - text.trim_start_matches([' ', '\t']).len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn changes(line: &str) -> Vec<(&str, String, String)> {
+        inline_diffs(line)
+            .into_iter()
+            .map(|(range, diff)| (&line[range], diff.old, diff.new))
+            .collect()
+    }
+
+    #[test]
+    fn finds_replacements_with_and_without_an_arrow() {
+        assert_eq!(
+            changes("Use ~~foo~~ bar, then ~~x~~ → y."),
+            vec![
+                ("~~foo~~ bar", "foo".to_string(), "bar".to_string()),
+                ("~~x~~ → y", "x".to_string(), "y".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn plain_strikethrough_and_code_are_not_changes() {
+        assert!(changes("This is ~~wrong~~.").is_empty());
+        assert!(changes("Run `~~a~~ b` as is.").is_empty());
+        assert!(changes("See ~~this~~ `code` here.").is_empty());
+        assert!(changes("Not ~~ spaced~~ word.").is_empty());
+    }
+}
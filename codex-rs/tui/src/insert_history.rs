@@ -2,6 +2,9 @@ use std::fmt;
 use std::io;
 use std::io::Write;
 
+use crate::file_reference::FileLinks;
+use crate::markdown::CODE_BLOCK_BORDER;
+use crate::markdown::is_preformatted_line;
 use crate::tui;
 use crossterm::Command;
 use crossterm::cursor::MoveTo;
@@ -20,11 +23,18 @@ use ratatui::text::Line;
 use ratatui::text::Span;
 use textwrap::Options as TwOptions;
 use textwrap::WordSplitter;
+use unicode_width::UnicodeWidthChar;
 
-/// Insert `lines` above the viewport.
-pub(crate) fn insert_history_lines(terminal: &mut tui::Tui, lines: Vec<Line>) {
+/// Insert `lines` above the viewport, wrapped at no more than `max_width`
+/// columns when it is set. File references become hyperlinks to `links`.
+pub(crate) fn insert_history_lines(
+    terminal: &mut tui::Tui,
+    lines: Vec<Line>,
+    max_width: Option<u16>,
+    links: Option<&FileLinks>,
+) {
     let mut out = std::io::stdout();
-    insert_history_lines_to_writer(terminal, &mut out, lines);
+    insert_wrapped_history_lines_to_writer(terminal, &mut out, lines, max_width, links);
 }
 
 /// Like `insert_history_lines`, but writes ANSI to the provided writer. This
@@ -36,6 +46,72 @@ pub fn insert_history_lines_to_writer<B, W>(
 ) where
     B: ratatui::backend::Backend,
     W: Write,
+{
+    insert_wrapped_history_lines_to_writer(terminal, writer, lines, None, None);
+}
+
+/// Like `insert_history_lines_to_writer`, but never hands `writer` more than
+/// `max_bytes` bytes between flushes: once that many are written it is
+/// flushed before anything else is written, and it is flushed again at the
+/// end. A writer backed by a fixed-size buffer can drain it on `flush`
+/// instead of growing, however many lines are inserted. Errors from the
+/// writer are ignored, as in the uncapped variant, so the escape sequences
+/// that restore the scroll region and cursor are still attempted.
+pub fn insert_capped_history_lines_to_writer<B, W>(
+    terminal: &mut crate::custom_terminal::Terminal<B>,
+    writer: &mut W,
+    lines: Vec<Line>,
+    max_bytes: usize,
+) where
+    B: ratatui::backend::Backend,
+    W: Write,
+{
+    let mut capped = CappedWriter {
+        inner: writer,
+        max_bytes: max_bytes.max(1),
+        pending: 0,
+    };
+    insert_wrapped_history_lines_to_writer(terminal, &mut capped, lines, None, None);
+    capped.flush().ok();
+}
+
+/// Writer that flushes `inner` every `max_bytes` bytes.
+struct CappedWriter<'a, W: Write> {
+    inner: &'a mut W,
+    max_bytes: usize,
+    /// Bytes written since the last flush.
+    pending: usize,
+}
+
+impl<W: Write> Write for CappedWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.pending >= self.max_bytes {
+            self.flush()?;
+        }
+        let room = self.max_bytes - self.pending;
+        let written = self.inner.write(&buf[..buf.len().min(room)])?;
+        self.pending += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()?;
+        self.pending = 0;
+        Ok(())
+    }
+}
+
+/// Like `insert_history_lines_to_writer`, but wraps at no more than
+/// `max_width` columns when it is set and links file references to `links`.
+pub(crate) fn insert_wrapped_history_lines_to_writer<B, W>(
+    terminal: &mut crate::custom_terminal::Terminal<B>,
+    writer: &mut W,
+    lines: Vec<Line>,
+    max_width: Option<u16>,
+    links: Option<&FileLinks>,
+) where
+    B: ratatui::backend::Backend,
+    W: Write,
 {
     let screen_size = terminal.backend().size().unwrap_or(Size::new(0, 0));
     let cursor_pos = terminal.get_cursor_position().ok();
@@ -44,7 +120,11 @@ pub fn insert_history_lines_to_writer<B, W>(
 
     // Pre-wrap lines using word-aware wrapping so terminal scrollback sees the same
     // formatting as the TUI. This avoids character-level hard wrapping by the terminal.
-    let wrapped = word_wrap_lines(&lines, area.width.max(1));
+    let width = match max_width {
+        Some(max) => area.width.min(max),
+        None => area.width,
+    };
+    let wrapped = word_wrap_lines(&lines, width.max(1));
     let wrapped_lines = wrapped.len() as u16;
     let cursor_top = if area.bottom() < screen_size.height {
         // If the viewport is not at the bottom of the screen, scroll it down to make room.
@@ -98,7 +178,7 @@ pub fn insert_history_lines_to_writer<B, W>(
 
     for line in wrapped {
         queue!(writer, Print("\r\n")).ok();
-        write_spans(writer, line.iter()).ok();
+        write_spans(writer, line.iter(), links).ok();
     }
 
     queue!(writer, ResetScrollRegion).ok();
@@ -216,7 +296,11 @@ impl ModifierDiff {
     }
 }
 
-fn write_spans<'a, I>(mut writer: &mut impl Write, content: I) -> io::Result<()>
+fn write_spans<'a, I>(
+    mut writer: &mut impl Write,
+    content: I,
+    links: Option<&FileLinks>,
+) -> io::Result<()>
 where
     I: Iterator<Item = &'a Span<'a>>,
 {
@@ -246,7 +330,15 @@ where
             bg = next_bg;
         }
 
-        queue!(writer, Print(span.content.clone()))?;
+        match links.and_then(|links| links.url_for(span)) {
+            Some(url) => queue!(
+                writer,
+                Print(format!("\x1b]8;;{url}\x1b\\")),
+                Print(span.content.clone()),
+                Print("\x1b]8;;\x1b\\"),
+            )?,
+            None => queue!(writer, Print(span.content.clone()))?,
+        }
     }
 
     queue!(
@@ -271,6 +363,26 @@ fn word_wrap_line(line: &Line, width: usize) -> Vec<Line<'static>> {
     if width == 0 {
         return vec![to_owned_line(line)];
     }
+    // Code lines with a border bar: wrap the code alone and draw the bar on
+    // every row so it stays a clean column.
+    if line.spans.len() > 1 && line.spans[0].content == CODE_BLOCK_BORDER {
+        let border: Span<'static> = Span::styled(CODE_BLOCK_BORDER, line.spans[0].style);
+        let border_width = border.width();
+        if width > border_width {
+            let body = Line {
+                style: line.style,
+                alignment: line.alignment,
+                spans: line.spans[1..].to_vec(),
+            };
+            return word_wrap_line(&body, width - border_width)
+                .into_iter()
+                .map(|mut row| {
+                    row.spans.insert(0, border.clone());
+                    row
+                })
+                .collect();
+        }
+    }
     // Concatenate content and keep span boundaries for later re-slicing.
     let mut flat = String::new();
     let mut span_bounds = Vec::new(); // (start_byte, end_byte, style)
@@ -283,6 +395,21 @@ fn word_wrap_line(line: &Line, width: usize) -> Vec<Line<'static>> {
         span_bounds.push((start, cursor, s.style));
     }
 
+    // Hand-aligned lines (box art, drawn tables) are clipped: wrapping them
+    // would scatter their columns across rows.
+    if is_preformatted_line(&flat) {
+        let mut end = 0usize;
+        let mut used = 0usize;
+        for (i, ch) in flat.char_indices() {
+            used += ch.width().unwrap_or(0);
+            if used > width {
+                break;
+            }
+            end = i + ch.len_utf8();
+        }
+        return vec![slice_line_spans(line, &span_bounds, 0, end)];
+    }
+
     // Use textwrap for robust word-aware wrapping; no hyphenation, no breaking words.
     let opts = TwOptions::new(width)
         .break_words(false)
@@ -389,7 +516,7 @@ mod tests {
         let spans = ["A".bold(), "B".into()];
 
         let mut actual: Vec<u8> = Vec::new();
-        write_spans(&mut actual, spans.iter()).unwrap();
+        write_spans(&mut actual, spans.iter(), None).unwrap();
 
         let mut expected: Vec<u8> = Vec::new();
         queue!(
@@ -410,6 +537,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn capped_insertion_flushes_before_exceeding_the_limit() {
+        /// Buffer that holds at most what was written since the last flush.
+        #[derive(Default)]
+        struct FixedBuffer {
+            held: Vec<u8>,
+            max_held: usize,
+            drained: Vec<u8>,
+        }
+        impl Write for FixedBuffer {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.held.extend_from_slice(buf);
+                self.max_held = self.max_held.max(self.held.len());
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                self.drained.append(&mut self.held);
+                Ok(())
+            }
+        }
+
+        let terminal = || {
+            let backend = ratatui::backend::TestBackend::new(80, 20);
+            let mut terminal = crate::custom_terminal::Terminal::with_options(backend).unwrap();
+            terminal.set_viewport_area(ratatui::layout::Rect::new(0, 19, 80, 1));
+            terminal
+        };
+        let lines = || -> Vec<Line<'static>> {
+            (0..2000)
+                .map(|i| Line::from(format!("line {i} {}", "x".repeat(60))))
+                .collect()
+        };
+
+        let mut uncapped: Vec<u8> = Vec::new();
+        insert_history_lines_to_writer(&mut terminal(), &mut uncapped, lines());
+
+        let mut buffer = FixedBuffer::default();
+        insert_capped_history_lines_to_writer(&mut terminal(), &mut buffer, lines(), 4096);
+        assert!(buffer.max_held <= 4096, "held {} bytes", buffer.max_held);
+        assert!(buffer.held.is_empty());
+        assert_eq!(buffer.drained, uncapped);
+    }
+
     #[test]
     fn line_height_counts_double_width_emoji() {
         let line = Line::from("😀😀😀"); // each emoji ~ width 2
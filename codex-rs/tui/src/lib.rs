@@ -3,6 +3,8 @@
 // alternate‑screen mode starts; that file opts‑out locally via `allow`.
 #![deny(clippy::print_stdout, clippy::print_stderr)]
 use app::App;
+use attachments::UserMessage;
+use chatwidget::InitialPrompt;
 use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
@@ -14,6 +16,7 @@ use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
 use codex_login::CodexAuth;
 use codex_ollama::DEFAULT_OSS_MODEL;
+use follow::LogFollower;
 use log_layer::TuiLogLayer;
 use std::fs::OpenOptions;
 use std::path::PathBuf;
@@ -25,32 +28,54 @@ use tracing_subscriber::prelude::*;
 mod app;
 mod app_event;
 mod app_event_sender;
+mod attachments;
 mod bottom_pane;
+mod bubble;
 mod chatwidget;
 mod citation_regex;
 mod cli;
+mod clipboard;
+mod code_blocks;
 mod colors;
 mod common;
 pub mod custom_terminal;
+mod details;
 mod diff_render;
+#[cfg(unix)]
+mod event_tap;
 mod exec_command;
+mod exec_policy;
+mod fence_renderer;
+mod file_reference;
 mod file_search;
+mod follow;
 mod get_git_diff;
 mod history_cell;
+mod inline_diff;
 pub mod insert_history;
 pub mod live_wrap;
 mod log_layer;
 mod markdown;
 mod markdown_stream;
+mod math;
 pub mod onboarding;
+mod plain_log;
+mod reasoning_cap;
 mod render;
+mod resume;
+mod retry;
+mod scrollback;
+mod search;
 mod session_log;
 mod shimmer;
+mod sign_off;
 mod slash_command;
 mod status_indicator_widget;
 mod streaming;
 mod text_block;
 mod text_formatting;
+mod transcript;
+mod truncation;
 mod tui;
 mod user_approval_widget;
 
@@ -65,6 +90,11 @@ mod updates;
 use color_eyre::owo_colors::OwoColorize;
 
 pub use cli::Cli;
+pub use fence_renderer::FenceRenderer;
+pub use fence_renderer::register_fence_renderer;
+pub use transcript::ExecRecord;
+pub use transcript::TurnRecord;
+pub use transcript::turns_from_events;
 
 // (tests access modules directly within the crate)
 
@@ -275,14 +305,35 @@ fn run_ratatui_app(
         tracing::error!("panic: {info}");
         prev_hook(info);
     }));
+    let follower = cli.follow.as_deref().map(LogFollower::open).transpose()?;
     let mut terminal = tui::init(&config)?;
     terminal.clear()?;
 
-    // Initialize high-fidelity session event logging if enabled.
-    session_log::maybe_init(&config);
+    // Initialize high-fidelity session event logging if enabled. A viewer
+    // does not record itself, which could truncate the log it follows.
+    if follower.is_none() {
+        session_log::maybe_init(&config);
+    }
 
-    let Cli { prompt, images, .. } = cli;
-    let mut app = App::new(config.clone(), prompt, images, should_show_trust_screen);
+    let Cli {
+        prompt,
+        images,
+        edit,
+        ..
+    } = cli;
+    let initial_message = UserMessage::new(prompt.unwrap_or_default(), images);
+    let initial_prompt = if edit {
+        InitialPrompt::Prefill
+    } else {
+        InitialPrompt::Submit
+    };
+    let mut app = App::new(
+        config.clone(),
+        Some(initial_message),
+        initial_prompt,
+        follower,
+        should_show_trust_screen,
+    );
 
     // Bridge log receiver into the AppEvent channel so latest log lines update the UI.
     {
@@ -1,25 +1,204 @@
 use crate::citation_regex::CITATION_REGEX;
+use crate::citation_regex::MARKDOWN_LINK_REGEX;
+use crate::details::DetailsPiece;
+use crate::details::split_details;
+use crate::details::summary_line;
+use crate::fence_renderer::render_fence;
+use crate::file_reference::link_file_references;
+use crate::inline_diff::InlineDiff;
+use crate::inline_diff::protect_inline_diffs;
+use crate::inline_diff::restore_inline_diffs;
+use crate::insert_history::word_wrap_lines;
+use crate::math::is_math_block_start;
+use crate::math::math_block_len;
+use crate::math::math_style;
+use crate::math::protect_inline_math;
+use crate::math::restore_inline_math;
+use crate::render::line_utils::is_blank_line_trim;
+use crate::render::line_utils::justify_line;
+use crate::render::markdown_utils::Fence;
+use crate::text_formatting::sanitize_control_chars;
 use codex_core::config::Config;
+use codex_core::config_types::CodeBlockStyle;
+use codex_core::config_types::Justify;
+use codex_core::config_types::LinkStyle;
+use codex_core::config_types::MarkdownEscapes;
+use codex_core::config_types::Theme;
 use codex_core::config_types::UriBasedFileOpener;
+use ratatui::style::Color;
+use ratatui::style::Style;
+use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::text::Span;
 use std::borrow::Cow;
+use std::ops::Range;
 use std::path::Path;
+use unicode_width::UnicodeWidthChar;
+
+/// Left bar drawn on every code line when `tui.code_block_style = "border"`.
+/// Line wrapping repeats it on continuation rows.
+pub(crate) const CODE_BLOCK_BORDER: &str = "▎ ";
+
+/// Presentation options for code blocks. These only affect what is drawn on
+/// screen; the transcript keeps the original characters.
+#[derive(Debug, Clone, Copy)]
+struct CodeBlockOptions {
+    /// Expand tabs to the next multiple of this many columns (0 keeps tabs).
+    tab_width: usize,
+    /// Render trailing whitespace as dim `·`.
+    show_trailing_whitespace: bool,
+    /// Border or background decoration.
+    style: CodeBlockStyle,
+    /// Theme the decoration colors are picked from.
+    theme: Theme,
+}
+
+/// Presentation options for prose, tables and preformatted text outside code
+/// blocks. Like `CodeBlockOptions`, they only affect what is drawn on screen.
+#[derive(Debug, Clone, Copy, Default)]
+struct TextOptions {
+    /// Shorten runs of blank lines to this many.
+    max_blank_lines: Option<usize>,
+    /// Fold a run of more than this many identical lines into one.
+    max_repeated_lines: Option<usize>,
+    /// Justify prose to this column.
+    justify_width: Option<u16>,
+    /// Show `$…$` and `$$…$$` math in its own style.
+    math: bool,
+    /// Show links as `text[n]`, leaving the URLs to [`link_references`].
+    reference_links: bool,
+    /// Show backslash escapes as written instead of honoring them.
+    show_escapes: bool,
+    /// Show `~~old~~ new` as an inline change.
+    inline_diffs: bool,
+    /// Background of every other table body row.
+    table_stripe: Option<Color>,
+}
+
+impl TextOptions {
+    fn from_config(config: &Config) -> Self {
+        Self {
+            max_blank_lines: config.tui.collapse_blank_lines,
+            max_repeated_lines: config.tui.collapse_repeated_lines,
+            justify_width: justify_width(config),
+            math: config.tui.render_math,
+            reference_links: config.tui.link_style == LinkStyle::Reference,
+            show_escapes: config.tui.markdown_escapes == MarkdownEscapes::Show,
+            inline_diffs: config.tui.inline_diffs,
+            table_stripe: table_stripe(config),
+        }
+    }
+}
+
+impl Default for CodeBlockOptions {
+    fn default() -> Self {
+        Self {
+            tab_width: 0,
+            show_trailing_whitespace: false,
+            style: CodeBlockStyle::Plain,
+            theme: Theme::Dark,
+        }
+    }
+}
+
+impl CodeBlockOptions {
+    fn from_config(config: &Config) -> Self {
+        Self {
+            tab_width: config.tui.tab_width,
+            show_trailing_whitespace: config.tui.show_trailing_whitespace,
+            style: config.tui.code_block_style,
+            theme: config.tui.theme,
+        }
+    }
+}
 
 pub(crate) fn append_markdown(
     markdown_source: &str,
     lines: &mut Vec<Line<'static>>,
     config: &Config,
 ) {
-    append_markdown_with_opener_and_cwd(markdown_source, lines, config.file_opener, &config.cwd);
+    let markdown_source = sanitize_control_chars(markdown_source, config.tui.control_chars);
+    append_markdown_with_options(
+        &markdown_source,
+        lines,
+        config.file_opener,
+        &config.cwd,
+        CodeBlockOptions::from_config(config),
+        TextOptions::from_config(config),
+    );
 }
 
+#[cfg(test)]
 fn append_markdown_with_opener_and_cwd(
     markdown_source: &str,
     lines: &mut Vec<Line<'static>>,
     file_opener: UriBasedFileOpener,
     cwd: &Path,
 ) {
+    append_markdown_with_options(
+        markdown_source,
+        lines,
+        file_opener,
+        cwd,
+        CodeBlockOptions::default(),
+        TextOptions::default(),
+    );
+}
+
+fn append_markdown_with_options(
+    markdown_source: &str,
+    lines: &mut Vec<Line<'static>>,
+    file_opener: UriBasedFileOpener,
+    cwd: &Path,
+    code_options: CodeBlockOptions,
+    text_options: TextOptions,
+) {
+    // A `<details>` section is its summary line followed by its body, which
+    // is rendered on its own so sections can nest.
+    let start = lines.len();
+    let pieces = split_details(markdown_source);
+    let count = pieces.len();
+    for (i, piece) in pieces.into_iter().enumerate() {
+        match piece {
+            DetailsPiece::Markdown(text) => {
+                append_markdown_text(text, lines, file_opener, cwd, code_options, text_options);
+            }
+            DetailsPiece::Details { summary, body } => {
+                if lines.len() > start && lines.last().is_some_and(|l| !is_blank_line_trim(l)) {
+                    lines.push(Line::from(""));
+                }
+                let Some(summary) = summary else {
+                    continue;
+                };
+                lines.push(summary_line(&summary));
+                append_markdown_with_options(
+                    body,
+                    lines,
+                    file_opener,
+                    cwd,
+                    code_options,
+                    text_options,
+                );
+                if i + 1 < count && lines.last().is_some_and(|l| !is_blank_line_trim(l)) {
+                    lines.push(Line::from(""));
+                }
+            }
+        }
+    }
+}
+
+/// Render markdown without `<details>` sections.
+fn append_markdown_text(
+    markdown_source: &str,
+    lines: &mut Vec<Line<'static>>,
+    file_opener: UriBasedFileOpener,
+    cwd: &Path,
+    code_options: CodeBlockOptions,
+    text_options: TextOptions,
+) {
+    // URLs of the links numbered so far, shared by every prose piece so a
+    // message has one sequence of reference numbers.
+    let mut link_urls: Vec<String> = Vec::new();
     // Historically, we fed the entire `markdown_source` into the renderer in
     // one pass. However, fenced code blocks sometimes lost leading whitespace
     // when formatted by the markdown renderer/highlighter. To preserve code
@@ -30,11 +209,82 @@ fn append_markdown_with_opener_and_cwd(
     for seg in split_text_and_fences(markdown_source) {
         match seg {
             Segment::Text(s) => {
-                let processed = rewrite_file_citations(&s, file_opener, cwd);
-                let rendered = tui_markdown::from_str(&processed);
-                crate::render::line_utils::push_owned_lines(&rendered.lines, lines);
+                let text_start = lines.len();
+                let pieces = split_text_pieces(&s, text_options.math);
+                let count = pieces.len();
+                for (i, piece) in pieces.iter().enumerate() {
+                    match piece {
+                        TextPiece::Prose(prose) => {
+                            let (prose, math, diffs) =
+                                preprocess_prose(prose, text_options, &mut link_urls);
+                            let processed = rewrite_file_citations(&prose, file_opener, cwd);
+                            let rendered = tui_markdown::from_str(&processed);
+                            let start = lines.len();
+                            crate::render::line_utils::push_owned_lines(&rendered.lines, lines);
+                            // Changes may hold math, so they go back first.
+                            restore_inline_diffs(&mut lines[start..], &diffs);
+                            restore_inline_math(&mut lines[start..], &math);
+                            link_file_references(&mut lines[start..]);
+                            if let Some(width) = text_options.justify_width {
+                                justify_prose(lines, start, width);
+                            }
+                        }
+                        TextPiece::Table(rows) => {
+                            lines.extend(render_table(rows, text_options.table_stripe));
+                            // Prose after the table starts with the blank
+                            // line that `tui_markdown` would drop.
+                            if i + 1 < count {
+                                lines.push(Line::from(""));
+                            }
+                        }
+                        TextPiece::Preformatted(rows) | TextPiece::Math(rows) => {
+                            // Keep the blank lines around the run that
+                            // `tui_markdown` drops from the prose next to it.
+                            let blank_before = i > 0
+                                && matches!(pieces[i - 1], TextPiece::Prose(p) if p.ends_with("\n\n"))
+                                && lines.last().is_some_and(|l| !is_blank_line_trim(l));
+                            if blank_before {
+                                lines.push(Line::from(""));
+                            }
+                            let style = match piece {
+                                TextPiece::Math(_) => math_style(),
+                                _ => Style::default(),
+                            };
+                            // Styled per span: the history writer only
+                            // emits span styles.
+                            lines.extend(rows.iter().map(|row| {
+                                Line::from(Span::styled(
+                                    expand_tabs(row, code_options.tab_width).into_owned(),
+                                    style,
+                                ))
+                            }));
+                            let blank_after = matches!(
+                                pieces.get(i + 1),
+                                Some(TextPiece::Prose(p)) if p.starts_with('\n')
+                            );
+                            if blank_after {
+                                lines.push(Line::from(""));
+                            }
+                        }
+                    }
+                }
+                if let Some(max) = text_options.max_blank_lines {
+                    collapse_blank_runs(lines, text_start, max);
+                }
+                if let Some(max) = text_options.max_repeated_lines {
+                    collapse_repeated_runs(lines, text_start, max);
+                }
             }
-            Segment::Code { content, .. } => {
+            Segment::Code { lang, content } => {
+                // A renderer registered by the host for the block's
+                // language takes its place.
+                let custom = lang
+                    .as_deref()
+                    .and_then(|info| render_fence(info, &content));
+                if let Some(custom) = custom {
+                    lines.extend(custom);
+                    continue;
+                }
                 // Emit the code content exactly as-is, line by line.
                 // We don't attempt syntax highlighting to avoid whitespace bugs.
                 for line in content.split_inclusive('\n') {
@@ -44,14 +294,588 @@ fn append_markdown_with_opener_and_cwd(
                     } else {
                         line
                     };
-                    let owned_line: Line<'static> = Line::from(Span::raw(line.to_string()));
-                    lines.push(owned_line);
+                    lines.push(render_code_line(line, code_options));
+                }
+            }
+        }
+    }
+}
+
+/// The `References` section listed under a message with
+/// `tui.link_style = "reference"`: the URLs of the links in
+/// `markdown_source`, numbered as `append_markdown` numbers them. Empty in
+/// inline mode or when the message has no links.
+pub(crate) fn link_references(markdown_source: &str, config: &Config) -> Vec<Line<'static>> {
+    if config.tui.link_style != LinkStyle::Reference {
+        return Vec::new();
+    }
+    let text_options = TextOptions::from_config(config);
+    let mut urls = Vec::new();
+    for seg in split_text_and_fences(markdown_source) {
+        let Segment::Text(text) = seg else {
+            continue;
+        };
+        for piece in split_text_pieces(&text, text_options.math) {
+            if let TextPiece::Prose(prose) = piece {
+                preprocess_prose(prose, text_options, &mut urls);
+            }
+        }
+    }
+    if urls.is_empty() {
+        return Vec::new();
+    }
+    let mut lines = vec![Line::from(""), Line::from("References".bold())];
+    lines.extend(
+        urls.into_iter()
+            .enumerate()
+            .map(|(i, url)| Line::from(vec![format!("[{}] ", i + 1).dim(), url.underlined()])),
+    );
+    lines
+}
+
+/// Rewrite `prose` for `tui_markdown`: protect inline math and inline
+/// changes, keep escapes with `show_escapes`, and number links in reference
+/// mode, adding their URLs to `urls`. Returns the prose and the math regions
+/// and changes to restore.
+fn preprocess_prose(
+    prose: &str,
+    options: TextOptions,
+    urls: &mut Vec<String>,
+) -> (String, Vec<String>, Vec<InlineDiff>) {
+    let (mut prose, math) = if options.math {
+        protect_inline_math(prose)
+    } else {
+        (prose.to_string(), Vec::new())
+    };
+    let mut diffs = Vec::new();
+    if options.inline_diffs {
+        (prose, diffs) = protect_inline_diffs(&prose);
+    }
+    if options.show_escapes {
+        prose = keep_escapes(&prose);
+    }
+    if options.reference_links {
+        prose = number_links(&prose, urls);
+    }
+    (prose, math, diffs)
+}
+
+/// Byte ranges of `prose` outside inline code, backticks excluded. As in
+/// CommonMark, an escaped backtick does not open a code span and backslashes
+/// inside one are literal. The rest of an unclosed span counts as code.
+fn outside_inline_code(prose: &str) -> Vec<Range<usize>> {
+    let bytes = prose.as_bytes();
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut in_code = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if !in_code => i += 1,
+            b'`' if in_code => {
+                in_code = false;
+                start = i + 1;
+            }
+            b'`' => {
+                in_code = true;
+                ranges.push(start..i);
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    if !in_code {
+        ranges.push(start..bytes.len());
+    }
+    ranges
+}
+
+/// Whether the character at byte `index` of `text` is escaped, i.e. preceded
+/// by an odd number of backslashes.
+fn is_escaped(text: &str, index: usize) -> bool {
+    let backslashes = text.as_bytes()[..index]
+        .iter()
+        .rev()
+        .take_while(|&&b| b == b'\\')
+        .count();
+    backslashes % 2 == 1
+}
+
+/// Escape the backslash of every escape outside inline code, so that
+/// `tui_markdown` shows `\*` as written rather than as `*`.
+fn keep_escapes(prose: &str) -> String {
+    let mut out = String::with_capacity(prose.len());
+    let mut pos = 0;
+    for range in outside_inline_code(prose) {
+        out.push_str(&prose[pos..range.start]);
+        let mut chars = prose[range.clone()].chars().peekable();
+        while let Some(c) = chars.next() {
+            out.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next_if(char::is_ascii_punctuation) {
+                    out.push_str("\\\\");
+                    out.push(escaped);
                 }
             }
         }
+        pos = range.end;
+    }
+    out.push_str(&prose[pos..]);
+    out
+}
+
+/// Rewrite the links in `prose` to `text[n]`. A URL gets the number of its
+/// first appearance in `urls`, where new URLs are added. Images, escaped
+/// brackets and links in inline code are left alone.
+fn number_links(prose: &str, urls: &mut Vec<String>) -> String {
+    let mut out = String::with_capacity(prose.len());
+    let mut pos = 0;
+    for range in outside_inline_code(prose) {
+        out.push_str(&prose[pos..range.start]);
+        let part = &prose[range.clone()];
+        let numbered = MARKDOWN_LINK_REGEX.replace_all(part, |caps: &regex_lite::Captures<'_>| {
+            let escaped = caps.get(0).is_some_and(|m| is_escaped(part, m.start()));
+            if &caps[1] == "!" || escaped {
+                return caps[0].to_string();
+            }
+            let (text, url) = (&caps[2], &caps[3]);
+            let n = match urls.iter().position(|known| known == url) {
+                Some(index) => index + 1,
+                None => {
+                    urls.push(url.to_string());
+                    urls.len()
+                }
+            };
+            format!("{text}\\[{n}\\]")
+        });
+        out.push_str(&numbered);
+        pos = range.end;
+    }
+    out.push_str(&prose[pos..]);
+    out
+}
+
+/// Column prose is justified to, when `tui.justify = "full"` and there is a
+/// fixed wrap width to justify to.
+fn justify_width(config: &Config) -> Option<u16> {
+    match config.tui.justify {
+        Justify::Ragged => None,
+        Justify::Full => config.tui.max_wrap_columns,
+    }
+}
+
+/// Wrap the prose lines in `lines[start..]` at `width` and justify every row
+/// but the last of each line, which ends its paragraph.
+fn justify_prose(lines: &mut Vec<Line<'static>>, start: usize, width: u16) {
+    for line in lines.split_off(start) {
+        let mut rows = word_wrap_lines(std::slice::from_ref(&line), width);
+        let last = rows.pop();
+        lines.extend(rows.iter().map(|row| justify_line(row, width as usize)));
+        lines.extend(last);
+    }
+}
+
+/// Contents of the fenced and indented code blocks in `markdown_source`, in
+/// order, as `append_markdown` renders them.
+pub(crate) fn code_block_contents(markdown_source: &str) -> Vec<String> {
+    split_text_and_fences(markdown_source)
+        .into_iter()
+        .filter_map(|seg| match seg {
+            Segment::Code { content, .. } => Some(content),
+            Segment::Text(_) => None,
+        })
+        .collect()
+}
+
+/// Drop the blank lines in `lines[start..]` that would make a run of blank
+/// lines longer than `max`. Blank lines just before `start` count toward the
+/// first run but are kept.
+fn collapse_blank_runs(lines: &mut Vec<Line<'static>>, start: usize, max: usize) {
+    let mut run = lines[..start]
+        .iter()
+        .rev()
+        .take_while(|line| is_blank_line_trim(line))
+        .count();
+    for line in lines.split_off(start) {
+        if is_blank_line_trim(&line) {
+            run += 1;
+            if run > max {
+                continue;
+            }
+        } else {
+            run = 0;
+        }
+        lines.push(line);
+    }
+}
+
+/// Marker appended to a line standing in for a run of identical lines.
+const REPEATED_LINE_MARKER: &str = " ⟲ ×";
+
+/// Replace each run of more than `max` identical non-blank lines in
+/// `lines[start..]` with its first line followed by `⟲ ×N`, N being the
+/// length of the run.
+fn collapse_repeated_runs(lines: &mut Vec<Line<'static>>, start: usize, max: usize) {
+    let mut rest = lines.split_off(start).into_iter().peekable();
+    while let Some(line) = rest.next() {
+        let text = line.to_string();
+        let mut run = 1;
+        if !is_blank_line_trim(&line) {
+            while rest.next_if(|next| next.to_string() == text).is_some() {
+                run += 1;
+            }
+        }
+        if run <= max {
+            lines.extend(std::iter::repeat_n(line, run));
+            continue;
+        }
+        let mut line = line;
+        line.spans
+            .push(format!("{REPEATED_LINE_MARKER}{run}").dim());
+        lines.push(line);
+    }
+}
+
+fn render_code_line(line: &str, options: CodeBlockOptions) -> Line<'static> {
+    let expanded = expand_tabs(line, options.tab_width);
+    let mut spans = if options.show_trailing_whitespace {
+        let body = expanded.trim_end_matches([' ', '\t']);
+        let trailing = expanded[body.len()..].chars().count();
+        let mut spans = vec![Span::raw(body.to_string())];
+        if trailing > 0 {
+            spans.push("·".repeat(trailing).dim());
+        }
+        spans
+    } else {
+        vec![Span::raw(expanded.into_owned())]
+    };
+    match options.style {
+        CodeBlockStyle::Plain => {}
+        CodeBlockStyle::Border => {
+            spans.insert(
+                0,
+                Span::styled(CODE_BLOCK_BORDER, code_border_style(options.theme)),
+            );
+        }
+        CodeBlockStyle::Background => {
+            // Set on each span rather than the line: the history writer only
+            // emits span styles, and wrapping keeps them per fragment.
+            if let Some(bg) = code_background(options.theme) {
+                for span in &mut spans {
+                    span.style = span.style.bg(bg);
+                }
+            }
+        }
+    }
+    Line::from(spans)
+}
+
+fn code_border_style(theme: Theme) -> Style {
+    match theme {
+        Theme::Dark => Style::default().fg(Color::DarkGray),
+        Theme::Light => Style::default().fg(Color::Gray),
+        Theme::Mono => Style::default().dim(),
+    }
+}
+
+/// Mono has no background so it stays legible on any terminal palette.
+fn code_background(theme: Theme) -> Option<Color> {
+    match theme {
+        Theme::Dark => Some(Color::Indexed(236)),
+        Theme::Light => Some(Color::Indexed(254)),
+        Theme::Mono => None,
+    }
+}
+
+/// Replace each tab with spaces up to the next tab stop.
+fn expand_tabs(line: &str, tab_width: usize) -> Cow<'_, str> {
+    if tab_width == 0 || !line.contains('\t') {
+        return Cow::Borrowed(line);
+    }
+    let mut out = String::with_capacity(line.len() + tab_width);
+    let mut col = 0;
+    for ch in line.chars() {
+        if ch == '\t' {
+            let pad = tab_width - (col % tab_width);
+            out.extend(std::iter::repeat_n(' ', pad));
+            col += pad;
+        } else {
+            out.push(ch);
+            col += ch.width().unwrap_or(0);
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// A text segment split into prose (rendered by `tui_markdown`), pipe tables
+/// (rendered by [`render_table`]) and preformatted lines (kept verbatim).
+enum TextPiece<'a> {
+    Prose(&'a str),
+    /// Raw table lines: header, delimiter row, then body rows.
+    Table(Vec<&'a str>),
+    /// A run of hand-aligned lines (see [`is_preformatted_line`]), without
+    /// their newlines.
+    Preformatted(Vec<&'a str>),
+    /// A `$$` math block, delimiters included, without the newlines.
+    Math(Vec<&'a str>),
+}
+
+/// Split out GitHub-style tables, runs of preformatted lines and, with
+/// `math`, `$$` math blocks. Only tables whose rows start with `|` are
+/// recognized, which matches what models emit and keeps detection cheap
+/// enough to run on every streamed commit. Lines are classified one at a
+/// time, so a box that is still streaming renders the same rows it will have
+/// once complete.
+fn split_text_pieces(text: &str, math: bool) -> Vec<TextPiece<'_>> {
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+    let mut pieces = Vec::new();
+    let mut prose_start = 0;
+    let mut offset = 0;
+    let mut i = 0;
+    while i < lines.len() {
+        let is_table_start = i + 1 < lines.len()
+            && is_table_row(lines[i])
+            && is_delimiter_row(lines[i + 1])
+            && split_table_cells(lines[i]).len() == split_table_cells(lines[i + 1]).len();
+        let is_math_start = math && !is_table_start && is_math_block_start(lines[i]);
+        let is_preformatted_start =
+            !is_table_start && !is_math_start && is_preformatted_line(lines[i]);
+        if !is_table_start && !is_math_start && !is_preformatted_start {
+            offset += lines[i].len();
+            i += 1;
+            continue;
+        }
+        if prose_start < offset {
+            pieces.push(TextPiece::Prose(&text[prose_start..offset]));
+        }
+        let mut rows = Vec::new();
+        if is_table_start {
+            while i < lines.len() && (rows.len() < 2 || is_table_row(lines[i])) {
+                rows.push(lines[i].trim_end_matches('\n'));
+                offset += lines[i].len();
+                i += 1;
+            }
+            pieces.push(TextPiece::Table(rows));
+        } else if is_math_start {
+            for line in &lines[i..i + math_block_len(&lines[i..])] {
+                rows.push(line.trim_end_matches('\n'));
+                offset += line.len();
+            }
+            i += rows.len();
+            pieces.push(TextPiece::Math(rows));
+        } else {
+            while i < lines.len() && is_preformatted_line(lines[i]) {
+                rows.push(lines[i].trim_end_matches('\n'));
+                offset += lines[i].len();
+                i += 1;
+            }
+            pieces.push(TextPiece::Preformatted(rows));
+        }
+        prose_start = offset;
+    }
+    if prose_start < text.len() {
+        pieces.push(TextPiece::Prose(&text[prose_start..]));
+    }
+    pieces
+}
+
+/// Whether `line` looks hand-aligned, like a row of box art or of a table
+/// drawn with `+`, `-` and `|`: it starts and ends with a frame character.
+/// Reflowing such a line breaks its alignment, so it is rendered verbatim
+/// and clipped to the width instead of wrapped, in prose and code alike.
+pub(crate) fn is_preformatted_line(line: &str) -> bool {
+    let mut chars = line.trim().chars();
+    match (chars.next(), chars.next_back()) {
+        (Some(first), Some(last)) => is_frame_char(first) && is_frame_char(last),
+        _ => false,
+    }
+}
+
+/// `+`, `|`, and the Unicode box drawing and block element characters.
+fn is_frame_char(c: char) -> bool {
+    matches!(c, '+' | '|' | '\u{2500}'..='\u{259F}')
+}
+
+fn is_table_row(line: &str) -> bool {
+    line.trim_start().starts_with('|')
+}
+
+fn is_delimiter_row(line: &str) -> bool {
+    is_table_row(line)
+        && split_table_cells(line).iter().all(|cell| {
+            let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+            !dashes.is_empty() && dashes.chars().all(|c| c == '-')
+        })
+}
+
+/// Cells of a table row, trimmed, without the outer pipes. An escaped `\|`
+/// stays in its cell.
+fn split_table_cells(line: &str) -> Vec<&str> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = match line.strip_suffix('|') {
+        Some(stripped) if !is_escaped(line, line.len() - 1) => stripped,
+        _ => line,
+    };
+    let mut cells = Vec::new();
+    let mut start = 0;
+    for (i, _) in line.match_indices('|') {
+        if !is_escaped(line, i) {
+            cells.push(line[start..i].trim());
+            start = i + 1;
+        }
+    }
+    cells.push(line[start..].trim());
+    cells
+}
+
+/// Render a table cell, which holds inline Markdown only, as one line. As
+/// in GitHub tables, `\|` is a pipe even inside inline code.
+fn render_table_cell(cell: &str) -> Line<'static> {
+    let cell = cell.replace("\\|", "|");
+    let cell = escape_block_start(&cell);
+    let rendered = tui_markdown::from_str(&cell);
+    let mut lines = Vec::new();
+    crate::render::line_utils::push_owned_lines(&rendered.lines, &mut lines);
+    Line::from(
+        lines
+            .into_iter()
+            .flat_map(|line| line.spans)
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Escape a marker at the start of `cell` that would make it a block, such
+/// as a heading, list item or rule, rather than inline text.
+fn escape_block_start(cell: &str) -> Cow<'_, str> {
+    let digits = cell.bytes().take_while(u8::is_ascii_digit).count();
+    let rest = &cell[digits..];
+    let mut chars = rest.chars();
+    let first = chars.next();
+    let marker_then_space = matches!(chars.next(), None | Some(' '));
+    match first {
+        Some('.' | ')') if digits > 0 && marker_then_space => {
+            Cow::Owned(format!("{}\\{rest}", &cell[..digits]))
+        }
+        _ if digits > 0 => Cow::Borrowed(cell),
+        Some('#' | '>') => Cow::Owned(format!("\\{cell}")),
+        Some(c @ ('-' | '+' | '*' | '_'))
+            if marker_then_space || rest.chars().all(|ch| ch == c || ch == ' ') =>
+        {
+            Cow::Owned(format!("\\{cell}"))
+        }
+        Some('~') if cell.starts_with("~~~") => Cow::Owned(format!("\\{cell}")),
+        Some('`') if cell.starts_with("```") && !cell.trim_start_matches('`').contains('`') => {
+            Cow::Owned(format!("\\{cell}"))
+        }
+        _ => Cow::Borrowed(cell),
     }
 }
 
+#[derive(Clone, Copy)]
+enum ColumnAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+/// Background of the striped table rows: `tui.table_stripe_color` when set,
+/// otherwise picked for the theme. Mono has none, like code blocks.
+fn table_stripe(config: &Config) -> Option<Color> {
+    if !config.tui.table_stripes {
+        return None;
+    }
+    config
+        .tui
+        .table_stripe_color
+        .as_deref()
+        .and_then(|name| name.parse::<Color>().ok())
+        .or(match config.tui.theme {
+            Theme::Dark => Some(Color::Indexed(236)),
+            Theme::Light => Some(Color::Indexed(254)),
+            Theme::Mono => None,
+        })
+}
+
+/// Render a table with every column padded to its widest cell. Widths are
+/// computed over all rows, so the table must be complete before rendering
+/// (the stream collector holds open tables back for this reason). With a
+/// `stripe`, the second, fourth, … body rows get it as their background.
+fn render_table(rows: &[&str], stripe: Option<Color>) -> Vec<Line<'static>> {
+    let render_cells = |row: &str| -> Vec<Line<'static>> {
+        split_table_cells(row)
+            .into_iter()
+            .map(render_table_cell)
+            .collect()
+    };
+    let header = render_cells(rows[0]);
+    let alignments: Vec<ColumnAlignment> = split_table_cells(rows[1])
+        .iter()
+        .map(|cell| match (cell.starts_with(':'), cell.ends_with(':')) {
+            (true, true) => ColumnAlignment::Center,
+            (false, true) => ColumnAlignment::Right,
+            _ => ColumnAlignment::Left,
+        })
+        .collect();
+    let body: Vec<Vec<Line<'static>>> = rows[2..].iter().map(|r| render_cells(r)).collect();
+
+    let mut widths: Vec<usize> = header.iter().map(|c| c.width()).collect();
+    for row in &body {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.width());
+        }
+    }
+
+    // `style` is patched onto every span, padding and separators included.
+    let format_row = |cells: &[Line<'static>], style: Style| -> Line<'static> {
+        let mut spans: Vec<Span<'static>> = Vec::new();
+        for (col, &width) in widths.iter().enumerate() {
+            if col > 0 {
+                spans.push(Span::from(" │ "));
+            }
+            let cell = cells.get(col);
+            let pad = width - cell.map_or(0, Line::width).min(width);
+            let (before, after) = match alignments[col] {
+                ColumnAlignment::Left => (0, pad),
+                ColumnAlignment::Right => (pad, 0),
+                ColumnAlignment::Center => (pad / 2, pad - pad / 2),
+            };
+            if before > 0 {
+                spans.push(Span::from(" ".repeat(before)));
+            }
+            if let Some(cell) = cell {
+                spans.extend(cell.spans.iter().cloned());
+            }
+            if after > 0 {
+                spans.push(Span::from(" ".repeat(after)));
+            }
+        }
+        Line::from(
+            spans
+                .into_iter()
+                .map(|span| span.patch_style(style))
+                .collect::<Vec<_>>(),
+        )
+    };
+
+    let mut out = vec![format_row(&header, Style::new().bold())];
+    let separator = widths
+        .iter()
+        .map(|w| "─".repeat(*w))
+        .collect::<Vec<_>>()
+        .join("─┼─");
+    out.push(Line::from(separator.dim()));
+    out.extend(body.iter().enumerate().map(|(i, row)| {
+        // On the span rather than the line, so the stripe ends with the
+        // table's last column instead of running to the edge of the screen.
+        match stripe {
+            Some(bg) if i % 2 == 1 => format_row(row, Style::new().bg(bg)),
+            _ => format_row(row, Style::new()),
+        }
+    }));
+    out
+}
+
 /// Rewrites file citations in `src` into markdown hyperlinks using the
 /// provided `scheme` (`vscode`, `cursor`, etc.). The resulting URI follows the
 /// format expected by VS Code-compatible file openers:
@@ -101,16 +925,18 @@ fn rewrite_file_citations<'a>(
 // use shared helper from `line_utils`
 
 // Minimal code block splitting.
-// - Recognizes fenced blocks opened by ``` or ~~~ (allowing leading whitespace).
-//   The opening fence may include a language string which we ignore.
-//   The closing fence must be on its own line (ignoring surrounding whitespace).
+// - Recognizes fenced blocks opened by three or more backticks or tildes
+//   (allowing leading whitespace), optionally followed by a language string.
+//   The closing fence must be on its own line (ignoring surrounding whitespace)
+//   and use the same character, at least as many times, so a ```` block can
+//   contain ``` lines.
 // - Additionally recognizes indented code blocks that begin after a blank line
 //   with a line starting with at least 4 spaces or a tab, and continue for
 //   consecutive lines that are blank or also indented by >= 4 spaces or a tab.
 enum Segment {
     Text(String),
     Code {
-        _lang: Option<String>,
+        lang: Option<String>,
         content: String,
     },
 }
@@ -125,7 +951,7 @@ fn split_text_and_fences(src: &str) -> Vec<Segment> {
         Indented,
     }
     let mut code_mode = CodeMode::None;
-    let mut fence_token = "";
+    let mut fence: Option<Fence> = None;
     let mut code_lang: Option<String> = None;
     let mut code_content = String::new();
     // We intentionally do not require a preceding blank line for indented code blocks,
@@ -137,114 +963,94 @@ fn split_text_and_fences(src: &str) -> Vec<Segment> {
             Some(l) => l.trim_start(),
             None => line.trim_start(),
         };
-        if code_mode == CodeMode::None {
-            let open = if trimmed_start.starts_with("```") {
-                Some("```")
-            } else if trimmed_start.starts_with("~~~") {
-                Some("~~~")
-            } else {
-                None
-            };
-            if let Some(tok) = open {
-                // Flush pending text segment.
-                if !curr_text.is_empty() {
-                    segments.push(Segment::Text(curr_text.clone()));
-                    curr_text.clear();
-                }
-                fence_token = tok;
-                // Capture language after the token on this line (before newline).
-                let after = &trimmed_start[tok.len()..];
-                let lang = after.trim();
-                code_lang = if lang.is_empty() {
-                    None
-                } else {
-                    Some(lang.to_string())
-                };
-                code_mode = CodeMode::Fenced;
-                code_content.clear();
-                // Do not include the opening fence line in output.
-                continue;
-            }
-            // Check for start of an indented code block: only after a blank line
-            // (or at the beginning), and the line must start with >=4 spaces or a tab.
-            let raw_line = match line_no_nl {
-                Some(l) => l,
-                None => line,
-            };
-            let leading_spaces = raw_line.chars().take_while(|c| *c == ' ').count();
-            let starts_with_tab = raw_line.starts_with('\t');
-            // Consider any line that begins with >=4 spaces or a tab to start an
-            // indented code block. This favors preserving indentation even when a
-            // preceding blank line is omitted (common in streamed model output).
-            let starts_indented_code = (leading_spaces >= 4) || starts_with_tab;
-            if starts_indented_code {
-                // Flush pending text and begin an indented code block.
-                if !curr_text.is_empty() {
-                    segments.push(Segment::Text(curr_text.clone()));
-                    curr_text.clear();
+        let raw_line = match line_no_nl {
+            Some(l) => l,
+            None => line,
+        };
+        let leading_spaces = raw_line.chars().take_while(|c| *c == ' ').count();
+        let starts_with_tab = raw_line.starts_with('\t');
+        match code_mode {
+            CodeMode::Fenced => {
+                // inside fenced code: check for closing fence on its own line
+                if fence.is_some_and(|open| open.is_closed_by(line)) {
+                    // End code block: emit segment without fences
+                    segments.push(Segment::Code {
+                        lang: code_lang.take(),
+                        content: code_content.clone(),
+                    });
+                    code_content.clear();
+                    code_mode = CodeMode::None;
+                    fence = None;
+                    continue;
                 }
-                code_mode = CodeMode::Indented;
-                code_content.clear();
+                // Accumulate code content exactly as-is.
                 code_content.push_str(line);
-                // Inside code now; do not treat this line as normal text.
                 continue;
             }
-            // Normal text line.
-            curr_text.push_str(line);
-        } else {
-            match code_mode {
-                CodeMode::Fenced => {
-                    // inside fenced code: check for closing fence on its own line
-                    let trimmed = match line_no_nl {
-                        Some(l) => l.trim(),
-                        None => line.trim(),
-                    };
-                    if trimmed == fence_token {
-                        // End code block: emit segment without fences
-                        segments.push(Segment::Code {
-                            _lang: code_lang.take(),
-                            content: code_content.clone(),
-                        });
-                        code_content.clear();
-                        code_mode = CodeMode::None;
-                        fence_token = "";
-                        continue;
-                    }
-                    // Accumulate code content exactly as-is.
+            CodeMode::Indented => {
+                // Continue while the line is blank, or starts with >=4 spaces, or a tab,
+                // unless it opens a fence.
+                let is_blank = raw_line.trim().is_empty();
+                if (is_blank || leading_spaces >= 4 || starts_with_tab)
+                    && Fence::open(trimmed_start).is_none()
+                {
                     code_content.push_str(line);
+                    continue;
                 }
-                CodeMode::Indented => {
-                    // Continue while the line is blank, or starts with >=4 spaces, or a tab.
-                    let raw_line = match line_no_nl {
-                        Some(l) => l,
-                        None => line,
-                    };
-                    let is_blank = raw_line.trim().is_empty();
-                    let leading_spaces = raw_line.chars().take_while(|c| *c == ' ').count();
-                    let starts_with_tab = raw_line.starts_with('\t');
-                    if is_blank || leading_spaces >= 4 || starts_with_tab {
-                        code_content.push_str(line);
-                    } else {
-                        // Close the indented code block and reprocess this line as normal text.
-                        segments.push(Segment::Code {
-                            _lang: None,
-                            content: code_content.clone(),
-                        });
-                        code_content.clear();
-                        code_mode = CodeMode::None;
-                        // Now handle current line as text.
-                        curr_text.push_str(line);
-                    }
-                }
-                CodeMode::None => unreachable!(),
+                // Close the indented code block and handle this line like any
+                // line outside code: a fence opened right after the block is
+                // a fence, as `is_inside_unclosed_fence` (which holds streamed
+                // lines back) sees it, not prose.
+                segments.push(Segment::Code {
+                    lang: None,
+                    content: code_content.clone(),
+                });
+                code_content.clear();
+                code_mode = CodeMode::None;
             }
+            CodeMode::None => {}
         }
+        if let Some((open, lang)) = Fence::open(trimmed_start) {
+            // Flush pending text segment.
+            if !curr_text.is_empty() {
+                segments.push(Segment::Text(curr_text.clone()));
+                curr_text.clear();
+            }
+            fence = Some(open);
+            code_lang = if lang.is_empty() {
+                None
+            } else {
+                Some(lang.to_string())
+            };
+            code_mode = CodeMode::Fenced;
+            code_content.clear();
+            // Do not include the opening fence line in output.
+            continue;
+        }
+        // Consider any line that begins with >=4 spaces or a tab to start an
+        // indented code block. This favors preserving indentation even when a
+        // preceding blank line is omitted (common in streamed model output).
+        let starts_indented_code = (leading_spaces >= 4) || starts_with_tab;
+        if starts_indented_code {
+            // Flush pending text and begin an indented code block.
+            if !curr_text.is_empty() {
+                segments.push(Segment::Text(curr_text.clone()));
+                curr_text.clear();
+            }
+            code_mode = CodeMode::Indented;
+            code_content.clear();
+            code_content.push_str(line);
+            // Inside code now; do not treat this line as normal text.
+            continue;
+        }
+        // Normal text line.
+        curr_text.push_str(line);
     }
 
     if code_mode != CodeMode::None {
         // Unterminated code fence: treat accumulated content as a code segment.
         segments.push(Segment::Code {
-            _lang: code_lang.take(),
+            lang: code_lang.take(),
             content: code_content.clone(),
         });
     } else if !curr_text.is_empty() {
@@ -435,4 +1241,349 @@ mod tests {
             "Hi! How can I help with codex-rs today? Want me to explore the repo, run tests, or work on a specific change?"
         );
     }
+
+    #[test]
+    fn code_block_tabs_expand_to_tab_stops() {
+        let options = CodeBlockOptions {
+            tab_width: 4,
+            ..CodeBlockOptions::default()
+        };
+        let line = render_code_line("\tx\ty", options);
+        assert_eq!(line.spans[0].content, "    x   y");
+
+        let keep = CodeBlockOptions {
+            tab_width: 0,
+            ..options
+        };
+        assert_eq!(render_code_line("\tx", keep).spans[0].content, "\tx");
+    }
+
+    #[test]
+    fn code_block_trailing_whitespace_is_visualized() {
+        let options = CodeBlockOptions {
+            tab_width: 4,
+            show_trailing_whitespace: true,
+            ..CodeBlockOptions::default()
+        };
+        let line = render_code_line("let x = 1;  ", options);
+        let text: Vec<&str> = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, vec!["let x = 1;", "··"]);
+    }
+
+    #[test]
+    fn blank_line_runs_collapse_to_the_limit() {
+        let mut lines: Vec<Line<'static>> = ["a", "", "", "", "b", "", "c"]
+            .into_iter()
+            .map(Line::from)
+            .collect();
+        collapse_blank_runs(&mut lines, 0, 1);
+        let text: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        assert_eq!(text, vec!["a", "", "b", "", "c"]);
+
+        // A blank line before `start` counts toward the run.
+        let mut lines: Vec<Line<'static>> =
+            ["a", "", "", "b"].into_iter().map(Line::from).collect();
+        collapse_blank_runs(&mut lines, 2, 1);
+        let text: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        assert_eq!(text, vec!["a", "", "b"]);
+    }
+
+    #[test]
+    fn repeated_lines_over_the_limit_fold_into_one() {
+        let src = format!("Checking.\n\n{}Done.\n", "still looping\n".repeat(50));
+        let mut out = Vec::new();
+        append_markdown_with_options(
+            &src,
+            &mut out,
+            UriBasedFileOpener::None,
+            Path::new("/"),
+            CodeBlockOptions::default(),
+            TextOptions {
+                max_repeated_lines: Some(3),
+                ..TextOptions::default()
+            },
+        );
+        let text: Vec<String> = out.iter().map(|l| l.to_string()).collect();
+        assert_eq!(text, vec!["Checking.", "", "still looping ⟲ ×50", "Done."]);
+
+        // Runs within the limit are left alone.
+        let mut lines: Vec<Line<'static>> = ["a", "b", "b", "b", "c"]
+            .into_iter()
+            .map(Line::from)
+            .collect();
+        collapse_repeated_runs(&mut lines, 0, 3);
+        let text: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        assert_eq!(text, vec!["a", "b", "b", "b", "c"]);
+    }
+
+    #[test]
+    fn reference_links_are_numbered_per_url_in_order() {
+        let src = "See [the guide](https://example.com/guide) and [the API](https://example.com/api).\n\n```\n[kept](https://example.com/code)\n```\n\nThe [guide](https://example.com/guide) again, `[raw](x)` and ![logo](https://example.com/logo.png).\n";
+        let mut out = Vec::new();
+        append_markdown_with_options(
+            src,
+            &mut out,
+            UriBasedFileOpener::None,
+            Path::new("/"),
+            CodeBlockOptions::default(),
+            TextOptions {
+                reference_links: true,
+                ..TextOptions::default()
+            },
+        );
+        let text: Vec<String> = out.iter().map(|l| l.to_string()).collect();
+        assert_eq!(text[0], "See the guide[1] and the API[2].");
+        assert!(text.contains(&"[kept](https://example.com/code)".to_string()));
+        assert!(
+            text.last()
+                .is_some_and(|line| line.starts_with("The guide[1] again, [raw](x) and")),
+            "{text:?}"
+        );
+
+        // Numbering continues from the URLs of earlier prose pieces.
+        let mut urls = vec!["https://a.example".to_string()];
+        let numbered = number_links(
+            "[b](https://b.example) then [a](https://a.example)",
+            &mut urls,
+        );
+        assert_eq!(numbered, "b\\[2\\] then a\\[1\\]");
+        assert_eq!(urls, vec!["https://a.example", "https://b.example"]);
+    }
+
+    #[test]
+    fn show_escapes_keeps_backslashes_outside_code_and_escaped_links() {
+        let src = "Match \\*.rs in `C:\\tmp`, see \\[docs\\](https://example.com).\n";
+        let render = |text_options| {
+            let mut out = Vec::new();
+            append_markdown_with_options(
+                src,
+                &mut out,
+                UriBasedFileOpener::None,
+                Path::new("/"),
+                CodeBlockOptions::default(),
+                text_options,
+            );
+            out.iter().map(|l| l.to_string()).collect::<Vec<_>>()
+        };
+        let reference_links = TextOptions {
+            reference_links: true,
+            ..TextOptions::default()
+        };
+        assert_eq!(
+            render(reference_links),
+            vec!["Match *.rs in C:\\tmp, see [docs](https://example.com)."]
+        );
+        assert_eq!(
+            render(TextOptions {
+                show_escapes: true,
+                ..reference_links
+            }),
+            vec!["Match \\*.rs in C:\\tmp, see \\[docs\\](https://example.com)."]
+        );
+    }
+
+    #[test]
+    fn pipe_tables_render_with_aligned_columns() {
+        let src = "Results:\n| name | count |\n|------|------:|\n| a | 1 |\n| longer name | 20 |\n\nDone.\n";
+        let mut out = Vec::new();
+        append_markdown_with_opener_and_cwd(
+            src,
+            &mut out,
+            UriBasedFileOpener::None,
+            Path::new("/"),
+        );
+        let rendered: Vec<String> = out
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert_eq!(
+            rendered,
+            vec![
+                "Results:",
+                "name        │ count",
+                "────────────┼──────",
+                "a           │     1",
+                "longer name │    20",
+                "",
+                "Done.",
+            ]
+        );
+    }
+
+    #[test]
+    fn striped_tables_shade_every_other_body_row_only() {
+        let src = "| name | count |\n|------|------:|\n| a | 1 |\n| b | 2 |\n| c | 3 |\n\nDone.\n";
+        let stripe = Color::Indexed(236);
+        let mut out = Vec::new();
+        append_markdown_with_options(
+            src,
+            &mut out,
+            UriBasedFileOpener::None,
+            Path::new("/"),
+            CodeBlockOptions::default(),
+            TextOptions {
+                table_stripe: Some(stripe),
+                ..TextOptions::default()
+            },
+        );
+        // Each row's spans share one background, or the line has none.
+        let backgrounds: Vec<Option<Option<Color>>> = out
+            .iter()
+            .map(|l| {
+                assert_eq!(l.style.bg, None);
+                let bg = l.spans.first().map(|s| s.style.bg);
+                assert!(l.spans.iter().all(|s| Some(s.style.bg) == bg), "{l:?}");
+                bg
+            })
+            .collect();
+        assert_eq!(
+            backgrounds,
+            vec![
+                Some(None),
+                Some(None),
+                Some(None),
+                Some(Some(stripe)),
+                Some(None),
+                None,
+                Some(None),
+            ]
+        );
+        assert_eq!(out[3].to_string(), "b    │     2");
+    }
+
+    #[test]
+    fn table_cells_render_inline_markdown_and_keep_escaped_pipes() {
+        let src = "| op | meaning |\n|----|---------|\n| `a \\| b` | **either** |\n| 1. | a \\| b &amp; c |\n";
+        let mut out = Vec::new();
+        append_markdown_with_opener_and_cwd(
+            src,
+            &mut out,
+            UriBasedFileOpener::None,
+            Path::new("/"),
+        );
+        let rendered: Vec<String> = out.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            rendered,
+            vec![
+                "op    │ meaning  ",
+                "──────┼──────────",
+                "a | b │ either   ",
+                "1.    │ a | b & c",
+            ]
+        );
+        assert!(out[2].spans.iter().any(|s| {
+            s.content == "either"
+                && s.style
+                    .add_modifier
+                    .contains(ratatui::style::Modifier::BOLD)
+        }));
+    }
+
+    #[test]
+    fn box_art_renders_verbatim_between_prose() {
+        let src = "Layout:\n\n+-----+\n| a   b |\n+-----+\n\nDone.\n";
+        let mut out = Vec::new();
+        append_markdown_with_opener_and_cwd(
+            src,
+            &mut out,
+            UriBasedFileOpener::None,
+            Path::new("/"),
+        );
+        let rendered: Vec<String> = out
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert_eq!(
+            rendered,
+            vec![
+                "Layout:",
+                "",
+                "+-----+",
+                "| a   b |",
+                "+-----+",
+                "",
+                "Done."
+            ]
+        );
+        assert!(is_preformatted_line("└──┴──┘"));
+        assert!(!is_preformatted_line("| not a frame"));
+        assert!(!is_preformatted_line("|"));
+    }
+
+    #[test]
+    fn full_justify_pads_inner_spaces_to_the_wrap_column() {
+        let src = "The quick brown fox jumps over the lazy dog and keeps running far away.\n\n```\na b\n```\n";
+        let mut out = Vec::new();
+        append_markdown_with_options(
+            src,
+            &mut out,
+            UriBasedFileOpener::None,
+            Path::new("/"),
+            CodeBlockOptions::default(),
+            TextOptions {
+                justify_width: Some(20),
+                ..TextOptions::default()
+            },
+        );
+        let rendered: Vec<String> = out
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        // Every row but the paragraph's last reaches column 20.
+        assert_eq!(
+            rendered[..4],
+            [
+                "The    quick   brown",
+                "fox  jumps  over the",
+                "lazy  dog  and keeps",
+                "running far away.",
+            ]
+        );
+        // The code block keeps its single space.
+        assert_eq!(rendered.last().map(String::as_str), Some("a b"));
+    }
+
+    #[test]
+    fn registered_fence_renderer_replaces_the_code_block() {
+        use std::sync::Arc;
+        use std::sync::atomic::AtomicUsize;
+        use std::sync::atomic::Ordering;
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let seen = Arc::clone(&calls);
+        crate::fence_renderer::register_fence_renderer(
+            "json",
+            Box::new(move |content| {
+                seen.fetch_add(1, Ordering::SeqCst);
+                vec![Line::from(format!(
+                    "json with {} lines",
+                    content.lines().count()
+                ))]
+            }),
+        );
+
+        let src = "Config:\n\n```JSON\n{\n  \"a\": 1\n}\n```\n\n```toml\na = 1\n```\n";
+        let mut out = Vec::new();
+        append_markdown_with_opener_and_cwd(
+            src,
+            &mut out,
+            UriBasedFileOpener::None,
+            Path::new("/"),
+        );
+        let rendered: Vec<String> = out
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(
+            rendered.contains(&"json with 3 lines".to_string()),
+            "{rendered:?}"
+        );
+        assert!(
+            !rendered.iter().any(|l| l.contains("\"a\"")),
+            "{rendered:?}"
+        );
+        // Languages without a renderer still render as plain code.
+        assert!(rendered.contains(&"a = 1".to_string()), "{rendered:?}");
+    }
 }
@@ -4,6 +4,8 @@ use codex_core::config::Config;
 use ratatui::text::Line;
 
 use crate::markdown;
+use crate::render::markdown_utils::close_unclosed_fence;
+use crate::render::markdown_utils::is_inside_open_table;
 use crate::render::markdown_utils::is_inside_unclosed_fence;
 use crate::render::markdown_utils::strip_empty_fenced_code_blocks;
 
@@ -28,6 +30,11 @@ impl MarkdownStreamCollector {
         self.committed_line_count
     }
 
+    /// The markdown source received since the stream began.
+    pub fn source(&self) -> &str {
+        &self.buffer
+    }
+
     pub fn clear(&mut self) {
         self.buffer.clear();
         self.committed_line_count = 0;
@@ -90,6 +97,13 @@ impl MarkdownStreamCollector {
             }
         }
 
+        // With repeated lines folded, the newest lines may still fold into
+        // the ones that follow, so hold back the trailing run of identical
+        // lines (even a run of one) until a different line arrives.
+        if config.tui.collapse_repeated_lines.is_some() {
+            complete_line_count = trailing_run_start(&rendered[..complete_line_count]);
+        }
+
         if self.committed_line_count >= complete_line_count {
             return Vec::new();
         }
@@ -101,6 +115,12 @@ impl MarkdownStreamCollector {
         if is_inside_unclosed_fence(&source) {
             return Vec::new();
         }
+        // Likewise hold back a table until a line that is not a row follows
+        // it, so column widths are computed once over every row instead of
+        // jumping as rows arrive.
+        if is_inside_open_table(&source) {
+            return Vec::new();
+        }
 
         let out = out_slice.to_vec();
         self.committed_line_count = complete_line_count;
@@ -109,7 +129,7 @@ impl MarkdownStreamCollector {
 
     /// Finalize the stream: emit all remaining lines beyond the last commit.
     /// If the buffer does not end with a newline, a temporary one is appended
-    /// for rendering. Optionally unwraps ```markdown language fences in
+    /// for rendering, and a fenced code block left open is closed. Optionally unwraps ```markdown language fences in
     /// non-test builds.
     pub fn finalize_and_drain(&mut self, config: &Config) -> Vec<Line<'static>> {
         let mut source: String = self.buffer.clone();
@@ -117,6 +137,9 @@ impl MarkdownStreamCollector {
             source.push('\n');
         }
         let source = unwrap_markdown_language_fence_if_enabled(source);
+        // The stream is complete, so a fence still open will not be closed;
+        // end the code block here (and drop it if it is empty).
+        let source = close_unclosed_fence(&source);
         let source = strip_empty_fenced_code_blocks(&source);
 
         let mut rendered: Vec<Line<'static>> = Vec::new();
@@ -134,6 +157,24 @@ impl MarkdownStreamCollector {
     }
 }
 
+/// Index of the first line of the run of identical lines that ends `lines`.
+/// A blank last line ends no run.
+fn trailing_run_start(lines: &[Line<'static>]) -> usize {
+    let Some(last) = lines.last() else {
+        return 0;
+    };
+    if crate::render::line_utils::is_blank_line_trim(last) {
+        return lines.len();
+    }
+    let text = last.to_string();
+    let run = lines
+        .iter()
+        .rev()
+        .take_while(|line| line.to_string() == text)
+        .count();
+    lines.len() - run
+}
+
 /// fence helpers are provided by `crate::render::markdown_utils`
 #[cfg(test)]
 fn unwrap_markdown_language_fence_if_enabled(s: String) -> String {
@@ -153,11 +194,12 @@ fn unwrap_markdown_language_fence_if_enabled(s: String) -> String {
     }
 
     // Identify opening fence and language.
-    let open = lines.first().map(|l| l.trim_start()).unwrap_or("");
-    if !open.starts_with("```") {
+    let Some((fence, lang)) = lines
+        .first()
+        .and_then(|l| crate::render::markdown_utils::Fence::open(l))
+    else {
         return s;
-    }
-    let lang = open.trim_start_matches("```").trim();
+    };
     let is_markdown_lang = lang.eq_ignore_ascii_case("markdown") || lang.eq_ignore_ascii_case("md");
     if !is_markdown_lang {
         return s;
@@ -168,7 +210,7 @@ fn unwrap_markdown_language_fence_if_enabled(s: String) -> String {
     while last_idx > 0 && lines[last_idx].trim().is_empty() {
         last_idx -= 1;
     }
-    if lines[last_idx].trim() != "```" {
+    if !fence.is_closed_by(lines[last_idx]) {
         return s;
     }
 
@@ -444,6 +486,26 @@ mod tests {
             .collect()
     }
 
+    #[test]
+    fn fifty_identical_streamed_lines_fold_into_one_marker() {
+        let mut cfg = test_config();
+        cfg.tui.collapse_repeated_lines = Some(3);
+        let mut deltas = vec!["Retrying the build.\n", "\n"];
+        deltas.extend(std::iter::repeat_n("error: linker failed\n", 50));
+        deltas.push("Giving up.\n");
+
+        let streamed = simulate_stream_markdown_for_tests(&deltas, true, &cfg);
+        assert_eq!(
+            lines_to_plain_strings(&streamed),
+            vec![
+                "Retrying the build.",
+                "",
+                "error: linker failed ⟲ ×50",
+                "Giving up.",
+            ]
+        );
+    }
+
     #[test]
     fn lists_and_fences_commit_without_duplication() {
         let cfg = test_config();
@@ -477,6 +539,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fence_opened_right_after_indented_code_is_styled_as_code() {
+        let mut cfg = test_config();
+        cfg.tui.code_block_style = codex_core::config_types::CodeBlockStyle::Border;
+        let deltas = vec![
+            "Example:\n",
+            "    let x = 1;\n",
+            "```rust\n",
+            "fn main() {}\n",
+            "```\n",
+            "Done.\n",
+        ];
+        let streamed =
+            lines_to_plain_strings(&simulate_stream_markdown_for_tests(&deltas, true, &cfg));
+
+        let mut rendered_all: Vec<ratatui::text::Line<'static>> = Vec::new();
+        crate::markdown::append_markdown(&deltas.concat(), &mut rendered_all, &cfg);
+        assert_eq!(streamed, lines_to_plain_strings(&rendered_all));
+
+        let is_code = |text: &str| {
+            let line = streamed.iter().find(|l| l.contains(text));
+            line.is_some_and(|l| l.starts_with(crate::markdown::CODE_BLOCK_BORDER))
+        };
+        assert!(is_code("let x = 1;"), "{streamed:?}");
+        assert!(is_code("fn main() {}"), "{streamed:?}");
+        assert!(streamed.contains(&"Example:".to_string()), "{streamed:?}");
+        assert!(streamed.contains(&"Done.".to_string()), "{streamed:?}");
+        assert!(!streamed.iter().any(|l| l.contains("```")), "{streamed:?}");
+    }
+
     #[test]
     fn utf8_boundary_safety_and_wide_chars() {
         let cfg = test_config();
@@ -546,4 +638,37 @@ mod tests {
             "heading should not merge with paragraph: {texts:?}"
         );
     }
+
+    #[test]
+    fn streamed_table_is_committed_once_with_stable_alignment() {
+        let cfg = test_config();
+        let rows = [
+            "| file | lines |\n",
+            "|------|------:|\n",
+            "| a.rs | 3 |\n",
+            "| much_longer_name.rs | 1200 |\n",
+        ];
+        let mut c = super::MarkdownStreamCollector::new();
+        for row in rows {
+            c.push_delta(row);
+            assert!(
+                c.commit_complete_lines(&cfg).is_empty(),
+                "open table must not be committed row by row"
+            );
+        }
+
+        c.push_delta("\nAll done.\n");
+        let committed = lines_to_plain_strings(&c.commit_complete_lines(&cfg));
+        assert_eq!(
+            committed,
+            vec![
+                "file                │ lines",
+                "────────────────────┼──────",
+                "a.rs                │     3",
+                "much_longer_name.rs │  1200",
+                "",
+                "All done.",
+            ]
+        );
+    }
 }
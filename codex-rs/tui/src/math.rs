@@ -0,0 +1,178 @@
+//! TeX math in agent output, `$…$` inline and `$$…$$` as a block.
+//!
+//! Terminals cannot typeset math, so it is shown as its source in a style of
+//! its own. Inline math is swapped for placeholders before the prose goes
+//! through `tui_markdown`, which would otherwise read `_` and `*` in it as
+//! emphasis, and put back once the prose is rendered.
+
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+
+/// Delimits the index of a region in a placeholder. Private-use characters
+/// are left alone by the markdown renderer and do not occur in model output.
+const PLACEHOLDER_START: char = '\u{E000}';
+const PLACEHOLDER_END: char = '\u{E001}';
+
+pub(crate) fn math_style() -> Style {
+    Style::default()
+        .fg(Color::Magenta)
+        .add_modifier(Modifier::ITALIC)
+}
+
+/// Whether `line` opens a `$$` math block.
+pub(crate) fn is_math_block_start(line: &str) -> bool {
+    line.trim_start().starts_with("$$")
+}
+
+/// Number of lines of the math block opening `lines`, through the line that
+/// closes it with `$$`. A block that is not closed yet runs to the end, so a
+/// block still streaming renders the same rows it will have once complete.
+pub(crate) fn math_block_len(lines: &[&str]) -> usize {
+    let Some(first) = lines.first() else {
+        return 0;
+    };
+    let first = first.trim();
+    if first.len() >= 4 && first.ends_with("$$") {
+        return 1;
+    }
+    lines
+        .iter()
+        .skip(1)
+        .position(|line| line.trim_end().ends_with("$$"))
+        .map_or(lines.len(), |closing| closing + 2)
+}
+
+/// Replace every inline math region of `prose` with a placeholder. Returns
+/// the rewritten prose and the source of each region, `$` included, in order.
+pub(crate) fn protect_inline_math(prose: &str) -> (String, Vec<String>) {
+    let mut out = String::with_capacity(prose.len());
+    let mut regions = Vec::new();
+    for line in prose.split_inclusive('\n') {
+        let mut pos = 0;
+        for range in inline_math_ranges(line) {
+            out.push_str(&line[pos..range.start]);
+            out.push(PLACEHOLDER_START);
+            out.push_str(&regions.len().to_string());
+            out.push(PLACEHOLDER_END);
+            regions.push(line[range.clone()].to_string());
+            pos = range.end;
+        }
+        out.push_str(&line[pos..]);
+    }
+    (out, regions)
+}
+
+/// Put the regions taken out by [`protect_inline_math`] back into the
+/// rendered `lines`, styled as math.
+pub(crate) fn restore_inline_math(lines: &mut [Line<'static>], regions: &[String]) {
+    if regions.is_empty() {
+        return;
+    }
+    for line in lines {
+        let spans = std::mem::take(&mut line.spans);
+        for span in spans {
+            let mut rest: &str = &span.content;
+            while let Some(start) = rest.find(PLACEHOLDER_START) {
+                let Some(len) = rest[start..].find(PLACEHOLDER_END) else {
+                    break;
+                };
+                let index = &rest[start + PLACEHOLDER_START.len_utf8()..start + len];
+                let Some(region) = index.parse::<usize>().ok().and_then(|i| regions.get(i)) else {
+                    break;
+                };
+                if start > 0 {
+                    line.spans
+                        .push(Span::styled(rest[..start].to_string(), span.style));
+                }
+                line.spans
+                    .push(Span::styled(region.clone(), span.style.patch(math_style())));
+                rest = &rest[start + len + PLACEHOLDER_END.len_utf8()..];
+            }
+            if !rest.is_empty() {
+                line.spans.push(Span::styled(rest.to_string(), span.style));
+            }
+        }
+    }
+}
+
+/// Byte ranges of the `$…$` regions of `line`, outside inline code. Like
+/// pandoc, the opening `$` must be followed and the closing `$` preceded by
+/// a non-space, and the closing `$` must not be followed by a digit, so
+/// prices such as "$5 and $10" are not math.
+fn inline_math_ranges(line: &str) -> Vec<std::ops::Range<usize>> {
+    let bytes = line.as_bytes();
+    let mut ranges = Vec::new();
+    let mut in_code = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'`' => in_code = !in_code,
+            b'\\' if !in_code => i += 1,
+            b'$' if !in_code => {
+                if let Some(end) = closing_dollar(bytes, i) {
+                    ranges.push(i..end + 1);
+                    i = end;
+                } else if bytes.get(i + 1) == Some(&b'$') {
+                    i += 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    ranges
+}
+
+/// Index of the `$` closing inline math opened at `open`, if any.
+fn closing_dollar(bytes: &[u8], open: usize) -> Option<usize> {
+    let first = *bytes.get(open + 1)?;
+    if first == b'$' || first.is_ascii_whitespace() {
+        return None;
+    }
+    let mut i = open + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'\n' => return None,
+            b'$' => {
+                let closes = !bytes[i - 1].is_ascii_whitespace()
+                    && !bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+                return closes.then_some(i);
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regions(line: &str) -> Vec<&str> {
+        inline_math_ranges(line)
+            .into_iter()
+            .map(|range| &line[range])
+            .collect()
+    }
+
+    #[test]
+    fn inline_math_needs_tight_dollars_and_skips_prices_and_code() {
+        assert_eq!(regions("so $x_1 + x_2$ holds"), vec!["$x_1 + x_2$"]);
+        assert_eq!(regions("costs $5 and $10 today"), Vec::<&str>::new());
+        assert_eq!(regions("run `echo $HOME$x` now"), Vec::<&str>::new());
+        assert_eq!(regions("an escaped \\$x$ sign"), Vec::<&str>::new());
+        assert_eq!(regions("$$x$$ is a block"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn math_block_runs_to_its_closing_line() {
+        assert_eq!(math_block_len(&["$$\n", "a^2\n", "$$\n", "after\n"]), 3);
+        assert_eq!(math_block_len(&["$$ a^2 $$\n", "after\n"]), 1);
+        assert_eq!(math_block_len(&["$$\n", "a^2\n"]), 2);
+    }
+}
@@ -0,0 +1,100 @@
+//! Plain-text mirror of the conversation (`tui.plain_log_path`).
+//!
+//! Unlike the JSONL session log, this is meant to be read (or `tail -f`ed)
+//! by a person: each completed turn is appended as its canonical text under
+//! simple headers.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::transcript::TurnRecord;
+
+pub(crate) struct PlainLog {
+    path: PathBuf,
+    /// Number of turns already appended to the file.
+    written: usize,
+    /// The last written turn as it was when written, to tell what it gained
+    /// since, e.g. an answer continued with `/continue`.
+    last_written: Option<TurnRecord>,
+}
+
+impl PlainLog {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            written: 0,
+            last_written: None,
+        }
+    }
+
+    /// Append the turns that have not been written yet, and whatever the
+    /// last written turn gained since it was written. Turns are written when
+    /// the task that produced them ends.
+    pub(crate) fn append_new_turns(&mut self, turns: &[TurnRecord]) -> std::io::Result<()> {
+        let mut text = String::new();
+        if let (Some(before), Some(after)) = (
+            &self.last_written,
+            self.written.checked_sub(1).and_then(|i| turns.get(i)),
+        ) {
+            text.push_str(&turn_update_to_plain_text(before, after));
+        }
+        let new = turns.get(self.written..).unwrap_or_default();
+        text.extend(new.iter().map(turn_to_plain_text));
+        if text.is_empty() {
+            return Ok(());
+        }
+        append_to_file(&self.path, &text)?;
+        self.written = turns.len();
+        self.last_written = turns.last().cloned();
+        Ok(())
+    }
+}
+
+/// Append `text` to the file at `path`, creating it and its directory if
+/// needed.
+pub(crate) fn append_to_file(path: &Path, text: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(text.as_bytes())
+}
+
+fn turn_to_plain_text(turn: &TurnRecord) -> String {
+    sections(turn)
+        .into_iter()
+        .filter(|(_, body)| !body.is_empty())
+        .map(|(title, body)| section(title, body))
+        .collect()
+}
+
+/// What `after` added to the turn written as `before`: the new tail of each
+/// section that grew, or the whole section when it was rewritten.
+fn turn_update_to_plain_text(before: &TurnRecord, after: &TurnRecord) -> String {
+    sections(before)
+        .into_iter()
+        .zip(sections(after))
+        .filter(|((_, old), (_, new))| old != new && !new.is_empty())
+        .map(
+            |((_, old), (title, new))| match new.strip_prefix(old.as_str()) {
+                Some(tail) => section(&format!("{title} (continued)"), tail),
+                None => section(&format!("{title} (revised)"), new),
+            },
+        )
+        .collect()
+}
+
+fn sections(turn: &TurnRecord) -> [(&'static str, &String); 4] {
+    [
+        ("context compacted", &turn.compaction),
+        ("user", &turn.user),
+        ("thinking", &turn.reasoning),
+        ("codex", &turn.answer),
+    ]
+}
+
+fn section(title: &str, body: &str) -> String {
+    format!("== {title} ==\n{}\n\n", body.trim_end_matches('\n'))
+}
@@ -0,0 +1,167 @@
+//! `tui.max_reasoning_lines` for the terminal scrollback.
+//!
+//! The transcript view hides the earlier lines of a capped reasoning block
+//! as it grows, but lines written to the terminal cannot be taken back. So
+//! with a cap, a reasoning block is held while it streams and written once it
+//! ends: its header, a marker for the lines left out, and its last lines.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use codex_core::config_types::HeaderGlyphs;
+use codex_core::config_types::Theme;
+use ratatui::text::Line;
+
+use crate::render::line_utils::is_blank_line_trim;
+use crate::scrollback::reasoning_hidden_line;
+use crate::streaming::StreamKind;
+use crate::streaming::controller::HistorySink;
+use crate::streaming::header_kind;
+
+/// The reasoning block being held, if any.
+#[derive(Debug, Default)]
+pub(crate) struct HeldReasoning {
+    header: Option<Line<'static>>,
+    /// The last lines of the block, at most the cap.
+    body: VecDeque<Line<'static>>,
+    /// Lines of the block dropped from the front of `body`.
+    hidden: usize,
+    /// Blank lines after `body`, which count toward the cap only once more
+    /// text follows them.
+    blank: Vec<Line<'static>>,
+}
+
+impl HeldReasoning {
+    /// Lay out committed stream `lines`, holding those of a reasoning block.
+    /// A reasoning or answer header ends the block held before it.
+    pub(crate) fn stream(
+        &mut self,
+        cap: usize,
+        theme: Theme,
+        glyphs: &HeaderGlyphs,
+        lines: Vec<Line<'static>>,
+    ) -> Vec<Line<'static>> {
+        let mut out = Vec::new();
+        for line in lines {
+            match header_kind(&line, theme, glyphs) {
+                Some(StreamKind::Reasoning) => {
+                    out.extend(self.close());
+                    self.header = Some(line);
+                }
+                Some(StreamKind::Answer) => {
+                    out.extend(self.close());
+                    out.push(line);
+                }
+                None if self.header.is_some() && is_blank_line_trim(&line) => {
+                    self.blank.push(line);
+                }
+                None if self.header.is_some() => {
+                    for line in self.blank.drain(..).chain([line]) {
+                        self.body.push_back(line);
+                        if self.body.len() > cap {
+                            self.body.pop_front();
+                            self.hidden += 1;
+                        }
+                    }
+                }
+                None => out.push(line),
+            }
+        }
+        out
+    }
+
+    /// End the held block: its header, the marker when lines were left out,
+    /// and its last lines. Nothing when no block is held.
+    pub(crate) fn close(&mut self) -> Vec<Line<'static>> {
+        let Some(header) = self.header.take() else {
+            return Vec::new();
+        };
+        let mut out = vec![header];
+        if self.hidden > 0 {
+            out.push(reasoning_hidden_line(self.hidden));
+        }
+        out.extend(self.body.drain(..));
+        out.append(&mut self.blank);
+        self.hidden = 0;
+        out
+    }
+}
+
+/// [`HistorySink`] that holds capped reasoning blocks before they reach the
+/// terminal; a plain pass-through without a cap.
+pub(crate) struct ReasoningCapSink<S> {
+    pub(crate) inner: S,
+    pub(crate) cap: Option<usize>,
+    pub(crate) theme: Theme,
+    pub(crate) glyphs: HeaderGlyphs,
+    pub(crate) held: Rc<RefCell<HeldReasoning>>,
+}
+
+impl<S: HistorySink> HistorySink for ReasoningCapSink<S> {
+    fn insert_history(&self, lines: Vec<Line<'static>>) {
+        let lines = match self.cap {
+            Some(cap) => self
+                .held
+                .borrow_mut()
+                .stream(cap, self.theme, &self.glyphs, lines),
+            None => lines,
+        };
+        if !lines.is_empty() {
+            self.inner.insert_history(lines);
+        }
+    }
+    fn start_commit_animation(&self) {
+        self.inner.start_commit_animation();
+    }
+    fn stop_commit_animation(&self) {
+        self.inner.stop_commit_animation();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streaming::render_header_line;
+    use pretty_assertions::assert_eq;
+
+    fn text(lines: &[Line<'_>]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn long_reasoning_is_written_as_its_last_lines_once_the_answer_starts() {
+        let glyphs = HeaderGlyphs::default();
+        let header = |kind| render_header_line(kind, Theme::default(), &glyphs);
+        let mut held = HeldReasoning::default();
+        let reasoning: Vec<Line<'static>> = std::iter::once(header(StreamKind::Reasoning))
+            .chain((1..=5).map(|i| Line::from(format!("step {i}"))))
+            .chain([Line::from("")])
+            .collect();
+        assert!(
+            held.stream(2, Theme::default(), &glyphs, reasoning)
+                .is_empty()
+        );
+        let out = held.stream(
+            2,
+            Theme::default(),
+            &glyphs,
+            vec![header(StreamKind::Answer), Line::from("done")],
+        );
+        assert_eq!(
+            text(&out)[1..],
+            [
+                "…earlier reasoning hidden (3 lines)",
+                "step 4",
+                "step 5",
+                "",
+                "codex",
+                "done"
+            ]
+        );
+        assert!(held.close().is_empty());
+    }
+}
@@ -24,6 +24,19 @@ pub fn push_owned_lines<'a>(src: &[Line<'a>], out: &mut Vec<Line<'static>>) {
     }
 }
 
+/// Drop the foreground and background colors of `lines`, keeping modifiers
+/// such as bold or dim.
+pub fn strip_colors(lines: &mut [Line<'_>]) {
+    for line in lines {
+        line.style.fg = None;
+        line.style.bg = None;
+        for span in &mut line.spans {
+            span.style.fg = None;
+            span.style.bg = None;
+        }
+    }
+}
+
 /// Consider a line blank if it has no spans or only spans whose contents are
 /// empty or consist solely of spaces (no tabs/newlines).
 pub fn is_blank_line_spaces_only(line: &Line<'_>) -> bool {
@@ -43,3 +56,62 @@ pub fn is_blank_line_trim(line: &Line<'_>) -> bool {
     }
     line.spans.iter().all(|s| s.content.trim().is_empty())
 }
+
+/// Widen the gaps between words of `line` so it is `width` columns wide.
+/// Leading indentation is kept as is; a line without a gap between words, or
+/// already at least `width` wide, is returned unchanged.
+pub fn justify_line(line: &Line<'_>, width: usize) -> Line<'static> {
+    let chars: Vec<(usize, char)> = line
+        .spans
+        .iter()
+        .enumerate()
+        .flat_map(|(i, s)| s.content.chars().map(move |c| (i, c)))
+        .collect();
+    let indent = chars.iter().take_while(|(_, c)| *c == ' ').count();
+    // A gap ends at a space followed by a word.
+    let gap_ends: Vec<usize> = (indent..chars.len().saturating_sub(1))
+        .filter(|&k| chars[k].1 == ' ' && chars[k + 1].1 != ' ')
+        .collect();
+    let missing = width.saturating_sub(line.width());
+    if gap_ends.is_empty() || missing == 0 {
+        return line_to_static(line);
+    }
+    let (each, extra) = (missing / gap_ends.len(), missing % gap_ends.len());
+    let mut contents: Vec<String> = vec![String::new(); line.spans.len()];
+    let mut gap = 0;
+    for (k, (span, c)) in chars.iter().enumerate() {
+        contents[*span].push(*c);
+        if gap < gap_ends.len() && gap_ends[gap] == k {
+            let pad = each + usize::from(gap < extra);
+            contents[*span].push_str(&" ".repeat(pad));
+            gap += 1;
+        }
+    }
+    Line {
+        style: line.style,
+        alignment: line.alignment,
+        spans: line
+            .spans
+            .iter()
+            .zip(contents)
+            .map(|(s, content)| Span::styled(content, s.style))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use ratatui::style::Style;
+    use ratatui::style::Stylize;
+
+    #[test]
+    fn stripping_colors_keeps_modifiers() {
+        let mut lines = vec![Line::from(vec!["codex".magenta().bold(), " ok".on_blue()]).red()];
+        strip_colors(&mut lines);
+        assert_eq!(lines[0].style, Style::default());
+        assert_eq!(lines[0].spans[0].style, Style::default().bold());
+        assert_eq!(lines[0].spans[1].style, Style::default());
+    }
+}
@@ -1,19 +1,84 @@
+/// The opening fence of a code block: a run of three or more backticks or
+/// tildes. Only a run of the same character at least as long closes it, so
+/// a block opened by ```` can hold ``` lines as code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fence {
+    marker: char,
+    len: usize,
+}
+
+impl Fence {
+    /// The fence `line` opens, if any, with its info string (the language),
+    /// trimmed. A run of backticks followed by another backtick is inline
+    /// code, not a fence.
+    pub fn open(line: &str) -> Option<(Fence, &str)> {
+        let t = line.trim_start();
+        let marker = t.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+        let len = t.chars().take_while(|c| *c == marker).count();
+        if len < 3 {
+            return None;
+        }
+        let info = t[len..].trim();
+        if marker == '`' && info.contains('`') {
+            return None;
+        }
+        Some((Fence { marker, len }, info))
+    }
+
+    /// Whether `line` closes the block this fence opened: nothing but the
+    /// same character, at least as many times, and whitespace.
+    pub fn is_closed_by(&self, line: &str) -> bool {
+        let t = line.trim();
+        t.len() >= self.len && t.chars().all(|c| c == self.marker)
+    }
+
+    /// The fence as written, e.g. ```` for four backticks.
+    pub fn token(&self) -> String {
+        self.marker.to_string().repeat(self.len)
+    }
+}
+
+/// The fence still open after `line`, given the one open before it.
+pub fn fence_after(open: Option<Fence>, line: &str) -> Option<Fence> {
+    match open {
+        Some(fence) if fence.is_closed_by(line) => None,
+        Some(fence) => Some(fence),
+        None => Fence::open(line).map(|(fence, _)| fence),
+    }
+}
+
+/// The fence of a code block left open at the end of `s`, if any.
+fn unclosed_fence(s: &str) -> Option<Fence> {
+    s.lines().fold(None, fence_after)
+}
+
 /// Returns true if the provided text contains an unclosed fenced code block
 /// (opened by ``` or ~~~, closed by a matching fence on its own line).
 pub fn is_inside_unclosed_fence(s: &str) -> bool {
-    let mut open = false;
-    for line in s.lines() {
-        let t = line.trim_start();
-        if t.starts_with("```") || t.starts_with("~~~") {
-            if !open {
-                open = true;
-            } else {
-                // closing fence on same pattern toggles off
-                open = false;
-            }
+    unclosed_fence(s).is_some()
+}
+
+/// Close a fenced code block left open at the end of `s` (for example when
+/// the model stopped before writing the closing fence), using the opening
+/// fence's marker, so text that follows it is not read as code.
+pub fn close_unclosed_fence(s: &str) -> String {
+    let mut out = s.to_string();
+    if let Some(fence) = unclosed_fence(s) {
+        if !out.ends_with('\n') {
+            out.push('\n');
         }
+        out.push_str(&fence.token());
     }
-    open
+    out
+}
+
+/// Returns true if the text ends in a run of `|`-prefixed lines that is not
+/// yet followed by anything else: either a pipe table still receiving rows or
+/// a header row whose delimiter has not arrived.
+pub fn is_inside_open_table(s: &str) -> bool {
+    s.lines()
+        .next_back()
+        .is_some_and(|last| last.trim_start().starts_with('|'))
 }
 
 /// Remove fenced code blocks that contain no content (whitespace-only) to avoid
@@ -26,22 +91,14 @@ pub fn strip_empty_fenced_code_blocks(s: &str) -> String {
     let mut i = 0usize;
     while i < lines.len() {
         let line = lines[i];
-        let trimmed_start = line.trim_start();
-        let fence_token = if trimmed_start.starts_with("```") {
-            "```"
-        } else if trimmed_start.starts_with("~~~") {
-            "~~~"
-        } else {
-            ""
-        };
-        if !fence_token.is_empty() {
+        if let Some((fence, _)) = Fence::open(line) {
             // Find a matching closing fence on its own line.
             let mut j = i + 1;
             let mut has_content = false;
             let mut found_close = false;
             while j < lines.len() {
                 let l = lines[j];
-                if l.trim() == fence_token {
+                if fence.is_closed_by(l) {
                     found_close = true;
                     break;
                 }
@@ -58,10 +115,14 @@ pub fn strip_empty_fenced_code_blocks(s: &str) -> String {
                 i = j + 1;
                 continue;
             }
-            // Not an empty fenced block; emit as-is.
-            out.push_str(line);
-            out.push('\n');
-            i += 1;
+            // Not an empty fenced block; emit it whole (to the end of the
+            // text when it is unclosed) so fence-like lines inside it are
+            // kept as code rather than matched against each other.
+            for l in &lines[i..lines.len().min(j + 1)] {
+                out.push_str(l);
+                out.push('\n');
+            }
+            i = j + 1;
         } else {
             out.push_str(line);
             out.push('\n');
@@ -70,3 +131,43 @@ pub fn strip_empty_fenced_code_blocks(s: &str) -> String {
     }
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn longer_fence_is_not_closed_by_a_shorter_one() {
+        let s = "````text\n```rust\nfn main() {}\n```\n";
+        assert!(is_inside_unclosed_fence(s));
+        assert_eq!(close_unclosed_fence(s), format!("{s}````"));
+        assert!(!is_inside_unclosed_fence(&format!("{s}`````\n")));
+    }
+
+    #[test]
+    fn tilde_fence_is_closed_by_tildes_only() {
+        assert!(is_inside_unclosed_fence("~~~\n```\n"));
+        assert!(!is_inside_unclosed_fence("~~~\n```\n~~~\n"));
+        assert_eq!(
+            Fence::open("  ~~~ toml"),
+            Some((
+                Fence {
+                    marker: '~',
+                    len: 3
+                },
+                "toml"
+            ))
+        );
+        assert_eq!(Fence::open("```a`b"), None);
+    }
+
+    #[test]
+    fn fence_lines_inside_a_longer_fence_are_not_stripped_as_empty_blocks() {
+        let s = "````md\n```rust\nfn main() {}\n```\n````\n\n```\n```\n";
+        assert_eq!(
+            strip_empty_fenced_code_blocks(s),
+            "````md\n```rust\nfn main() {}\n```\n````\n\n"
+        );
+    }
+}
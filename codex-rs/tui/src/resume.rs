@@ -0,0 +1,55 @@
+//! Conversation of a resumed session (`experimental_resume`).
+//!
+//! The core restores the model's context from the rollout file but sends
+//! nothing to the UI, so the prompts and answers are read back here to show
+//! where the session left off.
+
+use std::path::Path;
+
+use serde_json::Value;
+
+/// Prefixes of the context messages the core sends in the user role.
+const CONTEXT_PREFIXES: [&str; 2] = ["<user_instructions>", "<environment_context>"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ResumedMessage {
+    User(String),
+    Assistant(String),
+}
+
+/// Read the user prompts and assistant answers from the rollout at `path`,
+/// oldest first. Tool calls, reasoning and injected context are skipped.
+pub(crate) fn read_rollout_messages(path: &Path) -> std::io::Result<Vec<ResumedMessage>> {
+    let text = std::fs::read_to_string(path)?;
+    // The first line is the session metadata.
+    Ok(text.lines().skip(1).filter_map(parse_message).collect())
+}
+
+fn parse_message(line: &str) -> Option<ResumedMessage> {
+    let value: Value = serde_json::from_str(line).ok()?;
+    if value.get("type").and_then(Value::as_str) != Some("message") {
+        return None;
+    }
+    let text = value
+        .get("content")?
+        .as_array()?
+        .iter()
+        .filter(|item| {
+            matches!(
+                item.get("type").and_then(Value::as_str),
+                Some("input_text" | "output_text")
+            )
+        })
+        .filter_map(|item| item.get("text").and_then(Value::as_str))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if text.trim().is_empty() {
+        return None;
+    }
+    match value.get("role").and_then(Value::as_str)? {
+        "user" if CONTEXT_PREFIXES.iter().any(|p| text.starts_with(p)) => None,
+        "user" => Some(ResumedMessage::User(text)),
+        "assistant" => Some(ResumedMessage::Assistant(text)),
+        _ => None,
+    }
+}
@@ -0,0 +1,48 @@
+//! Automatic retry of turns that failed with a transient error
+//! (`tui.retry`).
+
+use std::time::Duration;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+
+/// Wait before retry `attempt` (counted from 1): `base`, then twice as long
+/// for each further attempt.
+pub(crate) fn backoff(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+}
+
+/// Sends a retry's event once its backoff has elapsed.
+pub(crate) trait RetryClock {
+    fn schedule(&self, delay: Duration, tx: AppEventSender, event: AppEvent);
+}
+
+/// Waits on a thread of its own.
+pub(crate) struct SystemRetryClock;
+
+impl RetryClock for SystemRetryClock {
+    fn schedule(&self, delay: Duration, tx: AppEventSender, event: AppEvent) {
+        std::thread::spawn(move || {
+            std::thread::sleep(delay);
+            tx.send(event);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn backoff_doubles_with_each_attempt() {
+        let base = Duration::from_millis(500);
+        let delays: Vec<Duration> = (1..=4).map(|attempt| backoff(base, attempt)).collect();
+        assert_eq!(
+            delays,
+            [500, 1000, 2000, 4000].map(Duration::from_millis).to_vec()
+        );
+        // Far attempts saturate rather than overflow.
+        assert!(backoff(base, 64) > backoff(base, 4));
+    }
+}
@@ -0,0 +1,1129 @@
+//! In-app copy of the transcript, laid out for the transcript view.
+//!
+//! Everything inserted into the terminal scrollback is also recorded here,
+//! grouped into blocks (a user prompt, a reasoning or answer stream, or any
+//! other history cell). The view scrolls over the wrapped rows of those
+//! blocks and, while following, positions itself according to the configured
+//! [`ScrollPolicy`].
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::rc::Rc;
+
+use codex_core::config_types::Expand;
+use codex_core::config_types::HeaderGlyphs;
+use codex_core::config_types::ScrollPolicy;
+use codex_core::config_types::Theme;
+use codex_core::config_types::Tui;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+
+use crate::details::expanded_summary_line;
+use crate::insert_history::word_wrap_lines;
+use crate::render::line_utils::is_blank_line_trim;
+use crate::streaming::StreamKind;
+use crate::streaming::controller::AppEventHistorySink;
+use crate::streaming::controller::HistorySink;
+use crate::streaming::header_kind;
+use crate::streaming::render_header_line;
+use crate::text_formatting::truncate_text;
+use crate::truncation::TruncationIndicator;
+
+/// Maximum length of a turn summary in [`Scrollback::outline`].
+const TURN_SUMMARY_MAX_GRAPHEMES: usize = 60;
+
+/// Lines kept for the transcript view. Past this, the oldest blocks are
+/// dropped, like a terminal scrollback; `/export` still writes everything.
+const MAX_LINES: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BlockKind {
+    User,
+    Reasoning,
+    Answer,
+    /// The agent's plan checklist, updated in place.
+    Plan,
+    Other,
+}
+
+impl From<StreamKind> for BlockKind {
+    fn from(kind: StreamKind) -> Self {
+        match kind {
+            StreamKind::Reasoning => BlockKind::Reasoning,
+            StreamKind::Answer => BlockKind::Answer,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Block {
+    kind: BlockKind,
+    first_line: usize,
+    /// Short id used to refer to the block, e.g. with `/goto`.
+    anchor: String,
+}
+
+/// A user turn: the prompt text and the first line of its header.
+#[derive(Debug, Clone)]
+struct Turn {
+    prompt: String,
+    first_line: usize,
+}
+
+/// One entry per user turn, for building an outline or sidebar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TurnOutlineEntry {
+    /// Zero-based turn number.
+    pub(crate) index: usize,
+    /// First line of the prompt, truncated.
+    pub(crate) summary: String,
+    /// Row of the turn header in the wrapped transcript at the current width.
+    pub(crate) row: usize,
+}
+
+pub(crate) struct Scrollback {
+    lines: Vec<Line<'static>>,
+    blocks: Vec<Block>,
+    turns: Vec<Turn>,
+    /// `line_rows[i]` is the first wrapped row of `lines[i]`; the final entry
+    /// is the total row count.
+    line_rows: Vec<usize>,
+    width: u16,
+    height: u16,
+    /// First visible row.
+    top: usize,
+    /// Cleared when the user scrolls away from the bottom (scroll lock).
+    follow: bool,
+    policy: ScrollPolicy,
+    /// Show each block's anchor dimly after its first line.
+    show_anchors: bool,
+    /// Theme the stored stream headers are styled with.
+    theme: Theme,
+    /// Glyphs the stored stream headers are drawn with.
+    header_glyphs: HeaderGlyphs,
+    /// Show at most this many lines of each reasoning block (after its
+    /// header), replacing the rest with a marker.
+    max_reasoning_lines: Option<usize>,
+    /// Wrap at most this many columns, even when the view is wider.
+    max_wrap_columns: Option<u16>,
+    /// Kinds of content shown in full; the others are folded.
+    expand: Expand,
+    /// Marker below the first line of a folded run.
+    truncation: TruncationIndicator,
+    /// Lines of the code blocks of answers, which tell code from prose.
+    code_lines: Vec<Range<usize>>,
+    /// The last block is an answer that is still streaming, so its code
+    /// blocks are not known yet and it is not folded.
+    answer_streaming: bool,
+    /// Lines of the `<details>` sections of answers, summary line first.
+    details_lines: Vec<Range<usize>>,
+    /// Blocks and turns dropped from the front to stay within
+    /// [`MAX_LINES`], so anchors and turn numbers stay stable.
+    evicted_blocks: usize,
+    evicted_turns: usize,
+    /// Rows each line wraps to, per wrap width, so growing a block or
+    /// resizing back to an earlier width does not wrap the history again.
+    /// Each entry covers a prefix of `lines`.
+    wrapped_heights: HashMap<u16, Vec<usize>>,
+}
+
+/// How a line is drawn in the view; see [`Scrollback::display_rows`].
+enum LineLayout {
+    Plain,
+    /// Left out by the reasoning cap or inside a fold.
+    Hidden,
+    /// First line of a block, followed by the marker for `hidden` capped
+    /// reasoning lines.
+    Header {
+        hidden: usize,
+    },
+    /// First line of a folded section of `len` lines, followed by its marker.
+    FoldStart {
+        len: usize,
+    },
+    /// Summary of an expanded `<details>` section.
+    ExpandedSummary,
+}
+
+impl Scrollback {
+    pub(crate) fn new(config: &Tui) -> Self {
+        Self {
+            lines: Vec::new(),
+            blocks: Vec::new(),
+            turns: Vec::new(),
+            line_rows: vec![0],
+            width: 0,
+            height: 0,
+            top: 0,
+            follow: true,
+            policy: config.scroll_policy,
+            show_anchors: config.show_anchors,
+            theme: config.theme,
+            header_glyphs: config.header_glyphs.clone(),
+            max_reasoning_lines: config.max_reasoning_lines,
+            max_wrap_columns: config.max_wrap_columns,
+            expand: config.expand,
+            truncation: TruncationIndicator::new(config),
+            code_lines: Vec::new(),
+            answer_streaming: false,
+            details_lines: Vec::new(),
+            evicted_blocks: 0,
+            evicted_turns: 0,
+            wrapped_heights: HashMap::new(),
+        }
+    }
+
+    /// Record a complete history cell as its own block.
+    pub(crate) fn push_block(&mut self, kind: BlockKind, lines: Vec<Line<'static>>) {
+        match lines.first() {
+            Some(first) => self.start_block(kind, first),
+            None => self.start_block(kind, &Line::default()),
+        }
+        self.append_lines(lines);
+        self.evict_overflow();
+        self.follow_output();
+    }
+
+    /// Anchor of the most recently recorded block.
+    pub(crate) fn last_anchor(&self) -> Option<String> {
+        self.blocks.last().map(|b| b.anchor.clone())
+    }
+
+    /// Swap the lines of the block with `anchor` for `lines`, shifting the
+    /// blocks and turns after it. Returns `false` if no such block exists.
+    pub(crate) fn replace_block(&mut self, anchor: &str, lines: Vec<Line<'static>>) -> bool {
+        let Some(i) = self.blocks.iter().position(|b| b.anchor == anchor) else {
+            return false;
+        };
+        let start = self.blocks[i].first_line;
+        let end = self
+            .blocks
+            .get(i + 1)
+            .map_or(self.lines.len(), |b| b.first_line);
+        let new_len = lines.len();
+        self.lines.splice(start..end, lines);
+        self.forget_wrapped_heights_from(start);
+        let shift = |line: &mut usize| {
+            if *line >= end {
+                *line = *line - end + start + new_len;
+            }
+        };
+        for block in &mut self.blocks[i + 1..] {
+            shift(&mut block.first_line);
+        }
+        for turn in &mut self.turns {
+            shift(&mut turn.first_line);
+        }
+        self.relayout_from(start);
+        self.top = self.top.min(self.max_top());
+        true
+    }
+
+    /// Mark the most recent block as the header of a new user turn.
+    pub(crate) fn mark_turn_start(&mut self, prompt: &str) {
+        if let Some(block) = self.blocks.last() {
+            self.turns.push(Turn {
+                prompt: prompt.to_string(),
+                first_line: block.first_line,
+            });
+        }
+    }
+
+    /// Drop the blocks after the prompt of the latest turn, such as the
+    /// output of an attempt that failed and is about to be sent again.
+    pub(crate) fn truncate_turn_output(&mut self) {
+        let Some(turn) = self.turns.last() else {
+            return;
+        };
+        let Some(i) = self
+            .blocks
+            .iter()
+            .position(|b| b.first_line == turn.first_line)
+        else {
+            return;
+        };
+        let end = self
+            .blocks
+            .get(i + 1)
+            .map_or(self.lines.len(), |b| b.first_line);
+        self.blocks.truncate(i + 1);
+        self.lines.truncate(end);
+        self.forget_wrapped_heights_from(end);
+        self.code_lines.retain(|range| range.end <= end);
+        self.details_lines.retain(|range| range.end <= end);
+        self.relayout_from(self.blocks[i].first_line);
+        self.top = self.top.min(self.max_top());
+        self.follow_output();
+    }
+
+    /// Every user turn with its summary and header row. Rows are derived
+    /// from the current wrapping, so they stay correct across resizes.
+    pub(crate) fn outline(&self) -> Vec<TurnOutlineEntry> {
+        self.turns
+            .iter()
+            .enumerate()
+            .map(|(index, turn)| {
+                let first_line = turn
+                    .prompt
+                    .lines()
+                    .find(|l| !l.trim().is_empty())
+                    .unwrap_or_default()
+                    .trim();
+                TurnOutlineEntry {
+                    index: self.evicted_turns + index,
+                    summary: truncate_text(first_line, TURN_SUMMARY_MAX_GRAPHEMES),
+                    row: self.line_rows[turn.first_line],
+                }
+            })
+            .collect()
+    }
+
+    /// Record lines committed by the stream controller. A stream header starts
+    /// a new reasoning/answer block; other lines continue the current block.
+    pub(crate) fn push_stream_lines(&mut self, lines: Vec<Line<'static>>) {
+        let start = self.layout_start();
+        for line in lines {
+            if let Some(kind) = header_kind(&line, self.theme, &self.header_glyphs) {
+                self.start_block(kind.into(), &line);
+            }
+            self.lines.push(line);
+        }
+        self.relayout_from(start);
+        self.evict_overflow();
+        self.follow_output();
+    }
+
+    /// Record where the code blocks of answers are, for folding answers per
+    /// `tui.expand`. Re-lays out only when something changed.
+    pub(crate) fn set_code_lines(&mut self, code_lines: Vec<Range<usize>>, answer_streaming: bool) {
+        if code_lines == self.code_lines && answer_streaming == self.answer_streaming {
+            return;
+        }
+        self.code_lines = code_lines;
+        self.answer_streaming = answer_streaming;
+        self.relayout();
+    }
+
+    /// Record where the `<details>` sections of answers are, for folding
+    /// them under their summary. Re-lays out only when something changed.
+    pub(crate) fn set_details_lines(&mut self, details_lines: Vec<Range<usize>>) {
+        if details_lines == self.details_lines {
+            return;
+        }
+        self.details_lines = details_lines;
+        self.relayout();
+    }
+
+    /// Whether there are `<details>` sections, and whether they are shown
+    /// in full.
+    pub(crate) fn details_state(&self) -> Option<bool> {
+        (!self.details_lines.is_empty()).then_some(self.expand.details)
+    }
+
+    /// Expand every `<details>` section, or fold them again.
+    pub(crate) fn toggle_details(&mut self) {
+        self.expand.details = !self.expand.details;
+        self.relayout();
+    }
+
+    fn relayout(&mut self) {
+        self.rewrap();
+        if self.follow {
+            self.follow_output();
+        } else {
+            self.top = self.top.min(self.max_top());
+        }
+    }
+
+    /// Resize the view. Rows are re-wrapped when the width changes.
+    pub(crate) fn set_viewport(&mut self, width: u16, height: u16) {
+        if width != self.width {
+            self.width = width;
+            self.rewrap();
+        }
+        self.height = height;
+        if self.follow {
+            self.follow_output();
+        } else {
+            self.top = self.top.min(self.max_top());
+        }
+    }
+
+    /// Limit wrapping to `columns` (full width when `None`) and re-wrap.
+    pub(crate) fn set_max_wrap_columns(&mut self, columns: Option<u16>) {
+        self.max_wrap_columns = columns;
+        self.rewrap();
+        if self.follow {
+            self.follow_output();
+        } else {
+            self.top = self.top.min(self.max_top());
+        }
+    }
+
+    /// Restyle the recorded reasoning/answer headers for `theme`. Block
+    /// kinds are kept alongside the lines, so each header is rebuilt rather
+    /// than patched.
+    pub(crate) fn set_theme(&mut self, theme: Theme) {
+        for block in &self.blocks {
+            let kind = match block.kind {
+                BlockKind::Reasoning => StreamKind::Reasoning,
+                BlockKind::Answer => StreamKind::Answer,
+                BlockKind::User | BlockKind::Plan | BlockKind::Other => continue,
+            };
+            if let Some(line) = self.lines.get_mut(block.first_line) {
+                if header_kind(line, self.theme, &self.header_glyphs) == Some(kind) {
+                    *line = render_header_line(kind, theme, &self.header_glyphs);
+                }
+            }
+        }
+        self.theme = theme;
+        self.wrapped_heights.clear();
+        self.rewrap();
+    }
+
+    /// Scroll by `delta` rows (negative is up). Scrolling up engages scroll
+    /// lock; scrolling back down to the bottom releases it.
+    pub(crate) fn scroll_by(&mut self, delta: isize) {
+        if delta < 0 {
+            self.top = self.top.saturating_sub(delta.unsigned_abs());
+            self.follow = false;
+        } else {
+            let max_top = self.max_top().max(self.top);
+            self.top = (self.top + delta as usize).min(max_top);
+            self.follow = self.top >= self.max_top();
+        }
+    }
+
+    /// Rows scrolled by `PageUp`/`PageDown`: the height of the view, and at
+    /// least one row.
+    pub(crate) fn page_height(&self) -> usize {
+        (self.height as usize).max(1)
+    }
+
+    /// Scroll so the header of the block with `anchor` is in view. Returns
+    /// `false` if there is no such block.
+    pub(crate) fn scroll_to_anchor(&mut self, anchor: &str) -> bool {
+        let anchor = anchor.trim_start_matches('#');
+        let Some(block) = self.blocks.iter().find(|b| b.anchor == anchor) else {
+            return false;
+        };
+        self.top = self.line_rows[block.first_line].min(self.max_top());
+        self.follow = self.top >= self.max_top();
+        true
+    }
+
+    /// Scroll so the header of the latest user turn is at the top of the
+    /// view, or as close as the remaining rows allow. Returns `false` if
+    /// there are no turns.
+    pub(crate) fn scroll_to_last_turn(&mut self) -> bool {
+        (self.evicted_turns + self.turns.len())
+            .checked_sub(1)
+            .is_some_and(|index| self.scroll_to_turn(index))
+    }
+
+    /// Scroll so the header of turn `index` is at the top. Returns `false`
+    /// when there is no such turn, or it has been dropped from the front.
+    pub(crate) fn scroll_to_turn(&mut self, index: usize) -> bool {
+        let Some(turn) = index
+            .checked_sub(self.evicted_turns)
+            .and_then(|index| self.turns.get(index))
+        else {
+            return false;
+        };
+        self.top = self.line_rows[turn.first_line].min(self.max_top());
+        self.follow = self.top >= self.max_top();
+        true
+    }
+
+    /// Scroll so `row` is in view, a third of the way down when the view
+    /// has to move.
+    pub(crate) fn reveal_row(&mut self, row: usize) {
+        if self.visible_rows().contains(&row) {
+            return;
+        }
+        self.top = row
+            .saturating_sub(self.height as usize / 3)
+            .min(self.max_top());
+        self.follow = self.top >= self.max_top();
+    }
+
+    /// Every recorded line.
+    pub(crate) fn lines(&self) -> &[Line<'static>] {
+        &self.lines
+    }
+
+    /// Lines of each answer block, in order.
+    pub(crate) fn answer_blocks(&self) -> Vec<Range<usize>> {
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, block)| block.kind == BlockKind::Answer)
+            .map(|(i, block)| {
+                let end = self
+                    .blocks
+                    .get(i + 1)
+                    .map_or(self.lines.len(), |b| b.first_line);
+                block.first_line..end
+            })
+            .collect()
+    }
+
+    /// Wrapped rows drawn for `lines`.
+    pub(crate) fn rows_of_lines(&self, lines: Range<usize>) -> Range<usize> {
+        let row = |line: usize| self.line_rows[line.min(self.lines.len())];
+        row(lines.start)..row(lines.end)
+    }
+
+    /// Number of wrapped rows at the current width.
+    pub(crate) fn row_count(&self) -> usize {
+        self.total_rows()
+    }
+
+    pub(crate) fn scroll_to_top(&mut self) {
+        self.top = 0;
+        self.follow = self.max_top() == 0;
+    }
+
+    /// Jump back to the live output and resume following it.
+    pub(crate) fn scroll_to_bottom(&mut self) {
+        self.follow = true;
+        self.follow_output();
+    }
+
+    pub(crate) fn is_following(&self) -> bool {
+        self.follow
+    }
+
+    /// Rows currently on screen.
+    pub(crate) fn visible_rows(&self) -> Range<usize> {
+        let total = self.total_rows();
+        let start = self.top.min(total);
+        start..(start + self.height as usize).min(total)
+    }
+
+    /// Rows occupied by the most recent block, with its kind.
+    #[cfg(test)]
+    pub(crate) fn last_block(&self) -> Option<(BlockKind, Range<usize>)> {
+        let block = self.blocks.last()?;
+        Some((
+            block.kind,
+            self.line_rows[block.first_line]..self.total_rows(),
+        ))
+    }
+
+    /// Wrapped rows in `range`, ready to draw.
+    pub(crate) fn rows(&self, range: Range<usize>) -> Vec<Line<'static>> {
+        if range.is_empty() {
+            return Vec::new();
+        }
+        let first = self.line_at_row(range.start);
+        let last = self.line_at_row(range.end - 1);
+        let skip = range.start - self.line_rows[first];
+        (first..=last)
+            .flat_map(|index| self.display_rows(index))
+            .skip(skip)
+            .take(range.len())
+            .collect()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn anchors(&self) -> Vec<String> {
+        self.blocks.iter().map(|b| b.anchor.clone()).collect()
+    }
+
+    /// Open a new block whose first line is `header`.
+    fn start_block(&mut self, kind: BlockKind, header: &Line<'static>) {
+        let anchor = block_anchor(self.evicted_blocks + self.blocks.len(), kind, header);
+        self.blocks.push(Block {
+            kind,
+            first_line: self.lines.len(),
+            anchor,
+        });
+    }
+
+    fn total_rows(&self) -> usize {
+        self.line_rows.last().copied().unwrap_or(0)
+    }
+
+    fn max_top(&self) -> usize {
+        self.total_rows().saturating_sub(self.height as usize)
+    }
+
+    fn line_at_row(&self, row: usize) -> usize {
+        // `line_rows` is non-decreasing, so the line containing `row` is the
+        // last one starting at or before it.
+        self.line_rows[..self.lines.len()]
+            .partition_point(|&start| start <= row)
+            .saturating_sub(1)
+    }
+
+    /// Columns lines are wrapped at; 0 when the view has no width yet and
+    /// lines are not wrapped.
+    fn wrap_width(&self) -> u16 {
+        match self.max_wrap_columns {
+            Some(max) if self.width > 0 => self.width.min(max.max(1)),
+            _ => self.width,
+        }
+    }
+
+    fn wrap(&self, line: &Line<'static>) -> Vec<Line<'static>> {
+        match self.wrap_width() {
+            0 => vec![line.clone()],
+            width => word_wrap_lines(std::slice::from_ref(line), width),
+        }
+    }
+
+    /// Rows `lines[index]` wraps to at the current width, from
+    /// [`Self::cache_wrapped_heights`].
+    fn wrapped_height(&self, index: usize) -> usize {
+        self.wrapped_heights
+            .get(&self.wrap_width())
+            .and_then(|heights| heights.get(index).copied())
+            .unwrap_or_else(|| self.wrap(&self.lines[index]).len())
+    }
+
+    /// Wrap the lines not yet measured at the current width.
+    fn cache_wrapped_heights(&mut self) {
+        let width = self.wrap_width();
+        let mut heights = self.wrapped_heights.remove(&width).unwrap_or_default();
+        for line in &self.lines[heights.len()..] {
+            heights.push(self.wrap(line).len());
+        }
+        self.wrapped_heights.insert(width, heights);
+    }
+
+    /// Drop the measured heights of `lines[start..]`, which changed.
+    fn forget_wrapped_heights_from(&mut self, start: usize) {
+        for heights in self.wrapped_heights.values_mut() {
+            heights.truncate(start);
+        }
+    }
+
+    /// Rows drawn for `lines[index]`: its wrapped rows, nothing if the
+    /// reasoning cap or a fold hides it, the fold marker after the first line
+    /// of a folded section, and for a block header the anchor (when shown)
+    /// and the marker of a capped reasoning block.
+    fn display_rows(&self, index: usize) -> Vec<Line<'static>> {
+        match self.line_layout(index) {
+            LineLayout::Plain => self.wrap(&self.lines[index]),
+            LineLayout::Hidden => Vec::new(),
+            LineLayout::Header { hidden } => {
+                let mut rows = self.wrap(&self.header_with_anchor(index));
+                if hidden > 0 {
+                    rows.push(reasoning_hidden_line(hidden));
+                }
+                rows
+            }
+            LineLayout::FoldStart { len } => {
+                let mut rows = self.wrap(&self.lines[index]);
+                rows.push(self.truncation.line(len - 1));
+                rows
+            }
+            LineLayout::ExpandedSummary => self.wrap(&expanded_summary_line(&self.lines[index])),
+        }
+    }
+
+    /// Number of [`Self::display_rows`] of `lines[index]`, without wrapping
+    /// lines already measured at the current width.
+    fn display_row_count(&self, index: usize) -> usize {
+        match self.line_layout(index) {
+            LineLayout::Plain => self.wrapped_height(index),
+            LineLayout::Hidden => 0,
+            LineLayout::Header { hidden } => {
+                let rows = if self.show_anchors {
+                    self.wrap(&self.header_with_anchor(index)).len()
+                } else {
+                    self.wrapped_height(index)
+                };
+                rows + usize::from(hidden > 0)
+            }
+            LineLayout::FoldStart { .. } => self.wrapped_height(index) + 1,
+            LineLayout::ExpandedSummary => {
+                self.wrap(&expanded_summary_line(&self.lines[index])).len()
+            }
+        }
+    }
+
+    fn line_layout(&self, index: usize) -> LineLayout {
+        let next = self.blocks.partition_point(|b| b.first_line <= index);
+        let Some(block) = next.checked_sub(1).and_then(|i| self.blocks.get(i)) else {
+            return LineLayout::Plain;
+        };
+        let hidden = self.hidden_reasoning_lines(next - 1);
+        if index == block.first_line {
+            LineLayout::Header { hidden }
+        } else if index <= block.first_line + hidden {
+            LineLayout::Hidden
+        } else {
+            match self.fold_at(next - 1, index) {
+                Some(fold) if index == fold.start => LineLayout::FoldStart { len: fold.len() },
+                Some(_) => LineLayout::Hidden,
+                None if self.details_lines.iter().any(|d| d.start == index) => {
+                    LineLayout::ExpandedSummary
+                }
+                None => LineLayout::Plain,
+            }
+        }
+    }
+
+    /// The header line `lines[index]`, with the block's anchor when shown.
+    fn header_with_anchor(&self, index: usize) -> Line<'static> {
+        let mut header = self.lines[index].clone();
+        if self.show_anchors {
+            let next = self.blocks.partition_point(|b| b.first_line <= index);
+            if let Some(block) = next.checked_sub(1).and_then(|i| self.blocks.get(i)) {
+                header.spans.push(format!("  #{}", block.anchor).dim());
+            }
+        }
+        header
+    }
+
+    /// The folded section of `blocks[i]` containing `index`, if any: the
+    /// body of a reasoning block, or a code block or a run of prose between
+    /// code blocks of an answer. Single lines are never folded.
+    fn fold_at(&self, i: usize, index: usize) -> Option<Range<usize>> {
+        let block = self.blocks.get(i)?;
+        let end = self
+            .blocks
+            .get(i + 1)
+            .map_or(self.lines.len(), |b| b.first_line);
+        let body = block.first_line + 1..end;
+        let section = match block.kind {
+            BlockKind::Reasoning if !self.expand.reasoning => body,
+            BlockKind::Answer if !(self.answer_streaming && i + 1 == self.blocks.len()) => {
+                if let Some(details) = self
+                    .details_lines
+                    .iter()
+                    .find(|details| !self.expand.details && details.contains(&index))
+                {
+                    return Some(details.clone());
+                }
+                let code = self
+                    .code_lines
+                    .iter()
+                    .find(|code| code.contains(&index))
+                    .cloned();
+                match code {
+                    Some(code) if !self.expand.code => code,
+                    None if !self.expand.prose => {
+                        let in_body =
+                            |code: &&Range<usize>| code.start >= body.start && code.end <= body.end;
+                        let start = self
+                            .code_lines
+                            .iter()
+                            .filter(in_body)
+                            .map(|code| code.end)
+                            .filter(|&end| end <= index)
+                            .max()
+                            .unwrap_or(body.start);
+                        let end = self
+                            .code_lines
+                            .iter()
+                            .filter(in_body)
+                            .map(|code| code.start)
+                            .filter(|&start| start > index)
+                            .min()
+                            .unwrap_or(body.end);
+                        // Blank lines before the run stay, so the first
+                        // line shown is text.
+                        let first = (start..end)
+                            .find(|&line| {
+                                self.lines[line]
+                                    .spans
+                                    .iter()
+                                    .any(|span| !span.content.trim().is_empty())
+                            })
+                            .unwrap_or(end);
+                        if index < first {
+                            return None;
+                        }
+                        first..end
+                    }
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        (section.len() > 1).then_some(section)
+    }
+
+    /// Number of body lines of `blocks[i]` hidden by the reasoning cap.
+    fn hidden_reasoning_lines(&self, i: usize) -> usize {
+        let (Some(cap), Some(block)) = (self.max_reasoning_lines, self.blocks.get(i)) else {
+            return 0;
+        };
+        if block.kind != BlockKind::Reasoning || !self.expand.reasoning {
+            return 0;
+        }
+        let end = self
+            .blocks
+            .get(i + 1)
+            .map_or(self.lines.len(), |b| b.first_line);
+        // The blank lines closing the block do not count toward the cap.
+        let body = &self.lines[block.first_line + 1..end];
+        let trailing_blank = body
+            .iter()
+            .rev()
+            .take_while(|line| is_blank_line_trim(line))
+            .count();
+        (body.len() - trailing_blank).saturating_sub(cap)
+    }
+
+    fn append_lines(&mut self, lines: Vec<Line<'static>>) {
+        let start = self.layout_start();
+        self.lines.extend(lines);
+        self.relayout_from(start);
+    }
+
+    /// First line whose layout appending to the last block can change.
+    /// Growing a capped reasoning block hides earlier lines of it, and
+    /// growing a folded one moves its marker, so its layout is recomputed
+    /// from the header.
+    fn layout_start(&self) -> usize {
+        match self.blocks.last() {
+            Some(block)
+                if block.kind == BlockKind::Reasoning
+                    && (self.max_reasoning_lines.is_some() || !self.expand.reasoning) =>
+            {
+                block.first_line
+            }
+            _ => self.lines.len(),
+        }
+    }
+
+    fn rewrap(&mut self) {
+        self.relayout_from(0);
+    }
+
+    /// Drop whole blocks from the front while there are more than
+    /// [`MAX_LINES`] lines, always keeping the latest block.
+    fn evict_overflow(&mut self) {
+        let excess = self.lines.len().saturating_sub(MAX_LINES);
+        if excess == 0 {
+            return;
+        }
+        let keep = self
+            .blocks
+            .iter()
+            .position(|b| b.first_line >= excess)
+            .unwrap_or(self.blocks.len().saturating_sub(1));
+        let Some(cut) = self.blocks.get(keep).map(|b| b.first_line) else {
+            return;
+        };
+        if cut == 0 {
+            return;
+        }
+        self.lines.drain(..cut);
+        for heights in self.wrapped_heights.values_mut() {
+            heights.drain(..cut.min(heights.len()));
+        }
+        self.blocks.drain(..keep);
+        self.evicted_blocks += keep;
+        for block in &mut self.blocks {
+            block.first_line -= cut;
+        }
+        let dropped = self.turns.iter().take_while(|t| t.first_line < cut).count();
+        self.turns.drain(..dropped);
+        self.evicted_turns += dropped;
+        for turn in &mut self.turns {
+            turn.first_line -= cut;
+        }
+        let rebase = |ranges: &mut Vec<Range<usize>>| {
+            ranges.retain(|range| range.start >= cut);
+            for range in ranges.iter_mut() {
+                *range = range.start - cut..range.end - cut;
+            }
+        };
+        rebase(&mut self.code_lines);
+        rebase(&mut self.details_lines);
+        let removed_rows = self.line_rows[cut];
+        self.line_rows.drain(..cut);
+        for row in &mut self.line_rows {
+            *row -= removed_rows;
+        }
+        self.top = self.top.saturating_sub(removed_rows).min(self.max_top());
+    }
+
+    fn relayout_from(&mut self, start: usize) {
+        self.cache_wrapped_heights();
+        self.line_rows.truncate(start + 1);
+        for index in start..self.lines.len() {
+            let rows = self.display_row_count(index);
+            self.line_rows.push(self.total_rows() + rows);
+        }
+    }
+
+    /// Reposition the view for newly appended output, unless scroll lock is
+    /// engaged.
+    fn follow_output(&mut self) {
+        if !self.follow {
+            return;
+        }
+        let bottom = self.max_top();
+        self.top = match (self.policy, self.blocks.last()) {
+            (ScrollPolicy::FollowActiveBlock, Some(block))
+                if matches!(block.kind, BlockKind::Reasoning | BlockKind::Answer) =>
+            {
+                bottom.max(self.line_rows[block.first_line])
+            }
+            _ => bottom,
+        };
+    }
+}
+
+/// Marker for the `hidden` earlier lines of a reasoning block left out by
+/// `tui.max_reasoning_lines`.
+pub(crate) fn reasoning_hidden_line(hidden: usize) -> Line<'static> {
+    Line::from(
+        format!("…earlier reasoning hidden ({hidden} lines)")
+            .dim()
+            .italic(),
+    )
+}
+
+/// Six hex digits of an FNV-1a hash over the block's position, kind and
+/// first line. Deterministic, so the same session replayed yields the same
+/// anchors.
+fn block_anchor(index: usize, kind: BlockKind, header: &Line<'_>) -> String {
+    let kind = match kind {
+        BlockKind::User => "user",
+        BlockKind::Reasoning => "reasoning",
+        BlockKind::Answer => "answer",
+        BlockKind::Plan => "plan",
+        BlockKind::Other => "other",
+    };
+    let text: String = header.spans.iter().map(|s| s.content.as_ref()).collect();
+    let mut hash: u32 = 0x811c_9dc5;
+    let bytes = index.to_le_bytes();
+    for byte in bytes.iter().chain(kind.as_bytes()).chain(text.as_bytes()) {
+        hash ^= u32::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    format!("{:06x}", hash & 0x00ff_ffff)
+}
+
+/// [`HistorySink`] that records committed stream lines before forwarding
+/// them to the terminal scrollback.
+pub(crate) struct RecordingHistorySink<S = AppEventHistorySink> {
+    pub(crate) inner: S,
+    pub(crate) scrollback: Rc<RefCell<Scrollback>>,
+}
+
+impl<S: HistorySink> HistorySink for RecordingHistorySink<S> {
+    fn insert_history(&self, lines: Vec<Line<'static>>) {
+        self.scrollback
+            .borrow_mut()
+            .push_stream_lines(lines.clone());
+        self.inner.insert_history(lines);
+    }
+    fn start_commit_animation(&self) {
+        self.inner.start_commit_animation();
+    }
+    fn stop_commit_animation(&self) {
+        self.inner.stop_commit_animation();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn scrollback(policy: ScrollPolicy) -> Scrollback {
+        Scrollback::new(&Tui {
+            scroll_policy: policy,
+            ..Default::default()
+        })
+    }
+
+    fn text(n: usize, prefix: &str) -> Vec<Line<'static>> {
+        (0..n)
+            .map(|i| Line::from(format!("{prefix} {i}")))
+            .collect()
+    }
+
+    fn header(kind: StreamKind) -> Line<'static> {
+        render_header_line(kind, Theme::default(), &HeaderGlyphs::default())
+    }
+
+    #[test]
+    fn follow_active_block_pins_new_block_then_tracks_tail() {
+        let mut sb = scrollback(ScrollPolicy::FollowActiveBlock);
+        sb.set_viewport(80, 5);
+        sb.push_block(BlockKind::User, text(8, "prompt"));
+        assert_eq!(sb.visible_rows(), 3..8);
+
+        // Reasoning starts: its header moves to the top of the view.
+        sb.push_stream_lines(vec![header(StreamKind::Reasoning)]);
+        sb.push_stream_lines(text(2, "thought"));
+        let (kind, rows) = sb.last_block().unwrap();
+        assert_eq!(kind, BlockKind::Reasoning);
+        assert_eq!(sb.visible_rows().start, rows.start);
+
+        // Once it outgrows the view, its tail is followed.
+        sb.push_stream_lines(text(10, "thought"));
+        let (_, rows) = sb.last_block().unwrap();
+        assert_eq!(sb.visible_rows().end, rows.end);
+    }
+
+    #[test]
+    fn follow_bottom_keeps_last_row_at_bottom() {
+        let mut sb = scrollback(ScrollPolicy::FollowBottom);
+        sb.set_viewport(80, 5);
+        sb.push_block(BlockKind::User, text(8, "prompt"));
+        sb.push_stream_lines(vec![header(StreamKind::Answer)]);
+        sb.push_stream_lines(text(1, "answer"));
+        assert_eq!(sb.visible_rows(), 5..10);
+    }
+
+    #[test]
+    fn scroll_lock_holds_position_until_bottom_is_reached() {
+        let mut sb = scrollback(ScrollPolicy::FollowBottom);
+        sb.set_viewport(80, 5);
+        sb.push_block(BlockKind::Other, text(10, "line"));
+        sb.scroll_by(-3);
+        assert!(!sb.is_following());
+        assert_eq!(sb.visible_rows(), 2..7);
+
+        sb.push_block(BlockKind::Other, text(4, "more"));
+        assert_eq!(sb.visible_rows(), 2..7);
+
+        sb.scroll_by(100);
+        assert!(sb.is_following());
+        assert_eq!(sb.visible_rows(), 9..14);
+    }
+
+    #[test]
+    fn page_down_advances_by_the_view_height_and_clamps_at_the_bottom() {
+        let mut sb = scrollback(ScrollPolicy::FollowBottom);
+        sb.set_viewport(80, 5);
+        sb.push_block(BlockKind::Other, text(12, "line"));
+        sb.scroll_to_top();
+        assert_eq!(sb.visible_rows(), 0..5);
+
+        let page = sb.page_height() as isize;
+        sb.scroll_by(page);
+        assert_eq!(sb.visible_rows(), 5..10);
+        assert!(!sb.is_following());
+        // The last page stops at the bottom and resumes following.
+        sb.scroll_by(page);
+        assert_eq!(sb.visible_rows(), 7..12);
+        assert!(sb.is_following());
+        sb.scroll_by(page);
+        assert_eq!(sb.visible_rows(), 7..12);
+
+        // Paging up engages scroll lock and stops at the top.
+        sb.scroll_by(-page);
+        assert_eq!(sb.visible_rows(), 2..7);
+        assert!(!sb.is_following());
+        sb.scroll_by(-page);
+        assert_eq!(sb.visible_rows(), 0..5);
+    }
+
+    #[test]
+    fn oldest_blocks_are_dropped_past_the_line_cap() {
+        let mut sb = scrollback(ScrollPolicy::FollowBottom);
+        sb.set_viewport(80, 10);
+        let blocks = MAX_LINES / 100 + 2;
+        for i in 0..blocks {
+            sb.push_block(BlockKind::User, text(100, &format!("turn {i}")));
+            sb.mark_turn_start(&format!("turn {i}"));
+        }
+        assert!(sb.lines().len() <= MAX_LINES);
+        let outline = sb.outline();
+        assert_eq!(outline.first().map(|e| e.index), Some(2));
+        assert_eq!(outline.last().map(|e| e.index), Some(blocks - 1));
+        assert_eq!(outline[0].row, 0);
+
+        assert!(!sb.scroll_to_turn(0));
+        assert!(sb.scroll_to_turn(2));
+        assert_eq!(sb.visible_rows().start, 0);
+        assert!(sb.scroll_to_last_turn());
+        assert_eq!(sb.visible_rows().start, outline[outline.len() - 1].row);
+    }
+
+    #[test]
+    fn outline_rows_follow_rewrapping() {
+        let mut sb = scrollback(ScrollPolicy::FollowBottom);
+        sb.set_viewport(80, 10);
+        sb.push_block(BlockKind::User, vec![Line::from("aaaa bbbb cccc")]);
+        sb.mark_turn_start("aaaa bbbb cccc");
+        sb.push_block(BlockKind::User, vec![Line::from("next")]);
+        sb.mark_turn_start("next");
+        assert_eq!(sb.outline()[1].row, 1);
+
+        sb.set_viewport(5, 10);
+        assert_eq!(sb.outline()[1].row, 3);
+    }
+
+    #[test]
+    fn goto_anchor_brings_block_header_into_view() {
+        let mut sb = Scrollback::new(&Tui {
+            show_anchors: true,
+            ..Default::default()
+        });
+        sb.set_viewport(80, 5);
+        sb.push_block(BlockKind::User, text(3, "first"));
+        sb.push_block(BlockKind::Other, text(20, "filler"));
+        let anchor = sb.anchors()[0].clone();
+        assert_eq!(anchor.len(), 6);
+
+        assert!(sb.scroll_to_anchor(&format!("#{anchor}")));
+        assert_eq!(sb.visible_rows().start, 0);
+        let header = &sb.rows(0..1)[0];
+        let header: String = header.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(header, format!("first 0  #{anchor}"));
+
+        assert!(!sb.scroll_to_anchor("zzzzzz"));
+    }
+
+    #[test]
+    fn rows_are_rewrapped_on_resize() {
+        let mut sb = scrollback(ScrollPolicy::FollowBottom);
+        sb.set_viewport(80, 10);
+        sb.push_block(BlockKind::Other, vec![Line::from("aaaa bbbb cccc")]);
+        assert_eq!(sb.visible_rows(), 0..1);
+        sb.set_viewport(5, 10);
+        let rows = sb.rows(sb.visible_rows());
+        let rendered: Vec<String> = rows
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert_eq!(rendered.len(), 3);
+        assert_eq!(rendered[0].trim_end(), "aaaa");
+    }
+
+    #[test]
+    fn wrapped_heights_are_kept_per_width_and_follow_edits() {
+        let mut sb = scrollback(ScrollPolicy::FollowBottom);
+        sb.set_viewport(80, 10);
+        sb.push_block(BlockKind::Other, vec![Line::from("aaaa bbbb cccc")]);
+        sb.set_viewport(5, 10);
+        assert_eq!(sb.row_count(), 3);
+        sb.set_viewport(80, 10);
+        assert_eq!(sb.row_count(), 1);
+        assert_eq!(sb.wrapped_heights.len(), 2);
+
+        let anchor = sb.last_anchor().unwrap();
+        assert!(sb.replace_block(&anchor, vec![Line::from("dddd eeee")]));
+        sb.set_viewport(5, 10);
+        assert_eq!(sb.row_count(), 2);
+    }
+
+    #[test]
+    fn replacing_a_block_shifts_later_blocks_and_turns() {
+        let mut sb = scrollback(ScrollPolicy::FollowBottom);
+        sb.set_viewport(80, 10);
+        sb.push_block(BlockKind::Plan, text(2, "step"));
+        let plan = sb.last_anchor().unwrap();
+        sb.push_block(BlockKind::User, text(1, "prompt"));
+        sb.mark_turn_start("prompt");
+
+        assert!(sb.replace_block(&plan, text(4, "step")));
+        assert_eq!(sb.outline()[0].row, 4);
+        assert_eq!(sb.visible_rows(), 0..5);
+        assert!(!sb.replace_block("zzzzzz", text(1, "x")));
+    }
+}
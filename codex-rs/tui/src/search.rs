@@ -0,0 +1,174 @@
+//! Text search in the transcript view (`/`, then `n`/`N`).
+//!
+//! Matches are found in the wrapped rows that are drawn, so they follow the
+//! current width, and highlighting only patches the matched characters: the
+//! rest of a styled line (a code block background, a header color) is left
+//! as it was.
+
+use std::ops::Range;
+
+use codex_core::config_types::Theme;
+use codex_core::config_types::Tui;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+
+#[derive(Debug, Default)]
+pub(crate) struct TranscriptSearch {
+    pub(crate) query: String,
+    /// The query is still being typed.
+    pub(crate) editing: bool,
+    /// Index of the current match among all matches.
+    pub(crate) current: usize,
+}
+
+/// A match within one wrapped row, in characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SearchMatch {
+    pub(crate) row: usize,
+    pub(crate) columns: Range<usize>,
+}
+
+/// Case-insensitive, non-overlapping occurrences of `query` in `rows`, where
+/// `rows[0]` is row `first_row`.
+pub(crate) fn find_matches(rows: &[Line<'_>], first_row: usize, query: &str) -> Vec<SearchMatch> {
+    let needle: Vec<char> = query.chars().map(fold_case).collect();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let mut matches = Vec::new();
+    for (i, line) in rows.iter().enumerate() {
+        let haystack: Vec<char> = line
+            .spans
+            .iter()
+            .flat_map(|span| span.content.chars())
+            .map(fold_case)
+            .collect();
+        let mut start = 0;
+        while start + needle.len() <= haystack.len() {
+            if haystack[start..start + needle.len()] == needle[..] {
+                matches.push(SearchMatch {
+                    row: first_row + i,
+                    columns: start..start + needle.len(),
+                });
+                start += needle.len();
+            } else {
+                start += 1;
+            }
+        }
+    }
+    matches
+}
+
+fn fold_case(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// `line` with `style` patched onto the characters in `columns`. Spans are
+/// split at the match boundaries; everything outside keeps its style.
+pub(crate) fn highlight(
+    line: &Line<'static>,
+    columns: Range<usize>,
+    style: Style,
+) -> Line<'static> {
+    let mut spans: Vec<Span<'static>> = Vec::with_capacity(line.spans.len() + 2);
+    let mut offset = 0;
+    for span in &line.spans {
+        let len = span.content.chars().count();
+        let start = columns.start.clamp(offset, offset + len) - offset;
+        let end = columns.end.clamp(offset, offset + len) - offset;
+        offset += len;
+        if start == end {
+            spans.push(span.clone());
+            continue;
+        }
+        let byte = |chars: usize| {
+            span.content
+                .char_indices()
+                .nth(chars)
+                .map_or(span.content.len(), |(i, _)| i)
+        };
+        let (before, rest) = span.content.split_at(byte(start));
+        let (inside, after) = rest.split_at(byte(end) - byte(start));
+        for (text, style) in [
+            (before, span.style),
+            (inside, span.style.patch(style)),
+            (after, span.style),
+        ] {
+            if !text.is_empty() {
+                spans.push(Span::styled(text.to_string(), style));
+            }
+        }
+    }
+    Line {
+        style: line.style,
+        alignment: line.alignment,
+        spans,
+    }
+}
+
+/// Styles of a match and of the current match: `tui.search_match_color` and
+/// `tui.search_current_color` when set, otherwise picked for the theme.
+pub(crate) fn match_styles(config: &Tui) -> (Style, Style) {
+    let (match_bg, current_bg) = match config.theme {
+        Theme::Dark => (Color::Yellow, Color::LightMagenta),
+        Theme::Light => (Color::LightYellow, Color::LightCyan),
+        Theme::Mono => {
+            let style = Style::default().add_modifier(Modifier::REVERSED);
+            return (
+                style,
+                style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            );
+        }
+    };
+    let color = |configured: Option<&str>, default| {
+        let bg = configured
+            .and_then(|name| name.parse::<Color>().ok())
+            .unwrap_or(default);
+        Style::default().fg(Color::Black).bg(bg)
+    };
+    (
+        color(config.search_match_color.as_deref(), match_bg),
+        color(config.search_current_color.as_deref(), current_bg),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use ratatui::style::Stylize;
+
+    #[test]
+    fn matches_are_case_insensitive_and_do_not_overlap() {
+        let rows = vec![Line::from("Foo foofoo"), Line::from("bar")];
+        let matches = find_matches(&rows, 7, "foo");
+        let columns: Vec<_> = matches.iter().map(|m| (m.row, m.columns.clone())).collect();
+        assert_eq!(columns, vec![(7, 0..3), (7, 4..7), (7, 7..10)]);
+        assert!(find_matches(&rows, 0, "").is_empty());
+    }
+
+    #[test]
+    fn highlight_splits_spans_and_keeps_their_styles() {
+        let line = Line::from(vec!["let ".cyan(), "needle".on_dark_gray(), " = 1;".into()]);
+        let style = Style::default().fg(Color::Black).bg(Color::Yellow);
+        let out = highlight(&line, 2..7, style);
+        let parts: Vec<(&str, Style)> = out
+            .spans
+            .iter()
+            .map(|s| (s.content.as_ref(), s.style))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![
+                ("le", Style::default().cyan()),
+                ("t ", Style::default().cyan().patch(style)),
+                ("nee", Style::default().on_dark_gray().patch(style)),
+                ("dle", Style::default().on_dark_gray()),
+                (" = 1;", Style::default()),
+            ]
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use std::fs::File;
 use std::fs::OpenOptions;
 use std::io::Write;
+#[cfg(unix)]
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Mutex;
 
@@ -12,17 +14,23 @@ use serde::Serialize;
 use serde_json::json;
 
 use crate::app_event::AppEvent;
+#[cfg(unix)]
+use crate::event_tap::EventTap;
 
 static LOGGER: Lazy<SessionLogger> = Lazy::new(SessionLogger::new);
 
 struct SessionLogger {
     file: OnceCell<Mutex<File>>,
+    #[cfg(unix)]
+    tap: OnceCell<EventTap>,
 }
 
 impl SessionLogger {
     fn new() -> Self {
         Self {
             file: OnceCell::new(),
+            #[cfg(unix)]
+            tap: OnceCell::new(),
         }
     }
 
@@ -45,7 +53,21 @@ impl SessionLogger {
         Ok(())
     }
 
+    #[cfg(unix)]
+    fn open_tap(&self, path: &Path) -> std::io::Result<()> {
+        let tap = EventTap::bind(path)?;
+        let _ = self.tap.set(tap);
+        Ok(())
+    }
+
     fn write_json_line(&self, value: serde_json::Value) {
+        #[cfg(unix)]
+        if let Some(tap) = self.tap.get() {
+            match serde_json::to_string(&value) {
+                Ok(serialized) => tap.send(serialized),
+                Err(e) => tracing::warn!("event tap serialize error: {}", e),
+            }
+        }
         let Some(mutex) = self.file.get() else {
             return;
         };
@@ -72,16 +94,23 @@ impl SessionLogger {
     }
 
     fn is_enabled(&self) -> bool {
+        #[cfg(unix)]
+        if self.tap.get().is_some() {
+            return true;
+        }
         self.file.get().is_some()
     }
 }
 
-fn now_ts() -> String {
+pub(crate) fn now_ts() -> String {
     // RFC3339 for readability; consumers can parse as needed.
     chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
 }
 
 pub(crate) fn maybe_init(config: &Config) {
+    #[cfg(unix)]
+    maybe_init_tap(config);
+
     let enabled = std::env::var("CODEX_TUI_RECORD_SESSION")
         .map(|v| matches!(v.as_str(), "1" | "true" | "TRUE" | "yes" | "YES"))
         .unwrap_or(false);
@@ -122,6 +151,30 @@ pub(crate) fn maybe_init(config: &Config) {
     LOGGER.write_json_line(header);
 }
 
+#[cfg(unix)]
+fn maybe_init_tap(config: &Config) {
+    let tap = &config.tui.event_tap;
+    if !tap.enabled {
+        return;
+    }
+    let path = match &tap.path {
+        Some(path) => path.clone(),
+        None => match codex_core::config::log_dir(config) {
+            Ok(dir) => dir.join("tui-events.sock"),
+            Err(_) => match crate::event_tap::fallback_socket_path() {
+                Ok(path) => path,
+                Err(e) => {
+                    tracing::error!("no place for the event tap socket: {}", e);
+                    return;
+                }
+            },
+        },
+    };
+    if let Err(e) = LOGGER.open_tap(&path) {
+        tracing::error!("failed to open event tap {:?}: {}", path, e);
+    }
+}
+
 pub(crate) fn log_inbound_app_event(event: &AppEvent) {
     // Log only if enabled
     if !LOGGER.is_enabled() {
@@ -150,12 +203,13 @@ pub(crate) fn log_inbound_app_event(event: &AppEvent) {
             });
             LOGGER.write_json_line(value);
         }
-        AppEvent::DispatchCommand(cmd) => {
+        AppEvent::DispatchCommand(cmd, args) => {
             let value = json!({
                 "ts": now_ts(),
                 "dir": "to_tui",
                 "kind": "slash_command",
                 "command": format!("{:?}", cmd),
+                "args": args,
             });
             LOGGER.write_json_line(value);
         }
@@ -217,6 +271,19 @@ pub(crate) fn log_outbound_op(op: &Op) {
     write_record("from_tui", "op", op);
 }
 
+pub(crate) fn log_session_title(title: &str) {
+    if !LOGGER.is_enabled() {
+        return;
+    }
+    let value = json!({
+        "ts": now_ts(),
+        "dir": "meta",
+        "kind": "session_title",
+        "title": title,
+    });
+    LOGGER.write_json_line(value);
+}
+
 pub(crate) fn log_session_end() {
     if !LOGGER.is_enabled() {
         return;
@@ -227,6 +294,10 @@ pub(crate) fn log_session_end() {
         "kind": "session_end",
     });
     LOGGER.write_json_line(value);
+    #[cfg(unix)]
+    if let Some(tap) = LOGGER.tap.get() {
+        tap.close();
+    }
 }
 
 fn write_record<T>(dir: &str, kind: &str, obj: &T)
@@ -0,0 +1,88 @@
+//! Boilerplate sign-offs at the end of answers ("Let me know if you need
+//! anything else."), matched by `tui.sign_offs` so they can be dimmed or
+//! left out on screen. The transcript keeps them, so `/export` is unchanged.
+
+use codex_core::config_types::SignOffAction;
+use codex_core::config_types::SignOffs;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use regex_lite::Regex;
+
+pub(crate) struct SignOffMatcher {
+    patterns: Vec<Regex>,
+    action: SignOffAction,
+}
+
+impl SignOffMatcher {
+    /// Compile the configured patterns. Invalid ones are logged and skipped.
+    pub(crate) fn new(config: &SignOffs) -> Self {
+        let patterns = config
+            .patterns
+            .iter()
+            .filter_map(
+                |pattern| match Regex::new(&format!("(?i)^(?:{pattern})$")) {
+                    Ok(regex) => Some(regex),
+                    Err(e) => {
+                        tracing::warn!("ignoring invalid sign-off pattern `{pattern}`: {e}");
+                        None
+                    }
+                },
+            )
+            .collect();
+        Self {
+            patterns,
+            action: config.action,
+        }
+    }
+
+    /// If the last sentence of `answer` is a sign-off, the text before it
+    /// and the lines to show in its place (none when it is stripped). An
+    /// answer that is only a sign-off is left alone.
+    pub(crate) fn split<'a>(&self, answer: &'a str) -> Option<(&'a str, Vec<Line<'static>>)> {
+        if self.patterns.is_empty() {
+            return None;
+        }
+        let answer = answer.trim_end();
+        let start = last_sentence_start(answer);
+        let sentence = &answer[start..];
+        if start == 0 || !self.patterns.iter().any(|p| p.is_match(sentence)) {
+            return None;
+        }
+        let lines = match self.action {
+            SignOffAction::Dim => vec![Line::from(sentence.to_string()).dim()],
+            SignOffAction::Strip => Vec::new(),
+        };
+        Some((answer[..start].trim_end(), lines))
+    }
+}
+
+/// Byte offset of the last sentence: the text after the last line break, or
+/// after the last `.`, `!` or `?` followed by whitespace.
+fn last_sentence_start(text: &str) -> usize {
+    let mut start = 0;
+    let mut after_boundary = false;
+    let mut prev = None;
+    for (i, c) in text.char_indices() {
+        if c == '\n' || (c.is_whitespace() && matches!(prev, Some('.' | '!' | '?'))) {
+            after_boundary = true;
+        } else if after_boundary && !c.is_whitespace() {
+            start = i;
+            after_boundary = false;
+        }
+        prev = Some(c);
+    }
+    start
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn last_sentence_follows_the_last_line_break_or_sentence_end() {
+        assert_eq!(last_sentence_start("One. Two? Three"), 10);
+        assert_eq!(last_sentence_start("v1.2 is out\n\nEnjoy"), 13);
+        assert_eq!(last_sentence_start("Only one"), 0);
+    }
+}
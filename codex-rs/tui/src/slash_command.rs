@@ -15,10 +15,23 @@ pub enum SlashCommand {
     New,
     Init,
     Compact,
+    Summarize,
+    Continue,
     Diff,
     Mention,
     Status,
+    Stats,
+    Model,
     Prompts,
+    Title,
+    Export,
+    ExportTurn,
+    CopyCommands,
+    Goto,
+    Theme,
+    Wrap,
+    ExecOutput,
+    ShowRequest,
     Logout,
     Quit,
     #[cfg(debug_assertions)]
@@ -32,11 +45,28 @@ impl SlashCommand {
             SlashCommand::New => "start a new chat during a conversation",
             SlashCommand::Init => "create an AGENTS.md file with instructions for Codex",
             SlashCommand::Compact => "summarize conversation to prevent hitting the context limit",
+            SlashCommand::Summarize => "ask the model for a summary of this session",
+            SlashCommand::Continue => "continue a response that was cut off by the length limit",
             SlashCommand::Quit => "exit Codex",
             SlashCommand::Diff => "show git diff (including untracked files)",
             SlashCommand::Mention => "mention a file",
             SlashCommand::Status => "show current session configuration and token usage",
+            SlashCommand::Stats => "show session statistics: turns, tokens, commands, time",
+            SlashCommand::Model => "switch the model in a new session: /model <name>",
             SlashCommand::Prompts => "show example prompts",
+            SlashCommand::Title => "name this session: /title <text>",
+            SlashCommand::Export => {
+                "export the transcript as Markdown: /export [compact|bookmarks]"
+            }
+            SlashCommand::ExportTurn => "export one turn as Markdown: /export-turn <n> <path>",
+            SlashCommand::CopyCommands => {
+                "copy this turn's commands as a shell script: /copy-commands [all]"
+            }
+            SlashCommand::Goto => "jump to a block by anchor: /goto <anchor>",
+            SlashCommand::Theme => "switch the color theme: /theme dark|light|mono",
+            SlashCommand::Wrap => "set the wrap width: /wrap <columns>|off",
+            SlashCommand::ExecOutput => "show or hide command output: /exec-output on|off",
+            SlashCommand::ShowRequest => "show and copy the last request sent to the model",
             SlashCommand::Logout => "log out of Codex",
             #[cfg(debug_assertions)]
             SlashCommand::TestApproval => "test approval request",
@@ -1,9 +1,12 @@
 use codex_core::config::Config;
+use codex_core::config_types::ReasoningSectionTitles;
+use codex_core::config_types::Theme;
 use ratatui::text::Line;
 
 use super::HeaderEmitter;
 use super::StreamKind;
 use super::StreamState;
+use crate::sign_off::SignOffMatcher;
 
 /// Sink for history insertions and animation control.
 pub(crate) trait HistorySink {
@@ -39,21 +42,66 @@ pub(crate) struct StreamController {
     states: [StreamState; 2],
     current_stream: Option<StreamKind>,
     finishing_after_drain: bool,
+    // Characters of reasoning received since the turn (or last answer) began.
+    reasoning_chars: usize,
+    // Trailing sign-offs of final answers to dim or strip (`tui.sign_offs`).
+    sign_offs: SignOffMatcher,
+    // Start of a reasoning section not yet added to the stream. With hidden
+    // section titles it holds the first line until it is complete.
+    section_start: Option<String>,
+    // Reasoning sections started this turn, for numbered section titles.
+    reasoning_sections: usize,
 }
 
 impl StreamController {
     pub(crate) fn new(config: Config) -> Self {
         Self {
+            sign_offs: SignOffMatcher::new(&config.tui.sign_offs),
             config,
             header: HeaderEmitter::new(),
             states: [StreamState::new(), StreamState::new()],
             current_stream: None,
             finishing_after_drain: false,
+            reasoning_chars: 0,
+            section_start: None,
+            reasoning_sections: 0,
         }
     }
 
+    /// Style headers emitted from now on with `theme`.
+    pub(crate) fn set_theme(&mut self, theme: Theme) {
+        self.config.tui.theme = theme;
+    }
+
+    /// Reset per-turn header state and the reasoning length counter.
     pub(crate) fn reset_headers_for_new_turn(&mut self) {
         self.header.reset_for_new_turn();
+        self.reasoning_chars = 0;
+        self.reasoning_sections = 0;
+    }
+
+    /// Length of the reasoning streamed so far, while reasoning is the active
+    /// stream. Returns `None` once the answer starts.
+    pub(crate) fn reasoning_chars(&self) -> Option<usize> {
+        (self.current_stream == Some(StreamKind::Reasoning)).then_some(self.reasoning_chars)
+    }
+
+    /// The stream currently being written, if any.
+    pub(crate) fn active_stream(&self) -> Option<StreamKind> {
+        self.current_stream
+    }
+
+    /// Source of the `kind` stream received so far, while it is the active
+    /// stream and has not been finalized.
+    pub(crate) fn unfinished_source(&self, kind: StreamKind) -> String {
+        if self.current_stream != Some(kind) {
+            return String::new();
+        }
+        let mut source = self.state(kind).collector.source().to_string();
+        if kind == StreamKind::Reasoning {
+            source.push_str(self.section_start.as_deref().unwrap_or_default());
+        }
+        source
     }
 
     pub(crate) fn is_write_cycle_active(&self) -> bool {
@@ -64,6 +112,8 @@ impl StreamController {
         self.states.iter_mut().for_each(|s| s.clear());
         self.current_stream = None;
         self.finishing_after_drain = false;
+        self.reasoning_chars = 0;
+        self.section_start = None;
         // leave header state unchanged; caller decides when to reset
     }
 
@@ -79,7 +129,12 @@ impl StreamController {
     }
 
     fn emit_header_if_needed(&mut self, kind: StreamKind, out_lines: &mut Lines) -> bool {
-        self.header.maybe_emit(kind, out_lines)
+        self.header.maybe_emit(
+            kind,
+            self.config.tui.theme,
+            &self.config.tui.header_glyphs,
+            out_lines,
+        )
     }
 
     #[inline]
@@ -98,6 +153,9 @@ impl StreamController {
     pub(crate) fn begin(&mut self, kind: StreamKind, sink: &impl HistorySink) {
         if let Some(current) = self.current_stream {
             if current != kind {
+                if current == StreamKind::Reasoning {
+                    self.flush_section_start();
+                }
                 // Synchronously flush completed lines from previous stream.
                 let cfg = self.config.clone();
                 let prev_state = self.state_mut(current);
@@ -126,8 +184,13 @@ impl StreamController {
             if prev.is_some() {
                 self.header.reset_for_stream(kind);
             }
+            // The answer supersedes the live reasoning counter.
+            if matches!(kind, StreamKind::Answer) {
+                self.reasoning_chars = 0;
+            }
             // Emit header immediately for reasoning; for answers, defer to first commit.
             if matches!(kind, StreamKind::Reasoning) {
+                self.section_start = Some(String::new());
                 let mut header_lines = Vec::new();
                 if self.emit_header_if_needed(kind, &mut header_lines) {
                     sink.insert_history(header_lines);
@@ -141,10 +204,20 @@ impl StreamController {
         let Some(kind) = self.current_stream else {
             return;
         };
+        let text = match kind {
+            StreamKind::Reasoning => {
+                self.reasoning_chars += delta.chars().count();
+                match self.reasoning_section_text(delta) {
+                    Some(text) => text,
+                    None => return,
+                }
+            }
+            StreamKind::Answer => delta.to_string(),
+        };
         let cfg = self.config.clone();
         let state = self.state_mut(kind);
-        state.collector.push_delta(delta);
-        if delta.contains('\n') {
+        state.collector.push_delta(&text);
+        if text.contains('\n') {
             let newly_completed = state.collector.commit_complete_lines(&cfg);
             if !newly_completed.is_empty() {
                 state.enqueue(newly_completed);
@@ -153,11 +226,57 @@ impl StreamController {
         }
     }
 
+    /// Text to add to the reasoning stream for `delta`, with the start of a
+    /// section set apart per `tui.reasoning_section_titles`. Returns `None`
+    /// while the first line of a section is held back to tell whether it is
+    /// a title.
+    fn reasoning_section_text(&mut self, delta: &str) -> Option<String> {
+        let Some(mut head) = self.section_start.take() else {
+            return Some(delta.to_string());
+        };
+        head.push_str(delta);
+        match self.config.tui.reasoning_section_titles {
+            ReasoningSectionTitles::Separator => Some(head),
+            ReasoningSectionTitles::Numbered => {
+                self.reasoning_sections += 1;
+                Some(format!(
+                    "**Reasoning {}**\n\n{head}",
+                    self.reasoning_sections
+                ))
+            }
+            ReasoningSectionTitles::Hidden => match head.split_once('\n') {
+                Some((first, rest)) if is_section_title(first) => Some(rest.to_string()),
+                Some(_) => Some(head),
+                None => {
+                    self.section_start = Some(head);
+                    None
+                }
+            },
+        }
+    }
+
+    /// Add a section start still held back to the reasoning stream, unless
+    /// it is only a hidden title.
+    fn flush_section_start(&mut self) {
+        let Some(head) = self.section_start.take() else {
+            return;
+        };
+        let hidden = self.config.tui.reasoning_section_titles == ReasoningSectionTitles::Hidden
+            && is_section_title(&head);
+        if !head.is_empty() && !hidden {
+            self.state_mut(StreamKind::Reasoning)
+                .collector
+                .push_delta(&head);
+        }
+    }
+
     /// Insert a reasoning section break and commit any newly completed lines.
     pub(crate) fn insert_reasoning_section_break(&mut self, sink: &impl HistorySink) {
         if self.current_stream != Some(StreamKind::Reasoning) {
             self.begin(StreamKind::Reasoning, sink);
         }
+        self.flush_section_start();
+        self.section_start = Some(String::new());
         let cfg = self.config.clone();
         let state = self.state_mut(StreamKind::Reasoning);
         state.collector.insert_section_break();
@@ -174,15 +293,34 @@ impl StreamController {
         kind: StreamKind,
         flush_immediately: bool,
         sink: &impl HistorySink,
+    ) -> bool {
+        self.finalize_with_tail(kind, flush_immediately, Vec::new(), sink)
+    }
+
+    /// Like [`Self::finalize`], with `tail` emitted after the stream's last
+    /// lines and before its link references, if any.
+    fn finalize_with_tail(
+        &mut self,
+        kind: StreamKind,
+        flush_immediately: bool,
+        tail: Lines,
+        sink: &impl HistorySink,
     ) -> bool {
         if self.current_stream != Some(kind) {
             return false;
         }
+        if matches!(kind, StreamKind::Reasoning) {
+            self.flush_section_start();
+        }
         let cfg = self.config.clone();
         // Finalize collector first.
         let remaining = {
             let state = self.state_mut(kind);
-            state.collector.finalize_and_drain(&cfg)
+            let references = crate::markdown::link_references(state.collector.source(), &cfg);
+            let mut remaining = state.collector.finalize_and_drain(&cfg);
+            remaining.extend(tail);
+            remaining.extend(references);
+            remaining
         };
         if flush_immediately {
             // Collect all output first to avoid emitting headers when there is no content.
@@ -261,9 +399,14 @@ impl StreamController {
     }
 
     /// Apply a full final answer: replace queued content with only the remaining tail,
-    /// then finalize immediately and notify completion.
+    /// then finalize immediately and notify completion. A trailing sign-off
+    /// matching `tui.sign_offs` is rendered dimmed on its own line, or left out.
     pub(crate) fn apply_final_answer(&mut self, message: &str, sink: &impl HistorySink) -> bool {
         self.begin(StreamKind::Answer, sink);
+        let (message, tail) = self
+            .sign_offs
+            .split(message)
+            .unwrap_or((message, Vec::new()));
         if !message.is_empty() {
             let mut msg_with_nl = message.to_string();
             if !msg_with_nl.ends_with('\n') {
@@ -276,6 +419,12 @@ impl StreamController {
                 .collector
                 .replace_with_and_mark_committed(&msg_with_nl, already_committed);
         }
-        self.finalize(StreamKind::Answer, true, sink)
+        self.finalize_with_tail(StreamKind::Answer, true, tail, sink)
     }
 }
+
+/// A line that is all bold, which is how models title reasoning sections.
+fn is_section_title(line: &str) -> bool {
+    let line = line.trim();
+    line.len() > 4 && line.starts_with("**") && line.ends_with("**")
+}
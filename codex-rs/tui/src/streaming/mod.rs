@@ -1,3 +1,6 @@
+use codex_core::config_types::HeaderGlyphs;
+use codex_core::config_types::Theme;
+
 use crate::markdown_stream::AnimatedLineStreamer;
 use crate::markdown_stream::MarkdownStreamCollector;
 pub(crate) mod controller;
@@ -38,6 +41,10 @@ impl StreamState {
     }
 }
 
+/// Decides when a `thinking` or `codex` header precedes stream output. Each
+/// block gets a header of its own once it has content: a turn with several
+/// agent messages (for example around tool calls) shows every message under
+/// its own `codex` header, in arrival order, and likewise for reasoning.
 pub(crate) struct HeaderEmitter {
     reasoning_emitted_this_turn: bool,
     answer_emitted_this_turn: bool,
@@ -91,6 +98,8 @@ impl HeaderEmitter {
     pub(crate) fn maybe_emit(
         &mut self,
         kind: StreamKind,
+        theme: Theme,
+        glyphs: &HeaderGlyphs,
         out_lines: &mut Vec<ratatui::text::Line<'static>>,
     ) -> bool {
         let already_emitted_this_turn = match kind {
@@ -99,7 +108,7 @@ impl HeaderEmitter {
         };
         let already_emitted_in_stream = self.has_emitted_for_stream(kind);
         if !already_emitted_in_stream && !already_emitted_this_turn {
-            out_lines.push(render_header_line(kind));
+            out_lines.push(render_header_line(kind, theme, glyphs));
             match kind {
                 StreamKind::Reasoning => {
                     self.reasoning_emitted_in_stream = true;
@@ -121,10 +130,60 @@ impl HeaderEmitter {
     }
 }
 
-fn render_header_line(kind: StreamKind) -> ratatui::text::Line<'static> {
+/// The stream whose header `line` is under `theme` and `glyphs`, if it is one.
+pub(crate) fn header_kind(
+    line: &ratatui::text::Line<'_>,
+    theme: Theme,
+    glyphs: &HeaderGlyphs,
+) -> Option<StreamKind> {
+    [StreamKind::Reasoning, StreamKind::Answer]
+        .into_iter()
+        .find(|kind| render_header_line(*kind, theme, glyphs) == *line)
+}
+
+pub(crate) fn render_header_line(
+    kind: StreamKind,
+    theme: Theme,
+    glyphs: &HeaderGlyphs,
+) -> ratatui::text::Line<'static> {
     use ratatui::style::Stylize;
-    match kind {
-        StreamKind::Reasoning => ratatui::text::Line::from("thinking".magenta().italic()),
-        StreamKind::Answer => ratatui::text::Line::from("codex".magenta().bold()),
-    }
+    let glyph = match kind {
+        StreamKind::Reasoning => glyphs.thinking.as_deref(),
+        StreamKind::Answer => glyphs.codex.as_deref(),
+    };
+    let label = match kind {
+        StreamKind::Reasoning => "thinking",
+        StreamKind::Answer => "codex",
+    };
+    let label = match theme {
+        Theme::Dark => label.magenta(),
+        Theme::Light => label.blue(),
+        Theme::Mono => label.into(),
+    };
+    let label = match kind {
+        StreamKind::Reasoning => label.italic(),
+        StreamKind::Answer => label.bold(),
+    };
+    let mut line = ratatui::text::Line::default();
+    line.spans.extend(header_glyph_span(glyph, glyphs));
+    line.spans.push(label);
+    line
+}
+
+/// Span drawn before a header label: `glyph` (or nothing, for a role without
+/// one) padded to the display width of the widest configured glyph, plus a
+/// space. The label stays a span of its own. `None` when no glyphs are set.
+pub(crate) fn header_glyph_span(
+    glyph: Option<&str>,
+    glyphs: &HeaderGlyphs,
+) -> Option<ratatui::text::Span<'static>> {
+    use unicode_width::UnicodeWidthStr;
+    let widest = [&glyphs.user, &glyphs.codex, &glyphs.thinking]
+        .into_iter()
+        .flatten()
+        .map(|g| g.width())
+        .max()?;
+    let glyph = glyph.unwrap_or_default();
+    let pad = " ".repeat(widest.saturating_sub(glyph.width()));
+    Some(ratatui::text::Span::raw(format!("{glyph}{pad} ")))
 }
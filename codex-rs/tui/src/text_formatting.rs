@@ -1,5 +1,10 @@
+use codex_core::config_types::ControlChars;
+use std::borrow::Cow;
+
 use unicode_segmentation::UnicodeSegmentation;
 
+use crate::render::markdown_utils::fence_after;
+
 /// Truncate a tool result to fit within the given height and width. If the text is valid JSON, we format it in a compact way before truncating.
 /// This is a best-effort approach that may not work perfectly for text where 1 grapheme is rendered as multiple terminal cells.
 pub(crate) fn format_and_truncate_tool_result(
@@ -100,12 +105,226 @@ pub(crate) fn truncate_text(text: &str, max_graphemes: usize) -> String {
     }
 }
 
+/// Characters that reorder or hide text, or that the terminal would act on
+/// instead of drawing. Tabs, newlines and the `\r` of a CRLF are left alone,
+/// as are the zero-width joiners used inside emoji and some scripts.
+fn is_unsafe_char(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0}'..='\u{8}'
+            | '\u{b}'..='\u{c}'
+            | '\u{e}'..='\u{1f}'
+            | '\u{7f}'..='\u{9f}'
+            | '\u{61c}'
+            | '\u{200b}'
+            | '\u{200e}'..='\u{200f}'
+            | '\u{202a}'..='\u{202e}'
+            | '\u{2060}'
+            | '\u{2066}'..='\u{2069}'
+            | '\u{feff}'
+    )
+}
+
+/// Apply `policy` to the unsafe characters in `text` (see [`ControlChars`]).
+pub(crate) fn sanitize_control_chars(text: &str, policy: ControlChars) -> Cow<'_, str> {
+    let unsafe_at =
+        |i: usize, c: char| is_unsafe_char(c) || (c == '\r' && !text[i + 1..].starts_with('\n'));
+    if policy == ControlChars::Keep || !text.char_indices().any(|(i, c)| unsafe_at(i, c)) {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    for (i, c) in text.char_indices() {
+        if !unsafe_at(i, c) {
+            out.push(c);
+        } else if policy == ControlChars::Mark {
+            out.push_str(&format!("⟨U+{:04X}⟩", u32::from(c)));
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Number of characters in `text` that [`sanitize_control_chars`] would
+/// mark or strip.
+pub(crate) fn count_control_chars(text: &str) -> usize {
+    match sanitize_control_chars(text, ControlChars::Strip) {
+        Cow::Borrowed(_) => 0,
+        Cow::Owned(stripped) => text.chars().count() - stripped.chars().count(),
+    }
+}
+
+/// Shorten runs of blank lines in `text` to at most `max` lines. Lines
+/// inside fenced code blocks are kept as they are.
+pub(crate) fn collapse_blank_lines(text: &str, max: usize) -> Cow<'_, str> {
+    let mut out = String::with_capacity(text.len());
+    let mut fence = None;
+    let mut run = 0;
+    let mut dropped = false;
+    for line in text.split_inclusive('\n') {
+        fence = fence_after(fence, line);
+        if fence.is_none() && line.trim().is_empty() {
+            run += 1;
+            if run > max {
+                dropped = true;
+                continue;
+            }
+        } else {
+            run = 0;
+        }
+        out.push_str(line);
+    }
+    if dropped {
+        Cow::Owned(out)
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Decode the HTML entities models commonly emit (`&amp;`, `&lt;`, `&#39;`,
+/// `&#x2014;`, ...). Anything that is not a recognized entity is left as is.
+pub(crate) fn decode_html_entities(text: &str) -> Cow<'_, str> {
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        // Entity names are short; don't scan arbitrarily far for the `;`.
+        let decoded = rest
+            .char_indices()
+            .take(12)
+            .find(|&(_, c)| c == ';')
+            .and_then(|(semi, _)| decode_entity(&rest[1..semi]).map(|c| (c, semi)));
+        match decoded {
+            Some((c, semi)) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
+
+/// [`decode_html_entities`] for Markdown source: entities in fenced and
+/// indented code blocks and in inline code spans are kept, since Markdown
+/// shows code literally.
+pub(crate) fn decode_html_entities_in_prose(markdown: &str) -> Cow<'_, str> {
+    if !markdown.contains('&') {
+        return Cow::Borrowed(markdown);
+    }
+    let mut out = String::with_capacity(markdown.len());
+    let mut fence = None;
+    for line in markdown.split_inclusive('\n') {
+        let in_fence = fence.is_some();
+        fence = fence_after(fence, line);
+        let indented = line.starts_with("    ") || line.starts_with('\t');
+        if in_fence || fence.is_some() || indented {
+            out.push_str(line);
+        } else {
+            out.push_str(&decode_html_entities_outside_code_spans(line));
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// [`decode_html_entities`] for one line of Markdown prose, keeping the
+/// entities inside its inline code spans.
+fn decode_html_entities_outside_code_spans(text: &str) -> Cow<'_, str> {
+    if !text.contains('&') || !text.contains('`') {
+        return decode_html_entities(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find('`') {
+        out.push_str(&decode_html_entities(&rest[..open]));
+        let ticks = backtick_run_len(&rest[open..]);
+        let code = &rest[open + ticks..];
+        // A span is closed by a run of exactly as many backticks; an
+        // unmatched run is literal text.
+        let end = match find_backtick_run(code, ticks) {
+            Some(close) => open + ticks + close + ticks,
+            None => open + ticks,
+        };
+        out.push_str(&rest[open..end]);
+        rest = &rest[end..];
+    }
+    out.push_str(&decode_html_entities(rest));
+    Cow::Owned(out)
+}
+
+fn backtick_run_len(s: &str) -> usize {
+    s.bytes().take_while(|b| *b == b'`').count()
+}
+
+/// Byte offset in `s` of the first run of exactly `len` backticks.
+fn find_backtick_run(s: &str, len: usize) -> Option<usize> {
+    let mut from = 0;
+    while let Some(found) = s[from..].find('`') {
+        let at = from + found;
+        let run = backtick_run_len(&s[at..]);
+        if run == len {
+            return Some(at);
+        }
+        from = at + run;
+    }
+    None
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    if let Some(num) = name.strip_prefix('#') {
+        let code = match num.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => num.parse().ok()?,
+        };
+        return char::from_u32(code).filter(|c| *c != '\0');
+    }
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        _ => return None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::unwrap_used)]
     use super::*;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn blank_lines_collapse_outside_code_fences() {
+        let text = "a\n\n\n\nb\n```\nx\n\n\n\ny\n```\n";
+        assert_eq!(
+            collapse_blank_lines(text, 1),
+            "a\n\nb\n```\nx\n\n\n\ny\n```\n"
+        );
+        let text = "a\n\n\nb\n~~~\nx\n\n\ny\n~~~\n\n\nc";
+        assert_eq!(
+            collapse_blank_lines(text, 1),
+            "a\n\nb\n~~~\nx\n\n\ny\n~~~\n\nc"
+        );
+        assert!(matches!(
+            collapse_blank_lines("a\n\nb", 1),
+            Cow::Borrowed(_)
+        ));
+    }
+
     #[test]
     fn test_truncate_text() {
         let text = "Hello, world!";
@@ -265,4 +484,52 @@ mod tests {
         assert_eq!(format_json_compact("null").unwrap(), "null");
         assert_eq!(format_json_compact(r#""string""#).unwrap(), r#""string""#);
     }
+
+    #[test]
+    fn html_entities_decode_and_unknown_ones_stay_literal() {
+        assert_eq!(
+            decode_html_entities("a &lt; b &amp;&amp; c &gt; d &#39;x&#x27;"),
+            "a < b && c > d 'x'"
+        );
+        assert_eq!(
+            decode_html_entities("&bogus; & &amp &#xZZ; &#0;"),
+            "&bogus; & &amp &#xZZ; &#0;"
+        );
+        assert!(matches!(decode_html_entities("plain"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn html_entities_in_code_stay_literal() {
+        let markdown = "a &amp; `b &amp;` ``c ` &lt;`` `&gt;\n\
+                        ~~~\n\
+                        x &lt; y\n\
+                        ~~~\n\
+                        \n    i &amp;= 1\n\
+                        &quot;done&quot;\n";
+        assert_eq!(
+            decode_html_entities_in_prose(markdown),
+            "a & `b &amp;` ``c ` &lt;`` `>\n\
+             ~~~\n\
+             x &lt; y\n\
+             ~~~\n\
+             \n    i &amp;= 1\n\
+             \"done\"\n"
+        );
+    }
+
+    #[test]
+    fn control_chars_are_marked_or_stripped() {
+        let text = "abc\u{202e}def\u{200b} \x1b[2Jx\r\n\u{1f469}\u{200d}\u{1f4bb}";
+        assert_eq!(
+            sanitize_control_chars(text, ControlChars::Mark),
+            "abc⟨U+202E⟩def⟨U+200B⟩ ⟨U+001B⟩[2Jx\r\n\u{1f469}\u{200d}\u{1f4bb}"
+        );
+        assert_eq!(
+            sanitize_control_chars(text, ControlChars::Strip),
+            "abcdef [2Jx\r\n\u{1f469}\u{200d}\u{1f4bb}"
+        );
+        assert_eq!(sanitize_control_chars(text, ControlChars::Keep), text);
+        assert_eq!(sanitize_control_chars("a\rb", ControlChars::Strip), "ab");
+        assert_eq!(count_control_chars(text), 3);
+    }
 }
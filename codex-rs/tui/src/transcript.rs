@@ -0,0 +1,489 @@
+//! Canonical, un-rendered record of the conversation.
+//!
+//! History cells are flattened into styled lines as soon as they are inserted
+//! into the terminal scrollback, so anything that needs the original text
+//! (export, re-rendering) reads it from here instead.
+
+use crate::render::markdown_utils::close_unclosed_fence;
+use crate::text_formatting::collapse_blank_lines;
+use crate::text_formatting::decode_html_entities_in_prose;
+use codex_core::protocol::AgentMessageEvent;
+use codex_core::protocol::AgentReasoningEvent;
+use codex_core::protocol::ContextCompactedEvent;
+use codex_core::protocol::ErrorEvent;
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExecCommandBeginEvent;
+use codex_core::protocol::ExecCommandEndEvent;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Source text of a single turn: the user prompt and what the agent produced
+/// in response.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TurnRecord {
+    pub user: String,
+    pub reasoning: String,
+    pub answer: String,
+    /// Summary that replaced the conversation history before this turn.
+    #[serde(default)]
+    pub compaction: String,
+    /// Commands the agent ran, in the order they finished.
+    #[serde(default)]
+    pub exec: Vec<ExecRecord>,
+    /// Errors reported while the turn ran, such as the one that ended it.
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+/// A command run during a turn, with its captured output.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExecRecord {
+    pub command: Vec<String>,
+    pub exit_code: i32,
+    pub stdout: String,
+    pub stderr: String,
+    pub duration: Duration,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Transcript {
+    turns: Vec<TurnRecord>,
+    /// The next answer continues a truncated one and is joined to it as is.
+    continuing_answer: bool,
+    /// Shorten runs of blank lines in exported reasoning and answers to this
+    /// many (`tui.collapse_blank_lines_in_export`).
+    max_blank_lines: Option<usize>,
+    /// Bumped on every change, so a copy of the Markdown can tell when it
+    /// is stale.
+    revision: u64,
+}
+
+impl Transcript {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// A transcript whose Markdown export collapses runs of blank lines in
+    /// model output to `max_blank_lines`.
+    pub(crate) fn with_max_blank_lines(max_blank_lines: Option<usize>) -> Self {
+        Self {
+            max_blank_lines,
+            ..Self::default()
+        }
+    }
+
+    /// Start a new turn with the given user prompt.
+    pub(crate) fn push_user(&mut self, text: &str) {
+        self.revision += 1;
+        self.turns.push(TurnRecord {
+            user: text.to_string(),
+            ..Default::default()
+        });
+    }
+
+    /// Append a completed reasoning block to the current turn.
+    pub(crate) fn push_reasoning(&mut self, text: &str) {
+        append_block(&mut self.current_mut().reasoning, text);
+    }
+
+    /// Append a completed agent message to the current turn. HTML entities
+    /// outside code are decoded, matching what is shown on screen.
+    pub(crate) fn push_answer(&mut self, text: &str) {
+        let text = &decode_html_entities_in_prose(text);
+        if std::mem::take(&mut self.continuing_answer) {
+            self.current_mut().answer.push_str(text);
+        } else {
+            append_block(&mut self.current_mut().answer, text);
+        }
+    }
+
+    /// Record that the core compacted the context into `summary`. It starts
+    /// a turn of its own, since no prompt precedes it.
+    pub(crate) fn push_compaction(&mut self, summary: &str) {
+        self.revision += 1;
+        self.turns.push(TurnRecord {
+            compaction: summary.to_string(),
+            ..Default::default()
+        });
+    }
+
+    /// Record a command that finished in the current turn.
+    pub(crate) fn push_exec(&mut self, exec: ExecRecord) {
+        self.current_mut().exec.push(exec);
+    }
+
+    /// Record an error reported during the current turn.
+    pub(crate) fn push_error(&mut self, message: &str) {
+        self.current_mut().errors.push(message.to_string());
+    }
+
+    /// Forget what the current turn produced, such as the output of an
+    /// attempt that failed and is about to be sent again.
+    pub(crate) fn clear_turn_output(&mut self) {
+        self.revision += 1;
+        if let Some(turn) = self.turns.last_mut() {
+            turn.reasoning.clear();
+            turn.answer.clear();
+            turn.exec.clear();
+            turn.errors.clear();
+        }
+    }
+
+    /// Join the next answer directly onto the current one, which was cut
+    /// short.
+    pub(crate) fn continue_answer(&mut self) {
+        self.continuing_answer = true;
+    }
+
+    /// Answer text of the latest turn, if it has one.
+    pub(crate) fn last_answer(&self) -> Option<&str> {
+        self.turns
+            .last()
+            .map(|turn| turn.answer.as_str())
+            .filter(|answer| !answer.is_empty())
+    }
+
+    /// Changes whenever the transcript does.
+    pub(crate) fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Every turn so far, oldest first.
+    pub(crate) fn turns(&self) -> &[TurnRecord] {
+        &self.turns
+    }
+
+    /// Render the transcript as Markdown, preserving the original characters
+    /// of every block.
+    pub(crate) fn to_markdown(&self) -> String {
+        self.turns
+            .iter()
+            .map(|turn| turn_to_markdown(turn, self.max_blank_lines))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Render only the prompts and answers as Markdown, leaving out
+    /// reasoning and compaction summaries, for sharing the questions and
+    /// answers alone.
+    pub(crate) fn to_compact_markdown(&self) -> String {
+        self.turns
+            .iter()
+            .map(|turn| TurnRecord {
+                user: turn.user.clone(),
+                answer: turn.answer.clone(),
+                ..Default::default()
+            })
+            .filter(|turn| !(turn.user.is_empty() && turn.answer.is_empty()))
+            .map(|turn| turn_to_markdown(&turn, self.max_blank_lines))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Render the most recent turns that fit in `max_chars` as Markdown.
+    /// Returns the Markdown and the number of oldest turns left out.
+    pub(crate) fn to_markdown_within(&self, max_chars: usize) -> (String, usize) {
+        let mut kept: Vec<String> = Vec::new();
+        let mut len = 0;
+        for turn in self.turns.iter().rev() {
+            let markdown = turn_to_markdown(turn, self.max_blank_lines);
+            // One separator line between turns.
+            len += markdown.len() + usize::from(!kept.is_empty());
+            if len > max_chars {
+                break;
+            }
+            kept.push(markdown);
+        }
+        let dropped = self.turns.len() - kept.len();
+        kept.reverse();
+        (kept.join("\n"), dropped)
+    }
+
+    /// Render only the turn at `index` (zero-based) as Markdown, or `None`
+    /// when there is no such turn.
+    pub(crate) fn turn_markdown(&self, index: usize) -> Option<String> {
+        self.turns
+            .get(index)
+            .map(|turn| turn_to_markdown(turn, self.max_blank_lines))
+    }
+
+    /// Render only the turns at `indices` (zero-based, ascending) as
+    /// Markdown. Indices with no turn are skipped.
+    pub(crate) fn turns_markdown(&self, indices: impl IntoIterator<Item = usize>) -> String {
+        indices
+            .into_iter()
+            .filter_map(|index| self.turn_markdown(index))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn current_mut(&mut self) -> &mut TurnRecord {
+        // Agent output can arrive before any prompt (e.g. an initial message
+        // submitted by the core), so open an empty turn on demand.
+        self.revision += 1;
+        if self.turns.is_empty() {
+            self.turns.push(TurnRecord::default());
+        }
+        let last = self.turns.len() - 1;
+        &mut self.turns[last]
+    }
+}
+
+/// Build the turns of a session from the events the core sent, without any
+/// rendering. Every task is a turn; its prompt is left empty, since prompts
+/// are submitted by the client rather than reported back as events. Only
+/// completed reasoning and agent messages are recorded: the deltas that
+/// precede them carry the same text. Commands are recorded when they end,
+/// with the command line of their begin event.
+pub fn turns_from_events(events: impl IntoIterator<Item = Event>) -> Vec<TurnRecord> {
+    let mut transcript = Transcript::new();
+    let mut commands: HashMap<String, Vec<String>> = HashMap::new();
+    // A task's turn is opened by its first output, so that a compaction,
+    // which opens a turn of its own, does not leave an empty one before it.
+    let mut task_open = false;
+    let open_turn = |transcript: &mut Transcript, task_open: &mut bool| {
+        if std::mem::take(task_open) {
+            transcript.push_user("");
+        }
+    };
+    for event in events {
+        match event.msg {
+            EventMsg::TaskStarted => task_open = true,
+            EventMsg::AgentReasoning(AgentReasoningEvent { text }) => {
+                open_turn(&mut transcript, &mut task_open);
+                transcript.push_reasoning(&text);
+            }
+            EventMsg::AgentMessage(AgentMessageEvent { message }) => {
+                open_turn(&mut transcript, &mut task_open);
+                transcript.push_answer(&message);
+            }
+            EventMsg::ContextCompacted(ContextCompactedEvent { summary }) => {
+                task_open = false;
+                transcript.push_compaction(&summary);
+            }
+            EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
+                call_id, command, ..
+            }) => {
+                commands.insert(call_id, command);
+            }
+            EventMsg::ExecCommandEnd(ExecCommandEndEvent {
+                call_id,
+                stdout,
+                stderr,
+                exit_code,
+                duration,
+            }) => {
+                open_turn(&mut transcript, &mut task_open);
+                let command = commands.remove(&call_id).unwrap_or_else(|| vec![call_id]);
+                transcript.push_exec(ExecRecord {
+                    command,
+                    exit_code,
+                    stdout,
+                    stderr,
+                    duration,
+                });
+            }
+            EventMsg::Error(ErrorEvent { message, .. }) => {
+                open_turn(&mut transcript, &mut task_open);
+                transcript.push_error(&message);
+            }
+            // A task that produced no text still counts as a turn.
+            EventMsg::TaskComplete(_) => open_turn(&mut transcript, &mut task_open),
+            _ => {}
+        }
+    }
+    transcript.turns
+}
+
+/// Append `text` as a new block. A fence left open by the previous block is
+/// closed first, so the new block is not swallowed into its code.
+fn append_block(dst: &mut String, text: &str) {
+    if !dst.is_empty() {
+        *dst = close_unclosed_fence(dst);
+        dst.push_str("\n\n");
+    }
+    dst.push_str(text);
+}
+
+fn turn_to_markdown(turn: &TurnRecord, max_blank_lines: Option<usize>) -> String {
+    let model_text = |text: &String| match max_blank_lines {
+        Some(max) => collapse_blank_lines(text, max).into_owned(),
+        None => text.clone(),
+    };
+    [
+        ("context compacted", turn.compaction.clone()),
+        ("user", turn.user.clone()),
+        ("thinking", model_text(&turn.reasoning)),
+        ("codex", model_text(&turn.answer)),
+    ]
+    .into_iter()
+    .filter(|(_, body)| !body.is_empty())
+    .map(|(title, body)| {
+        // Keep an unclosed fence from running into the next section.
+        let body = close_unclosed_fence(&body);
+        let nl = if body.ends_with('\n') { "" } else { "\n" };
+        format!("### {title}\n\n{body}{nl}")
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+#[cfg(test)]
+impl Transcript {
+    /// Free the reasoning text of the turn at `index`. Returns `false` when
+    /// there is no such turn.
+    pub(crate) fn drop_reasoning(&mut self, index: usize) -> bool {
+        self.revision += 1;
+        match self.turns.get_mut(index) {
+            Some(turn) => {
+                turn.reasoning = String::new();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn markdown_export_groups_blocks_by_turn() {
+        let mut t = Transcript::new();
+        t.push_user("hi");
+        t.push_reasoning("**Greeting**");
+        t.push_answer("Hello!");
+        t.push_user("bye");
+        t.push_answer("Goodbye.");
+
+        assert_eq!(
+            t.to_markdown(),
+            "### user\n\nhi\n\n### thinking\n\n**Greeting**\n\n### codex\n\nHello!\n\n\
+             ### user\n\nbye\n\n### codex\n\nGoodbye.\n"
+        );
+    }
+
+    #[test]
+    fn export_collapses_blank_lines_in_answers_only_when_asked() {
+        let mut t = Transcript::new();
+        t.push_user("a\n\n\nb");
+        t.push_answer("one\n\n\n\ntwo");
+        assert!(t.to_markdown().contains("one\n\n\n\ntwo"));
+
+        let mut t = Transcript::with_max_blank_lines(Some(1));
+        t.push_user("a\n\n\nb");
+        t.push_answer("one\n\n\n\ntwo");
+        assert_eq!(
+            t.to_markdown(),
+            "### user\n\na\n\n\nb\n\n### codex\n\none\n\ntwo\n"
+        );
+    }
+
+    #[test]
+    fn revision_changes_with_the_transcript_only() {
+        let mut t = Transcript::new();
+        let start = t.revision();
+        t.push_user("hi");
+        let after_prompt = t.revision();
+        assert_ne!(after_prompt, start);
+        let _ = t.to_markdown();
+        assert_eq!(t.revision(), after_prompt);
+        t.push_answer("hello");
+        assert_ne!(t.revision(), after_prompt);
+    }
+
+    #[test]
+    fn markdown_within_a_budget_drops_the_oldest_turns() {
+        let mut t = Transcript::new();
+        t.push_user("first");
+        t.push_answer("one");
+        t.push_user("second");
+        t.push_answer("two");
+
+        let (all, dropped) = t.to_markdown_within(usize::MAX);
+        assert_eq!((all.as_str(), dropped), (t.to_markdown().as_str(), 0));
+
+        let last = "### user\n\nsecond\n\n### codex\n\ntwo\n";
+        assert_eq!(t.to_markdown_within(last.len() + 5), (last.to_string(), 1));
+        assert_eq!(t.to_markdown_within(3), (String::new(), 2));
+    }
+
+    #[test]
+    fn turns_from_events_has_one_turn_per_task_with_its_full_answer() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/longer-markdown-session.jsonl"
+        );
+        let log = std::fs::read_to_string(path).expect("read fixture");
+        let events: Vec<Event> = log
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .filter(|v| v["kind"] == "codex_event")
+            .map(|v| serde_json::from_value(v["payload"].clone()).expect("parse event"))
+            .collect();
+
+        let turns = turns_from_events(events);
+        let answers: Vec<&str> = turns.iter().map(|t| t.answer.as_str()).collect();
+        assert_eq!(
+            answers,
+            vec![
+                "## Summary\n\nThe build script is **fine**; the failure comes from the linker.\n\n- `cc` is missing\n- `PATH` is not exported\n",
+                "## Second turn\n\nTo install the linker on Debian:\n\n```sh\nsudo apt install build-essential\n```\n\nThen re-run `cargo build`.\n",
+                "## Third turn\n\n1. Clean the target directory\n2. Rebuild with `--release`\n\n> Incremental builds may hide the problem.\n",
+            ]
+        );
+        assert!(turns.iter().all(|t| t.user.is_empty()));
+    }
+
+    #[test]
+    fn turns_from_events_record_commands_with_their_command_line() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/failing-command.jsonl"
+        );
+        let log = std::fs::read_to_string(path).expect("read fixture");
+        let events: Vec<Event> = log
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .filter(|v| v["kind"] == "codex_event")
+            .map(|v| serde_json::from_value(v["payload"].clone()).expect("parse event"))
+            .collect();
+
+        let turns = turns_from_events(events);
+        assert_eq!(turns.len(), 1);
+        let commands: Vec<(String, i32)> = turns[0]
+            .exec
+            .iter()
+            .map(|exec| (exec.command.join(" "), exec.exit_code))
+            .collect();
+        assert_eq!(
+            commands,
+            vec![
+                ("bash -lc cargo test -p demo".to_string(), 101),
+                ("bash -lc git status --short".to_string(), 0),
+            ]
+        );
+        assert_eq!(turns[0].exec[1].stdout, " M src/lib.rs\n");
+    }
+
+    #[test]
+    fn continued_answer_is_joined_without_a_break() {
+        let mut t = Transcript::new();
+        t.push_user("list");
+        t.push_answer("one, tw");
+        t.continue_answer();
+        t.push_answer("o, three");
+        t.push_answer("Done.");
+
+        assert_eq!(
+            t.to_markdown(),
+            "### user\n\nlist\n\n### codex\n\none, two, three\n\nDone.\n"
+        );
+    }
+}
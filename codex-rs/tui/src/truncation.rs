@@ -0,0 +1,87 @@
+//! The indicator that stands in for lines left out of a collapsed or
+//! truncated block, such as `… 7 more lines`. Its wording and color come from
+//! `tui.truncation_indicator` and `tui.truncation_indicator_color`, so every
+//! block that hides lines says so the same way.
+
+use codex_core::config_types::Tui;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+
+/// Wording used when `tui.truncation_indicator` is unset.
+const DEFAULT_TEXT: &str = "… {n} more {lines}";
+
+#[derive(Debug, Clone)]
+pub(crate) struct TruncationIndicator {
+    /// Template with `{n}` for the number of hidden lines and `{lines}` for
+    /// "line" or "lines" to match it.
+    text: String,
+    style: Style,
+}
+
+impl Default for TruncationIndicator {
+    fn default() -> Self {
+        Self {
+            text: DEFAULT_TEXT.to_string(),
+            style: Style::default().add_modifier(Modifier::DIM | Modifier::ITALIC),
+        }
+    }
+}
+
+impl TruncationIndicator {
+    pub(crate) fn new(config: &Tui) -> Self {
+        let mut indicator = Self::default();
+        if let Some(text) = &config.truncation_indicator {
+            indicator.text = text.clone();
+        }
+        if let Some(color) = config
+            .truncation_indicator_color
+            .as_deref()
+            .and_then(|name| name.parse::<Color>().ok())
+        {
+            indicator.style = Style::default().fg(color).add_modifier(Modifier::ITALIC);
+        }
+        indicator
+    }
+
+    /// The indicator for `hidden` lines left out.
+    pub(crate) fn line(&self, hidden: usize) -> Line<'static> {
+        let noun = if hidden == 1 { "line" } else { "lines" };
+        let text = self
+            .text
+            .replace("{n}", &hidden.to_string())
+            .replace("{lines}", noun);
+        Line::from(Span::styled(text, self.style))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn text(line: &Line<'_>) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn default_wording_matches_the_count() {
+        let indicator = TruncationIndicator::default();
+        assert_eq!(text(&indicator.line(1)), "… 1 more line");
+        assert_eq!(text(&indicator.line(7)), "… 7 more lines");
+    }
+
+    #[test]
+    fn configured_text_and_color_are_used() {
+        let config = Tui {
+            truncation_indicator: Some("(show {n} hidden {lines})".to_string()),
+            truncation_indicator_color: Some("cyan".to_string()),
+            ..Tui::default()
+        };
+        let line = TruncationIndicator::new(&config).line(3);
+        assert_eq!(text(&line), "(show 3 hidden lines)");
+        assert_eq!(line.spans[0].style.fg, Some(Color::Cyan));
+    }
+}
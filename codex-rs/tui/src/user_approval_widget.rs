@@ -36,6 +36,9 @@ pub(crate) enum ApprovalRequest {
         id: String,
         command: Vec<String>,
         reason: Option<String>,
+        /// The `dangerous` exec_policy rule the command matched. Such requests
+        /// need the user to type `yes` rather than press a single key.
+        dangerous_rule: Option<String>,
     },
     ApplyPatch {
         id: String,
@@ -104,6 +107,10 @@ pub(crate) struct UserApprovalWidget<'a> {
     /// Currently selected index in *select* mode.
     selected_option: usize,
 
+    /// Text typed so far when the request needs an explicit `yes`; `None`
+    /// for the usual single-key selection.
+    confirm_input: Option<String>,
+
     /// Set to `true` once a decision has been sent – the parent view can then
     /// remove this widget from its queue.
     done: bool,
@@ -113,20 +120,31 @@ impl UserApprovalWidget<'_> {
     pub(crate) fn new(approval_request: ApprovalRequest, app_event_tx: AppEventSender) -> Self {
         let confirmation_prompt = match &approval_request {
             ApprovalRequest::Exec {
-                command, reason, ..
+                command,
+                reason,
+                dangerous_rule,
+                ..
             } => {
                 let cmd = strip_bash_lc_and_escape(command);
                 // Present a single-line summary without cwd: "codex wants to run: <cmd>"
                 let mut cmd_span: Span = cmd.clone().into();
                 cmd_span.style = cmd_span.style.add_modifier(Modifier::DIM);
-                let mut contents: Vec<Line> = vec![
+                let mut contents: Vec<Line> = Vec::new();
+                if let Some(rule) = dangerous_rule {
+                    contents.push(Line::from(vec![
+                        "⚠ ".red().bold(),
+                        "Dangerous command".red().bold(),
+                        format!(" (matches `{rule}`)").red(),
+                    ]));
+                }
+                contents.extend([
                     Line::from(vec![
                         "? ".fg(Color::Blue),
                         "Codex wants to run ".bold(),
                         cmd_span,
                     ]),
                     Line::from(""),
-                ];
+                ]);
                 if let Some(reason) = reason {
                     contents.push(Line::from(reason.clone().italic()));
                     contents.push(Line::from(""));
@@ -155,6 +173,14 @@ impl UserApprovalWidget<'_> {
             }
         };
 
+        let confirm_input = match &approval_request {
+            ApprovalRequest::Exec {
+                dangerous_rule: Some(_),
+                ..
+            } => Some(String::new()),
+            _ => None,
+        };
+
         Self {
             select_options: match &approval_request {
                 ApprovalRequest::Exec { .. } => &COMMAND_SELECT_OPTIONS,
@@ -164,6 +190,7 @@ impl UserApprovalWidget<'_> {
             app_event_tx,
             confirmation_prompt,
             selected_option: 0,
+            confirm_input,
             done: false,
         }
     }
@@ -180,7 +207,11 @@ impl UserApprovalWidget<'_> {
     /// was consumed—callers can assume it always is.
     pub(crate) fn handle_key_event(&mut self, key: KeyEvent) {
         if key.kind == KeyEventKind::Press {
-            self.handle_select_key(key);
+            if self.confirm_input.is_some() {
+                self.handle_confirm_key(key);
+            } else {
+                self.handle_select_key(key);
+            }
         }
     }
 
@@ -229,6 +260,32 @@ impl UserApprovalWidget<'_> {
         }
     }
 
+    /// Escalated flow: only typing `yes` and pressing Enter runs the command;
+    /// Enter with anything else denies it.
+    fn handle_confirm_key(&mut self, key_event: KeyEvent) {
+        let Some(input) = self.confirm_input.as_mut() else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Enter => {
+                let decision = if input.trim().eq_ignore_ascii_case("yes") {
+                    ReviewDecision::Approved
+                } else {
+                    ReviewDecision::Denied
+                };
+                self.send_decision(decision);
+            }
+            KeyCode::Esc => {
+                self.send_decision(ReviewDecision::Abort);
+            }
+            _ => {}
+        }
+    }
+
     fn send_decision(&mut self, decision: ReviewDecision) {
         self.send_decision_with_feedback(decision, String::new())
     }
@@ -320,8 +377,24 @@ impl UserApprovalWidget<'_> {
     }
 
     pub(crate) fn desired_height(&self, width: u16) -> u16 {
+        // The escalated flow uses the same three rows as the command options:
+        // title, input line and hint.
         self.get_confirmation_prompt_height(width) + self.select_options.len() as u16
     }
+
+    fn render_confirm_input(&self, input: &str, area: Rect, buf: &mut Buffer) {
+        let [title_area, input_area, description_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+        ])
+        .areas(area.inner(Margin::new(1, 0)));
+        Line::from("Type yes to run this command".red().bold()).render(title_area, buf);
+        Line::from(vec!["> ".red(), input.to_string().into()]).render(input_area, buf);
+        Line::from("Enter confirms; anything but `yes` denies. Esc cancels.")
+            .style(Style::new().italic().fg(Color::DarkGray))
+            .render(description_area.inner(Margin::new(1, 0)), buf);
+    }
 }
 
 impl WidgetRef for &UserApprovalWidget<'_> {
@@ -332,6 +405,27 @@ impl WidgetRef for &UserApprovalWidget<'_> {
             .constraints([Constraint::Length(prompt_height), Constraint::Min(0)])
             .areas(area);
 
+        self.confirmation_prompt.clone().render(prompt_chunk, buf);
+
+        let border_color = if self.confirm_input.is_some() {
+            Color::Red
+        } else {
+            Color::Cyan
+        };
+        Block::bordered()
+            .border_type(BorderType::QuadrantOutside)
+            .border_style(Style::default().fg(border_color))
+            .borders(Borders::LEFT)
+            .render_ref(
+                Rect::new(0, response_chunk.y, 1, response_chunk.height),
+                buf,
+            );
+
+        if let Some(input) = &self.confirm_input {
+            self.render_confirm_input(input, response_chunk, buf);
+            return;
+        }
+
         let lines: Vec<Line> = self
             .select_options
             .iter()
@@ -358,7 +452,6 @@ impl WidgetRef for &UserApprovalWidget<'_> {
         };
         Line::from(title).render(title_area, buf);
 
-        self.confirmation_prompt.clone().render(prompt_chunk, buf);
         let areas = Layout::horizontal(
             lines
                 .iter()
@@ -374,15 +467,6 @@ impl WidgetRef for &UserApprovalWidget<'_> {
         Line::from(self.select_options[self.selected_option].description)
             .style(Style::new().italic().fg(Color::DarkGray))
             .render(description_area.inner(Margin::new(1, 0)), buf);
-
-        Block::bordered()
-            .border_type(BorderType::QuadrantOutside)
-            .border_style(Style::default().fg(Color::Cyan))
-            .borders(Borders::LEFT)
-            .render_ref(
-                Rect::new(0, response_chunk.y, 1, response_chunk.height),
-                buf,
-            );
     }
 }
 
@@ -402,6 +486,7 @@ mod tests {
             id: "1".to_string(),
             command: vec!["echo".to_string()],
             reason: None,
+            dangerous_rule: None,
         };
         let mut widget = UserApprovalWidget::new(req, tx);
         widget.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
@@ -424,6 +509,7 @@ mod tests {
             id: "2".to_string(),
             command: vec!["echo".to_string()],
             reason: None,
+            dangerous_rule: None,
         };
         let mut widget = UserApprovalWidget::new(req, tx);
         widget.handle_key_event(KeyEvent::new(KeyCode::Char('Y'), KeyModifiers::NONE));
@@ -437,4 +523,35 @@ mod tests {
             })
         )));
     }
+
+    #[test]
+    fn dangerous_command_requires_typing_yes() {
+        let (tx_raw, rx) = channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let req = ApprovalRequest::Exec {
+            id: "3".to_string(),
+            command: vec!["rm".to_string(), "-rf".to_string(), "build".to_string()],
+            reason: None,
+            dangerous_rule: Some("rm -rf".to_string()),
+        };
+        let mut widget = UserApprovalWidget::new(req, tx);
+
+        // The single-key shortcut is just input here.
+        widget.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert!(!widget.is_complete());
+
+        for c in "es".chars() {
+            widget.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        widget.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(widget.is_complete());
+        let events: Vec<AppEvent> = rx.try_iter().collect();
+        assert!(events.iter().any(|e| matches!(
+            e,
+            AppEvent::CodexOp(Op::ExecApproval {
+                decision: ReviewDecision::Approved,
+                ..
+            })
+        )));
+    }
 }
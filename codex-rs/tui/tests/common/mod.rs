@@ -0,0 +1,33 @@
+//! History lines for the `insert_history` benchmark and its budget test,
+//! rendered from a recorded session.
+
+use codex_core::protocol::Event;
+use ratatui::text::Line;
+use ratatui::text::Span;
+
+/// The answers of the session recorded in `tests/fixtures/{name}`, each under
+/// a `codex` header and followed by a blank line, as they reach the terminal.
+pub fn fixture_history_lines(name: &str) -> Vec<Line<'static>> {
+    let path = format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"));
+    let log = std::fs::read_to_string(path).expect("read fixture");
+    let events: Vec<Event> = log
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|v| v["kind"] == "codex_event")
+        .map(|v| serde_json::from_value(v["payload"].clone()).expect("parse event"))
+        .collect();
+    let mut lines = Vec::new();
+    for turn in codex_tui::turns_from_events(events) {
+        lines.push(Line::from("codex"));
+        for line in tui_markdown::from_str(&turn.answer).lines {
+            let spans: Vec<Span<'static>> = line
+                .spans
+                .into_iter()
+                .map(|span| Span::styled(span.content.into_owned(), span.style))
+                .collect();
+            lines.push(Line::from(spans).style(line.style));
+        }
+        lines.push(Line::from(""));
+    }
+    lines
+}
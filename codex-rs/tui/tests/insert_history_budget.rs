@@ -0,0 +1,77 @@
+//! Guards `insert_history_lines_to_writer` against large regressions: a
+//! recorded session must be written within generous time, allocation and
+//! output budgets. `benches/insert_history.rs` measures the same pipeline
+//! precisely.
+
+#![expect(clippy::expect_used)]
+
+mod common;
+
+use std::alloc::GlobalAlloc;
+use std::alloc::Layout;
+use std::alloc::System;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_tui::custom_terminal::Terminal;
+use codex_tui::insert_history::insert_history_lines_to_writer;
+use ratatui::backend::TestBackend;
+use ratatui::layout::Rect;
+
+/// Counts allocations, so the test can bound how many the pipeline makes.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn recorded_session_is_inserted_within_budget() {
+    let lines = common::fixture_history_lines("longer-markdown-session.jsonl");
+    let line_count = lines.len();
+    let text_bytes: usize = lines
+        .iter()
+        .flat_map(|line| &line.spans)
+        .map(|span| span.content.len())
+        .sum();
+    let mut terminal = Terminal::with_options(TestBackend::new(80, 24)).expect("create terminal");
+    terminal.set_viewport_area(Rect::new(0, 23, 80, 1));
+    let mut out = Vec::new();
+
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    insert_history_lines_to_writer(&mut terminal, &mut out, lines);
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
+
+    // Far above what the pipeline needs, so only a real regression (such as
+    // re-wrapping every line per row) trips them, even in a debug build.
+    assert!(
+        elapsed < Duration::from_secs(1),
+        "{line_count} lines took {elapsed:?}"
+    );
+    assert!(
+        allocations < line_count * 200,
+        "{line_count} lines made {allocations} allocations"
+    );
+    // Each row costs a line break and the escapes of its styles.
+    assert!(
+        out.len() < text_bytes * 4 + line_count * 64,
+        "{text_bytes} bytes of text became {} bytes of ANSI output",
+        out.len()
+    );
+}